@@ -1,19 +1,37 @@
+use crate::verify::DAG_PB_CODEC;
+use ::cid::Cid as LibCid;
+use ::cid::Version;
 use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
-/// Newtype for IPFS CIDv0 (base58-encoded, starts with "Qm", 46 characters).
+/// Multihash code of sha2-256, the hash of every CIDv0.
+const SHA2_256_CODE: u64 = 0x12;
+
+/// Newtype for IPFS CIDv0 (base58-encoded sha2-256 multihash, starts with "Qm",
+/// 46 characters).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String")
+)]
 pub struct CidV0(String);
 
 /// Newtype for IPFS CIDv1 (multibase-encoded with various encodings).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String")
+)]
 pub struct CidV1(String);
 
 /// Represents an IPFS Content Identifier (CID).
 /// Supports both CIDv0 (base58-encoded SHA-256 multihash) and CIDv1 (multibase-encoded).
+///
+/// CIDs are decoded on construction: strings that merely look like CIDs but
+/// do not decode to a well-formed multihash are rejected.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Cid {
     /// CIDv0: Always a base58-encoded multihash starting with "Qm"
@@ -30,16 +48,39 @@ pub enum CidError {
     InvalidFormat,
     #[error("invalid CIDv0: must start with 'Qm' and be 46 characters")]
     InvalidV0,
+    #[error("invalid CID: {0}")]
+    Malformed(#[from] ::cid::Error),
+    #[error("{0} has no CIDv0 form: only dag-pb CIDs with a sha2-256 hash do")]
+    NoV0Form(String),
+}
+
+/// Decodes a CID string, rejecting trailing bytes after the multihash.
+fn decode(s: &str) -> Result<LibCid, CidError> {
+    let bytes = if s.starts_with("Qm") {
+        ::cid::multibase::Base::Base58Btc
+            .decode(s)
+            .map_err(::cid::Error::from)?
+    } else {
+        ::cid::multibase::decode(s).map_err(::cid::Error::from)?.1
+    };
+    let cid = LibCid::try_from(bytes.as_slice())?;
+    if cid.encoded_len() != bytes.len() {
+        return Err(CidError::InvalidFormat);
+    }
+    Ok(cid)
 }
 
 impl CidV0 {
     /// Creates a new CIDv0 from a string.
-    /// CIDv0 must start with "Qm" and be exactly 46 characters long.
+    /// CIDv0 must start with "Qm", be exactly 46 characters long and decode to
+    /// a sha2-256 multihash.
     pub fn new(cid: String) -> Result<Self, CidError> {
-        if cid.starts_with("Qm") && cid.len() == 46 {
-            Ok(CidV0(cid))
-        } else {
-            Err(CidError::InvalidV0)
+        if !(cid.starts_with("Qm") && cid.len() == 46) {
+            return Err(CidError::InvalidV0);
+        }
+        match decode(&cid)?.version() {
+            Version::V0 => Ok(CidV0(cid)),
+            Version::V1 => Err(CidError::InvalidV0),
         }
     }
 
@@ -79,8 +120,15 @@ impl Display for CidV0 {
 impl CidV1 {
     /// Creates a new CIDv1 from a string.
     /// CIDv1 typically starts with 'b' (base32) or 'z' (base58btc), but can have other multibase prefixes.
-    pub fn new(cid: String) -> Self {
-        CidV1(cid)
+    /// The string must decode to a version 1 CID.
+    pub fn new(cid: String) -> Result<Self, CidError> {
+        if cid.is_empty() {
+            return Err(CidError::EmptyString);
+        }
+        match decode(&cid)?.version() {
+            Version::V1 => Ok(CidV1(cid)),
+            Version::V0 => Err(CidError::InvalidFormat),
+        }
     }
 
     /// Returns the CIDv1 as a string slice.
@@ -94,9 +142,19 @@ impl CidV1 {
     }
 }
 
-impl From<String> for CidV1 {
-    fn from(value: String) -> Self {
-        CidV1(value)
+impl TryFrom<String> for CidV1 {
+    type Error = CidError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        CidV1::new(value)
+    }
+}
+
+impl TryFrom<&str> for CidV1 {
+    type Error = CidError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        CidV1::new(value.to_string())
     }
 }
 
@@ -134,6 +192,50 @@ impl Cid {
     pub fn is_v1(&self) -> bool {
         matches!(self, Cid::V1(_))
     }
+
+    /// Multicodec code of the content, e.g. `0x70` for dag-pb (always the case
+    /// for CIDv0) or `0x55` for raw blocks.
+    pub fn codec(&self) -> u64 {
+        self.decoded().codec()
+    }
+
+    /// Multihash code of the hash function, e.g. `0x12` for sha2-256 (always
+    /// the case for CIDv0).
+    pub fn hash_algorithm(&self) -> u64 {
+        self.decoded().hash().code()
+    }
+
+    /// The CIDv1 form of this CID, in base32. CIDv1s are returned unchanged.
+    pub fn to_v1(&self) -> Cid {
+        match self {
+            Cid::V0(_) => {
+                let v1 = LibCid::new_v1(DAG_PB_CODEC, *self.decoded().hash());
+                Cid::V1(CidV1(v1.to_string()))
+            }
+            Cid::V1(_) => self.clone(),
+        }
+    }
+
+    /// The CIDv0 form of this CID. Only dag-pb CIDs hashed with sha2-256 have
+    /// one; CIDv0s are returned unchanged.
+    pub fn to_v0(&self) -> Result<Cid, CidError> {
+        match self {
+            Cid::V0(_) => Ok(self.clone()),
+            Cid::V1(_) => {
+                let decoded = self.decoded();
+                if decoded.codec() != DAG_PB_CODEC || decoded.hash().code() != SHA2_256_CODE {
+                    return Err(CidError::NoV0Form(self.to_string()));
+                }
+                let v0 = LibCid::new_v0(*decoded.hash())
+                    .map_err(|_| CidError::NoV0Form(self.to_string()))?;
+                Ok(Cid::V0(CidV0(v0.to_string())))
+            }
+        }
+    }
+
+    fn decoded(&self) -> LibCid {
+        decode(self.as_str()).expect("CIDs are validated on construction")
+    }
 }
 
 impl TryFrom<String> for Cid {
@@ -146,7 +248,7 @@ impl TryFrom<String> for Cid {
 
         // CIDv0: starts with "Qm" and is 46 characters long
         if value.starts_with("Qm") && value.len() == 46 {
-            return Ok(Cid::V0(CidV0(value)));
+            return CidV0::new(value).map(Cid::V0);
         }
 
         // CIDv1: multibase-encoded, typically starts with 'b' (base32) or 'z' (base58btc)
@@ -160,7 +262,7 @@ impl TryFrom<String> for Cid {
                 first_char,
                 'b' | 'B' | 'z' | 'f' | 'F' | 'm' | 'M' | 'u' | 'U'
             ) {
-                return Ok(Cid::V1(CidV1(value)));
+                return CidV1::new(value).map(Cid::V1);
             }
         }
 
@@ -260,21 +362,21 @@ mod tests {
     #[test]
     fn test_cidv1_new() {
         let cid_str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string();
-        let cid = CidV1::new(cid_str.clone());
+        let cid = CidV1::new(cid_str.clone()).unwrap();
         assert_eq!(cid.as_str(), cid_str);
     }
 
     #[test]
-    fn test_cidv1_from_string() {
+    fn test_cidv1_try_from_string() {
         let cid_str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string();
-        let cid = CidV1::from(cid_str.clone());
+        let cid = CidV1::try_from(cid_str.clone()).unwrap();
         assert_eq!(cid.as_str(), cid_str);
     }
 
     #[test]
     fn test_cidv1_display() {
         let cid_str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
-        let cid = CidV1::new(cid_str.to_string());
+        let cid = CidV1::new(cid_str.to_string()).unwrap();
         assert_eq!(format!("{}", cid), cid_str);
     }
 
@@ -290,7 +392,7 @@ mod tests {
     #[test]
     fn test_cid_from_cidv1() {
         let cid_str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
-        let cidv1 = CidV1::new(cid_str.to_string());
+        let cidv1 = CidV1::new(cid_str.to_string()).unwrap();
         let cid = Cid::from(cidv1);
         assert!(cid.is_v1());
         assert_eq!(cid.as_str(), cid_str);
@@ -358,7 +460,7 @@ mod tests {
     #[test]
     fn test_cidv1_serde() {
         let cid_str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
-        let cid = CidV1::new(cid_str.to_string());
+        let cid = CidV1::new(cid_str.to_string()).unwrap();
 
         let json = serde_json::to_string(&cid).unwrap();
         assert_eq!(json, format!("\"{}\"", cid_str));
@@ -405,7 +507,68 @@ mod tests {
     #[test]
     fn test_cidv1_into_inner() {
         let cid_str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string();
-        let cid = CidV1::new(cid_str.clone());
+        let cid = CidV1::new(cid_str.clone()).unwrap();
         assert_eq!(cid.into_inner(), cid_str);
     }
+
+    #[test]
+    fn test_cid_rejects_lookalikes() {
+        // Right shape, but 'l' is not in the base58 alphabet.
+        assert!(matches!(
+            Cid::try_from("QmFile1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            Err(CidError::Malformed(_))
+        ));
+        assert!(Cid::try_from("bafybeigfolderv2cidexampleexampleexampleexampleexa").is_err());
+        // Trailing bytes after the multihash.
+        assert!(matches!(
+            Cid::try_from("bafybeiewrs2w4yqe6dgevi4ha77o3dtgrpvxhnsbz6a5c4gsozzrgxjppuaa"),
+            Err(CidError::InvalidFormat)
+        ));
+        // A CIDv0 multihash in base58btc multibase is not a CIDv1.
+        assert!(CidV1::new("zQmYULJoNGPDmoRq4WNWTDTUvJGJv1hosox8H6vVd1kCsY8".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_cid_codec_and_hash_algorithm() {
+        let v0 = Cid::try_from("QmYULJoNGPDmoRq4WNWTDTUvJGJv1hosox8H6vVd1kCsY8").unwrap();
+        assert_eq!(v0.codec(), 0x70);
+        assert_eq!(v0.hash_algorithm(), 0x12);
+
+        let raw =
+            Cid::try_from("bafkreiewrs2w4yqe6dgevi4ha77o3dtgrpvxhnsbz6a5c4gsozzrgxjppu").unwrap();
+        assert_eq!(raw.codec(), 0x55);
+        assert_eq!(raw.hash_algorithm(), 0x12);
+    }
+
+    #[test]
+    fn test_cid_version_conversions() {
+        let v0 = Cid::try_from("QmYULJoNGPDmoRq4WNWTDTUvJGJv1hosox8H6vVd1kCsY8").unwrap();
+        let v1 = v0.to_v1();
+        assert_eq!(
+            v1.as_str(),
+            "bafybeiewrs2w4yqe6dgevi4ha77o3dtgrpvxhnsbz6a5c4gsozzrgxjppu"
+        );
+        assert_eq!(v1.to_v0().unwrap(), v0);
+        assert_eq!(v0.to_v0().unwrap(), v0);
+        assert_eq!(v1.to_v1(), v1);
+
+        let v1 =
+            Cid::try_from("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi").unwrap();
+        assert_eq!(
+            v1.to_v0().unwrap().as_str(),
+            "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR"
+        );
+
+        let raw =
+            Cid::try_from("bafkreiewrs2w4yqe6dgevi4ha77o3dtgrpvxhnsbz6a5c4gsozzrgxjppu").unwrap();
+        assert!(matches!(raw.to_v0(), Err(CidError::NoV0Form(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cidv0_serde_rejects_invalid() {
+        let result: Result<CidV0, _> =
+            serde_json::from_str("\"QmFile1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"");
+        assert!(result.is_err());
+    }
 }
@@ -3,7 +3,7 @@
 //! This crate is the single source of truth for client-side content
 //! addressing in the Aleph Rust workspace:
 //!
-//! - [`cid`]: the string-validated [`cid::Cid`] type (CIDv0/CIDv1), with
+//! - [`cid`]: the decoded and validated [`cid::Cid`] type (CIDv0/CIDv1), with
 //!   serde support behind the `serde` feature.
 //! - [`verify`]: streaming CID hashers ([`verify::Hasher`]) for IPFS
 //!   CIDv0/CIDv1 (UnixFS dag-pb, 256 KiB chunks, raw leaves), plus
@@ -138,10 +138,7 @@ impl Hasher {
             return Ok(Self::for_ipfs());
         }
 
-        let parsed = LibCid::try_from(expected.as_str())
-            .map_err(|e| UnsupportedCid(format!("{expected}: {e}")))?;
-
-        match parsed.codec() {
+        match expected.codec() {
             RAW_CODEC => Ok(Self::CidRaw {
                 hasher: Sha256::new(),
             }),
@@ -18,6 +18,23 @@ pub fn generate_evm_key(chain: Chain) -> Result<(Zeroizing<String>, String)> {
     Ok((key_hex, address))
 }
 
+/// Generate a random BIP39 phrase of `word_count` words and derive the first
+/// MetaMask account from it. Returns (hex_key, address, phrase).
+pub fn generate_evm_mnemonic_key(
+    chain: Chain,
+    word_count: usize,
+) -> Result<(Zeroizing<String>, String, Zeroizing<String>)> {
+    use aleph_types::account::{DEFAULT_EVM_DERIVATION_PATH, EvmAccount, generate_mnemonic};
+
+    let phrase = Zeroizing::new(generate_mnemonic(word_count).map_err(|e| anyhow::anyhow!(e))?);
+    let account = EvmAccount::from_mnemonic(chain, &phrase, DEFAULT_EVM_DERIVATION_PATH)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let key_hex = Zeroizing::new(hex::encode(account.signing_key().to_bytes()));
+    let address = account.address().to_string();
+
+    Ok((key_hex, address, phrase))
+}
+
 /// Generate a random Ed25519 private key and return (hex_key, address).
 pub fn generate_sol_key(chain: Chain) -> Result<(Zeroizing<String>, String)> {
     use ed25519_dalek::SigningKey;
@@ -64,6 +81,20 @@ mod tests {
         assert_ne!(k1, k2);
     }
 
+    #[test]
+    fn generate_evm_mnemonic_key_is_recoverable() {
+        let (key_hex, address, phrase) = generate_evm_mnemonic_key(Chain::Ethereum, 24).unwrap();
+        assert_eq!(phrase.split(' ').count(), 24);
+        let recovered = aleph_types::account::EvmAccount::from_mnemonic(
+            Chain::Ethereum,
+            &phrase,
+            aleph_types::account::DEFAULT_EVM_DERIVATION_PATH,
+        )
+        .unwrap();
+        assert_eq!(recovered.address().as_str(), address);
+        assert_eq!(*key_hex, hex::encode(recovered.signing_key().to_bytes()));
+    }
+
     #[test]
     fn generate_sol_key_produces_valid_address() {
         let (key_hex, address) = generate_sol_key(Chain::Sol).unwrap();
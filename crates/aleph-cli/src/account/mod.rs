@@ -121,7 +121,8 @@ impl Account for CliAccount {
 /// The private key is read from `private_key` if provided, otherwise
 /// from the `ALEPH_PRIVATE_KEY` environment variable.
 ///
-/// The hex string may optionally have a `0x` prefix.
+/// The hex string may optionally have a `0x` prefix. Keys of SVM chains may
+/// also be base58-encoded.
 pub fn load_account(private_key: Option<&str>, chain: Chain) -> Result<CliAccount> {
     let key_hex = Zeroizing::new(match private_key {
         Some(k) => k.to_string(),
@@ -129,8 +130,7 @@ pub fn load_account(private_key: Option<&str>, chain: Chain) -> Result<CliAccoun
             .context("no private key provided; use --private-key or set ALEPH_PRIVATE_KEY")?,
     });
 
-    let key_hex = key_hex.strip_prefix("0x").unwrap_or(&key_hex);
-    let key_bytes = Zeroizing::new(hex::decode(key_hex).context("invalid hex in private key")?);
+    let key_bytes = decode_private_key(&key_hex, &chain)?;
 
     if chain.is_evm() {
         let account = EvmAccount::new(chain, &key_bytes).map_err(|e| anyhow::anyhow!(e))?;
@@ -143,6 +143,21 @@ pub fn load_account(private_key: Option<&str>, chain: Chain) -> Result<CliAccoun
     }
 }
 
+/// Decode a private key given as hex, with an optional `0x` prefix. Keys of
+/// SVM chains may also be base58-encoded, as Solana wallets export them.
+pub fn decode_private_key(key: &str, chain: &Chain) -> Result<Zeroizing<Vec<u8>>> {
+    let key = key.strip_prefix("0x").unwrap_or(key);
+    if chain.is_svm() && hex::decode(key).is_err() {
+        let bytes = bs58::decode(key)
+            .into_vec()
+            .context("private key is neither hex nor base58")?;
+        return Ok(Zeroizing::new(bytes));
+    }
+    Ok(Zeroizing::new(
+        hex::decode(key).context("invalid hex in private key")?,
+    ))
+}
+
 /// Load a named account from the account store.
 ///
 /// Retrieves the private key from the OS keychain and constructs the
@@ -249,6 +264,19 @@ mod tests {
         assert!(err.to_string().contains("invalid hex"));
     }
 
+    #[test]
+    fn decode_private_key_accepts_base58_for_svm_only() {
+        let key = [7u8; 32];
+        let b58_key = bs58::encode(key).into_string();
+        assert_eq!(
+            *decode_private_key(&format!("0x{}", hex::encode(key)), &Chain::Ethereum).unwrap(),
+            key
+        );
+        assert_eq!(*decode_private_key(&b58_key, &Chain::Sol).unwrap(), key);
+        let err = decode_private_key(&b58_key, &Chain::Ethereum).unwrap_err();
+        assert!(err.to_string().contains("invalid hex"));
+    }
+
     #[test]
     fn load_account_wrong_key_length() {
         let err = load_account(Some("abcd"), Chain::Ethereum).unwrap_err();
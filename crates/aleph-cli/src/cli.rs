@@ -1,7 +1,8 @@
 use aleph_sdk::aggregate_models::corechannel::NodeHash;
 use aleph_sdk::credit::PriceSource;
-use aleph_types::chain::Address;
+use aleph_types::chain::{Address, Chain};
 use aleph_types::item_hash::ItemHash;
+use aleph_types::memory_size::{MemorySize, MemorySizeError};
 use aleph_types::message::execution::environment::GpuProperties;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
@@ -21,10 +22,28 @@ fn parse_price_source(s: &str) -> Result<PriceSource, String> {
     s.parse::<PriceSource>().map_err(|e| e.to_string())
 }
 
+/// Clap adapter for [`Chain::from_str`], accepting chain codes such as `ETH`
+/// or `sol`.
+fn parse_chain(s: &str) -> Result<Chain, String> {
+    s.parse::<Chain>().map_err(|e| e.to_string())
+}
+
+/// Clap adapter accepting the BIP39 word counts.
+fn parse_mnemonic_words(s: &str) -> Result<usize, String> {
+    let words = s.parse::<usize>().map_err(|e| e.to_string())?;
+    if !aleph_types::account::MNEMONIC_WORD_COUNTS.contains(&words) {
+        return Err(format!(
+            "expected one of {:?}",
+            aleph_types::account::MNEMONIC_WORD_COUNTS
+        ));
+    }
+    Ok(words)
+}
+
 /// Parse a human-readable size string into MiB.
 ///
-/// Supports binary units (MiB, GiB, TiB) and decimal units (MB, GB, TB).
-/// Bare numbers without units are rejected.
+/// Accepts the units of [`MemorySize`], rounded to the nearest MiB; sizes
+/// rounding to 0 MiB and bare numbers without units are rejected.
 ///
 /// ```text
 /// parse_size_to_mib("2GiB")  -> Ok(2048)
@@ -33,40 +52,10 @@ fn parse_price_source(s: &str) -> Result<PriceSource, String> {
 /// parse_size_to_mib("1024")  -> Err (no unit)
 /// ```
 pub fn parse_size_to_mib(s: &str) -> Result<u64, String> {
-    let s = s.trim();
-    // Find where the numeric part ends and the unit begins
-    let unit_start = s
-        .find(|c: char| c.is_alphabetic())
-        .ok_or_else(|| format!("missing unit in size '{s}' (use e.g. 20GB, 1024MB, 1TiB)"))?;
-    let (num_str, unit) = s.split_at(unit_start);
-    let value: f64 = num_str
-        .trim()
-        .parse()
-        .map_err(|_| format!("invalid number in size '{s}'"))?;
-    if value < 0.0 {
-        return Err(format!("size cannot be negative: '{s}'"));
-    }
-
-    // Convert to MiB
-    let mib = match unit.to_lowercase().as_str() {
-        // Binary units (1024-based)
-        "mib" => value,
-        "gib" => value * 1024.0,
-        "tib" => value * 1024.0 * 1024.0,
-        // Decimal units (1000-based), converted to MiB
-        "mb" => value * 1_000_000.0 / 1_048_576.0,
-        "gb" => value * 1_000_000_000.0 / 1_048_576.0,
-        "tb" => value * 1_000_000_000_000.0 / 1_048_576.0,
-        _ => {
-            return Err(format!(
-                "unknown size unit '{unit}' (use MB, GB, TB, MiB, GiB, TiB)"
-            ));
-        }
-    };
-
-    let mib_rounded = mib.round() as u64;
+    let size: MemorySize = s.parse().map_err(|e: MemorySizeError| e.to_string())?;
+    let mib_rounded = (size.bytes() as f64 / (1u64 << 20) as f64).round() as u64;
     if mib_rounded == 0 {
-        return Err(format!("size too small: '{s}' rounds to 0 MiB"));
+        return Err(format!("size too small: '{}' rounds to 0 MiB", s.trim()));
     }
     Ok(mib_rounded)
 }
@@ -109,17 +98,28 @@ const LONG_VERSION: &str = concat!(
 #[command(name = "aleph", version, long_version = LONG_VERSION, about = "Aleph CLI")]
 pub struct Cli {
     /// CCN to talk to: either a config alias name (see `aleph config ccn list`) or a raw URL (anything containing `://`).
-    #[arg(long)]
+    #[arg(long, visible_alias = "node-url", env = "ALEPH_API_URL")]
     pub ccn: Option<String>,
 
-    /// Output results as JSON (for scripting/tooling).
+    /// Output results as JSON (for scripting/tooling). Same as `--output json`.
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// Output format, taking precedence over --json. Defaults to tables on a
+    /// terminal and JSON when the output is piped; `aleph config defaults
+    /// --default-output` changes the default.
+    #[arg(long, global = true, value_enum)]
+    pub output: Option<crate::config::store::OutputFormat>,
+
     /// Named network from config (see: aleph config network list).
     #[arg(long)]
     pub network: Option<String>,
 
+    /// Log filter for the SDK, written to stderr: a level such as `debug`,
+    /// or directives such as `aleph_sdk=trace`. Logging is off by default.
+    #[arg(long, global = true, env = "ALEPH_LOG")]
+    pub log: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -239,6 +239,19 @@ Forget is irreversible. You can only forget messages your own address owns \
     Forget(ForgetArgs),
     /// Get a message by its item hash
     Get(GetMessageArgs),
+    /// Show the lineage of a message: amends, confirmations and forgets
+    #[command(long_about = "\
+Show everything that happened to a message, oldest first: its publication, \
+the on-chain confirmations of each revision, the amends of a post and the \
+FORGET messages that removed it.
+
+For a POST, either the original post or one of its amends can be given: the \
+whole amend chain is shown in both cases.
+
+Examples:
+  aleph message history abc123...
+  aleph message history abc123... --json")]
+    History(MessageHistoryArgs),
     // Boxing because of a large enum variant.
     /// List messages (with filters)
     List(Box<MessageListArgs>),
@@ -246,6 +259,22 @@ Forget is irreversible. You can only forget messages your own address owns \
     Retry(RetryArgs),
     /// Sync messages from one node to another
     Sync(Box<SyncArgs>),
+    /// Stream new messages matching the filters as they arrive
+    #[command(long_about = "\
+Stream the messages matching the filters as the CCN receives them, over its \
+websocket. Each message is printed on one line; with --json, as NDJSON (one \
+JSON message per line).
+
+The connection is re-established automatically when it drops, and the \
+messages sent in the meantime are fetched from the REST API so that none is \
+missed. Pass --no-backfill to skip that, or --no-reconnect to stop on the \
+first connection error instead.
+
+Examples:
+  aleph message watch --message-type post --channels TEST
+  aleph message watch --addresses 0x... --json | jq .item_hash
+  aleph message watch --message-type store --count 10")]
+    Watch(Box<MessageWatchArgs>),
 }
 
 #[derive(Args)]
@@ -277,6 +306,30 @@ pub struct GetMessageArgs {
     pub item_hash: ItemHash,
 }
 
+#[derive(Args)]
+pub struct MessageHistoryArgs {
+    /// The item hash of the message, or of one of its amends for a POST.
+    pub item_hash: ItemHash,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MessageWatchArgs {
+    /// Stop after this many messages.
+    #[arg(long)]
+    pub count: Option<u64>,
+
+    /// Do not fetch the messages missed while the websocket was reconnecting.
+    #[arg(long)]
+    pub no_backfill: bool,
+
+    /// Stop on the first connection error instead of reconnecting.
+    #[arg(long)]
+    pub no_reconnect: bool,
+
+    #[command(flatten)]
+    pub filter: MessageFilterCli,
+}
+
 #[derive(Args)]
 pub struct RetryArgs {
     /// The item hash of the rejected message to re-submit.
@@ -310,19 +363,11 @@ pub struct PostListArgs {
 use aleph_sdk::client::{MessageFilter, PostFilter, SortBy, SortOrder};
 use aleph_types::message::{MessageStatus, MessageType};
 use aleph_types::timestamp::Timestamp;
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
-use std::str::FromStr;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 
+/// Clap adapter for the `FromStr` impl of [`Timestamp`]: RFC3339 or unix seconds.
 fn parse_timestamp(s: &str) -> Result<Timestamp, String> {
-    // Try unix seconds (int/float)
-    if let Ok(timestamp) = s.parse::<f64>() {
-        return Ok(Timestamp::from(timestamp));
-    }
-    // Fallback: deserialize as RFC3339
-    let timestamp = DateTime::<FixedOffset>::from_str(s)
-        .map_err(|e| e.to_string())?
-        .timestamp();
-    Ok(Timestamp::from(timestamp as f64))
+    s.parse::<Timestamp>().map_err(|e| e.to_string())
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -436,6 +481,72 @@ mod tests {
         assert_eq!(FrameworkCli::Nextjs.to_string(), "nextjs");
         assert_eq!(FrameworkCli::Other.to_string(), "other");
     }
+
+    #[test]
+    fn output_is_accepted_anywhere() {
+        use crate::config::store::OutputFormat;
+
+        let cli = Cli::try_parse_from(["aleph", "--output", "yaml", "message", "list"]).unwrap();
+        assert_eq!(cli.output, Some(OutputFormat::Yaml));
+        // The flag is global: it is also accepted after the subcommand.
+        let cli = Cli::try_parse_from(["aleph", "message", "list", "--output", "ndjson"]).unwrap();
+        assert_eq!(cli.output, Some(OutputFormat::Ndjson));
+        let cli =
+            Cli::try_parse_from(["aleph", "config", "defaults", "--default-output", "ndjson"])
+                .unwrap();
+        assert_eq!(cli.output, None);
+    }
+
+    #[test]
+    fn instance_logs_accepts_no_follow() {
+        let cli = Cli::try_parse_from(["aleph", "instance", "logs", "a41fb91c3e68", "--no-follow"])
+            .unwrap();
+        let Commands::Instance {
+            command: InstanceCommand::Logs(args),
+        } = cli.command
+        else {
+            panic!("expected instance logs");
+        };
+        assert!(args.no_follow);
+        assert_eq!(args.target.vm_id, "a41fb91c3e68");
+    }
+
+    #[test]
+    fn account_create_mnemonic_words() {
+        let cli = Cli::try_parse_from([
+            "aleph",
+            "account",
+            "create",
+            "alice",
+            "--mnemonic",
+            "--words",
+            "24",
+        ])
+        .unwrap();
+        let Commands::Account {
+            command: AccountCommand::Create(args),
+        } = cli.command
+        else {
+            panic!("expected account create");
+        };
+        assert!(args.mnemonic);
+        assert_eq!(args.words, 24);
+
+        for invalid in [
+            &[
+                "aleph",
+                "account",
+                "create",
+                "alice",
+                "--mnemonic",
+                "--words",
+                "13",
+            ][..],
+            &["aleph", "account", "create", "alice", "--words", "24"],
+        ] {
+            assert!(Cli::try_parse_from(invalid).is_err());
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -473,7 +584,6 @@ pub enum ChainCli {
 
 impl From<ChainCli> for aleph_types::chain::Chain {
     fn from(v: ChainCli) -> Self {
-        use aleph_types::chain::Chain;
         match v {
             ChainCli::Arb => Chain::Arbitrum,
             ChainCli::Aurora => Chain::Aurora,
@@ -725,7 +835,7 @@ pub struct PostCreateArgs {
     #[arg(long = "type")]
     pub post_type: String,
 
-    /// JSON content. If absent, reads from stdin.
+    /// JSON content, `@path` to read it from a file, or `-` for stdin (the default).
     #[arg(long)]
     pub content: Option<String>,
 
@@ -747,7 +857,7 @@ pub struct PostAmendArgs {
     #[arg(long = "ref")]
     pub reference: ItemHash,
 
-    /// JSON content. If absent, reads from stdin.
+    /// JSON content, `@path` to read it from a file, or `-` for stdin (the default).
     #[arg(long)]
     pub content: Option<String>,
 
@@ -832,7 +942,8 @@ pub struct AggregateCreateArgs {
     #[arg(long)]
     pub key: String,
 
-    /// JSON content (object, array, or scalar). If absent, reads from stdin.
+    /// JSON content (object, array, or scalar), `@path` to read it from a file,
+    /// or `-` for stdin (the default).
     #[arg(long)]
     pub content: Option<String>,
 
@@ -965,6 +1076,8 @@ pub enum NodeCommand {
     List(NodeListArgs),
     /// Stake ALEPH tokens on a node
     Stake(StakeArgs),
+    /// Show the version, sync status and multiaddresses of the CCN in use
+    Status,
     /// Unlink a CRN from your CCN
     Unlink(UnlinkCrnArgs),
     /// Remove your stake from a node
@@ -983,9 +1096,26 @@ pub struct NodeListArgs {
     pub all: bool,
 
     /// Filter by node type.
-    #[arg(long, value_enum, rename_all = "lowercase")]
+    #[arg(long, value_enum, rename_all = "lowercase", conflicts_with_all = ["ccn", "crn"])]
     pub r#type: Option<NodeTypeCli>,
 
+    /// Only list CCNs. Shorthand for `--type ccn`.
+    #[arg(long, conflicts_with_all = ["crn", "gpu"])]
+    pub ccn: bool,
+
+    /// Only list CRNs. Shorthand for `--type crn`.
+    #[arg(long)]
+    pub crn: bool,
+
+    /// Only list nodes whose score is at least this value (between 0 and 1).
+    #[arg(long)]
+    pub score_min: Option<f64>,
+
+    /// Only list CRNs advertising GPU support. The corechannel aggregate does
+    /// not describe node hardware, so this also fetches the CRN list.
+    #[arg(long)]
+    pub gpu: bool,
+
     /// Address of the corechannel aggregate owner. Defaults to the mainnet address.
     #[arg(long)]
     pub corechannel_address: Option<String>,
@@ -1214,6 +1344,10 @@ With --encrypted, the key is instead stored on disk as a password-protected \
 Ethereum keystore V3 file (EVM chains only). The password is asked once per \
 command at signing time; set ALEPH_PASSWORD for non-interactive use.
 
+With --mnemonic, the key is derived from a new BIP39 seed phrase, printed \
+once, along the first MetaMask path (m/44'/60'/0'/0/0). Write it down: it \
+recovers the account in any BIP39 wallet (EVM chains only).
+
 To import an existing key (private-key, keystore file, or Ledger), use \
 `aleph account import` instead.
 
@@ -1221,6 +1355,7 @@ Examples:
   aleph account create alice                    # EVM (default chain: eth)
   aleph account create alice --chain sol        # Solana
   aleph account create alice --encrypted        # password-protected keystore
+  aleph account create alice --mnemonic --words 24
   aleph account use alice                       # set as default for signing")]
     Create(AccountCreateArgs),
     /// Remove an account and its stored key material
@@ -1232,9 +1367,10 @@ Examples:
 Import an existing key into the OS keychain under the given name. Three \
 sources are supported, mutually exclusive:
 
-  --private-key <HEX>   Hex-encoded private key on the command line. If \
-omitted (and no other source given), the key is read from stdin so it does \
-not appear in shell history.
+  --private-key <HEX>   Hex-encoded private key on the command line (base58 \
+is accepted too for Solana keys, as exported by Phantom). If omitted (and no \
+other source given), the key is read from stdin so it does not appear in \
+shell history.
 
   --from-file <PATH>    Read from a file containing a raw 32-byte binary \
 key, a hex-encoded text key, or an Ethereum keystore V3 file (detected \
@@ -1300,6 +1436,15 @@ pub struct AccountCreateArgs {
     /// The key is stored as an Ethereum keystore V3 file (EVM chains only).
     #[arg(long)]
     pub encrypted: bool,
+
+    /// Derive the key from a new BIP39 seed phrase and print the phrase
+    /// (EVM chains only).
+    #[arg(long)]
+    pub mnemonic: bool,
+
+    /// Number of words of the seed phrase (only with --mnemonic).
+    #[arg(long, requires = "mnemonic", default_value_t = 12, value_parser = parse_mnemonic_words)]
+    pub words: usize,
 }
 
 #[derive(Args)]
@@ -1312,7 +1457,8 @@ pub struct AccountImportArgs {
     #[arg(long, value_enum, default_value = "eth")]
     pub chain: ChainCli,
 
-    /// Hex-encoded private key. If not provided, reads from stdin.
+    /// Hex-encoded private key (or base58 for SVM chains). If not provided,
+    /// reads from stdin.
     #[arg(long, conflicts_with = "ledger")]
     pub private_key: Option<String>,
 
@@ -1400,6 +1546,12 @@ pub struct AccountExportArgs {
     /// Skip confirmation prompt.
     #[arg(long)]
     pub yes: bool,
+
+    /// Write an Ethereum keystore V3 file to PATH instead of printing the
+    /// raw key. Encrypted accounts are copied as-is; local keys are
+    /// encrypted with a new password (EVM chains only).
+    #[arg(long, value_name = "PATH")]
+    pub keystore: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -1575,6 +1727,10 @@ pub struct FileUploadArgs {
     #[arg(long, value_enum)]
     pub storage_engine: Option<StorageEngineCli>,
 
+    /// Shorthand for `--storage-engine ipfs`.
+    #[arg(long, conflicts_with = "storage_engine")]
+    pub ipfs: bool,
+
     /// Payment type for the STORE message. `credit` (default) consumes
     /// credits; `hold` requires locked stake on the account.
     #[arg(long, value_enum)]
@@ -1657,8 +1813,8 @@ pub struct FileDownloadArgs {
     pub owner: Option<String>,
 
     /// Output file path. Defaults to `./<file_hash>` in the current directory.
-    #[arg(short, long)]
-    pub output: Option<std::path::PathBuf>,
+    #[arg(short = 'o', long)]
+    pub output_file: Option<std::path::PathBuf>,
 
     /// Write file contents to stdout instead of saving to a file.
     #[arg(long)]
@@ -1780,7 +1936,7 @@ Examples:
     /// account's address is used.
     List(InstanceListArgs),
     /// Stream logs from a running VM instance
-    Logs(CrnArgs),
+    Logs(InstanceLogsArgs),
     /// Manage TCP/UDP port forwards for VMs, programs, or IPFS websites.
     #[command(visible_alias = "pfw")]
     PortForward {
@@ -1850,6 +2006,7 @@ Examples:
   aleph instance show a41fb91c3e68
   aleph instance show a41fb91c3e68 --verbose
   aleph instance show a41fb91c3e68 --json")]
+    #[command(visible_alias = "info")]
     Show(InstanceShowArgs),
     /// SSH into a dispatched VM instance
     #[command(long_about = "\
@@ -1998,6 +2155,11 @@ pub struct InstanceCreateArgs {
     #[arg(long)]
     pub ssh_key: Vec<String>,
 
+    /// Payment type for the INSTANCE message. `credit` (default) consumes
+    /// credits; `hold` requires locked stake on the account.
+    #[arg(long, value_enum)]
+    pub payment_type: Option<PaymentTypeCli>,
+
     /// Channel name.
     #[arg(long)]
     pub channel: Option<String>,
@@ -2170,6 +2332,16 @@ pub struct CrnArgs {
     pub signing: SigningArgs,
 }
 
+#[derive(Args)]
+pub struct InstanceLogsArgs {
+    #[command(flatten)]
+    pub target: CrnArgs,
+
+    /// Print the logs written so far and exit instead of following them.
+    #[arg(long)]
+    pub no_follow: bool,
+}
+
 /// Start is separate because it's unauthenticated: signing args are still
 /// required to construct the CrnClient but no auth headers are sent.
 #[derive(Args)]
@@ -2278,8 +2450,8 @@ pub struct InstanceBackupDownloadArgs {
     /// Either a VM item hash (or prefix), or a presigned backup URL.
     pub vm_id_or_url: String,
     /// Output path. Defaults to ./backup-<vm_id_short>.tar.
-    #[arg(short, long)]
-    pub output: Option<std::path::PathBuf>,
+    #[arg(short = 'o', long)]
+    pub output_file: Option<std::path::PathBuf>,
     /// CRN to target: a node hash or unique hash prefix or suffix (resolved via
     /// the scheduler) or a raw URL (ignored when the positional arg is already a
     /// presigned URL).
@@ -2377,6 +2549,33 @@ pub enum ConfigCommand {
         #[clap(subcommand)]
         command: NetworkCommand,
     },
+    /// Show or change the defaults applied when a flag is omitted
+    #[command(long_about = "\
+Show or change the defaults applied when a flag is omitted. Without flags, \
+prints every default: network and CCN (`aleph config network use`, `aleph \
+config ccn use`), account (`aleph account use`), message channel and output \
+format.
+
+Examples:
+  aleph config defaults
+  aleph config defaults --channel MY-APP --default-output json
+  aleph config defaults --unset-channel")]
+    Defaults(ConfigDefaultsArgs),
+}
+
+#[derive(Args)]
+pub struct ConfigDefaultsArgs {
+    /// Channel of the messages sent without --channel.
+    #[arg(long, conflicts_with = "unset_channel")]
+    pub channel: Option<String>,
+
+    /// Stop applying a default channel.
+    #[arg(long)]
+    pub unset_channel: bool,
+
+    /// Output format of the commands run without --output or --json.
+    #[arg(long, value_enum)]
+    pub default_output: Option<crate::config::store::OutputFormat>,
 }
 
 #[derive(Subcommand)]
@@ -2959,6 +3158,8 @@ pub enum ProgramCommand {
     /// Show full information about a single program (creation time,
     /// ownership, per-ref freshness for code / runtime / data / volumes).
     Show(ProgramShowArgs),
+    /// Send an HTTP request to a deployed program and print its answer.
+    Run(ProgramRunArgs),
 }
 
 #[derive(Args)]
@@ -3098,6 +3299,28 @@ pub struct ProgramShowArgs {
     pub item_hash: ItemHash,
 }
 
+#[derive(Args)]
+pub struct ProgramRunArgs {
+    /// Item hash of the program to call.
+    pub item_hash: ItemHash,
+    /// Path to request, relative to the root of the program. May carry a
+    /// query string (e.g. `/items?page=2`).
+    #[arg(default_value = "/")]
+    pub path: String,
+    /// HTTP method.
+    #[arg(short = 'X', long, default_value = "GET")]
+    pub method: String,
+    /// Request header as `Name: value` (repeatable).
+    #[arg(short = 'H', long = "header")]
+    pub headers: Vec<String>,
+    /// Request body.
+    #[arg(short, long)]
+    pub data: Option<String>,
+    /// Call the program on this CRN instead of through the aleph.sh gateway.
+    #[arg(long)]
+    pub crn: Option<Url>,
+}
+
 #[cfg(test)]
 mod credit_transfer_args_tests {
     use super::*;
@@ -3146,6 +3369,60 @@ mod credit_transfer_args_tests {
         assert_value_validation_err(&["aleph", "post", "list", "--hashes", "not-a-hash"]);
     }
 
+    #[test]
+    fn message_history_takes_an_item_hash() {
+        let hash = "0000000000000000000000000000000000000000000000000000000000000001";
+        let cli = Cli::try_parse_from(["aleph", "message", "history", hash]).expect("clap parse");
+        match cli.command {
+            Commands::Message {
+                command: MessageCommand::History(args),
+            } => assert_eq!(args.item_hash.to_string(), hash),
+            _ => panic!("expected message history"),
+        }
+        assert!(Cli::try_parse_from(["aleph", "message", "history", "not-a-hash"]).is_err());
+    }
+
+    #[test]
+    fn node_url_is_an_alias_of_ccn() {
+        let cli = Cli::try_parse_from([
+            "aleph",
+            "--node-url",
+            "http://localhost:4024",
+            "node",
+            "status",
+        ])
+        .expect("clap parse");
+        assert_eq!(cli.ccn.as_deref(), Some("http://localhost:4024"));
+    }
+
+    #[test]
+    fn message_watch_takes_filters_and_options() {
+        let cli = Cli::try_parse_from([
+            "aleph",
+            "message",
+            "watch",
+            "--message-type",
+            "post",
+            "--channels",
+            "TEST",
+            "--count",
+            "3",
+            "--no-backfill",
+        ])
+        .expect("clap parse");
+        match cli.command {
+            Commands::Message {
+                command: MessageCommand::Watch(args),
+            } => {
+                assert_eq!(args.count, Some(3));
+                assert!(args.no_backfill);
+                assert!(!args.no_reconnect);
+                assert_eq!(args.filter.channels.unwrap(), ["TEST"]);
+            }
+            _ => panic!("expected message watch"),
+        }
+    }
+
     /// Hex addresses bypass the account store, so this test does not need any
     /// store fixture. It pins the contract: `--addresses` / `--owners` accept
     /// hex strings without touching disk.
@@ -3666,8 +3943,8 @@ pub struct WebsiteDeployArgs {
     #[arg(long)]
     pub tag: Vec<String>,
     /// Override payment chain (defaults to signing account's chain).
-    #[arg(long)]
-    pub payment_chain: Option<String>,
+    #[arg(long, value_parser = parse_chain)]
+    pub payment_chain: Option<Chain>,
     /// Payment type for the STORE message and the websites aggregate entry.
     /// `credit` (default) consumes credits; `hold` requires locked stake.
     #[arg(long, value_enum)]
@@ -3913,14 +4190,35 @@ pub struct PortForwardRefreshArgs {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum ConfidentialCommand {
     /// Initialize a confidential session (fetch cert, verify chain, derive session keys, post to CRN).
     InitSession(ConfidentialInitSessionArgs),
     /// Validate the VM launch measurement and inject the disk-decryption secret.
     Start(ConfidentialStartArgs),
     /// All-in-one: allocate, init session, then start an existing confidential VM.
-    #[command(alias = "create")]
     Launch(ConfidentialLaunchArgs),
+    /// Guided end-to-end flow: create a confidential instance on an SEV-capable
+    /// CRN, then allocate it, verify its attestation and inject the secret.
+    #[command(long_about = "\
+Create and boot a confidential instance in one go.
+
+The root filesystem must be an encrypted image: the secret injected at boot \
+is what unlocks it, and the CRN never sees it in clear. The command then:
+
+  1. picks the best-scored SEV-capable CRN (unless --crn-hash is given),
+  2. publishes an INSTANCE message with a trusted execution environment,
+  3. allocates the VM and opens a launch session, after checking the CRN's \
+platform certificate chain against AMD's roots,
+  4. verifies the launch measurement against the expected firmware,
+  5. injects the disk-decryption secret.
+
+If a step after the INSTANCE message fails, resume with \
+`aleph instance confidential launch <VM_HASH>`.
+
+Examples:
+  aleph instance confidential create my-vm --image <ENCRYPTED_ROOTFS> --size 2vcpu-4gb")]
+    Create(ConfidentialCreateArgs),
 }
 
 #[derive(Args)]
@@ -3980,8 +4278,8 @@ pub struct ConfidentialStartArgs {
 
 #[derive(Args)]
 pub struct ConfidentialLaunchArgs {
-    /// Existing VM hash. If omitted, runs `instance create --confidential ...`
-    /// first to allocate a fresh VM.
+    /// Existing VM hash. To create a new VM, use `aleph instance confidential
+    /// create` instead.
     pub vm_id: Option<String>,
     /// CRN to target: a node hash (resolved via the scheduler) or a raw URL.
     /// Overrides the CRN otherwise discovered via the scheduler.
@@ -4004,7 +4302,29 @@ pub struct ConfidentialLaunchArgs {
     /// VM disk-decryption secret.
     #[arg(long)]
     pub secret: Option<String>,
-    // NB: forwarded `instance create` flags get flattened in Task 17.
+    /// Enable debug logging.
+    #[arg(long)]
+    pub debug: bool,
+}
+
+#[derive(Args)]
+pub struct ConfidentialCreateArgs {
+    #[command(flatten)]
+    pub instance: InstanceCreateArgs,
+    /// Reuse existing session files if present.
+    #[arg(long)]
+    pub keep_session: bool,
+    /// Expected OVMF firmware hash (hex). Defaults to the hash of the
+    /// network's default confidential firmware.
+    #[arg(long)]
+    pub firmware_hash: Option<String>,
+    /// Path to a local OVMF blob; computes its SHA-256 and overrides
+    /// `--firmware-hash`.
+    #[arg(long)]
+    pub firmware_file: Option<std::path::PathBuf>,
+    /// VM disk-decryption secret. Prompts interactively if absent.
+    #[arg(long)]
+    pub secret: Option<String>,
     /// Enable debug logging.
     #[arg(long)]
     pub debug: bool,
@@ -4074,13 +4394,68 @@ mod confidential_parser_tests {
     }
 
     #[test]
-    fn launch_accepts_create_alias() {
-        let cli = parse(&["aleph", "instance", "confidential", "create"]);
+    fn create_takes_instance_flags() {
+        let cli = parse(&[
+            "aleph",
+            "instance",
+            "confidential",
+            "create",
+            "my-vm",
+            "--image",
+            "ubuntu22-encrypted",
+            "--size",
+            "2vcpu-4gb",
+            "--secret",
+            "hunter2",
+        ]);
         let Commands::Instance {
-            command: InstanceCommand::Confidential(ConfidentialCommand::Launch(_)),
+            command: InstanceCommand::Confidential(ConfidentialCommand::Create(a)),
         } = cli.command
         else {
             panic!("wrong subcommand");
         };
+        assert_eq!(a.instance.name, "my-vm");
+        assert_eq!(a.instance.size.as_deref(), Some("2vcpu-4gb"));
+        assert_eq!(a.secret.as_deref(), Some("hunter2"));
+    }
+}
+
+#[cfg(test)]
+mod instance_create_args_tests {
+    use super::*;
+
+    #[test]
+    fn create_accepts_payment_type() {
+        let cli = Cli::try_parse_from([
+            "aleph",
+            "instance",
+            "create",
+            "web",
+            "--image",
+            "ubuntu22",
+            "--size",
+            "1vcpu-2gb",
+            "--payment-type",
+            "hold",
+        ])
+        .expect("clap parse");
+        match cli.command {
+            Commands::Instance {
+                command: InstanceCommand::Create(args),
+            } => assert!(matches!(args.payment_type, Some(PaymentTypeCli::Hold))),
+            _ => panic!("expected instance create"),
+        }
+    }
+
+    #[test]
+    fn info_is_an_alias_of_show() {
+        let cli =
+            Cli::try_parse_from(["aleph", "instance", "info", "a41fb91c3e68"]).expect("clap parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Instance {
+                command: InstanceCommand::Show(_),
+            }
+        ));
     }
 }
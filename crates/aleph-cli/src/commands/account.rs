@@ -1,4 +1,4 @@
-use crate::account::generate::generate_key;
+use crate::account::generate::{generate_evm_mnemonic_key, generate_key};
 use crate::account::store::{AccountKind, AccountStore};
 use crate::cli::{
     AccountBalanceArgs, AccountCommand, AccountCreateArgs, AccountExportArgs, AccountImportArgs,
@@ -8,7 +8,7 @@ use crate::cli::{
 };
 use crate::commands::message::{ForgetTargets, forget_targets};
 use crate::common::{
-    confirm_typed_match, format_address, resolve_account, resolve_address,
+    confirm_typed_match, format_address, print_structured, resolve_account, resolve_address,
     resolve_address_or_active, submit_or_preview,
 };
 use aleph_sdk::client::{AccountBalance, AlephAccountClient, AlephClient};
@@ -48,22 +48,52 @@ pub async fn handle_account_command(
 
 fn handle_create(store: &AccountStore, args: AccountCreateArgs, json: bool) -> Result<()> {
     let chain: aleph_types::chain::Chain = args.chain.into();
+    let mnemonic_words = args.mnemonic.then_some(args.words);
+    if mnemonic_words.is_some() && !chain.is_evm() {
+        bail!("--mnemonic is only supported for EVM chains");
+    }
 
     if args.encrypted {
-        return handle_create_encrypted(store, &args.name, chain, json);
+        return handle_create_encrypted(store, &args.name, chain, mnemonic_words, json);
     }
 
-    let (key_hex, address) = generate_key(chain.clone())?;
+    let (key_hex, address, phrase) = create_key(chain.clone(), mnemonic_words)?;
 
     store.add_local_account(&args.name, chain.clone(), address.clone(), &key_hex)?;
 
-    print_account_created(&args.name, &chain, &address, "local", json)
+    print_account_created(
+        &args.name,
+        &chain,
+        &address,
+        "local",
+        phrase.as_ref().map(|p| p.as_str()),
+        json,
+    )
+}
+
+/// Generates the key of a new account, derived from a fresh seed phrase of
+/// `mnemonic_words` words if set. Returns (hex_key, address, phrase).
+fn create_key(
+    chain: aleph_types::chain::Chain,
+    mnemonic_words: Option<usize>,
+) -> Result<(Zeroizing<String>, String, Option<Zeroizing<String>>)> {
+    match mnemonic_words {
+        Some(words) => {
+            let (key_hex, address, phrase) = generate_evm_mnemonic_key(chain, words)?;
+            Ok((key_hex, address, Some(phrase)))
+        }
+        None => {
+            let (key_hex, address) = generate_key(chain)?;
+            Ok((key_hex, address, None))
+        }
+    }
 }
 
 fn handle_create_encrypted(
     store: &AccountStore,
     name: &str,
     chain: aleph_types::chain::Chain,
+    mnemonic_words: Option<usize>,
     json: bool,
 ) -> Result<()> {
     use crate::account::{keystore, password};
@@ -77,14 +107,21 @@ fn handle_create_encrypted(
     store.check_name_available(name)?;
 
     let passphrase = password::read_new_password()?;
-    let (key_hex, address) = generate_key(chain.clone())?;
+    let (key_hex, address, phrase) = create_key(chain.clone(), mnemonic_words)?;
     let key_bytes = keystore::decode_key_hex(&key_hex)?;
     let ks = keystore::encrypt_key(&key_bytes, &passphrase, &address)?;
     let ks_json = serde_json::to_string_pretty(&ks)?;
 
     store.add_keystore_account(name, chain.clone(), address.clone(), &ks_json)?;
 
-    print_account_created(name, &chain, &address, "encrypted", json)
+    print_account_created(
+        name,
+        &chain,
+        &address,
+        "encrypted",
+        phrase.as_ref().map(|p| p.as_str()),
+        json,
+    )
 }
 
 fn print_account_created(
@@ -92,21 +129,30 @@ fn print_account_created(
     chain: &aleph_types::chain::Chain,
     address: &str,
     kind: &str,
+    mnemonic: Option<&str>,
     json: bool,
 ) -> Result<()> {
     if json {
-        let output = serde_json::json!({
+        let mut output = serde_json::json!({
             "name": name,
             "chain": chain,
             "address": address,
             "kind": kind,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        if let Some(mnemonic) = mnemonic {
+            output["mnemonic"] = serde_json::json!(mnemonic);
+        }
+        print_structured(&output)?;
     } else {
         eprintln!("Account '{name}' created.");
         eprintln!("  Type:    {kind}");
         eprintln!("  Chain:   {chain}");
         eprintln!("  Address: {address}");
+        if let Some(mnemonic) = mnemonic {
+            eprintln!();
+            eprintln!("Seed phrase (write it down, it is not shown again):");
+            eprintln!("  {mnemonic}");
+        }
     }
     Ok(())
 }
@@ -155,7 +201,10 @@ fn handle_import(store: &AccountStore, args: AccountImportArgs, json: bool) -> R
                     .context("failed to read private key from stdin")?,
             },
         };
-        Zeroizing::new(raw.strip_prefix("0x").unwrap_or(&raw).to_string())
+        let raw = Zeroizing::new(raw);
+        // Keys are stored in hex, whatever encoding they were given in.
+        let key_bytes = crate::account::decode_private_key(raw.trim(), &chain)?;
+        Zeroizing::new(hex::encode(&*key_bytes))
     };
 
     let account = crate::account::load_account(Some(&key_hex), chain.clone())?;
@@ -232,7 +281,7 @@ fn print_account_imported(
             "address": address,
             "kind": kind,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Account '{name}' imported.");
         eprintln!("  Type:    {kind}");
@@ -317,7 +366,7 @@ fn handle_import_ledger(store: &AccountStore, args: AccountImportArgs, json: boo
             "kind": "ledger",
             "derivation_path": path_str,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Account '{}' imported.", args.name);
         eprintln!("  Type:    ledger");
@@ -342,7 +391,7 @@ fn handle_migrate(store: &AccountStore, args: AccountMigrateArgs, json: bool) ->
 
     if result.migrated.is_empty() && result.skipped.is_empty() {
         if json {
-            println!("{}", serde_json::json!({"migrated": [], "skipped": []}));
+            print_structured(&serde_json::json!({"migrated": [], "skipped": []}))?;
         } else {
             eprintln!("No Python CLI accounts found in {}.", python_home.display());
         }
@@ -377,13 +426,10 @@ fn handle_migrate(store: &AccountStore, args: AccountMigrateArgs, json: bool) ->
                 })
             })
             .collect();
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "migrated": migrated,
-                "skipped": skipped,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "migrated": migrated,
+            "skipped": skipped,
+        }))?;
         return Ok(());
     }
 
@@ -444,7 +490,7 @@ async fn handle_list(client: &AlephClient, store: &AccountStore, json: bool) ->
 
     if manifest.accounts.is_empty() && manifest.aliases.is_empty() {
         if json {
-            println!("[]");
+            print_structured(&[] as &[serde_json::Value])?;
         } else {
             eprintln!("No accounts. Create one with: aleph account create <NAME>");
         }
@@ -488,7 +534,7 @@ async fn handle_list(client: &AlephClient, store: &AccountStore, json: bool) ->
                 "kind": "alias",
             }));
         }
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
         return Ok(());
     }
 
@@ -558,7 +604,7 @@ async fn handle_show(
                 "credits": bal.credits,
             });
         }
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         let is_default =
             store.default_account_name().ok().flatten().as_deref() == Some(name.as_str());
@@ -626,7 +672,7 @@ async fn handle_balance(
             "locked_aleph_tokens": balance.locked_aleph_tokens,
             "credits": balance.credits,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!(
             "Address: {}",
@@ -666,7 +712,7 @@ fn handle_use(store: &AccountStore, args: AccountUseArgs, json: bool) -> Result<
 
     if json {
         let output = serde_json::json!({ "default": args.name });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Default account set to '{}'.", args.name);
     }
@@ -711,7 +757,7 @@ fn handle_set(store: &AccountStore, args: AccountSetArgs, json: bool) -> Result<
             "address": after.address,
             "renamed_from": (final_name != target).then(|| target.clone()),
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         if before.chain != after.chain {
             eprintln!("Chain: {} -> {}", before.chain, after.chain);
@@ -734,6 +780,10 @@ fn handle_export(store: &AccountStore, args: AccountExportArgs, json: bool) -> R
         );
     }
 
+    if let Some(path) = &args.keystore {
+        return export_keystore(store, &entry, path, json);
+    }
+
     if !confirm_typed_match(
         "WARNING: This will display your private key in the terminal.",
         "yes",
@@ -765,13 +815,70 @@ fn handle_export(store: &AccountStore, args: AccountExportArgs, json: bool) -> R
             "address": entry.address,
             "private_key": *key,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         println!("0x{}", *key);
     }
     Ok(())
 }
 
+/// Writes the key of `entry` to a new keystore V3 file at `path`. The key
+/// only ever leaves the store encrypted, so no confirmation is asked.
+fn export_keystore(
+    store: &AccountStore,
+    entry: &crate::account::store::AccountEntry,
+    path: &Path,
+    json: bool,
+) -> Result<()> {
+    use crate::account::{keystore, password};
+
+    let ks_json = match entry.kind {
+        AccountKind::Keystore => store.read_keystore_json(&entry.name)?,
+        AccountKind::Local => {
+            if !entry.chain.is_evm() {
+                bail!("keystore export is only supported for EVM accounts");
+            }
+            let key_hex = Zeroizing::new(store.get_private_key(&entry.name)?);
+            let key_bytes = keystore::decode_key_hex(&key_hex)?;
+            let passphrase = password::read_new_password()?;
+            let ks = keystore::encrypt_key(&key_bytes, &passphrase, &entry.address)?;
+            serde_json::to_string_pretty(&ks)?
+        }
+        AccountKind::Ledger => unreachable!("rejected by the caller"),
+    };
+
+    write_new_restricted(path, &ks_json)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    if json {
+        let output = serde_json::json!({
+            "name": entry.name,
+            "chain": entry.chain,
+            "address": entry.address,
+            "keystore": path,
+        });
+        print_structured(&output)?;
+    } else {
+        eprintln!("Account '{}' exported to {}.", entry.name, path.display());
+    }
+    Ok(())
+}
+
+/// Creates `path` with owner-only permissions, refusing to overwrite an
+/// existing file.
+fn write_new_restricted(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    opts.open(path)?.write_all(content.as_bytes())
+}
+
 fn handle_alias_command(store: &AccountStore, command: AliasCommand, json: bool) -> Result<()> {
     match command {
         AliasCommand::Add(args) => handle_alias_add(store, args, json),
@@ -788,7 +895,7 @@ fn handle_alias_add(store: &AccountStore, args: AliasAddArgs, json: bool) -> Res
             "name": args.name,
             "address": args.address,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Alias '{}' added ({}).", args.name, args.address);
     }
@@ -800,7 +907,7 @@ fn handle_alias_list(store: &AccountStore, json: bool) -> Result<()> {
 
     if manifest.aliases.is_empty() {
         if json {
-            println!("[]");
+            print_structured(&[] as &[serde_json::Value])?;
         } else {
             eprintln!("No aliases. Add one with: aleph account alias add <NAME> <ADDRESS>");
         }
@@ -818,7 +925,7 @@ fn handle_alias_list(store: &AccountStore, json: bool) -> Result<()> {
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("{:<16} ADDRESS", "NAME");
         for alias in &manifest.aliases {
@@ -833,7 +940,7 @@ fn handle_alias_remove(store: &AccountStore, args: AliasRemoveArgs, json: bool)
 
     if json {
         let output = serde_json::json!({ "removed": args.name });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Alias '{}' removed.", args.name);
     }
@@ -928,7 +1035,7 @@ async fn handle_ssh_list(client: &AlephClient, args: SshListArgs, json: bool) ->
     let keys = client.list_ssh_keys(&address).await?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&keys)?);
+        print_structured(&keys)?;
         return Ok(());
     }
 
@@ -1081,6 +1188,22 @@ mod ssh_tests {
 mod tests {
     use super::*;
 
+    #[test]
+    fn write_new_restricted_never_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alice.json");
+        write_new_restricted(&path, "{}").unwrap();
+        assert!(write_new_restricted(&path, "{\"x\":1}").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
     #[test]
     fn format_credits_zero() {
         assert_eq!(format_credits(0), "0 ($0.00)");
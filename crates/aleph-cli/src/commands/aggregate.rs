@@ -4,13 +4,13 @@ use crate::cli::{
     AggregateGetArgs, AggregateListArgs, AggregateUnsetArgs,
 };
 use crate::common::{
-    confirm_action, read_content, resolve_account, resolve_address, submit_or_preview,
+    confirm_action, print_structured, read_content, resolve_account, resolve_address,
+    resolve_channel, submit_or_preview,
 };
 use aleph_sdk::builder::MessageBuilder;
 use aleph_sdk::client::{AlephAggregateClient, AlephClient, AlephMessageClient, MessageWithStatus};
 use aleph_types::account::Account;
 use aleph_types::chain::Address;
-use aleph_types::channel::Channel;
 use aleph_types::item_hash::ItemHash;
 use aleph_types::message::MessageType;
 use anyhow::{Result, anyhow, bail};
@@ -237,8 +237,8 @@ async fn handle_aggregate_edit(
     if let Some(addr) = on_behalf_of {
         builder = builder.on_behalf_of(addr);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
@@ -261,7 +261,7 @@ pub async fn handle_aggregate_command(
             handle_aggregate_unset(aleph_client, ccn_url, json, args).await?;
         }
         AggregateCommand::Get(args) => {
-            handle_aggregate_get(aleph_client, json, args).await?;
+            handle_aggregate_get(aleph_client, args).await?;
         }
         AggregateCommand::List(args) => {
             handle_aggregate_list(aleph_client, json, args).await?;
@@ -314,18 +314,14 @@ async fn handle_aggregate_create(
     if let Some(addr) = on_behalf_of {
         builder = builder.on_behalf_of(addr);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
 }
 
-async fn handle_aggregate_get(
-    aleph_client: &AlephClient,
-    json: bool,
-    args: AggregateGetArgs,
-) -> Result<()> {
+async fn handle_aggregate_get(aleph_client: &AlephClient, args: AggregateGetArgs) -> Result<()> {
     let address = resolve_owner_address(args.address.as_deref())?;
     let value: serde_json::Value = match aleph_client.get_aggregate(&address, &args.key).await {
         Ok(v) => v,
@@ -335,12 +331,7 @@ async fn handle_aggregate_get(
         }
         Err(e) => return Err(e.into()),
     };
-    if json {
-        println!("{}", serde_json::to_string(&value)?);
-    } else {
-        println!("{}", serde_json::to_string_pretty(&value)?);
-    }
-    Ok(())
+    print_structured(&value)
 }
 
 async fn handle_aggregate_list(
@@ -352,8 +343,7 @@ async fn handle_aggregate_list(
     let aggregates = aleph_client.get_all_aggregates(&address).await?;
 
     if json {
-        println!("{}", serde_json::to_string(&aggregates)?);
-        return Ok(());
+        return print_structured(&aggregates);
     }
 
     if aggregates.is_empty() {
@@ -365,7 +355,7 @@ async fn handle_aggregate_list(
     keys.sort();
     for key in keys {
         println!("=== {key} ===");
-        println!("{}", serde_json::to_string_pretty(&aggregates[key])?);
+        print_structured(&aggregates[key])?;
     }
     Ok(())
 }
@@ -408,8 +398,8 @@ async fn handle_aggregate_forget(
     if let Some(owner) = args.on_behalf_of {
         builder = builder.on_behalf_of(resolve_address(&owner)?);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
@@ -525,8 +515,8 @@ async fn handle_aggregate_unset(
     if let Some(addr) = on_behalf_of {
         builder = builder.on_behalf_of(addr);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
@@ -1,5 +1,7 @@
 use crate::cli::AuthorizationCommand;
-use crate::common::{format_address, resolve_account, resolve_address, submit_or_preview};
+use crate::common::{
+    format_address, print_structured, resolve_account, resolve_address, submit_or_preview,
+};
 use aleph_sdk::authorization::AlephAuthorizationClient;
 use aleph_sdk::client::AlephClient;
 use aleph_sdk::messages::AuthorizationBuilder;
@@ -37,7 +39,7 @@ pub async fn handle_authorization_command(
             };
 
             if json {
-                println!("{}", serde_json::to_string_pretty(&filtered)?);
+                print_structured(&filtered)?;
             } else if filtered.is_empty() {
                 eprintln!("No authorizations found for {address}");
             } else {
@@ -87,7 +89,7 @@ pub async fn handle_authorization_command(
             };
 
             if json {
-                println!("{}", serde_json::to_string_pretty(&filtered)?);
+                print_structured(&filtered)?;
             } else if filtered.is_empty() {
                 eprintln!("No received authorizations found for {address}");
             } else {
@@ -1,28 +1,52 @@
 //! `aleph instance confidential` command tree.
 
 use crate::cli::{
-    ConfidentialCommand, ConfidentialInitSessionArgs, ConfidentialLaunchArgs, ConfidentialStartArgs,
+    ConfidentialCommand, ConfidentialCreateArgs, ConfidentialInitSessionArgs,
+    ConfidentialLaunchArgs, ConfidentialStartArgs,
 };
+use crate::commands::instance_show::fetch_instance_message;
 use crate::commands::instance_target::resolve_target;
-use crate::common::{confirm_action, resolve_account};
+use crate::common::{confirm_action, print_structured, resolve_account};
 use crate::config::store::ConfigStore;
 use crate::sevctl::Sevctl;
+use aleph_sdk::client::AlephClient;
 use aleph_sdk::confidential::{
-    DEFAULT_CONFIDENTIAL_FIRMWARE_HASH_HEX, build_secret_packet, calculate_firmware_hash,
-    compute_expected_measure,
+    ConfidentialError, DEFAULT_CONFIDENTIAL_FIRMWARE_HASH_HEX, calculate_firmware_hash,
+};
+use aleph_sdk::confidential_session::{
+    LaunchSession, SessionError, SessionKeys, initialize_session, inject_disk_secret,
 };
 use aleph_sdk::crn::{CrnClient, CrnError};
+use aleph_sdk::crns_list::{CrnFilter, CrnListEntry, CrnListResponse};
 use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{Message, MessageContentEnum};
 use anyhow::{Context, Result, anyhow, bail};
 use std::time::{Duration, Instant};
-use subtle::ConstantTimeEq;
 use url::Url;
+use zeroize::Zeroizing;
+
+/// SEV policy used for instances created by `confidential create`: the
+/// `NoDebug` bit, as set in the INSTANCE message by `instance create`.
+const NO_DEBUG_POLICY: u32 = 0x1;
 
-pub async fn dispatch(scheduler_url: Url, json: bool, cmd: ConfidentialCommand) -> Result<()> {
+pub async fn dispatch(
+    aleph_client: &AlephClient,
+    ccn_url: &Url,
+    scheduler_url: Url,
+    json: bool,
+    cmd: ConfidentialCommand,
+) -> Result<()> {
     match cmd {
         ConfidentialCommand::InitSession(args) => handle_init_session(scheduler_url, args).await,
-        ConfidentialCommand::Start(args) => handle_start(scheduler_url, json, args).await,
-        ConfidentialCommand::Launch(args) => handle_launch(scheduler_url, json, args).await,
+        ConfidentialCommand::Start(args) => {
+            handle_start(aleph_client, scheduler_url, json, args).await
+        }
+        ConfidentialCommand::Launch(args) => {
+            handle_launch(aleph_client, scheduler_url, json, args).await
+        }
+        ConfidentialCommand::Create(args) => {
+            handle_create(aleph_client, ccn_url, scheduler_url, json, args).await
+        }
     }
 }
 
@@ -77,17 +101,14 @@ async fn handle_init_session(scheduler_url: Url, args: ConfidentialInitSessionAr
 
     // 7. Derive session keys (writes vm_{godh,session}.b64 + vm_{tek,tik}.bin).
     let prefix = session_dir.join("vm");
-    let files = sevctl
+    sevctl
         .session(&prefix, &cert_path, args.policy)
         .await
         .context("sevctl session failed")?;
 
     // 8. POST session.b64 + godh.b64 to the CRN.
-    let session_bytes = std::fs::read(&files.session)
-        .with_context(|| format!("reading {}", files.session.display()))?;
-    let godh_bytes =
-        std::fs::read(&files.godh).with_context(|| format!("reading {}", files.godh.display()))?;
-    crn.initialize_confidential(&vm_id, &session_bytes, &godh_bytes)
+    let session = LaunchSession::load(&prefix)?;
+    initialize_session(&crn, &vm_id, &session)
         .await
         .context("CRN rejected the initialize request")?;
 
@@ -96,9 +117,16 @@ async fn handle_init_session(scheduler_url: Url, args: ConfidentialInitSessionAr
     Ok(())
 }
 
-async fn handle_start(scheduler_url: Url, json: bool, args: ConfidentialStartArgs) -> Result<()> {
-    // 1. Resolve target.
+async fn handle_start(
+    aleph_client: &AlephClient,
+    scheduler_url: Url,
+    json: bool,
+    args: ConfidentialStartArgs,
+) -> Result<()> {
+    // 1. Resolve target, and the SEV policy the instance was created with.
     let (vm_id, crn_url) = resolve_target(&scheduler_url, &args.vm_id, args.crn.as_deref()).await?;
+    let message = fetch_instance_message(aleph_client, &vm_id).await?;
+    let policy = instance_sev_policy(&message)?;
 
     // 2. Session dir must exist.
     let session_dir = ConfigStore::confidential_sessions_dir()?.join(vm_id.to_string());
@@ -112,14 +140,7 @@ async fn handle_start(scheduler_url: Url, json: bool, args: ConfidentialStartArg
     let account = resolve_account(&args.identity)?;
     let crn = CrnClient::new(&account, crn_url.clone())?;
 
-    // 4. Fetch measurement.
-    let measurement = crn
-        .get_measurement(&vm_id)
-        .await
-        .context("fetching VM measurement from CRN")?;
-    let (vm_measure, nonce) = measurement.split_launch_measure()?;
-
-    // 5. Resolve expected firmware hash.
+    // 4. Resolve expected firmware hash.
     let firmware_hash_hex = if let Some(path) = args.firmware_file.as_deref() {
         calculate_firmware_hash(path).with_context(|| format!("hashing {}", path.display()))?
     } else if let Some(h) = args.firmware_hash.as_deref() {
@@ -137,65 +158,43 @@ async fn handle_start(scheduler_url: Url, json: bool, args: ConfidentialStartArg
             )
         })?;
 
-    // 6. Read TIK. Read before the TEK because it is needed first, to validate
-    //    the launch measurement in step 7; the TEK is only used later (step 11)
-    //    to encrypt the secret table, and reading it now would be wasted work if
-    //    the measurement check fails.
-    let tik_path = session_dir.join("vm_tik.bin");
-    let tik_bytes =
-        std::fs::read(&tik_path).with_context(|| format!("reading {}", tik_path.display()))?;
-    let tik: [u8; 16] = tik_bytes
-        .try_into()
-        .map_err(|v: Vec<u8>| anyhow!("vm_tik.bin must be 16 bytes (got {})", v.len()))?;
+    // 5. Read the TEK/TIK written by `sevctl session`.
+    let keys = SessionKeys::load(&session_dir.join("vm"))?;
 
-    // 7. Validate measurement (constant time).
-    let expected = compute_expected_measure(&measurement.sev_info, &tik, &firmware_hash, &nonce);
-    if expected.ct_eq(&vm_measure).unwrap_u8() == 0 {
-        bail!(
-            "VM measurement does not match expected firmware (hash {firmware_hash_hex}). \
-             The VM may be running tampered code, or the firmware hash is wrong. \
-             Pass --firmware-file to recompute locally. Refusing to inject secret."
-        );
-    }
-
-    // 8. Read TEK.
-    let tek_path = session_dir.join("vm_tek.bin");
-    let tek_bytes =
-        std::fs::read(&tek_path).with_context(|| format!("reading {}", tek_path.display()))?;
-    let tek: [u8; 16] = tek_bytes
-        .try_into()
-        .map_err(|v: Vec<u8>| anyhow!("vm_tek.bin must be 16 bytes (got {})", v.len()))?;
-
-    // 9. Acquire secret.
-    let secret = match args.secret {
+    // 6. Acquire secret.
+    let secret = Zeroizing::new(match args.secret {
         Some(s) => s,
         None => {
             rpassword::prompt_password("VM disk-decryption secret: ").context("reading secret")?
         }
-    };
-
-    // 10. Random IV (16 bytes).
-    use rand::RngCore;
-    let mut iv = [0u8; 16];
-    rand::rngs::OsRng.fill_bytes(&mut iv);
+    });
 
-    // 11. Build + inject.
-    let (packet_header, encrypted_secret) =
-        build_secret_packet(&tek, &tik, &vm_measure, &secret, iv);
-    crn.inject_secret(&vm_id, &packet_header, &encrypted_secret)
-        .await
-        .context("CRN rejected the secret injection")?;
+    // 7. Validate the measurement (constant time) and the policy the VM was
+    //    launched with against the one of the INSTANCE message, then inject
+    //    the secret.
+    match inject_disk_secret(&crn, &vm_id, &keys, &firmware_hash, Some(policy), &secret).await {
+        Ok(()) => {}
+        Err(SessionError::Confidential(ConfidentialError::PolicyMismatch { expected, actual })) => {
+            bail!(
+                "VM was launched with SEV policy {actual:#x}, but instance {vm_id} requires \
+                 {expected:#x}. Refusing to inject secret."
+            )
+        }
+        Err(SessionError::Confidential(ConfidentialError::MeasurementMismatch)) => bail!(
+            "VM measurement does not match expected firmware (hash {firmware_hash_hex}). \
+             The VM may be running tampered code, or the firmware hash is wrong. \
+             Pass --firmware-file to recompute locally. Refusing to inject secret."
+        ),
+        Err(e) => return Err(anyhow::Error::from(e).context("injecting the disk secret")),
+    }
 
-    // 12. Output.
+    // 8. Output.
     if json {
-        println!(
-            "{}",
-            serde_json::json!({
-                "status": "ok",
-                "vm_id": vm_id.to_string(),
-                "crn_url": crn_url.to_string(),
-            })
-        );
+        print_structured(&serde_json::json!({
+            "status": "ok",
+            "vm_id": vm_id.to_string(),
+            "crn_url": crn_url.to_string(),
+        }))?;
     } else {
         println!("Instance {vm_id} is starting on {crn_url}.");
         println!("  Networking: aleph instance show {vm_id} --verbose");
@@ -205,20 +204,34 @@ async fn handle_start(scheduler_url: Url, json: bool, args: ConfidentialStartArg
     Ok(())
 }
 
-async fn handle_launch(scheduler_url: Url, json: bool, args: ConfidentialLaunchArgs) -> Result<()> {
+async fn handle_launch(
+    aleph_client: &AlephClient,
+    scheduler_url: Url,
+    json: bool,
+    args: ConfidentialLaunchArgs,
+) -> Result<()> {
     // 0. Sevctl available? Fail fast before doing anything.
     let _sevctl = Sevctl::find()?;
 
     // 1. Determine vm_id + crn_url.
     let vm_id_input = args.vm_id.as_deref().ok_or_else(|| {
         anyhow!(
-            "creating a new VM from `confidential launch` requires `instance create` flag forwarding, \
-             which lands in a follow-up. For now, run `aleph instance create --confidential ...` first, \
-             then call `aleph instance confidential launch <vm-hash>`."
+            "`confidential launch` boots an existing VM. To create a new one, run \
+             `aleph instance confidential create <NAME> --image <ENCRYPTED_ROOTFS> ...`."
         )
     })?;
     let (vm_id, crn_url) = resolve_target(&scheduler_url, vm_id_input, args.crn.as_deref()).await?;
+    launch(aleph_client, scheduler_url, json, vm_id, crn_url, args).await
+}
 
+async fn launch(
+    aleph_client: &AlephClient,
+    scheduler_url: Url,
+    json: bool,
+    vm_id: ItemHash,
+    crn_url: Url,
+    args: ConfidentialLaunchArgs,
+) -> Result<()> {
     // 2. Allocate on the CRN (the "start" step in Python parlance).
     let account = resolve_account(&args.identity)?;
     let crn = CrnClient::new(&account, crn_url.clone())?;
@@ -253,7 +266,142 @@ async fn handle_launch(scheduler_url: Url, json: bool, args: ConfidentialLaunchA
         json,
         debug: args.debug,
     };
-    handle_start(scheduler_url, json, start_args).await
+    handle_start(aleph_client, scheduler_url, json, start_args).await
+}
+
+/// SEV policy of the trusted-execution config of an INSTANCE message: the
+/// policy the VM must report in its launch measurement.
+fn instance_sev_policy(message: &Message) -> Result<u32> {
+    let MessageContentEnum::Instance(content) = message.content() else {
+        bail!("item {} is not an INSTANCE message", message.item_hash);
+    };
+    let tee = content
+        .environment
+        .trusted_execution
+        .as_ref()
+        .ok_or_else(|| {
+            anyhow!(
+                "instance {} is not a confidential instance (no trusted execution environment)",
+                message.item_hash
+            )
+        })?;
+    Ok(tee.policy)
+}
+
+async fn handle_create(
+    aleph_client: &AlephClient,
+    ccn_url: &Url,
+    scheduler_url: Url,
+    json: bool,
+    mut args: ConfidentialCreateArgs,
+) -> Result<()> {
+    // 0. Sevctl available? Fail fast before publishing anything.
+    let _sevctl = Sevctl::find()?;
+
+    // 1. Rootfs guidance: nothing on-chain tells us whether the image is
+    //    encrypted, so make the requirement explicit before spending credits.
+    if !json {
+        eprintln!(
+            "Note: confidential instances boot an encrypted root filesystem, unlocked by the \
+             secret injected at launch. An unencrypted image will not boot."
+        );
+    }
+
+    // 2. CRN selection: the instance has to land on an SEV-capable node, so
+    //    pin one unless the user already did.
+    let instance = &mut args.instance;
+    instance.confidential = true;
+    // Waiting for networking is meaningless here: the VM stays paused until
+    // the secret is injected below.
+    instance.wait = None;
+    let dry_run = instance.signing.dry_run;
+    let crn_override = match instance.crn_hash {
+        Some(hash) => Some(hash.to_string()),
+        None if instance.interactive => None,
+        None => {
+            let crns = crate::commands::instance::fetch_crn_list().await?;
+            let chosen = pick_confidential_crn(&crns)?;
+            if !json {
+                eprintln!("Selected CRN {} ({}).", chosen.name, chosen.address);
+            }
+            instance.crn_hash = Some(chosen.hash.parse().map_err(|e| {
+                anyhow!(
+                    "CRN list returned an invalid node hash '{}': {e}",
+                    chosen.hash
+                )
+            })?);
+            Some(chosen.address.clone())
+        }
+    };
+
+    // 3. Publish the INSTANCE message with its trusted execution environment.
+    let identity = args.instance.signing.identity.clone();
+    let vm_id = crate::commands::instance::handle_instance_create(
+        aleph_client,
+        ccn_url,
+        &scheduler_url,
+        json,
+        args.instance,
+    )
+    .await?;
+    if dry_run {
+        return Ok(());
+    }
+
+    // Without a pinned node (interactive placement), the scheduler tells
+    // where the VM landed.
+    let (vm_id, crn_url) =
+        resolve_target(&scheduler_url, &vm_id.to_string(), crn_override.as_deref()).await?;
+
+    // 4-6. Allocate, attest and inject the secret.
+    let launch_args = ConfidentialLaunchArgs {
+        vm_id: Some(vm_id.to_string()),
+        crn: Some(crn_url.to_string()),
+        identity,
+        policy: NO_DEBUG_POLICY,
+        keep_session: args.keep_session,
+        firmware_hash: args.firmware_hash,
+        firmware_file: args.firmware_file,
+        secret: args.secret,
+        debug: args.debug,
+    };
+    launch(
+        aleph_client,
+        scheduler_url,
+        json,
+        vm_id.clone(),
+        crn_url,
+        launch_args,
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "instance {vm_id} was created but did not boot. Once the cause is fixed, resume \
+                 with 'aleph instance confidential launch {vm_id}'."
+        )
+    })
+}
+
+/// Picks the best-scored active CRN able to host confidential VMs.
+///
+/// Nodes with terms and conditions are skipped: accepting them is left to the
+/// interactive picker (`-i`) or to an explicit `--crn-hash`.
+fn pick_confidential_crn(crns: &CrnListResponse) -> Result<&CrnListEntry> {
+    let filter = CrnFilter {
+        ipv6: true,
+        confidential: true,
+        ..CrnFilter::default()
+    };
+    crns.filter(&filter)
+        .into_iter()
+        .filter(|crn| crn.terms_and_conditions.is_none())
+        .max_by(|a, b| a.score.unwrap_or(0.0).total_cmp(&b.score.unwrap_or(0.0)))
+        .ok_or_else(|| {
+            anyhow!(
+                "no active CRN supports confidential VMs right now. Pick one explicitly with \
+                 --crn-hash, or use -i to browse the available nodes."
+            )
+        })
 }
 
 /// Polls `crn.get_measurement(vm_id)` until it returns 200 or `deadline` elapses.
@@ -309,3 +457,55 @@ async fn poll_measurement_ready(
         step = step.saturating_add(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const INSTANCE_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../fixtures/messages/instance/instance-gpu-payg.json"
+    ));
+
+    #[test]
+    fn instance_sev_policy_reads_the_trusted_execution_config() {
+        let mut value: serde_json::Value = serde_json::from_str(INSTANCE_FIXTURE).unwrap();
+        let message: Message = serde_json::from_value(value.clone()).unwrap();
+        assert!(instance_sev_policy(&message).is_err());
+
+        value["content"]["environment"]["trusted_execution"] = json!({"policy": 0x5});
+        let message: Message = serde_json::from_value(value).unwrap();
+        assert_eq!(instance_sev_policy(&message).unwrap(), 0x5);
+    }
+
+    fn crn(name: &str, score: f64, confidential: bool, terms: Option<&str>) -> serde_json::Value {
+        json!({
+            "hash": format!("{:0>64}", name.len()),
+            "name": name,
+            "address": format!("https://{name}.example"),
+            "score": score,
+            "confidential_support": confidential,
+            "ipv6_check": {"host": true, "vm": true},
+            "terms_and_conditions": terms,
+        })
+    }
+
+    #[test]
+    fn pick_confidential_crn_prefers_best_score_without_terms() {
+        let crns: CrnListResponse = serde_json::from_value(json!({
+            "crns": [
+                crn("plain", 0.99, false, None),
+                crn("sev-low", 0.5, true, None),
+                crn("sev-terms", 0.95, true, Some("abcd")),
+                crn("sev-high", 0.8, true, None),
+            ]
+        }))
+        .unwrap();
+        assert_eq!(pick_confidential_crn(&crns).unwrap().name, "sev-high");
+
+        let none: CrnListResponse =
+            serde_json::from_value(json!({ "crns": [crn("plain", 0.99, false, None)] })).unwrap();
+        assert!(pick_confidential_crn(&none).is_err());
+    }
+}
@@ -1,10 +1,11 @@
+use crate::account::store::AccountStore;
 use crate::cli::{
     CcnAddArgs, CcnCommand, CcnListArgs, CcnRemoveArgs, CcnShowArgs, CcnUseArgs, ConfigCommand,
-    NetworkAddArgs, NetworkCommand, NetworkEthereumArgs, NetworkRemoveArgs, NetworkSetArgs,
-    NetworkShowArgs, NetworkUseArgs,
+    ConfigDefaultsArgs, NetworkAddArgs, NetworkCommand, NetworkEthereumArgs, NetworkRemoveArgs,
+    NetworkSetArgs, NetworkShowArgs, NetworkUseArgs,
 };
-use crate::common::confirm_action;
-use crate::config::store::{ConfigStore, EthereumPatch, NetworkEntry};
+use crate::common::{confirm_action, print_structured};
+use crate::config::store::{ConfigStore, EthereumPatch, NetworkEntry, OutputFormat};
 use aleph_sdk::credit::EthereumConfig;
 use anyhow::{Context, Result};
 
@@ -32,9 +33,56 @@ pub async fn handle_config_command(
         ConfigCommand::Network { command } => {
             handle_network_command(command, json, cli_network).await
         }
+        ConfigCommand::Defaults(args) => handle_defaults(args, json),
     }
 }
 
+fn handle_defaults(args: ConfigDefaultsArgs, json: bool) -> Result<()> {
+    let store = ConfigStore::open().context("failed to open config store")?;
+    if let Some(channel) = args.channel.as_deref() {
+        store.set_default_channel(Some(channel))?;
+    } else if args.unset_channel {
+        store.set_default_channel(None)?;
+    }
+    if let Some(output) = args.default_output {
+        store.set_default_output(output)?;
+    }
+
+    let manifest = store.load_manifest()?;
+    let default_ccn = match &manifest.default_network {
+        Some(network) => store.get_network(network)?.default_ccn,
+        None => None,
+    };
+    let default_account = AccountStore::open()
+        .and_then(|accounts| accounts.default_account_name())
+        .context("failed to open account store")?;
+    let output = manifest.default_output.unwrap_or_default();
+    if json {
+        let output = serde_json::json!({
+            "network": manifest.default_network,
+            "ccn": default_ccn,
+            "account": default_account,
+            "channel": manifest.default_channel,
+            "output": output,
+        });
+        print_structured(&output)?;
+    } else {
+        let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        eprintln!("Network: {}", or_dash(&manifest.default_network));
+        eprintln!("CCN:     {}", or_dash(&default_ccn));
+        eprintln!("Account: {}", or_dash(&default_account));
+        eprintln!("Channel: {}", or_dash(&manifest.default_channel));
+        let output = match output {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Yaml => "yaml",
+        };
+        eprintln!("Output:  {output}");
+    }
+    Ok(())
+}
+
 async fn handle_network_command(
     command: NetworkCommand,
     json: bool,
@@ -64,14 +112,11 @@ fn handle_network_add(store: &ConfigStore, args: NetworkAddArgs, json: bool) ->
     }
     let entry = store.get_network(&args.name)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "name": args.name,
-                "ethereum": entry.ethereum,
-                "scheduler_url": entry.scheduler_url,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "name": args.name,
+            "ethereum": entry.ethereum,
+            "scheduler_url": entry.scheduler_url,
+        }))?;
     } else {
         eprintln!("Network '{}' added.", args.name);
         if let Some(eth) = &entry.ethereum {
@@ -103,14 +148,11 @@ fn handle_network_set(
     }
     let entry = store.get_network(&name)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "network": name,
-                "ethereum": entry.ethereum,
-                "scheduler_url": entry.scheduler_url,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "network": name,
+            "ethereum": entry.ethereum,
+            "scheduler_url": entry.scheduler_url,
+        }))?;
     } else {
         eprintln!("Network '{name}' updated.");
         if let Some(eth) = &entry.ethereum {
@@ -182,7 +224,7 @@ fn handle_network_list(store: &ConfigStore, json: bool) -> Result<()> {
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&rows)?);
+        print_structured(&rows)?;
         return Ok(());
     }
     eprintln!("{:<2} {:<16} {:<6} DEFAULT CCN", "", "NAME", "CCNS");
@@ -207,10 +249,7 @@ fn handle_network_list(store: &ConfigStore, json: bool) -> Result<()> {
 fn handle_network_use(store: &ConfigStore, args: NetworkUseArgs, json: bool) -> Result<()> {
     store.set_default_network(&args.name)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({ "default_network": args.name }))?
-        );
+        print_structured(&serde_json::json!({ "default_network": args.name }))?;
     } else {
         eprintln!("Default network set to '{}'.", args.name);
     }
@@ -244,7 +283,7 @@ fn handle_network_show(
             "ethereum": net.ethereum,
             "scheduler_url": net.scheduler_url,
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Name:        {}", net.name);
         eprintln!("Default:     {}", if is_default { "yes" } else { "no" });
@@ -281,10 +320,7 @@ fn handle_network_remove(store: &ConfigStore, args: NetworkRemoveArgs, json: boo
     }
     store.remove_network(&args.name)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({ "removed": args.name }))?
-        );
+        print_structured(&serde_json::json!({ "removed": args.name }))?;
     } else {
         eprintln!("Network '{}' removed.", args.name);
     }
@@ -330,14 +366,11 @@ fn handle_add(
     let network = resolve_ccn_scope(store, args.network.as_deref().or(cli_network))?.name;
     store.add_ccn(&network, &args.name, &args.url)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "network": network,
-                "name": args.name,
-                "url": args.url,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "network": network,
+            "name": args.name,
+            "url": args.url,
+        }))?;
     } else {
         eprintln!("CCN '{}' added to network '{}'.", args.name, network);
         eprintln!("  URL: {}", args.url);
@@ -354,13 +387,10 @@ fn handle_use(
     let network = resolve_ccn_scope(store, args.network.as_deref().or(cli_network))?.name;
     store.set_default_ccn(&network, &args.name)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "network": network,
-                "default_ccn": args.name,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "network": network,
+            "default_ccn": args.name,
+        }))?;
     } else {
         eprintln!(
             "Default CCN for network '{}' set to '{}'.",
@@ -389,7 +419,7 @@ fn handle_list(
                     })
                 })
                 .collect();
-            println!("{}", serde_json::to_string_pretty(&items)?);
+            print_structured(&items)?;
             return Ok(());
         }
         eprintln!("{:<16} {:<16} URL", "NETWORK", "NAME");
@@ -402,7 +432,7 @@ fn handle_list(
     let net = resolve_ccn_scope(store, args.network.as_deref().or(cli_network))?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&net.ccns)?);
+        print_structured(&net.ccns)?;
         return Ok(());
     }
 
@@ -457,7 +487,7 @@ async fn handle_show(
         if let Some(v) = &version {
             output["version"] = serde_json::json!(v);
         }
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Network: {}", net.name);
         eprintln!("Name:    {}", entry.name);
@@ -487,13 +517,10 @@ fn handle_remove(
     }
     store.remove_ccn(&network, &args.name)?;
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "network": network,
-                "removed": args.name,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "network": network,
+            "removed": args.name,
+        }))?;
     } else {
         eprintln!("CCN '{}' removed from network '{}'.", args.name, network);
     }
@@ -5,8 +5,8 @@ use crate::cli::{
     CreditTokenCli, SigningArgs, TransferCreditArgs,
 };
 use crate::common::{
-    confirm_submission, format_address, resolve_account, resolve_address, resolve_network,
-    submit_or_preview,
+    confirm_submission, format_address, print_structured, resolve_account, resolve_address,
+    resolve_channel, resolve_network, submit_or_preview,
 };
 use aleph_sdk::builder::MessageBuilder;
 use aleph_sdk::client::{
@@ -20,7 +20,6 @@ use aleph_sdk::credit_transfer::{
 };
 use aleph_types::account::{Account, EvmAccount};
 use aleph_types::chain::Address as AlephAddress;
-use aleph_types::channel::Channel;
 use aleph_types::item_hash::ItemHash;
 use aleph_types::message::MessageType;
 use alloy_network::EthereumWallet;
@@ -104,7 +103,7 @@ async fn handle_history(
         .await?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&history)?);
+        print_structured(&history)?;
         return Ok(());
     }
 
@@ -157,7 +156,7 @@ async fn handle_summary(
         .await?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&summary)?);
+        print_structured(&summary)?;
         return Ok(());
     }
 
@@ -532,8 +531,8 @@ async fn handle_transfer(
         "content": content,
     });
     let mut builder = MessageBuilder::new(&account, MessageType::Post, envelope);
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
 
@@ -662,7 +661,7 @@ fn print_dry_run_summary(
     if json {
         let mut output = summary_json(amount_display, estimate, ethereum);
         output["dry_run"] = serde_json::Value::Bool(true);
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         print_human_estimate(amount_display, estimate, ethereum);
         eprintln!("\nDry run — transaction not submitted.");
@@ -681,7 +680,7 @@ fn print_submission_result(
     if json {
         let mut output = summary_json(amount_display, estimate, ethereum);
         output["tx_hash"] = serde_json::Value::String(format!("{tx_hash}"));
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("\nTransaction submitted: {tx_hash}");
         if let Some(base) = &ethereum.explorer_tx_base {
@@ -1,11 +1,11 @@
-use aleph_sdk::crn::CrnClient;
+use aleph_sdk::crn::{CrnClient, LogEntry, LogType};
 use anyhow::{Result, bail};
 use futures_util::StreamExt;
 use url::Url;
 
-use crate::cli::{CrnArgs, CrnStartArgs, InstanceReinstallArgs, SigningArgs};
+use crate::cli::{CrnArgs, CrnStartArgs, InstanceLogsArgs, InstanceReinstallArgs, SigningArgs};
 use crate::commands::instance_target::resolve_target;
-use crate::common::{confirm_action, resolve_account};
+use crate::common::{confirm_action, print_structured, print_structured_entry, resolve_account};
 
 fn build_client(crn_url: &Url, signing: &SigningArgs) -> Result<CrnClient> {
     let account = resolve_account(&signing.identity)?;
@@ -18,15 +18,12 @@ pub async fn handle_start(scheduler_url: Url, json: bool, args: CrnStartArgs) ->
     let response = client.start_instance(&vm_id).await?;
 
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "success": response.success,
-                "successful": response.successful,
-                "failing": response.failing,
-                "errors": response.errors,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "success": response.success,
+            "successful": response.successful,
+            "failing": response.failing,
+            "errors": response.errors,
+        }))?;
     } else if response.successful {
         eprintln!("Instance {vm_id} started on {crn_url}");
     } else {
@@ -49,8 +46,8 @@ pub async fn handle_start(scheduler_url: Url, json: bool, args: CrnStartArgs) ->
         match crate::commands::instance_wait::wait_until_ready(&scheduler_url, &vm_id, timeout)
             .await?
         {
-            WaitOutcome::Ready(conn) => report_ready(&conn, &vm_id, json),
-            WaitOutcome::Timeout => report_timeout(&vm_id, json),
+            WaitOutcome::Ready(conn) => report_ready(&conn, &vm_id, json)?,
+            WaitOutcome::Timeout => report_timeout(&vm_id, json)?,
         }
     }
 
@@ -74,14 +71,11 @@ pub async fn handle_operation(
     }
 
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "vm_id": vm_id.to_string(),
-                "operation": operation,
-                "status": "ok",
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "vm_id": vm_id.to_string(),
+            "operation": operation,
+            "status": "ok",
+        }))?;
     } else {
         let past_tense = match operation {
             "stop" => "stopped",
@@ -121,15 +115,12 @@ pub async fn handle_reinstall(
     client.reinstall_instance(&vm_id, !args.keep_data).await?;
 
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "vm_id": vm_id.to_string(),
-                "operation": "reinstall",
-                "keep_data": args.keep_data,
-                "status": "ok",
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "vm_id": vm_id.to_string(),
+            "operation": "reinstall",
+            "keep_data": args.keep_data,
+            "status": "ok",
+        }))?;
     } else if args.keep_data {
         eprintln!("Instance {vm_id} reinstalled on {crn_url} (data volumes preserved)");
     } else {
@@ -166,32 +157,39 @@ fn sanitize_log(s: &str) -> String {
     result
 }
 
-pub async fn handle_logs(scheduler_url: Url, json: bool, args: CrnArgs) -> Result<()> {
-    let (vm_id, crn_url) = resolve_target(&scheduler_url, &args.vm_id, args.crn.as_deref()).await?;
-    let client = build_client(&crn_url, &args.signing)?;
-    let mut stream = std::pin::pin!(client.stream_logs(&vm_id).await?);
+pub async fn handle_logs(scheduler_url: Url, json: bool, args: InstanceLogsArgs) -> Result<()> {
+    let target = args.target;
+    let (vm_id, crn_url) =
+        resolve_target(&scheduler_url, &target.vm_id, target.crn.as_deref()).await?;
+    let client = build_client(&crn_url, &target.signing)?;
 
-    while let Some(result) = stream.next().await {
-        let entry = result?;
-        if json {
-            println!(
-                "{}",
-                serde_json::to_string(&serde_json::json!({
-                    "type": format!("{:?}", entry.log_type).to_lowercase(),
-                    "message": entry.message,
-                }))?
-            );
-        } else {
-            use aleph_sdk::crn::LogType;
-            let msg = sanitize_log(&entry.message);
-            match entry.log_type {
-                LogType::Stdout => println!("{msg}"),
-                LogType::Stderr => eprintln!("{msg}"),
-                LogType::System => eprintln!("[system] {msg}"),
-            }
+    if args.no_follow {
+        for entry in client.get_logs(&vm_id).await? {
+            print_log_entry(&entry, json)?;
         }
+        return Ok(());
     }
 
+    let mut stream = std::pin::pin!(client.stream_logs(&vm_id).await?);
+    while let Some(result) = stream.next().await {
+        print_log_entry(&result?, json)?;
+    }
+
+    Ok(())
+}
+
+/// Prints one log entry: as a JSON line, or on stdout/stderr depending on
+/// the stream the VM wrote it to.
+fn print_log_entry(entry: &LogEntry, json: bool) -> Result<()> {
+    if json {
+        return print_structured_entry(entry);
+    }
+    let msg = sanitize_log(&entry.message);
+    match entry.log_type {
+        LogType::Stdout => println!("{msg}"),
+        LogType::Stderr => eprintln!("{msg}"),
+        LogType::System => eprintln!("[system] {msg}"),
+    }
     Ok(())
 }
 
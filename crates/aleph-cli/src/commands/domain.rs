@@ -5,14 +5,15 @@ use crate::cli::{
     DomainListArgs, DomainRemoveArgs,
 };
 use crate::common::{
-    confirm_tty, format_epoch_for_tty, now_secs_f64, resolve_account, resolve_address,
-    resolve_address_or_active, submit_or_preview,
+    confirm_tty, format_epoch_for_tty, now_secs_f64, print_structured, resolve_account,
+    resolve_address, resolve_address_or_active, submit_or_preview,
 };
 use aleph_sdk::aggregate_models::domains::{
     DOMAINS_AGGREGATE_KEY, DomainEntry, DomainOptions, DomainTargetType, DomainsAggregate,
 };
 use aleph_sdk::aggregate_models::websites::{DEFAULT_IPFS_CATCH_ALL_PATH, WEBSITE_CHANNEL};
 use aleph_sdk::client::{AlephAggregateClient, AlephClient};
+use aleph_sdk::domain_dns::required_dns_records;
 use aleph_sdk::messages::AggregateBuilder;
 use aleph_types::account::Account;
 use aleph_types::channel::Channel;
@@ -74,7 +75,7 @@ async fn handle_domain_list(
         .collect();
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&rows)?);
+        print_structured(&rows)?;
     } else if rows.is_empty() {
         println!("(no domains)");
     } else {
@@ -209,11 +210,9 @@ async fn handle_domain_add(
 /// `domain -> message_id` mapping; nothing resolves `https://<domain>` until
 /// the user adds the DNS records the Aleph gateway expects.
 ///
-/// For type=ipfs we print the three concrete records the public.aleph.sh
-/// gateway requires (CNAME for the host, CNAME for `_dnslink.<host>`, TXT
-/// for `_control.<host>` containing the owner address). For program/instance
-/// entries we fall back to a generic pointer since the gateway/record layout
-/// differs.
+/// The records are the ones [`required_dns_records`] lists for the kind of
+/// target: CNAMEs to the public.aleph.sh gateway and a TXT record on
+/// `_control.<host>` containing the owner address.
 fn print_domain_add_next_steps(
     domain: &str,
     kind: DomainTargetType,
@@ -221,28 +220,20 @@ fn print_domain_add_next_steps(
 ) {
     eprintln!();
     eprintln!("Domain '{domain}' registered in the aggregate.");
-    match kind {
-        DomainTargetType::Ipfs => {
-            eprintln!("To finish wiring it up, create the following DNS records:");
-            eprintln!();
-            eprintln!("  CNAME {domain}");
-            eprintln!("        -> ipfs.public.aleph.sh");
-            eprintln!();
-            eprintln!("  CNAME _dnslink.{domain}");
-            eprintln!("        -> _dnslink.{domain}.static.public.aleph.sh");
-            eprintln!();
-            eprintln!("  TXT   _control.{domain}");
-            eprintln!("        -> {owner}");
-            eprintln!();
-            eprintln!("Once DNS has propagated, https://{domain} will serve the site.");
-        }
-        DomainTargetType::Program | DomainTargetType::Instance => {
-            eprintln!("Configure DNS for '{domain}' to point at the Aleph runtime host for");
-            eprintln!("your program/instance, then visit https://{domain} once DNS has");
-            eprintln!("propagated. See the Aleph dashboard for the exact target.");
-        }
+    eprintln!("To finish wiring it up, create the following DNS records:");
+    for rule in required_dns_records(domain, kind, owner) {
+        eprintln!();
+        eprintln!("  {:<5} {}", rule.record_type, rule.name);
+        eprintln!("        -> {}", rule.expected);
     }
     eprintln!();
+    let target = match kind {
+        DomainTargetType::Ipfs => "site",
+        DomainTargetType::Program => "program",
+        DomainTargetType::Instance => "instance",
+    };
+    eprintln!("Once DNS has propagated, https://{domain} will serve the {target}.");
+    eprintln!();
     eprintln!("Inspect with `aleph domain list`, revert with `aleph domain remove {domain}`.");
 }
 
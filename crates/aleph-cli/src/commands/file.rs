@@ -3,8 +3,8 @@ use crate::cli::{
     PaymentTypeCli, SortOrderCli, StorageEngineCli,
 };
 use crate::common::{
-    print_submission_result, report_authenticated_upload_status, resolve_account, resolve_address,
-    submit_or_preview,
+    print_structured, print_submission_result, report_authenticated_upload_status, resolve_account,
+    resolve_address, resolve_channel, submit_or_preview,
 };
 use aleph_sdk::client::{
     AccountFile, AlephAccountClient, AlephClient, AlephMessageClient, AlephStorageClient,
@@ -14,7 +14,6 @@ use aleph_sdk::messages::StoreBuilder;
 use aleph_sdk::verify::Hasher;
 use aleph_types::account::Account;
 use aleph_types::chain::Address;
-use aleph_types::channel::Channel;
 use aleph_types::item_hash::ItemHash;
 use aleph_types::message::execution::base::Payment;
 use aleph_types::message::{FileRef, MessageContentEnum, MessageType, StorageEngine};
@@ -78,8 +77,11 @@ async fn handle_file_upload(
     aleph_client: &AlephClient,
     ccn_url: &Url,
     json: bool,
-    args: FileUploadArgs,
+    mut args: FileUploadArgs,
 ) -> Result<()> {
+    if args.ipfs {
+        args.storage_engine = Some(StorageEngineCli::Ipfs);
+    }
     if !args.path.exists() {
         bail!("path not found: {}", args.path.display());
     }
@@ -133,17 +135,17 @@ async fn handle_single_file_upload(
     if let Some(reference) = args.reference {
         builder = builder.reference(reference);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
 
     if dry_run {
         if json {
-            println!("{}", serde_json::to_string_pretty(&pending)?);
+            print_structured(&pending)?;
         } else {
             eprintln!("Dry run: message not submitted.\n");
-            println!("{}", serde_json::to_string_pretty(&pending)?);
+            print_structured(&pending)?;
         }
         return Ok(());
     }
@@ -211,14 +213,11 @@ async fn handle_folder_upload(
         let entries = walk_folder_summary(&args.path)?;
         let total_bytes: u64 = entries.iter().map(|(_, size)| size).sum();
         if json {
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&serde_json::json!({
-                    "dry_run": true,
-                    "files": entries.len(),
-                    "size_bytes": total_bytes,
-                }))?
-            );
+            print_structured(&serde_json::json!({
+                "dry_run": true,
+                "files": entries.len(),
+                "size_bytes": total_bytes,
+            }))?;
         } else {
             eprintln!(
                 "Dry run: would upload {} file(s), {} bytes total. No HTTP calls made.",
@@ -252,8 +251,8 @@ async fn handle_folder_upload(
     if let Some(reference) = args.reference {
         builder = builder.reference(reference);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
 
@@ -300,8 +299,8 @@ async fn handle_folder_upload_via_gateway(
     if let Some(reference) = args.reference {
         builder = builder.reference(reference);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, false, json).await?;
@@ -347,8 +346,8 @@ async fn handle_file_pin(
     if let Some(reference) = args.reference {
         builder = builder.reference(reference);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
 
@@ -399,9 +398,18 @@ async fn handle_file_download(
         use std::io::Write;
         std::io::stdout().write_all(&bytes)?;
     } else {
-        let output = args.output.unwrap_or_else(|| file_hash.to_string().into());
-        download.to_file(&output).await?;
-        if !json {
+        let output = args
+            .output_file
+            .unwrap_or_else(|| file_hash.to_string().into());
+        if json {
+            download.to_file(&output).await?;
+        } else {
+            let saved = download
+                .with_progress(crate::common::render_download_progress)
+                .to_file(&output)
+                .await;
+            eprintln!();
+            saved?;
             eprintln!("Saved to {}", output.display());
         }
     }
@@ -439,7 +447,7 @@ async fn handle_file_list(
         .await?;
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&files)?);
+        print_structured(&files)?;
     } else {
         // Fetch the address-wide total separately. Cursor pages carry their
         // own copy of `total_size`, but the iterator hides them; one extra
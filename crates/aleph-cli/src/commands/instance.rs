@@ -1,9 +1,10 @@
 use crate::cli::{
     ImageRef, InstanceCommand, InstanceCreateArgs, InstanceDeleteArgs, InstanceListArgs,
-    InstancePriceArgs, parse_size_to_mib,
+    InstancePriceArgs, PaymentTypeCli, parse_size_to_mib,
 };
 use crate::common::{
-    confirm_action, resolve_account, resolve_address, resolve_address_or_active, submit_or_preview,
+    confirm_action, print_structured, resolve_account, resolve_address, resolve_address_or_active,
+    resolve_channel, submit_or_preview,
 };
 use aleph_sdk::aggregate_models::vm_images::{VmImagesData, VmImagesError};
 use aleph_sdk::caching_aggregate_client::CachingAggregateClient;
@@ -15,9 +16,8 @@ use aleph_sdk::scheduler::{SchedulerClient, VmEntry};
 use aleph_sdk::ssh::{AlephSshClient, SshKey};
 use aleph_types::account::Account;
 use aleph_types::chain::Address;
-use aleph_types::channel::Channel;
 use aleph_types::item_hash::ItemHash;
-use aleph_types::message::execution::base::{Payment, PaymentType};
+use aleph_types::message::execution::base::Payment;
 use aleph_types::message::execution::environment::{
     GpuDeviceClass, GpuProperties, HostRequirements, Hypervisor, NodeRequirements,
     TrustedExecutionEnvironment,
@@ -503,7 +503,7 @@ fn format_rows_text(rows: &[InstanceRow]) -> String {
 
 fn render_rows(rows: &[InstanceRow], json: bool) -> Result<()> {
     if json {
-        println!("{}", serde_json::to_string_pretty(&format_rows_json(rows))?);
+        print_structured(&format_rows_json(rows))?;
     } else {
         print!("{}", format_rows_text(rows));
     }
@@ -584,7 +584,7 @@ pub async fn handle_instance_command(
         }
         InstanceCommand::Confidential(sub) => {
             let scheduler_url = crate::common::resolve_scheduler_url(network_override)?;
-            super::confidential::dispatch(scheduler_url, json, sub).await?;
+            super::confidential::dispatch(aleph_client, ccn_url, scheduler_url, json, sub).await?;
         }
     }
     Ok(())
@@ -934,13 +934,15 @@ pub(crate) fn resolve_runtime_ref(
     Ok(resolved)
 }
 
-async fn handle_instance_create(
+/// Builds and submits an INSTANCE message, returning its item hash (also on
+/// `--dry-run`, where nothing is submitted).
+pub(crate) async fn handle_instance_create(
     aleph_client: &AlephClient,
     ccn_url: &Url,
     scheduler_url: &Url,
     json: bool,
     mut args: InstanceCreateArgs,
-) -> Result<()> {
+) -> Result<ItemHash> {
     let dry_run = args.signing.dry_run;
     let wait = args.wait;
     let account = resolve_account(&args.signing.identity)?;
@@ -1126,10 +1128,9 @@ async fn handle_instance_create(
         .vcpus(vcpus)
         .memory(MiB::from(memory_mib))
         .hypervisor(Hypervisor::Qemu)
-        .payment(Payment {
-            chain: None,
-            receiver: None,
-            payment_type: PaymentType::Credit,
+        .payment(match args.payment_type.unwrap_or(PaymentTypeCli::Credit) {
+            PaymentTypeCli::Hold => Payment::hold(),
+            PaymentTypeCli::Credit => Payment::credits(),
         })
         .ssh_keys(ssh_keys);
 
@@ -1184,8 +1185,8 @@ async fn handle_instance_create(
         builder = builder.volumes(volumes);
     }
 
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
 
     let pending = builder.build()?;
@@ -1203,11 +1204,11 @@ async fn handle_instance_create(
         match crate::commands::instance_wait::wait_until_ready(scheduler_url, &vm_id, wait_timeout)
             .await?
         {
-            WaitOutcome::Ready(conn) => report_ready(&conn, &vm_id, json),
-            WaitOutcome::Timeout => report_timeout(&vm_id, json),
+            WaitOutcome::Ready(conn) => report_ready(&conn, &vm_id, json)?,
+            WaitOutcome::Timeout => report_timeout(&vm_id, json)?,
         }
     }
-    Ok(())
+    Ok(vm_id)
 }
 
 /// A selectable GPU model: pricing/sizing info (from the pricing aggregate)
@@ -1664,22 +1665,19 @@ async fn handle_instance_price(
     let total_dollars = total_credits * 1e-6;
 
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "size": size_slug,
-                "compute_units": compute_units,
-                "vcpus": vcpus,
-                "memory_mib": memory_mib,
-                "disk_mib": disk_mib,
-                "gpu": gpu_option.map(|o| o.model_id.clone()),
-                "confidential": args.confidential,
-                "compute_credits_per_hour": compute_credits,
-                "storage_credits_per_hour": extra_storage_credits,
-                "total_credits_per_hour": total_credits,
-                "dollars_per_hour": total_dollars,
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "size": size_slug,
+            "compute_units": compute_units,
+            "vcpus": vcpus,
+            "memory_mib": memory_mib,
+            "disk_mib": disk_mib,
+            "gpu": gpu_option.map(|o| o.model_id.clone()),
+            "confidential": args.confidential,
+            "compute_credits_per_hour": compute_credits,
+            "storage_credits_per_hour": extra_storage_credits,
+            "total_credits_per_hour": total_credits,
+            "dollars_per_hour": total_dollars,
+        }))?;
     } else {
         if let Some(slug) = &size_slug {
             eprintln!("Size:    {slug}");
@@ -11,7 +11,7 @@ use crate::cli::{
     InstanceBackupDownloadArgs, InstanceBackupInfoArgs, InstanceBackupRestoreArgs, SigningArgs,
 };
 use crate::commands::instance_target::resolve_target;
-use crate::common::resolve_account;
+use crate::common::{print_structured, resolve_account};
 
 pub async fn dispatch(scheduler_url: Url, json: bool, sub: InstanceBackupCommand) -> Result<()> {
     match sub {
@@ -74,25 +74,21 @@ async fn handle_create(
     } else {
         initial
     };
-    render_create_result(&vm_id, json, &result);
-    Ok(())
+    render_create_result(&vm_id, json, &result)
 }
 
 fn render_create_result(
     vm_id: &aleph_types::item_hash::ItemHash,
     json: bool,
     result: &aleph_sdk::crn::CreateBackup,
-) {
+) -> Result<()> {
     use aleph_sdk::crn::CreateBackup;
     if json {
         match result {
-            CreateBackup::Started => println!(
-                "{}",
-                serde_json::json!({"vm_id": vm_id.to_string(), "status": "queued"})
+            CreateBackup::Started => print_structured(
+                &serde_json::json!({"vm_id": vm_id.to_string(), "status": "queued"}),
             ),
-            CreateBackup::Complete(meta) => {
-                println!("{}", serde_json::to_string_pretty(meta).unwrap())
-            }
+            CreateBackup::Complete(meta) => print_structured(meta),
         }
     } else {
         match result {
@@ -108,6 +104,7 @@ fn render_create_result(
                 println!("download_url {}", meta.download_url);
             }
         }
+        Ok(())
     }
 }
 
@@ -161,13 +158,13 @@ async fn handle_info(scheduler_url: Url, json: bool, args: InstanceBackupInfoArg
     if json {
         match status {
             BackupStatus::InProgress => {
-                println!("{}", serde_json::json!({"status": "in_progress"}));
+                print_structured(&serde_json::json!({"status": "in_progress"}))?;
             }
             BackupStatus::Complete(meta) => {
-                println!("{}", serde_json::to_string_pretty(&meta)?);
+                print_structured(&meta)?;
             }
             BackupStatus::NotFound => {
-                println!("{}", serde_json::json!({"status": "not_found"}));
+                print_structured(&serde_json::json!({"status": "not_found"}))?;
             }
         }
     } else {
@@ -308,14 +305,11 @@ async fn download_and_render(
     tokio::fs::rename(&part, &output).await?;
 
     if json {
-        println!(
-            "{}",
-            serde_json::json!({
-                "path": output.to_string_lossy(),
-                "bytes": written,
-                "checksum": format!("sha256:{digest}")
-            })
-        );
+        print_structured(&serde_json::json!({
+            "path": output.to_string_lossy(),
+            "bytes": written,
+            "checksum": format!("sha256:{digest}")
+        }))?;
     } else {
         eprintln!(
             "Saved {written} bytes to {} (sha256:{digest}).",
@@ -567,7 +561,7 @@ async fn handle_download(
         .ok()
         .filter(|u| !u.scheme().is_empty() && u.has_host());
     if let Some(direct_url) = parsed_url {
-        return download_from_url(direct_url, args.output, json).await;
+        return download_from_url(direct_url, args.output_file, json).await;
     }
 
     let (vm_id, crn_url) =
@@ -583,7 +577,9 @@ async fn handle_download(
         ),
     };
 
-    let output = args.output.unwrap_or_else(|| default_output_path(&vm_id));
+    let output = args
+        .output_file
+        .unwrap_or_else(|| default_output_path(&vm_id));
     eprintln!("Downloading backup for {vm_id} ({} bytes)...", meta.size);
     download_and_render(
         client.http_client(),
@@ -605,14 +601,11 @@ async fn handle_delete(
     client.delete_backup(&vm_id, &args.backup_id).await?;
 
     if json {
-        println!(
-            "{}",
-            serde_json::json!({
-                "vm_id": vm_id.to_string(),
-                "backup_id": args.backup_id,
-                "status": "deleted"
-            })
-        );
+        print_structured(&serde_json::json!({
+            "vm_id": vm_id.to_string(),
+            "backup_id": args.backup_id,
+            "status": "deleted"
+        }))?;
     } else {
         eprintln!("Deleted backup {} for {vm_id}.", args.backup_id);
     }
@@ -634,7 +627,7 @@ async fn handle_restore(
     };
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&response)?);
+        print_structured(&response)?;
     } else {
         eprintln!(
             "Restored {} (status: {}).",
@@ -909,7 +902,7 @@ mod tests {
         let scheduler_url = Url::parse("http://unused.invalid/").unwrap();
         let args = InstanceBackupDownloadArgs {
             vm_id_or_url: FULL_HASH.to_string(),
-            output: Some(output.clone()),
+            output_file: Some(output.clone()),
             crn: Some(crn_server.uri()),
             signing: evm_signing_args(),
         };
@@ -947,7 +940,7 @@ mod tests {
         let scheduler_url = Url::parse("http://unused.invalid/").unwrap();
         let args = InstanceBackupDownloadArgs {
             vm_id_or_url: FULL_HASH.to_string(),
-            output: Some(output.clone()),
+            output_file: Some(output.clone()),
             crn: Some(crn_server.uri()),
             signing: evm_signing_args(),
         };
@@ -995,7 +988,7 @@ mod tests {
         let scheduler_url = Url::parse("http://unused.invalid/").unwrap();
         let args = InstanceBackupDownloadArgs {
             vm_id_or_url: format!("{}/dl", server.uri()),
-            output: Some(output.clone()),
+            output_file: Some(output.clone()),
             crn: None,
             signing: evm_signing_args(),
         };
@@ -1022,7 +1015,7 @@ mod tests {
         let scheduler_url = Url::parse("http://unused.invalid/").unwrap();
         let args = InstanceBackupDownloadArgs {
             vm_id_or_url: format!("{}/dl", server.uri()),
-            output: Some(output.clone()),
+            output_file: Some(output.clone()),
             crn: None,
             signing: evm_signing_args(),
         };
@@ -5,6 +5,7 @@
 //! owner's port-forwarding aggregate.
 
 use crate::cli::InstanceShowArgs;
+use crate::common::print_structured;
 use aleph_sdk::client::{AlephAggregateClient, AlephClient, AlephMessageClient, MessageWithStatus};
 use aleph_sdk::crn::fetch_active_vms;
 use aleph_sdk::scheduler::SchedulerClient;
@@ -191,7 +192,7 @@ pub(crate) fn build_from_message(message: &Message) -> anyhow::Result<InstanceSh
     let payment = content.base.payment.as_ref().map(payment_info);
 
     let resources = Resources {
-        vcpus: content.base.resources.vcpus,
+        vcpus: content.base.resources.vcpus.get(),
         memory_mib: u64::from(content.base.resources.memory),
         hypervisor: content.environment.hypervisor.as_ref().map(hypervisor_str),
         gpus: content
@@ -706,7 +707,7 @@ fn format_proto(flags: &aleph_sdk::aggregate_models::port_forwarding::PortFlags)
     }
 }
 
-async fn fetch_instance_message(
+pub(crate) async fn fetch_instance_message(
     aleph_client: &AlephClient,
     item_hash: &ItemHash,
 ) -> Result<Message> {
@@ -800,7 +801,7 @@ pub async fn handle_instance_show(
 ) -> Result<()> {
     let show = build_instance_show(aleph_client, scheduler_url, &args).await?;
     if json {
-        println!("{}", serde_json::to_string_pretty(&render_json(&show))?);
+        print_structured(&render_json(&show))?;
     } else {
         print!("{}", render_text(&show));
     }
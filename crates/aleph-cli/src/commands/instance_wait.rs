@@ -17,6 +17,8 @@ use aleph_sdk::scheduler::SchedulerClient;
 use aleph_types::item_hash::ItemHash;
 use url::Url;
 
+use crate::common::print_structured;
+
 /// Interval between successive polls.
 pub(crate) const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -149,16 +151,20 @@ pub(crate) async fn wait_until_ready(
 
 /// Report a successful wait to the user. Human output goes to stderr (so it
 /// does not pollute `--json` consumers); the SSH hint references the item
-/// hash. When `json` is set, the connectivity is merged into the caller's
-/// JSON object instead.
-pub(crate) fn report_ready(conn: &Connectivity, vm_id: &ItemHash, json: bool) {
+/// hash. When `json` is set, the connectivity is printed as a structured
+/// document instead.
+pub(crate) fn report_ready(
+    conn: &Connectivity,
+    vm_id: &ItemHash,
+    json: bool,
+) -> anyhow::Result<()> {
     if json {
         let payload = serde_json::json!({
             "ready": true,
             "ipv6": conn.ipv6,
             "ipv4": conn.ipv4,
         });
-        println!("{payload}");
+        print_structured(&payload)?;
     } else {
         eprintln!("Instance ready.");
         if let Some(ipv6) = &conn.ipv6 {
@@ -169,24 +175,26 @@ pub(crate) fn report_ready(conn: &Connectivity, vm_id: &ItemHash, json: bool) {
         }
         eprintln!("  SSH:  aleph instance ssh {vm_id}");
     }
+    Ok(())
 }
 
 /// Report a wait timeout. The create/start itself succeeded; this only tells
 /// the user the VM is not reachable yet.
-pub(crate) fn report_timeout(vm_id: &ItemHash, json: bool) {
+pub(crate) fn report_timeout(vm_id: &ItemHash, json: bool) -> anyhow::Result<()> {
     if json {
         let payload = serde_json::json!({
             "ready": false,
             "ipv6": serde_json::Value::Null,
             "ipv4": serde_json::Value::Null,
         });
-        println!("{payload}");
+        print_structured(&payload)?;
     } else {
         eprintln!(
             "warning: instance not reachable yet; \
              check with `aleph instance show {vm_id} --verbose`"
         );
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,14 +1,14 @@
 use crate::cli::{ForgetArgs, GetMessageArgs, MessageCommand, RetryArgs, SigningArgs};
 use crate::common::{
-    confirm_action, repost_or_preview, resolve_account, resolve_address, submit_or_preview,
+    confirm_action, format_epoch_for_tty, format_table, print_structured, repost_or_preview,
+    resolve_account, resolve_address, resolve_channel, submit_or_preview,
 };
 use aleph_sdk::builder::MessageBuilder;
 use aleph_sdk::client::{AlephClient, AlephMessageClient, MessageWithStatus};
-use aleph_types::channel::Channel;
 use aleph_types::item_hash::ItemHash;
-use aleph_types::message::MessageType;
 use aleph_types::message::item_type::ItemType;
 use aleph_types::message::pending::PendingMessage;
+use aleph_types::message::{Message, MessageType};
 use anyhow::{Result, anyhow, bail};
 use futures_util::{StreamExt, TryStreamExt};
 use url::Url;
@@ -22,7 +22,10 @@ pub async fn handle_message_command(
     match command {
         MessageCommand::Get(GetMessageArgs { item_hash }) => {
             let message = aleph_client.get_message(&item_hash).await?;
-            println!("{}", serde_json::to_string_pretty(&message)?);
+            print_structured(&message)?;
+        }
+        MessageCommand::History(args) => {
+            super::message_history::handle_message_history(aleph_client, json, args).await?;
         }
         MessageCommand::List(args) => {
             let messages: Vec<_> = aleph_client
@@ -30,7 +33,11 @@ pub async fn handle_message_command(
                 .take(args.count as usize)
                 .try_collect()
                 .await?;
-            println!("{}", serde_json::to_string_pretty(&messages)?);
+            if json {
+                print_structured(&messages)?;
+            } else {
+                print!("{}", format_message_table(&messages));
+            }
         }
         MessageCommand::Sync(sync_args) => {
             super::sync::handle_sync(*sync_args).await?;
@@ -41,11 +48,35 @@ pub async fn handle_message_command(
         MessageCommand::Retry(args) => {
             handle_retry(aleph_client, ccn_url, json, args).await?;
         }
+        MessageCommand::Watch(args) => {
+            super::message_watch::handle_message_watch(aleph_client, ccn_url, json, *args).await?;
+        }
     }
 
     Ok(())
 }
 
+fn format_message_table(messages: &[Message]) -> String {
+    let rows: Vec<Vec<String>> = messages
+        .iter()
+        .map(|m| {
+            vec![
+                m.item_hash.to_string(),
+                m.message_type.to_string(),
+                m.sender.to_string(),
+                format_epoch_for_tty(m.time.as_f64()),
+                if m.confirmed() {
+                    "confirmed"
+                } else {
+                    "unconfirmed"
+                }
+                .to_string(),
+            ]
+        })
+        .collect();
+    format_table(&["HASH", "TYPE", "SENDER", "TIME", "STATUS"], &rows)
+}
+
 async fn handle_forget(
     aleph_client: &AlephClient,
     ccn_url: &Url,
@@ -119,8 +150,8 @@ pub async fn forget_targets(
     if let Some(owner) = targets.on_behalf_of {
         builder = builder.on_behalf_of(resolve_address(&owner)?);
     }
-    if let Some(ch) = targets.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(targets.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
@@ -171,12 +202,30 @@ async fn handle_retry(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::test_utils::inline_message;
     use aleph_sdk::client::AlephClient;
     use aleph_types::item_hash::ItemHash;
     use url::Url;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
+    #[test]
+    fn message_table_lists_hash_type_sender_time_and_status() {
+        let message: Message = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../fixtures/messages/post/post.json"
+        )))
+        .unwrap();
+        let table = format_message_table(std::slice::from_ref(&message));
+        let mut lines = table.lines();
+        assert!(lines.next().unwrap().starts_with("HASH"));
+        let row = lines.next().unwrap();
+        assert!(row.starts_with(&message.item_hash.to_string()));
+        assert!(row.contains(&message.sender.to_string()));
+        assert!(row.contains("POST"));
+        assert!(row.ends_with("unconfirmed"));
+    }
+
     #[test]
     fn forget_envelope_shape() {
         let hashes = vec!["abc123".to_string()];
@@ -197,23 +246,10 @@ mod tests {
 
     /// Build the `{status, message: ...}` envelope returned by GET /api/v0/messages/{hash}.
     fn processed_envelope() -> serde_json::Value {
-        serde_json::json!({
-            "status": "processed",
-            "message": {
-                "sender": "0xABCD",
-                "chain": "ETH",
-                "signature": "0xSIG",
-                "type": "POST",
-                "item_type": "inline",
-                "item_content": "{\"type\":\"test\",\"address\":\"0xABCD\",\"time\":1234.0}",
-                "item_hash": HASH,
-                "time": 1234.0,
-                "channel": null,
-                "content": {"type": "test", "address": "0xABCD", "time": 1234.0},
-                "confirmed": true,
-                "confirmations": [],
-            }
-        })
+        let content = serde_json::json!({"type": "test", "address": "0xABCD", "time": 1234.0});
+        let mut message = inline_message("POST", HASH, content, 1234.0);
+        message["confirmed"] = true.into();
+        serde_json::json!({"status": "processed", "message": message})
     }
 
     /// Build a `{status: rejected}` envelope for an inline message.
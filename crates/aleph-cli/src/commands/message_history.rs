@@ -0,0 +1,447 @@
+//! `aleph message history` - lineage of a single message.
+//!
+//! Collects everything that happened to a message into one timeline: its
+//! publication, the amends of a post (resolved from either end of the chain),
+//! the on-chain confirmations of every revision and the FORGET messages that
+//! removed it.
+
+use crate::cli::MessageHistoryArgs;
+use crate::common::{format_epoch_for_tty, print_structured};
+use aleph_sdk::client::{AlephClient, AlephMessageClient, MessageWithStatus};
+use aleph_types::chain::{Address, Chain};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{ForgetContent, Message, MessageStatus, MessageType};
+use aleph_types::timestamp::Timestamp;
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HistoryEventKind {
+    Published,
+    Amended,
+    Confirmed,
+    Pending,
+    Rejected,
+    Removed,
+    Forgotten,
+}
+
+impl HistoryEventKind {
+    fn label(self) -> &'static str {
+        match self {
+            HistoryEventKind::Published => "published",
+            HistoryEventKind::Amended => "amended",
+            HistoryEventKind::Confirmed => "confirmed",
+            HistoryEventKind::Pending => "pending",
+            HistoryEventKind::Rejected => "rejected",
+            HistoryEventKind::Removed => "removed",
+            HistoryEventKind::Forgotten => "forgotten",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HistoryEvent {
+    /// `None` for confirmations the CCN did not timestamp and for FORGET
+    /// messages that could not be fetched.
+    pub time: Option<Timestamp>,
+    pub event: HistoryEventKind,
+    /// The message the event is about: the revision for publications and
+    /// confirmations, the FORGET message for forgets.
+    pub item_hash: ItemHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<Chain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Position on the timeline: `time`, or the time of the related message
+    /// when the event has none of its own.
+    #[serde(skip)]
+    sort_key: f64,
+}
+
+impl HistoryEvent {
+    fn new(event: HistoryEventKind, item_hash: ItemHash, time: Option<Timestamp>) -> Self {
+        Self {
+            sort_key: time.as_ref().map_or(f64::INFINITY, Timestamp::as_f64),
+            time,
+            event,
+            item_hash,
+            sender: None,
+            chain: None,
+            height: None,
+            tx_hash: None,
+            detail: None,
+        }
+    }
+
+    fn sender(mut self, sender: Address) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MessageHistory {
+    pub item_hash: ItemHash,
+    pub status: MessageStatus,
+    pub message_type: Option<MessageType>,
+    pub timeline: Vec<HistoryEvent>,
+}
+
+pub async fn handle_message_history(
+    aleph_client: &AlephClient,
+    json: bool,
+    args: MessageHistoryArgs,
+) -> Result<()> {
+    let history = build_history(aleph_client, &args.item_hash).await?;
+    if json {
+        print_structured(&history)?;
+    } else {
+        print_history(&history);
+    }
+    Ok(())
+}
+
+pub(crate) async fn build_history(
+    aleph_client: &AlephClient,
+    item_hash: &ItemHash,
+) -> Result<MessageHistory> {
+    let status = aleph_client.get_message(item_hash).await?;
+    let status_kind = status.status();
+    let mut timeline = Vec::new();
+
+    let message_type = match status {
+        MessageWithStatus::Processed { message } => {
+            revision_events(aleph_client, &message, &mut timeline).await?;
+            Some(message.message_type)
+        }
+        MessageWithStatus::Removing { message, reason }
+        | MessageWithStatus::Removed { message, reason } => {
+            revision_events(aleph_client, &message, &mut timeline).await?;
+            let reason = serde_json::to_string(&reason)?;
+            // The CCN does not date removals: list them last.
+            timeline.push(
+                HistoryEvent::new(HistoryEventKind::Removed, message.item_hash.clone(), None)
+                    .detail(format!("{status_kind}: {}", reason.trim_matches('"'))),
+            );
+            Some(message.message_type)
+        }
+        MessageWithStatus::Pending { messages } => {
+            let message_type = messages.first().map(|m| m.message_type);
+            for message in messages {
+                timeline.push(
+                    HistoryEvent::new(
+                        HistoryEventKind::Pending,
+                        message.item_hash,
+                        Some(message.time.into()),
+                    )
+                    .sender(message.sender),
+                );
+            }
+            message_type
+        }
+        MessageWithStatus::Rejected {
+            message,
+            error_code,
+        } => {
+            timeline.push(
+                HistoryEvent::new(
+                    HistoryEventKind::Rejected,
+                    message.item_hash,
+                    Some(message.time),
+                )
+                .sender(message.sender)
+                .detail(format!("error code {error_code}")),
+            );
+            Some(message.message_type)
+        }
+        MessageWithStatus::Forgotten {
+            message,
+            forgotten_by,
+        } => {
+            timeline.push(
+                HistoryEvent::new(
+                    HistoryEventKind::Published,
+                    message.item_hash,
+                    Some(Timestamp::from(message.time)),
+                )
+                .sender(message.sender)
+                .detail(message.message_type.to_string()),
+            );
+            for forget_hash in forgotten_by {
+                timeline.push(forget_event(aleph_client, forget_hash).await?);
+            }
+            Some(message.message_type)
+        }
+    };
+
+    // Stable: events sharing a time keep their discovery order, so a
+    // revision is listed before its own confirmations.
+    timeline.sort_by(|a, b| a.sort_key.total_cmp(&b.sort_key));
+    Ok(MessageHistory {
+        item_hash: item_hash.clone(),
+        status: status_kind,
+        message_type,
+        timeline,
+    })
+}
+
+/// Publication and confirmation events of `message`, or of every revision of
+/// the post it belongs to.
+async fn revision_events(
+    aleph_client: &AlephClient,
+    message: &Message,
+    timeline: &mut Vec<HistoryEvent>,
+) -> Result<()> {
+    if message.message_type != MessageType::Post {
+        push_revision(message, HistoryEventKind::Published, timeline);
+        return Ok(());
+    }
+    let history = aleph_client.get_post_history(&message.item_hash).await?;
+    push_revision(
+        history.original.message(),
+        HistoryEventKind::Published,
+        timeline,
+    );
+    for amend in &history.amends {
+        push_revision(amend, HistoryEventKind::Amended, timeline);
+    }
+    Ok(())
+}
+
+fn push_revision(message: &Message, kind: HistoryEventKind, timeline: &mut Vec<HistoryEvent>) {
+    let published = message.time.as_f64();
+    let mut event = HistoryEvent::new(kind, message.item_hash.clone(), Some(message.time.clone()))
+        .sender(message.sender.clone());
+    if kind == HistoryEventKind::Published {
+        event = event.detail(message.message_type.to_string());
+    }
+    timeline.push(event);
+
+    for confirmation in &message.confirmations {
+        let mut event = HistoryEvent::new(
+            HistoryEventKind::Confirmed,
+            message.item_hash.clone(),
+            confirmation.time.clone(),
+        );
+        if event.time.is_none() {
+            event.sort_key = published;
+        }
+        event.chain = Some(confirmation.chain.clone());
+        event.height = Some(confirmation.height);
+        event.tx_hash = Some(confirmation.hash.clone());
+        timeline.push(event);
+    }
+}
+
+async fn forget_event(aleph_client: &AlephClient, forget_hash: ItemHash) -> Result<HistoryEvent> {
+    let forget = match aleph_client.get_message(&forget_hash).await? {
+        MessageWithStatus::Processed { message }
+        | MessageWithStatus::Removing { message, .. }
+        | MessageWithStatus::Removed { message, .. } => message,
+        other => {
+            return Ok(
+                HistoryEvent::new(HistoryEventKind::Forgotten, forget_hash, None)
+                    .detail(format!("FORGET message is {}", other.status())),
+            );
+        }
+    };
+    let mut event = HistoryEvent::new(
+        HistoryEventKind::Forgotten,
+        forget.item_hash.clone(),
+        Some(forget.time.clone()),
+    )
+    .sender(forget.sender.clone());
+    if let Some(reason) = forget
+        .typed_content::<ForgetContent>()
+        .and_then(ForgetContent::reason)
+    {
+        event = event.detail(format!("reason: {reason}"));
+    }
+    Ok(event)
+}
+
+fn print_history(history: &MessageHistory) {
+    match history.message_type {
+        Some(message_type) => println!("{message_type} {} ({})", history.item_hash, history.status),
+        None => println!("{} ({})", history.item_hash, history.status),
+    }
+    if history.timeline.is_empty() {
+        println!("(no events)");
+        return;
+    }
+    for event in &history.timeline {
+        let time = event
+            .time
+            .as_ref()
+            .map(|t| format_epoch_for_tty(t.as_f64()))
+            .unwrap_or_else(|| "-".to_string());
+        let mut line = format!(
+            "{time:<23}  {:<10} {}",
+            event.event.label(),
+            event.item_hash
+        );
+        if let Some(sender) = &event.sender {
+            line.push_str(&format!(" by {sender}"));
+        }
+        if let (Some(chain), Some(height)) = (&event.chain, event.height) {
+            line.push_str(&format!(" on {chain} at height {height}"));
+        }
+        if let Some(tx_hash) = &event.tx_hash {
+            line.push_str(&format!(" (tx {tx_hash})"));
+        }
+        if let Some(detail) = &event.detail {
+            line.push_str(&format!(" - {detail}"));
+        }
+        println!("{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::inline_message;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ORIGINAL: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+    const AMEND: &str = "0000000000000000000000000000000000000000000000000000000000000002";
+    const FORGET: &str = "0000000000000000000000000000000000000000000000000000000000000003";
+
+    fn post(
+        hash: &str,
+        content_type: &str,
+        reference: Option<&str>,
+        time: f64,
+    ) -> serde_json::Value {
+        let mut content = serde_json::json!({
+            "type": content_type,
+            "address": "0xABCD",
+            "time": time,
+            "content": {"body": hash},
+        });
+        if let Some(reference) = reference {
+            content["ref"] = reference.into();
+        }
+        let mut message = inline_message("POST", hash, content, time);
+        message["confirmed"] = true.into();
+        message["confirmations"] = serde_json::json!([
+            {"chain": "ETH", "height": 42, "hash": "0xTX", "time": time + 60.0},
+        ]);
+        message
+    }
+
+    #[tokio::test]
+    async fn post_history_lists_amends_and_confirmations_in_order() {
+        let server = MockServer::start().await;
+        let original = post(ORIGINAL, "note", None, 1000.0);
+        let amend = post(AMEND, "amend", Some(ORIGINAL), 2000.0);
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{AMEND}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"status": "processed", "message": amend.clone()}),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{ORIGINAL}")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"status": "processed", "message": original})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("refs", ORIGINAL))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "messages": [amend],
+                "next_cursor": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let history = build_history(&client, &AMEND.parse().unwrap())
+            .await
+            .unwrap();
+
+        let events: Vec<_> = history
+            .timeline
+            .iter()
+            .map(|e| (e.event, e.item_hash.to_string()))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (HistoryEventKind::Published, ORIGINAL.to_string()),
+                (HistoryEventKind::Confirmed, ORIGINAL.to_string()),
+                (HistoryEventKind::Amended, AMEND.to_string()),
+                (HistoryEventKind::Confirmed, AMEND.to_string()),
+            ]
+        );
+        assert_eq!(history.timeline[1].height, Some(42));
+    }
+
+    #[tokio::test]
+    async fn forgotten_message_lists_its_forgets() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{ORIGINAL}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "forgotten",
+                "message": {
+                    "sender": "0xABCD",
+                    "chain": "ETH",
+                    "signature": "0xSIG",
+                    "type": "POST",
+                    "item_hash": ORIGINAL,
+                    "time": "2024-01-01T00:00:00Z",
+                    "channel": null,
+                },
+                "forgotten_by": [FORGET],
+            })))
+            .mount(&server)
+            .await;
+        let content = serde_json::json!({
+            "address": "0xABCD",
+            "time": 1704067260.0,
+            "hashes": [ORIGINAL],
+            "reason": "cleanup",
+        });
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{FORGET}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "processed",
+                "message": inline_message("FORGET", FORGET, content, 1704067260.0),
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let history = build_history(&client, &ORIGINAL.parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(history.status, MessageStatus::Forgotten);
+        assert_eq!(history.timeline.len(), 2);
+        assert_eq!(history.timeline[0].event, HistoryEventKind::Published);
+        let forget = &history.timeline[1];
+        assert_eq!(forget.event, HistoryEventKind::Forgotten);
+        assert_eq!(forget.item_hash.to_string(), FORGET);
+        assert_eq!(forget.detail.as_deref(), Some("reason: cleanup"));
+    }
+}
@@ -0,0 +1,96 @@
+//! `aleph message watch` - live stream of the messages matching a filter.
+//!
+//! Messages come from the CCN websocket. By default they go through
+//! [`AlephClient::watch_messages`], which backfills the messages missed while
+//! the connection was re-established; `--no-backfill` uses the bare
+//! subscription instead.
+
+use crate::cli::MessageWatchArgs;
+use crate::common::{format_epoch_for_tty, print_structured_entry};
+use aleph_sdk::client::{AlephClient, AlephMessageClient, MessageError, MessageFilter};
+use aleph_types::message::Message;
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use url::Url;
+
+pub async fn handle_message_watch(
+    aleph_client: &AlephClient,
+    ccn_url: &Url,
+    json: bool,
+    args: MessageWatchArgs,
+) -> Result<()> {
+    let filter: MessageFilter = args.filter.clone().into();
+    if !json {
+        eprintln!("Watching {ccn_url}... (Ctrl-C to stop)");
+    }
+    if args.no_backfill {
+        let messages = aleph_client.subscribe_to_messages(&filter, None).await?;
+        print_messages(messages, json, &args).await
+    } else {
+        let messages = aleph_client.watch_messages(filter).await?;
+        print_messages(messages, json, &args).await
+    }
+}
+
+async fn print_messages(
+    mut messages: impl Stream<Item = Result<Message, MessageError>> + Unpin,
+    json: bool,
+    args: &MessageWatchArgs,
+) -> Result<()> {
+    let mut printed = 0u64;
+    while let Some(item) = messages.next().await {
+        match item {
+            Ok(message) => {
+                if json {
+                    print_structured_entry(&message)?;
+                } else {
+                    println!("{}", format_message_line(&message));
+                }
+                printed += 1;
+                if args.count.is_some_and(|count| printed >= count) {
+                    break;
+                }
+            }
+            // The subscription reconnects on its own; the error only ends the
+            // watch when asked to.
+            Err(e) if args.no_reconnect => return Err(e.into()),
+            Err(e) => eprintln!("  {e}, reconnecting..."),
+        }
+    }
+    Ok(())
+}
+
+fn format_message_line(message: &Message) -> String {
+    let mut line = format!(
+        "{:<23}  {:<10} {} by {}",
+        format_epoch_for_tty(message.time.as_f64()),
+        message.message_type.to_string(),
+        message.item_hash,
+        message.sender,
+    );
+    if let Some(channel) = &message.channel {
+        line.push_str(&format!(" on {channel}"));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POST_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../fixtures/messages/post/post.json"
+    ));
+
+    #[test]
+    fn message_line_shows_time_type_hash_sender_and_channel() {
+        let message: Message = serde_json::from_str(POST_FIXTURE).unwrap();
+        assert_eq!(
+            format_message_line(&message),
+            "2025-11-07 11:37:11 UTC  POST       \
+             d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c \
+             by 0xB68B9D4f3771c246233823ed1D3Add451055F9Ef on TEST"
+        );
+    }
+}
@@ -15,10 +15,14 @@ pub mod instance_ssh;
 pub mod instance_target;
 pub mod instance_wait;
 pub mod message;
+pub mod message_history;
+pub mod message_watch;
 pub mod node;
 pub mod port_forward;
 pub mod post;
 pub mod program;
 pub mod sync;
+#[cfg(test)]
+mod test_utils;
 pub mod token;
 pub mod website;
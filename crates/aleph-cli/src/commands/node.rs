@@ -1,21 +1,69 @@
 use crate::cli::{NodeCommand, NodeListArgs, NodeTypeCli};
-use crate::common::{resolve_account, resolve_address, resolve_network, submit_or_preview};
-use aleph_sdk::aggregate_models::corechannel::{CORECHANNEL_ADDRESS, CcnInfo, CrnInfo, CrnStatus};
+use crate::common::{
+    format_table, print_structured, resolve_account, resolve_address, resolve_network,
+    submit_or_preview,
+};
+use aleph_sdk::aggregate_models::corechannel::{
+    CORECHANNEL_ADDRESS, CcnInfo, CoreChannelContent, CrnInfo, CrnStatus,
+};
 use aleph_sdk::client::{AlephAggregateClient, AlephClient};
 use aleph_sdk::corechannel::{self, AmendDetails};
 use aleph_types::account::Account;
 use aleph_types::chain::Address;
 use anyhow::{Result, bail};
 use serde::Serialize;
+use std::collections::HashSet;
 use url::Url;
 
 #[derive(Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
+#[allow(clippy::large_enum_variant)]
 enum NodeInfo {
     Ccn(CcnInfo),
     Crn(CrnInfo),
 }
 
+/// Criteria of `aleph node list`.
+struct NodeFilter<'a> {
+    owner: Option<&'a Address>,
+    node_type: Option<NodeTypeCli>,
+    score_min: Option<f64>,
+    /// Hashes of the CRNs with GPU support, when only those are wanted.
+    gpu_nodes: Option<&'a HashSet<String>>,
+}
+
+impl NodeFilter<'_> {
+    fn select(&self, corechannel: CoreChannelContent) -> Vec<NodeInfo> {
+        let keep = |owner: &Address, score: f64| {
+            self.owner.is_none_or(|a| a == owner) && self.score_min.is_none_or(|min| score >= min)
+        };
+        let mut nodes = Vec::new();
+        if !matches!(self.node_type, Some(NodeTypeCli::Crn)) {
+            nodes.extend(
+                corechannel
+                    .nodes
+                    .into_iter()
+                    .filter(|ccn| keep(&ccn.owner, ccn.score))
+                    .map(NodeInfo::Ccn),
+            );
+        }
+        if !matches!(self.node_type, Some(NodeTypeCli::Ccn)) {
+            nodes.extend(
+                corechannel
+                    .resource_nodes
+                    .into_iter()
+                    .filter(|crn| keep(&crn.owner, crn.score))
+                    .filter(|crn| {
+                        self.gpu_nodes
+                            .is_none_or(|gpu_nodes| gpu_nodes.contains(&crn.hash.to_string()))
+                    })
+                    .map(NodeInfo::Crn),
+            );
+        }
+        nodes
+    }
+}
+
 pub async fn handle_node_command(
     aleph_client: &AlephClient,
     ccn_url: &Url,
@@ -25,6 +73,7 @@ pub async fn handle_node_command(
 ) -> Result<()> {
     match command {
         NodeCommand::List(args) => list_nodes(aleph_client, json, args).await,
+        NodeCommand::Status => node_status(aleph_client, ccn_url, json).await,
         NodeCommand::CreateCcn(args) => {
             let tag = resolve_effective_tag(args.network_tag.as_deref(), cli_network)?;
             let account = resolve_account(&args.signing.identity)?;
@@ -113,6 +162,33 @@ fn resolve_effective_tag(override_tag: Option<&str>, cli_network: Option<&str>)
     Ok(entry.name)
 }
 
+async fn node_status(aleph_client: &AlephClient, ccn_url: &Url, json: bool) -> Result<()> {
+    let info = aleph_client.get_node_info().await?;
+    if json {
+        print_structured(&info)?;
+        return Ok(());
+    }
+
+    eprintln!("CCN      {ccn_url}");
+    eprintln!("Version  {}", info.version.as_deref().unwrap_or("unknown"));
+    if let Some(sync) = &info.sync {
+        if let Some(height) = sync.eth_height {
+            let behind = sync
+                .eth_height_remaining
+                .map(|remaining| format!(" ({remaining} blocks behind)"))
+                .unwrap_or_default();
+            eprintln!("ETH sync {height}{behind}");
+        }
+        if let Some(pending) = sync.pending_messages {
+            eprintln!("Pending  {pending} messages");
+        }
+    }
+    for multiaddress in &info.multiaddresses {
+        eprintln!("P2P      {multiaddress}");
+    }
+    Ok(())
+}
+
 async fn list_nodes(aleph_client: &AlephClient, json: bool, args: NodeListArgs) -> Result<()> {
     let filter_address = if args.all {
         None
@@ -136,52 +212,193 @@ async fn list_nodes(aleph_client: &AlephClient, json: bool, args: NodeListArgs)
     };
     let aggregate = aleph_client.get_corechannel_aggregate(&cc_address).await?;
 
-    let mut nodes: Vec<NodeInfo> = Vec::new();
-
-    let include_ccn = !matches!(args.r#type, Some(NodeTypeCli::Crn));
-    let include_crn = !matches!(args.r#type, Some(NodeTypeCli::Ccn));
-
-    if include_ccn {
-        for ccn in aggregate.corechannel.nodes {
-            if filter_address.as_ref().is_none_or(|a| *a == ccn.owner) {
-                nodes.push(NodeInfo::Ccn(ccn));
-            }
-        }
+    let node_type = match args.r#type {
+        Some(node_type) => Some(node_type),
+        None if args.ccn => Some(NodeTypeCli::Ccn),
+        None if args.crn || args.gpu => Some(NodeTypeCli::Crn),
+        None => None,
+    };
+    if args.gpu && matches!(node_type, Some(NodeTypeCli::Ccn)) {
+        bail!("--gpu only applies to CRNs");
     }
+    let gpu_nodes: Option<HashSet<String>> = if args.gpu {
+        let list = crate::commands::instance::fetch_crn_list().await?;
+        Some(
+            list.crns
+                .into_iter()
+                .filter(|crn| crn.gpu_support)
+                .map(|crn| crn.hash)
+                .collect(),
+        )
+    } else {
+        None
+    };
 
-    if include_crn {
-        for crn in aggregate.corechannel.resource_nodes {
-            if filter_address.as_ref().is_none_or(|a| *a == crn.owner) {
-                nodes.push(NodeInfo::Crn(crn));
-            }
-        }
-    }
+    let filter = NodeFilter {
+        owner: filter_address.as_ref(),
+        node_type,
+        score_min: args.score_min,
+        gpu_nodes: gpu_nodes.as_ref(),
+    };
+    let nodes = filter.select(aggregate.corechannel);
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&nodes)?);
+        print_structured(&nodes)?;
     } else if nodes.is_empty() {
         match &filter_address {
             Some(addr) => eprintln!("No nodes found for {addr}"),
             None => eprintln!("No nodes found"),
         }
     } else {
-        for node in &nodes {
-            match node {
-                NodeInfo::Ccn(ccn) => {
-                    eprintln!("CCN  {}  {}  score: {:.2}", ccn.hash, ccn.name, ccn.score,);
-                }
-                NodeInfo::Crn(crn) => {
-                    let status = match &crn.status {
-                        CrnStatus::Linked { parent } => format!("linked (parent: {parent})"),
-                        CrnStatus::Waiting => "waiting".to_string(),
-                    };
-                    eprintln!(
-                        "CRN  {}  {}  score: {:.2}  status: {}",
-                        crn.hash, crn.name, crn.score, status,
-                    );
-                }
-            }
+        let ccns: Vec<&CcnInfo> = nodes
+            .iter()
+            .filter_map(|node| match node {
+                NodeInfo::Ccn(ccn) => Some(ccn),
+                NodeInfo::Crn(_) => None,
+            })
+            .collect();
+        let crns: Vec<&CrnInfo> = nodes
+            .iter()
+            .filter_map(|node| match node {
+                NodeInfo::Crn(crn) => Some(crn),
+                NodeInfo::Ccn(_) => None,
+            })
+            .collect();
+        if !ccns.is_empty() {
+            print!("{}", format_ccn_table(&ccns));
+        }
+        if !ccns.is_empty() && !crns.is_empty() {
+            println!();
+        }
+        if !crns.is_empty() {
+            print!("{}", format_crn_table(&crns));
         }
     }
     Ok(())
 }
+
+fn format_ccn_table(ccns: &[&CcnInfo]) -> String {
+    let rows: Vec<Vec<String>> = ccns
+        .iter()
+        .map(|ccn| {
+            vec![
+                ccn.hash.to_string(),
+                ccn.name.clone(),
+                format!("{:.2}", ccn.score),
+                ccn.owner.to_string(),
+                ccn.multiaddress.clone(),
+            ]
+        })
+        .collect();
+    format_table(&["CCN", "NAME", "SCORE", "OWNER", "MULTIADDRESS"], &rows)
+}
+
+fn format_crn_table(crns: &[&CrnInfo]) -> String {
+    let rows: Vec<Vec<String>> = crns
+        .iter()
+        .map(|crn| {
+            let status = match &crn.status {
+                CrnStatus::Linked { .. } => "linked",
+                CrnStatus::Waiting => "waiting",
+            };
+            vec![
+                crn.hash.to_string(),
+                crn.name.clone(),
+                format!("{:.2}", crn.score),
+                status.to_string(),
+                crn.owner.to_string(),
+                crn.address.clone(),
+            ]
+        })
+        .collect();
+    format_table(
+        &["CRN", "NAME", "SCORE", "STATUS", "OWNER", "ADDRESS"],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_sdk::aggregate_models::corechannel::NodeHash;
+    use std::str::FromStr;
+
+    fn hash(byte: u8) -> NodeHash {
+        NodeHash::from_str(&hex::encode([byte; 32])).unwrap()
+    }
+
+    fn corechannel() -> CoreChannelContent {
+        let alice = Address::from("0xA11CE".to_string());
+        let bob = Address::from("0xB0B".to_string());
+        let mut ccn = CcnInfo::new(hash(1), "ccn", alice.clone(), "/ip4/1.2.3.4/tcp/4025");
+        ccn.score = 0.9;
+        let mut fast = CrnInfo::new(hash(2), "fast", alice, "https://fast.example");
+        fast.score = 0.8;
+        let mut slow = CrnInfo::new(hash(3), "slow", bob, "https://slow.example");
+        slow.score = 0.2;
+        CoreChannelContent::new(vec![ccn], vec![fast, slow])
+    }
+
+    fn names(nodes: &[NodeInfo]) -> Vec<&str> {
+        nodes
+            .iter()
+            .map(|node| match node {
+                NodeInfo::Ccn(ccn) => ccn.name.as_str(),
+                NodeInfo::Crn(crn) => crn.name.as_str(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn filter_by_type_score_and_gpu() {
+        let all = NodeFilter {
+            owner: None,
+            node_type: None,
+            score_min: None,
+            gpu_nodes: None,
+        };
+        assert_eq!(names(&all.select(corechannel())), ["ccn", "fast", "slow"]);
+
+        let scored_crns = NodeFilter {
+            node_type: Some(NodeTypeCli::Crn),
+            score_min: Some(0.5),
+            ..all
+        };
+        assert_eq!(names(&scored_crns.select(corechannel())), ["fast"]);
+
+        let gpu_nodes = HashSet::from([hash(3).to_string()]);
+        let gpu = NodeFilter {
+            node_type: Some(NodeTypeCli::Crn),
+            gpu_nodes: Some(&gpu_nodes),
+            ..all
+        };
+        assert_eq!(names(&gpu.select(corechannel())), ["slow"]);
+    }
+
+    #[test]
+    fn filter_by_owner() {
+        let bob = Address::from("0xB0B".to_string());
+        let filter = NodeFilter {
+            owner: Some(&bob),
+            node_type: None,
+            score_min: None,
+            gpu_nodes: None,
+        };
+        assert_eq!(names(&filter.select(corechannel())), ["slow"]);
+    }
+
+    #[test]
+    fn crn_table_aligns_columns() {
+        let content = corechannel();
+        let crns: Vec<&CrnInfo> = content.resource_nodes.iter().collect();
+        let out = format_crn_table(&crns);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("CRN "));
+        assert!(lines[0].ends_with("OWNER    ADDRESS"));
+        assert!(lines[1].contains("0.80   waiting"));
+        assert!(lines[2].ends_with("0xB0B    https://slow.example"));
+        let address_column = lines[0].find("ADDRESS").unwrap();
+        assert_eq!(lines[1].find("https://").unwrap(), address_column);
+    }
+}
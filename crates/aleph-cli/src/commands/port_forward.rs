@@ -10,7 +10,8 @@ use crate::cli::{
     PortForwardRefreshArgs, PortForwardUpdateArgs,
 };
 use crate::common::{
-    resolve_account, resolve_address, resolve_address_or_active, submit_or_preview,
+    print_structured, resolve_account, resolve_address, resolve_address_or_active,
+    submit_or_preview,
 };
 use aleph_sdk::aggregate_models::port_forwarding::{
     PORT_FORWARDING_AGGREGATE_KEY, PortFlags, PortForwardingAggregate, Ports,
@@ -83,7 +84,7 @@ async fn handle_list(
 
     if matching_count == 0 {
         if json {
-            println!("[]");
+            print_structured(&[] as &[serde_json::Value])?;
         } else if let Some(vm_id) = vm_filter {
             eprintln!("No port forwards found for VM {vm_id}");
         } else {
@@ -95,7 +96,7 @@ async fn handle_list(
     let externals = resolve_external_ports(scheduler_url, &aggregate).await;
 
     if json {
-        println!("{}", render_list_json(&aggregate, &externals, vm_filter));
+        print_structured(&render_list_json(&aggregate, &externals, vm_filter))?;
     } else {
         print!("{}", render_list_text(&aggregate, &externals, vm_filter));
     }
@@ -181,7 +182,7 @@ pub(crate) fn render_list_json(
     aggregate: &PortForwardingAggregate,
     externals: &HashMap<ItemHash, HashMap<u16, u16>>,
     vm_filter: Option<&ItemHash>,
-) -> String {
+) -> serde_json::Value {
     let mut entries: Vec<(&ItemHash, &Ports)> = aggregate
         .iter()
         .filter_map(|(k, v)| v.as_ref().map(|p| (k, p)))
@@ -207,7 +208,7 @@ pub(crate) fn render_list_json(
         }
     }
 
-    serde_json::to_string_pretty(&rows).unwrap_or_else(|_| "[]".to_string())
+    serde_json::Value::Array(rows)
 }
 
 /// Render the port-forwarding list as a text table.
@@ -549,14 +550,11 @@ async fn handle_refresh(
     client.update_instance_config(&vm_id).await?;
 
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "vm_id": vm_id.to_string(),
-                "crn_url": crn_url.to_string(),
-                "status": "refreshed"
-            }))?
-        );
+        print_structured(&serde_json::json!({
+            "vm_id": vm_id.to_string(),
+            "crn_url": crn_url.to_string(),
+            "status": "refreshed"
+        }))?;
     } else {
         eprintln!("CRN {crn_url} refreshed for VM {vm_id}");
     }
@@ -604,8 +602,7 @@ mod tests {
                 .unwrap();
         agg.insert(deleted, None);
 
-        let rendered = super::render_list_json(&agg, &HashMap::new(), None);
-        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let parsed = super::render_list_json(&agg, &HashMap::new(), None);
         let arr = parsed.as_array().unwrap();
         // 2 rows for the one VM with two ports; the null entry is omitted.
         assert_eq!(arr.len(), 2);
@@ -623,8 +620,7 @@ mod tests {
         let other =
             ItemHash::from_str("3333333333333333333333333333333333333333333333333333333333333333")
                 .unwrap();
-        let rendered = super::render_list_json(&agg, &HashMap::new(), Some(&other));
-        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let parsed = super::render_list_json(&agg, &HashMap::new(), Some(&other));
         assert_eq!(parsed.as_array().unwrap().len(), 0);
     }
 
@@ -664,8 +660,7 @@ mod tests {
                 )]),
             }),
         );
-        let rendered = super::render_list_json(&agg, &HashMap::new(), None);
-        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let parsed = super::render_list_json(&agg, &HashMap::new(), None);
         let arr = parsed.as_array().unwrap();
         assert_eq!(arr.len(), 2);
         assert!(arr[0]["item_hash"].as_str().unwrap().starts_with("1111"));
@@ -691,8 +686,7 @@ mod tests {
         let mut externals = HashMap::new();
         externals.insert(vm, HashMap::from([(80u16, 24001u16)]));
 
-        let rendered = super::render_list_json(&agg, &externals, None);
-        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let parsed = super::render_list_json(&agg, &externals, None);
         assert_eq!(parsed[0]["external_port"], 24001);
     }
 
@@ -1,8 +1,10 @@
 use crate::cli::{PostAmendArgs, PostCommand, PostCreateArgs};
-use crate::common::{read_content, resolve_account, resolve_address, submit_or_preview};
+use crate::common::{
+    print_structured, read_content, resolve_account, resolve_address, resolve_channel,
+    submit_or_preview,
+};
 use aleph_sdk::builder::MessageBuilder;
 use aleph_sdk::client::{AlephClient, AlephPostClient};
-use aleph_types::channel::Channel;
 use aleph_types::message::MessageType;
 use anyhow::{Result, bail};
 use url::Url;
@@ -23,11 +25,11 @@ pub async fn handle_post_command(
             match args.api_version {
                 0 => {
                     let response = aleph_client.get_posts_v0(&filter, pagination).await?;
-                    println!("{}", serde_json::to_string_pretty(&response.posts)?);
+                    print_structured(&response.posts)?;
                 }
                 1 => {
                     let response = aleph_client.get_posts_v1(&filter, pagination).await?;
-                    println!("{}", serde_json::to_string_pretty(&response.posts)?);
+                    print_structured(&response.posts)?;
                 }
                 v => {
                     bail!("unsupported API version: {v} (expected 0 or 1)");
@@ -62,8 +64,8 @@ async fn handle_post_create(
     if let Some(owner) = args.on_behalf_of {
         builder = builder.on_behalf_of(resolve_address(&owner)?);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
@@ -87,8 +89,8 @@ async fn handle_post_amend(
     if let Some(owner) = args.on_behalf_of {
         builder = builder.on_behalf_of(resolve_address(&owner)?);
     }
-    if let Some(ch) = args.channel {
-        builder = builder.channel(Channel::from(ch));
+    if let Some(ch) = resolve_channel(args.channel)? {
+        builder = builder.channel(ch);
     }
     let pending = builder.build()?;
     submit_or_preview(aleph_client, ccn_url, &pending, dry_run, json).await
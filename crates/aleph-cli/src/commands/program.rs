@@ -1,19 +1,20 @@
 use crate::cli::{
     ImageRef, PaymentTypeCli, ProgramCommand, ProgramCreateArgs, ProgramDeleteArgs,
-    ProgramListArgs, ProgramShowArgs, ProgramUpdateArgs, StorageEngineCli,
+    ProgramListArgs, ProgramRunArgs, ProgramShowArgs, ProgramUpdateArgs, StorageEngineCli,
 };
 use crate::commands::instance::{
     parse_ephemeral_volumes, parse_immutable_volumes, parse_persistent_volumes, resolve_runtime_ref,
 };
 use crate::common::{
-    confirm_action, print_submission_result, resolve_account, resolve_address,
-    resolve_address_or_active, submit_or_preview,
+    confirm_action, print_structured, print_submission_result, resolve_account, resolve_address,
+    resolve_address_or_active, resolve_channel, submit_or_preview,
 };
 use crate::program::archive::prepare_archive;
 use aleph_sdk::aggregate_models::vm_images::VmImagesData;
 use aleph_sdk::client::{
     AlephAggregateClient, AlephClient, AlephMessageClient, MessageError, MessageFilter,
-    MessageWithStatus, PaginationParams, SortBy, SortOrder, hash_file,
+    MessageWithStatus, PaginationParams, ProgramEndpoint, ProgramRequest, SortBy, SortOrder,
+    hash_file,
 };
 use aleph_sdk::messages::{ForgetBuilder, ProgramBuilder, StoreBuilder};
 use aleph_sdk::verify::Hasher;
@@ -46,6 +47,7 @@ pub async fn handle_program_command(
         ProgramCommand::Delete(args) => handle_delete(aleph_client, ccn_url, json, args).await,
         ProgramCommand::Update(args) => handle_update(aleph_client, ccn_url, json, args).await,
         ProgramCommand::Show(args) => handle_show(aleph_client, json, args).await,
+        ProgramCommand::Run(args) => handle_run(aleph_client, json, args).await,
     }
 }
 
@@ -109,8 +111,8 @@ async fn handle_create(
     if let Some(owner) = &args.on_behalf_of {
         store_builder = store_builder.on_behalf_of(resolve_address(owner)?);
     }
-    if let Some(ch) = &args.channel {
-        store_builder = store_builder.channel(Channel::from(ch.clone()));
+    if let Some(ch) = resolve_channel(args.channel.clone())? {
+        store_builder = store_builder.channel(ch);
     }
     let store_pending = store_builder.build()?;
 
@@ -163,8 +165,8 @@ async fn handle_create(
     if let Some(owner) = &args.on_behalf_of {
         program_builder = program_builder.on_behalf_of(resolve_address(owner)?);
     }
-    if let Some(ch) = &args.channel {
-        program_builder = program_builder.channel(Channel::from(ch.clone()));
+    if let Some(ch) = resolve_channel(args.channel.clone())? {
+        program_builder = program_builder.channel(ch);
     }
 
     let program_pending = program_builder.build()?;
@@ -175,11 +177,11 @@ async fn handle_create(
     if dry_run {
         if json {
             let envelopes = serde_json::json!([store_pending, program_pending]);
-            println!("{}", serde_json::to_string_pretty(&envelopes)?);
+            print_structured(&envelopes)?;
         } else {
             eprintln!("Dry run - messages not submitted.\n");
-            println!("{}", serde_json::to_string_pretty(&store_pending)?);
-            println!("{}", serde_json::to_string_pretty(&program_pending)?);
+            print_structured(&store_pending)?;
+            print_structured(&program_pending)?;
         }
         return Ok(());
     }
@@ -362,7 +364,7 @@ fn extract_program_row(message: &Message) -> Option<ProgramRow> {
         persistent: program.on.persistent.unwrap_or(false),
         internet: program.environment.internet,
         updatable: program.base.allow_amend,
-        vcpus: program.base.resources.vcpus,
+        vcpus: program.base.resources.vcpus.get(),
         memory_mib: u64::from(program.base.resources.memory),
         runtime: program.runtime.reference.clone(),
         created_at: message.content.time.clone(),
@@ -484,10 +486,7 @@ fn format_program_rows_text(rows: &[ProgramRow]) -> String {
 
 fn render_program_rows(rows: &[ProgramRow], json: bool) -> Result<()> {
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&format_program_rows_json(rows))?
-        );
+        print_structured(&format_program_rows_json(rows))?;
     } else {
         print!("{}", format_program_rows_text(rows));
     }
@@ -787,9 +786,9 @@ pub(crate) fn build_program_show_info(message: &Message) -> ProgramShowInfo {
         entrypoint: program.code.entrypoint.clone(),
         interface: program_interface_from(program.code.interface.as_ref()),
         encoding: encoding_str(&program.code.encoding).to_string(),
-        vcpus: program.base.resources.vcpus,
+        vcpus: program.base.resources.vcpus.get(),
         memory_mib: u64::from(program.base.resources.memory),
-        timeout_seconds: program.base.resources.seconds,
+        timeout_seconds: program.base.resources.seconds.as_secs(),
         internet: program.environment.internet,
         persistent: program.on.persistent.unwrap_or(false),
         updatable: program.base.allow_amend,
@@ -877,8 +876,8 @@ async fn handle_update(
     if args.on_behalf_of.is_some() {
         store_builder = store_builder.on_behalf_of(owner_address);
     }
-    if let Some(ch) = &args.channel {
-        store_builder = store_builder.channel(Channel::from(ch.clone()));
+    if let Some(ch) = resolve_channel(args.channel.clone())? {
+        store_builder = store_builder.channel(ch);
     }
     let store_pending = store_builder.build()?;
 
@@ -889,7 +888,7 @@ async fn handle_update(
         if !json {
             eprintln!("Dry run - message not submitted.\n");
         }
-        println!("{}", serde_json::to_string_pretty(&store_pending)?);
+        print_structured(&store_pending)?;
         return Ok(());
     }
 
@@ -1391,6 +1390,67 @@ fn format_ts(t: &Timestamp) -> String {
         .unwrap_or_else(|_| format!("{}", t.as_f64()))
 }
 
+async fn handle_run(aleph_client: &AlephClient, json: bool, args: ProgramRunArgs) -> Result<()> {
+    let request = build_program_request(&args)?;
+    let endpoint = match args.crn {
+        Some(crn_url) => ProgramEndpoint::Crn(crn_url),
+        None => ProgramEndpoint::Gateway,
+    };
+    let response = aleph_client
+        .run_program_at(&args.item_hash, &endpoint, request)
+        .await?;
+
+    if json {
+        let headers: std::collections::BTreeMap<_, _> = response
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        print_structured(&serde_json::json!({
+            "url": response.url.as_str(),
+            "status": response.status.as_u16(),
+            "headers": headers,
+            "body": String::from_utf8_lossy(&response.body),
+        }))?;
+    } else {
+        use std::io::Write;
+        std::io::stdout().write_all(&response.body)?;
+    }
+    if !response.status.is_success() {
+        bail!("program answered {}", response.status);
+    }
+    Ok(())
+}
+
+fn build_program_request(args: &ProgramRunArgs) -> Result<ProgramRequest> {
+    let method = reqwest::Method::from_bytes(args.method.to_ascii_uppercase().as_bytes())
+        .with_context(|| format!("invalid HTTP method '{}'", args.method))?;
+    let mut request = ProgramRequest::new(method, args.path.clone());
+    for header in &args.headers {
+        let (name, value) = header
+            .split_once(':')
+            .with_context(|| format!("invalid header '{header}': expected 'Name: value'"))?;
+        request = request.header(
+            name.trim()
+                .parse()
+                .with_context(|| format!("invalid header name in '{header}'"))?,
+            value
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid header value in '{header}'"))?,
+        );
+    }
+    if let Some(data) = &args.data {
+        request = request.body(data.clone());
+    }
+    Ok(request)
+}
+
 async fn handle_show(aleph_client: &AlephClient, json: bool, args: ProgramShowArgs) -> Result<()> {
     // 1. Fetch the PROGRAM message (reuses existing helper; bails on
     //    pending/forgotten/removed/wrong-type).
@@ -1416,10 +1476,7 @@ async fn handle_show(aleph_client: &AlephClient, json: bool, args: ProgramShowAr
 
     // 5. Render.
     if json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&render_show_json(&info, &refs, &volumes))?
-        );
+        print_structured(&render_show_json(&info, &refs, &volumes))?;
     } else {
         print!("{}", render_show_text(&info, &refs, &volumes));
     }
@@ -1430,6 +1487,28 @@ async fn handle_show(aleph_client: &AlephClient, json: bool, args: ProgramShowAr
 mod tests {
     use super::*;
 
+    #[test]
+    fn build_program_request_parses_method_and_headers() {
+        let args = ProgramRunArgs {
+            item_hash: ItemHash::from([1u8; 32]),
+            path: "/items".into(),
+            method: "post".into(),
+            headers: vec!["Content-Type: application/json".into()],
+            data: Some("{}".into()),
+            crn: None,
+        };
+        let request = build_program_request(&args).unwrap();
+        assert_eq!(request.method, reqwest::Method::POST);
+        assert_eq!(request.headers["content-type"], "application/json");
+        assert_eq!(request.body.as_deref(), Some(&b"{}"[..]));
+
+        let args = ProgramRunArgs {
+            headers: vec!["no-colon".into()],
+            ..args
+        };
+        assert!(build_program_request(&args).is_err());
+    }
+
     const PROGRAM_FIXTURE: &str = include_str!(concat!(
         env!("CARGO_MANIFEST_DIR"),
         "/../../fixtures/messages/program/program.json"
@@ -0,0 +1,25 @@
+//! Helpers shared by the unit tests of the commands.
+
+/// JSON of an inline message carrying `content`, as served by the CCN API.
+/// Tests override the fields they care about, such as `confirmations`.
+pub(crate) fn inline_message(
+    message_type: &str,
+    item_hash: &str,
+    content: serde_json::Value,
+    time: f64,
+) -> serde_json::Value {
+    serde_json::json!({
+        "sender": "0xABCD",
+        "chain": "ETH",
+        "signature": "0xSIG",
+        "type": message_type,
+        "item_type": "inline",
+        "item_content": content.to_string(),
+        "item_hash": item_hash,
+        "time": time,
+        "channel": null,
+        "content": content,
+        "confirmed": false,
+        "confirmations": [],
+    })
+}
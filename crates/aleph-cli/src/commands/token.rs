@@ -2,7 +2,9 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::account::CliAccount;
 use crate::cli::{SigningArgs, SwapTokenCli, SwapVenueCli, TokenCommand, TokenSwapArgs};
-use crate::common::{confirm_submission, resolve_account, resolve_address, resolve_network};
+use crate::common::{
+    confirm_submission, print_structured, resolve_account, resolve_address, resolve_network,
+};
 use aleph_sdk::credit::{EthereumConfig, format_token_amount, parse_token_amount};
 use aleph_sdk::swap::cow::CowApi;
 use aleph_sdk::swap::cow::chains::cow_chain;
@@ -244,7 +246,7 @@ async fn swap_via_cow(
         if json {
             let mut output = quote_json(sell_token, &quote);
             output["dry_run"] = serde_json::Value::Bool(true);
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            print_structured(&output)?;
         } else {
             eprintln!("\nDry run - order not submitted.");
         }
@@ -284,7 +286,7 @@ async fn swap_via_cow(
 
             if json {
                 let output = result_json_usdc(sell_token, &quote, &order_uid);
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                print_structured(&output)?;
             } else {
                 eprintln!("Order submitted: {order_uid}");
                 eprintln!("https://explorer.cow.fi/orders/{order_uid}");
@@ -308,7 +310,7 @@ async fn swap_via_cow(
 
             if json {
                 let output = result_json_eth(sell_token, &quote, &tx_hash_str);
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                print_structured(&output)?;
             } else {
                 eprintln!("Transaction submitted: {tx_hash_str}");
                 eprintln!("https://explorer.cow.fi/tx/{tx_hash_str}");
@@ -360,7 +362,7 @@ async fn swap_via_uniswap(
         if json {
             let mut output = quote_json_uniswap(sell_token, &quote);
             output["dry_run"] = serde_json::Value::Bool(true);
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            print_structured(&output)?;
         } else {
             eprintln!("\nDry run - swap not submitted.");
         }
@@ -412,7 +414,7 @@ async fn swap_via_uniswap(
 
     if json {
         let output = result_json_uniswap(sell_token, &quote, &tx_hash_str);
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        print_structured(&output)?;
     } else {
         eprintln!("Transaction submitted: {tx_hash_str}");
         if let Some(base) = &ethereum.explorer_tx_base {
@@ -484,7 +486,7 @@ async fn swap_via_ophis(
         if json {
             let mut output = quote_json_ophis(sell_token, &quote);
             output["dry_run"] = serde_json::Value::Bool(true);
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            print_structured(&output)?;
         } else {
             eprintln!("\nDry run - order not submitted.");
         }
@@ -523,7 +525,7 @@ async fn swap_via_ophis(
 
             if json {
                 let output = result_json_ophis_usdc(sell_token, &quote, &order_uid);
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                print_structured(&output)?;
             } else {
                 eprintln!("Order submitted: {order_uid}");
                 eprintln!("https://explorer.cow.fi/orders/{order_uid}");
@@ -553,7 +555,7 @@ async fn swap_via_ophis(
 
             if json {
                 let output = result_json_ophis_eth(sell_token, &quote, &tx_hash_str);
-                println!("{}", serde_json::to_string_pretty(&output)?);
+                print_structured(&output)?;
             } else {
                 eprintln!("Transaction submitted: {tx_hash_str}");
                 eprintln!("https://explorer.cow.fi/tx/{tx_hash_str}");
@@ -5,8 +5,8 @@ use crate::cli::{
     WebsiteShowArgs, WebsiteUpdateArgs,
 };
 use crate::common::{
-    confirm_tty, format_epoch_for_tty, now_secs_f64, resolve_account, resolve_address,
-    resolve_address_or_active, submit_or_preview,
+    confirm_tty, format_epoch_for_tty, now_secs_f64, print_structured, resolve_account,
+    resolve_address, resolve_address_or_active, submit_or_preview,
 };
 use aleph_sdk::aggregate_models::domains::DomainsAggregate;
 use aleph_sdk::aggregate_models::websites::{
@@ -122,7 +122,7 @@ async fn handle_website_list(
         .collect();
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&rows)?);
+        print_structured(&rows)?;
     } else if rows.is_empty() {
         println!("(no websites)");
     } else {
@@ -204,7 +204,7 @@ async fn handle_website_show(
     };
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&out)?);
+        print_structured(&out)?;
     } else {
         println!("Name:       {}", out.name);
         println!("Version:    {}", out.version);
@@ -410,7 +410,8 @@ async fn handle_website_deploy(
         chain: args
             .payment_chain
             .clone()
-            .unwrap_or_else(|| account.chain().to_string()),
+            .unwrap_or_else(|| account.chain())
+            .to_string(),
         kind: payment_kind_str(args.payment_type).to_string(),
     };
     let entry = WebsiteEntry {
@@ -513,7 +514,7 @@ async fn handle_website_deploy(
         domains_attached,
     };
     if json {
-        println!("{}", serde_json::to_string_pretty(&out)?);
+        print_structured(&out)?;
     } else {
         println!("Deployed '{}' v{}", out.name, out.version);
         println!("  volume_id: {}", out.volume_id);
@@ -643,7 +644,7 @@ async fn handle_website_update(
             domains_repointed: vec![],
         };
         if json {
-            println!("{}", serde_json::to_string_pretty(&out)?);
+            print_structured(&out)?;
         }
         return Ok(());
     }
@@ -776,7 +777,7 @@ async fn handle_website_update(
         domains_repointed,
     };
     if json {
-        println!("{}", serde_json::to_string_pretty(&out)?);
+        print_structured(&out)?;
     } else {
         println!(
             "Updated '{}' to v{} (volume_id={})",
@@ -873,7 +874,7 @@ async fn handle_website_delete(
         orphaned_domains,
     };
     if json {
-        println!("{}", serde_json::to_string_pretty(&out)?);
+        print_structured(&out)?;
     } else {
         println!("Deleted '{}'", out.name);
         if !out.orphaned_domains.is_empty() {
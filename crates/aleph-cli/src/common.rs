@@ -1,10 +1,12 @@
-use std::io::Read;
+use std::io::{IsTerminal, Read};
+use std::sync::OnceLock;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use aleph_sdk::client::{AlephMessageClient, MessageError, MessageWithStatus};
 use aleph_types::item_hash::ItemHash;
 use aleph_types::message::pending::PendingMessage;
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
 use url::Url;
 
 /// Current Unix time as fractional seconds, matching the float `updated_at`
@@ -40,6 +42,21 @@ pub fn render_upload_progress(sent: u64, total: u64) {
     eprint!("\r  uploaded {sent}/{total} bytes ({pct:.1}%)");
 }
 
+/// Render one download-progress tick to stderr, overwriting the current line.
+///
+/// Counterpart of [`render_upload_progress`] for
+/// [`FileDownload::with_progress`](aleph_sdk::client::FileDownload::with_progress).
+/// `total` is unknown when the server sends no `Content-Length`.
+pub fn render_download_progress(received: u64, total: Option<u64>) {
+    match total {
+        Some(total) if total > 0 => {
+            let pct = (received as f64 / total as f64 * 100.0).min(100.0);
+            eprint!("\r  downloaded {received}/{total} bytes ({pct:.1}%)");
+        }
+        _ => eprint!("\r  downloaded {received} bytes"),
+    }
+}
+
 /// Returns true if the error is an HTTP 429 Too Many Requests.
 pub fn is_rate_limited(err: &MessageError) -> bool {
     matches!(err, MessageError::ApiError { status: 429, .. })
@@ -73,15 +90,156 @@ where
     unreachable!()
 }
 
-/// Read JSON content from --content flag or stdin.
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Records the output format of this run, for [`print_structured`]. Call site:
+/// `main.rs`.
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+/// Prints structured command output to stdout in the format selected with
+/// `--output`: pretty JSON by default, NDJSON, YAML or a table on request.
+pub fn print_structured<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    let format = OUTPUT_FORMAT.get().copied().unwrap_or(OutputFormat::Json);
+    print!(
+        "{}",
+        render_structured(&serde_json::to_value(value)?, format)?
+    );
+    Ok(())
+}
+
+/// Prints one entry of a streamed output (logs, watched messages) as soon as
+/// it arrives: one compact line for JSON and NDJSON, one `---` document for
+/// YAML.
+pub fn print_structured_entry<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    let format = OUTPUT_FORMAT.get().copied().unwrap_or(OutputFormat::Json);
+    print!(
+        "{}",
+        render_structured_entry(&serde_json::to_value(value)?, format)?
+    );
+    Ok(())
+}
+
+fn render_structured_entry(value: &serde_json::Value, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json | OutputFormat::Ndjson => format!("{}\n", serde_json::to_string(value)?),
+        OutputFormat::Yaml => format!("---\n{}", serde_yaml::to_string(value)?),
+        OutputFormat::Table => render_structured(value, format)?,
+    })
+}
+
+fn render_structured(value: &serde_json::Value, format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Ndjson => match value {
+            serde_json::Value::Array(items) => {
+                let mut out = String::new();
+                for item in items {
+                    out.push_str(&serde_json::to_string(item)?);
+                    out.push('\n');
+                }
+                out
+            }
+            other => format!("{}\n", serde_json::to_string(other)?),
+        },
+        OutputFormat::Yaml => serde_yaml::to_string(value)?,
+        OutputFormat::Json => format!("{}\n", serde_json::to_string_pretty(value)?),
+        OutputFormat::Table => render_table(value),
+    })
+}
+
+/// Table rendering of a JSON value: a list of objects gets one column per key,
+/// an object one `KEY  VALUE` row per field. Nested values are shown as
+/// compact JSON.
+fn render_table(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+
+    fn cell(value: &Value) -> String {
+        match value {
+            Value::Null => "-".to_string(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    match value {
+        Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+            let mut headers: Vec<&str> = Vec::new();
+            for item in items.iter().filter_map(Value::as_object) {
+                for key in item.keys() {
+                    if !headers.contains(&key.as_str()) {
+                        headers.push(key);
+                    }
+                }
+            }
+            let rows: Vec<Vec<String>> = items
+                .iter()
+                .filter_map(Value::as_object)
+                .map(|item| {
+                    headers
+                        .iter()
+                        .map(|h| item.get(*h).map_or_else(|| "-".to_string(), cell))
+                        .collect()
+                })
+                .collect();
+            let headers: Vec<String> = headers.iter().map(|h| h.to_uppercase()).collect();
+            let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+            format_table(&headers, &rows)
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| format!("{}\n", cell(item)))
+            .collect(),
+        Value::Object(fields) => {
+            let rows: Vec<Vec<String>> = fields
+                .iter()
+                .map(|(key, value)| vec![key.clone(), cell(value)])
+                .collect();
+            format_table(&["KEY", "VALUE"], &rows)
+        }
+        other => format!("{}\n", cell(other)),
+    }
+}
+
+/// Left-aligned columns separated by two spaces; the last one is not padded.
+pub fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&header).chain(rows) {
+        let last = row.len().saturating_sub(1);
+        for (i, (cell, width)) in row.iter().zip(&widths).enumerate() {
+            if i == last {
+                out.push_str(cell);
+            } else {
+                out.push_str(&format!("{cell:<width$}  "));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Read JSON content from the --content flag: inline JSON, `@path` to read a
+/// file, or `-` (or no flag at all) to read stdin.
 pub fn read_content(content_flag: Option<String>) -> Result<serde_json::Value> {
-    let raw = match content_flag {
-        Some(c) => c,
-        None => {
+    let raw = match content_flag.as_deref() {
+        None | Some("-") => {
             let mut buf = String::new();
             std::io::stdin().read_to_string(&mut buf)?;
             buf
         }
+        Some(c) => match c.strip_prefix('@') {
+            Some(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read content from {path}"))?,
+            None => c.to_string(),
+        },
     };
     let value: serde_json::Value = serde_json::from_str(&raw)?;
     Ok(value)
@@ -149,10 +307,10 @@ pub async fn submit_or_preview(
 
 fn print_dry_run(pending: &PendingMessage, json: bool) -> Result<()> {
     if json {
-        println!("{}", serde_json::to_string_pretty(pending)?);
+        print_structured(pending)?;
     } else {
         eprintln!("Dry run - message not submitted.\n");
-        println!("{}", serde_json::to_string_pretty(pending)?);
+        print_structured(pending)?;
     }
     Ok(())
 }
@@ -234,8 +392,11 @@ pub async fn report_authenticated_upload_status(
                     "item_hash": pending.item_hash.to_string(),
                     "explorer_url": explorer,
                 });
-                println!("{}", serde_json::to_string_pretty(&envelope)?);
-                bail!("Message rejected by the CCN (error code {error_code})");
+                print_structured(&envelope)?;
+                bail!(
+                    "Message rejected by the CCN: {reason} (error code {error_code})",
+                    reason = describe_rejection_error_code(error_code),
+                );
             }
             bail!(
                 "Message rejected by the CCN: {reason} (error code {error_code}).\nSee: {explorer}",
@@ -297,7 +458,7 @@ fn print_json_result(
         "publication_status": publication_status,
         "message_status": message_status,
     });
-    println!("{}", serde_json::to_string_pretty(&output)?);
+    print_structured(&output)?;
     Ok(())
 }
 
@@ -343,10 +504,7 @@ pub fn format_api_error(
             })
         };
         // Print the structured error to stdout for tooling to parse
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&error_json).unwrap_or_default()
-        );
+        let _ = print_structured(&error_json);
         return format!("API request failed (HTTP {status})");
     }
 
@@ -435,11 +593,12 @@ fn describe_rejection_error_code(code: i64) -> &'static str {
 }
 
 use aleph_types::chain::Address;
+use aleph_types::channel::Channel;
 
 use crate::account::store::AccountStore;
 use crate::account::{CliAccount, load_account, load_account_by_name};
 use crate::cli::IdentityArgs;
-use crate::config::store::ConfigStore;
+use crate::config::store::{ConfigStore, OutputFormat};
 
 /// Resolve the CCN URL using a provided `ConfigStore` (testable form).
 ///
@@ -534,6 +693,52 @@ pub fn resolve_network(
     resolve_network_with_store(&store, network_override)
 }
 
+/// Channel of a new message: the `--channel` flag if given, else the
+/// configured `default_channel`.
+pub fn resolve_channel_with_store(
+    store: &ConfigStore,
+    channel: Option<String>,
+) -> Result<Option<Channel>> {
+    if let Some(channel) = channel {
+        return Ok(Some(Channel::from(channel)));
+    }
+    let manifest = store.load_manifest().map_err(|e| anyhow!("{e}"))?;
+    Ok(manifest.default_channel.map(Channel::from))
+}
+
+/// Resolve the channel of a new message using the user-global config store.
+pub fn resolve_channel(channel: Option<String>) -> Result<Option<Channel>> {
+    if let Some(channel) = channel {
+        return Ok(Some(Channel::from(channel)));
+    }
+    let store = ConfigStore::open().map_err(|e| anyhow!("failed to open config store: {e}"))?;
+    resolve_channel_with_store(&store, None)
+}
+
+/// Output format of this run: `--output`, else `--json`, else the configured
+/// `default_output`, else tables on a terminal and JSON when piped. An
+/// unreadable config is ignored: it must not stop commands that do not
+/// depend on it.
+pub fn resolve_output_format(format: Option<OutputFormat>, json: bool) -> OutputFormat {
+    if let Some(format) = format {
+        return format;
+    }
+    if json {
+        return OutputFormat::Json;
+    }
+    let configured = ConfigStore::open()
+        .and_then(|store| store.load_manifest())
+        .ok()
+        .and_then(|manifest| manifest.default_output);
+    configured.unwrap_or_else(|| {
+        if std::io::stdout().is_terminal() {
+            OutputFormat::Table
+        } else {
+            OutputFormat::Json
+        }
+    })
+}
+
 /// Resolve the Aleph VM scheduler base URL for a network.
 pub fn resolve_scheduler_url_with_store(
     store: &ConfigStore,
@@ -723,6 +928,57 @@ mod tests {
         assert!(confirm_typed_match("WARNING", "expected", true).unwrap());
     }
 
+    #[test]
+    fn ndjson_prints_array_items_one_per_line() {
+        let value = serde_json::json!([{"a": 1}, {"b": [2, 3]}]);
+        let out = render_structured(&value, OutputFormat::Ndjson).unwrap();
+        assert_eq!(out, "{\"a\":1}\n{\"b\":[2,3]}\n");
+    }
+
+    #[test]
+    fn yaml_renders_nested_values() {
+        let value = serde_json::json!({
+            "name": "aleph",
+            "tags": ["a", "b"],
+            "meta": {"size": 3, "empty": {}, "1st": null},
+        });
+        let out = render_structured(&value, OutputFormat::Yaml).unwrap();
+        let parsed: serde_json::Value = serde_yaml::from_str(&out).unwrap();
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn table_renders_lists_and_objects() {
+        let value = serde_json::json!([
+            {"hash": "abc", "size": 3},
+            {"hash": "de", "tags": ["x"]},
+        ]);
+        let out = render_structured(&value, OutputFormat::Table).unwrap();
+        assert_eq!(
+            out,
+            "HASH  SIZE  TAGS\nabc   3     -\nde    -     [\"x\"]\n"
+        );
+
+        let value = serde_json::json!({"key": "value", "owner": null});
+        let out = render_structured(&value, OutputFormat::Table).unwrap();
+        assert_eq!(out, "KEY    VALUE\nkey    value\nowner  -\n");
+    }
+
+    #[test]
+    fn stream_entries_stay_on_one_line() {
+        let value = serde_json::json!({"a": [1, 2]});
+        let out = render_structured_entry(&value, OutputFormat::Json).unwrap();
+        assert_eq!(out, "{\"a\":[1,2]}\n");
+        let out = render_structured_entry(&value, OutputFormat::Yaml).unwrap();
+        assert!(out.starts_with("---\n"));
+    }
+
+    #[test]
+    fn format_table_aligns_columns() {
+        let rows = vec![vec!["abc".to_string(), "x".to_string()]];
+        assert_eq!(format_table(&["A", "B"], &rows), "A    B\nabc  x\n");
+    }
+
     #[test]
     fn read_content_from_flag() {
         let value = read_content(Some(r#"{"key": "value"}"#.to_string())).unwrap();
@@ -740,6 +996,16 @@ mod tests {
         assert_eq!(value["a"]["b"][1], 2);
     }
 
+    #[test]
+    fn read_content_from_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("content.json");
+        std::fs::write(&path, r#"{"from": "file"}"#).unwrap();
+        let value = read_content(Some(format!("@{}", path.display()))).unwrap();
+        assert_eq!(value["from"], "file");
+        assert!(read_content(Some(format!("@{}/missing.json", dir.path().display()))).is_err());
+    }
+
     #[test]
     fn format_api_error_extracts_nested_message() {
         let body = r#"{"error":{"code":503,"message":"forget address does not match"},"message_status":"rejected"}"#;
@@ -964,6 +1230,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn channel_flag_overrides_the_configured_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ConfigStore::with_manifest_path(dir.path().join("config.toml"));
+        assert!(resolve_channel_with_store(&store, None).unwrap().is_none());
+
+        store.set_default_channel(Some("MY-APP")).unwrap();
+        assert_eq!(
+            resolve_channel_with_store(&store, None).unwrap(),
+            Some(Channel::from("MY-APP".to_string()))
+        );
+        assert_eq!(
+            resolve_channel_with_store(&store, Some("OTHER".to_string())).unwrap(),
+            Some(Channel::from("OTHER".to_string()))
+        );
+    }
+
     fn temp_account_store() -> (TempDir, AccountStore) {
         let dir = tempfile::tempdir().unwrap();
         let store = AccountStore::with_manifest_path(dir.path().join("accounts.toml"));
@@ -4,9 +4,9 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 pub const BUILTIN_CCN_NAME: &str = "official";
-pub const BUILTIN_CCN_URL: &str = "https://api.aleph.im";
+pub const BUILTIN_CCN_URL: &str = aleph_sdk::network::MAINNET_CCN_URL;
 pub const BUILTIN_NETWORK_NAME: &str = "mainnet";
-pub const BUILTIN_SCHEDULER_URL: &str = "https://scheduler.api.aleph.cloud";
+pub const BUILTIN_SCHEDULER_URL: &str = aleph_sdk::network::MAINNET_SCHEDULER_URL;
 
 /// One named CCN endpoint inside a network.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,10 +77,40 @@ impl EthereumPatch {
     }
 }
 
+/// Output format of the CLI commands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable output: tables and summaries.
+    #[default]
+    #[serde(alias = "text")]
+    #[value(alias = "text")]
+    Table,
+    /// Pretty-printed JSON, as with `--json`.
+    Json,
+    /// One compact JSON document per line; lists are printed one item per line.
+    Ndjson,
+    /// YAML.
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Whether commands should print structured data rather than text.
+    pub fn is_structured(self) -> bool {
+        self != OutputFormat::Table
+    }
+}
+
 /// The on-disk config manifest (`config.toml`).
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ConfigManifest {
     pub default_network: Option<String>,
+    /// Channel of the messages sent without `--channel`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_channel: Option<String>,
+    /// Output format of the commands run without `--json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_output: Option<OutputFormat>,
     #[serde(default)]
     pub networks: Vec<NetworkEntry>,
 }
@@ -266,6 +296,20 @@ impl ConfigStore {
         Ok(self.load_manifest()?.default_network)
     }
 
+    /// Sets the channel of the messages sent without `--channel`; `None`
+    /// clears it.
+    pub fn set_default_channel(&self, channel: Option<&str>) -> Result<(), ConfigError> {
+        let mut manifest = self.load_manifest()?;
+        manifest.default_channel = channel.map(str::to_string);
+        self.save_manifest(&manifest)
+    }
+
+    pub fn set_default_output(&self, output: OutputFormat) -> Result<(), ConfigError> {
+        let mut manifest = self.load_manifest()?;
+        manifest.default_output = Some(output);
+        self.save_manifest(&manifest)
+    }
+
     pub fn list_networks(&self) -> Result<Vec<NetworkEntry>, ConfigError> {
         Ok(self.load_manifest()?.networks)
     }
@@ -407,6 +451,8 @@ mod tests {
     fn roundtrip_manifest_serde_without_ethereum() {
         let manifest = ConfigManifest {
             default_network: Some("mainnet".to_string()),
+            default_channel: None,
+            default_output: None,
             networks: vec![NetworkEntry {
                 name: "mainnet".to_string(),
                 default_ccn: Some("official".to_string()),
@@ -439,6 +485,8 @@ mod tests {
     fn roundtrip_manifest_serde_with_ethereum() {
         let manifest = ConfigManifest {
             default_network: Some("mainnet".to_string()),
+            default_channel: None,
+            default_output: None,
             networks: vec![NetworkEntry {
                 name: "mainnet".to_string(),
                 default_ccn: Some("official".to_string()),
@@ -573,6 +621,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn defaults_roundtrip_and_serialize_before_networks() {
+        let (_dir, store) = temp_store();
+        store.add_network("mainnet").unwrap();
+        store.set_default_channel(Some("MY-APP")).unwrap();
+        store.set_default_output(OutputFormat::Json).unwrap();
+
+        let manifest = store.load_manifest().unwrap();
+        assert_eq!(manifest.default_channel.as_deref(), Some("MY-APP"));
+        assert_eq!(manifest.default_output, Some(OutputFormat::Json));
+        let serialized = toml::to_string_pretty(&manifest).unwrap();
+        assert!(serialized.contains("default_output = \"json\""));
+
+        store.set_default_channel(None).unwrap();
+        assert!(store.load_manifest().unwrap().default_channel.is_none());
+    }
+
     #[test]
     fn set_default_network_unknown_errors() {
         let (_dir, store) = temp_store();
@@ -75,7 +75,15 @@ async fn main() {
 
 async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
-    let json = cli.json;
+    if let Some(filter) = &cli.log {
+        tracing_subscriber::fmt()
+            .with_env_filter(filter.as_str())
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    let output = common::resolve_output_format(cli.output, cli.json);
+    common::set_output_format(output);
+    let json = output.is_structured();
 
     // Completions subcommand short-circuits: no network or config resolution
     // is needed just to print a shell completion script.
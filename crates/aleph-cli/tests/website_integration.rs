@@ -159,7 +159,7 @@ fn website_show_resolves_ipfs_cid() {
     assert_eq!(parsed["name"], "my-site", "stdout: {stdout}");
     assert_eq!(parsed["version"], 2, "stdout: {stdout}");
     assert_eq!(
-        parsed["ipfs_cid"], "bafybeigfolderv2cidexampleexampleexampleexampleexa",
+        parsed["ipfs_cid"], "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
         "stdout: {stdout}"
     );
     let domains = parsed["domains"]
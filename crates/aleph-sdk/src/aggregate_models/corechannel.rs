@@ -6,6 +6,7 @@ use aleph_types::chain::Address;
 use aleph_types::item_hash::{AlephItemHash, AlephItemHashError};
 use aleph_types::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::str::FromStr;
 use std::sync::LazyLock;
@@ -51,6 +52,54 @@ pub struct CcnInfo {
     pub score: f64,
     pub reward: Address,
     pub multiaddress: String,
+    /// Amount of ALEPH staked on this node, per staker address. Does not
+    /// include the owner's own node deposit.
+    #[serde(default)]
+    pub stakers: HashMap<Address, f64>,
+}
+
+impl CcnInfo {
+    /// A freshly registered CCN: registered now, with a zero score, no
+    /// stakers and rewards going to the owner.
+    pub fn new(
+        hash: NodeHash,
+        name: impl Into<String>,
+        owner: Address,
+        multiaddress: impl Into<String>,
+    ) -> Self {
+        Self {
+            hash,
+            name: name.into(),
+            time: Timestamp::now(),
+            reward: owner.clone(),
+            owner,
+            score: 0.0,
+            multiaddress: multiaddress.into(),
+            stakers: HashMap::new(),
+        }
+    }
+
+    /// Adds `amount` to the stake of `staker` on this node.
+    pub fn with_stake(mut self, staker: Address, amount: f64) -> Self {
+        *self.stakers.entry(staker).or_default() += amount;
+        self
+    }
+
+    /// Total amount of ALEPH staked on this node by all its stakers.
+    pub fn total_staked(&self) -> f64 {
+        self.stakers.values().sum()
+    }
+
+    /// Amount `address` stakes on this node, or `None` if it is not a staker.
+    ///
+    /// EVM addresses are compared case-insensitively since the aggregate does
+    /// not normalize their checksum casing.
+    pub fn stake_of(&self, address: &Address) -> Option<f64> {
+        self.stakers
+            .iter()
+            .find(|(staker, _)| staker.same_as(address))
+            .map(|(_, amount)| *amount)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +125,92 @@ pub struct CrnInfo {
     /// Stream reward address. Only used for PAYG.
     #[serde(default, deserialize_with = "deserialize_optional_address")]
     pub stream_reward: Option<Address>,
+    /// Node type, `compute` for every CRN registered so far.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub node_type: Option<String>,
+    /// Address allowed to manage the node on behalf of its owner.
+    #[serde(default, deserialize_with = "deserialize_optional_address")]
+    pub manager: Option<Address>,
+    /// Whether the node only accepts stakers from `authorized`.
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub authorized: Vec<Address>,
+    /// Share of the score driven by the node's measured performance, in
+    /// `[0, 1]`.
+    #[serde(default)]
+    pub performance: Option<f64>,
+    /// Share of the score driven by the node's network diversity, in `[0, 1]`.
+    #[serde(default)]
+    pub decentralization: Option<f64>,
+    /// aleph-vm version last reported by the node.
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub version: Option<String>,
+    /// Item hash of the node's terms and conditions, if it requires users to
+    /// accept some.
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub terms_and_conditions: Option<String>,
+    /// Page where users request access to a locked node.
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub registration_url: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub picture: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub banner: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub description: Option<String>,
+    /// When the node was first seen inactive, if it currently is.
+    #[serde(default)]
+    pub inactive_since: Option<f64>,
+}
+
+impl CrnInfo {
+    /// A freshly registered CRN: registered now, with a zero score, not
+    /// linked to any CCN and rewards going to the owner.
+    pub fn new(
+        hash: NodeHash,
+        name: impl Into<String>,
+        owner: Address,
+        address: impl Into<String>,
+    ) -> Self {
+        Self {
+            hash,
+            name: name.into(),
+            time: Timestamp::now(),
+            reward: owner.clone(),
+            owner,
+            score: 0.0,
+            address: address.into(),
+            status: CrnStatus::Waiting,
+            stream_reward: None,
+            node_type: Some("compute".to_string()),
+            manager: None,
+            locked: false,
+            authorized: Vec::new(),
+            performance: None,
+            decentralization: None,
+            version: None,
+            terms_and_conditions: None,
+            registration_url: None,
+            picture: None,
+            banner: None,
+            description: None,
+            inactive_since: None,
+        }
+    }
+
+    /// Links the node to the CCN `parent`.
+    pub fn linked_to(mut self, parent: NodeHash) -> Self {
+        self.status = CrnStatus::Linked { parent };
+        self
+    }
+
+    pub fn parent(&self) -> Option<NodeHash> {
+        match self.status {
+            CrnStatus::Linked { parent } => Some(parent),
+            CrnStatus::Waiting => None,
+        }
+    }
 }
 
 /// Deserialize an address that may be an empty string. Empty strings are treated as `None`.
@@ -91,17 +226,62 @@ where
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Deserialize a string that may be empty. Empty strings are treated as `None`.
+fn deserialize_optional_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    Ok(s.filter(|s| !s.is_empty()))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CoreChannelContent {
     pub nodes: Vec<CcnInfo>,
     pub resource_nodes: Vec<CrnInfo>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl CoreChannelContent {
+    pub fn new(nodes: Vec<CcnInfo>, resource_nodes: Vec<CrnInfo>) -> Self {
+        Self {
+            nodes,
+            resource_nodes,
+        }
+    }
+
+    /// CRNs linked to the CCN `parent`.
+    pub fn resource_nodes_of(&self, parent: NodeHash) -> impl Iterator<Item = &CrnInfo> {
+        self.resource_nodes
+            .iter()
+            .filter(move |crn| crn.parent() == Some(parent))
+    }
+
+    /// Total amount of ALEPH staked across all CCNs.
+    pub fn total_staked(&self) -> f64 {
+        self.nodes.iter().map(CcnInfo::total_staked).sum()
+    }
+
+    /// Stakes held by `address`, as `(node, amount)` pairs. Empty if the
+    /// address does not stake on any node.
+    pub fn stakes_by_address(&self, address: &Address) -> Vec<(NodeHash, f64)> {
+        self.nodes
+            .iter()
+            .filter_map(|node| node.stake_of(address).map(|amount| (node.hash, amount)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CoreChannelAggregate {
     pub corechannel: CoreChannelContent,
 }
 
+impl From<CoreChannelContent> for CoreChannelAggregate {
+    fn from(corechannel: CoreChannelContent) -> Self {
+        Self { corechannel }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +305,147 @@ mod tests {
         assert!(matches!(waiting_with_explicit_parent, CrnStatus::Waiting));
     }
 
+    fn ccn(hash: &str, stakers: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "hash": hash,
+            "name": "node",
+            "time": 1700000000.0,
+            "owner": "0x0000000000000000000000000000000000000001",
+            "score": 0.9,
+            "reward": "0x0000000000000000000000000000000000000001",
+            "multiaddress": "",
+            "stakers": stakers,
+        })
+    }
+
+    #[test]
+    fn test_staking_helpers() {
+        let first = "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef";
+        let second = "feedfacefeedfacefeedfacefeedfacefeedfacefeedfacefeedfacefeedface";
+        let content: CoreChannelContent = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                ccn(first, serde_json::json!({
+                    "0xAbCd000000000000000000000000000000000001": 1000.0,
+                    "0x0000000000000000000000000000000000000002": 500.5,
+                })),
+                ccn(second, serde_json::json!({
+                    "0xabcd000000000000000000000000000000000001": 250.0,
+                })),
+            ],
+            "resource_nodes": [],
+        }))
+        .unwrap();
+
+        assert_eq!(content.nodes[0].total_staked(), 1500.5);
+        assert_eq!(content.total_staked(), 1750.5);
+
+        let staker = address!("0xABCD000000000000000000000000000000000001");
+        let mut stakes = content.stakes_by_address(&staker);
+        stakes.sort_by(|a, b| a.1.total_cmp(&b.1));
+        assert_eq!(
+            stakes,
+            vec![
+                (NodeHash::from_str(second).unwrap(), 250.0),
+                (NodeHash::from_str(first).unwrap(), 1000.0),
+            ]
+        );
+        assert!(
+            content
+                .stakes_by_address(&address!("0x0000000000000000000000000000000000000003"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_ccn_without_stakers_defaults_to_empty() {
+        let mut value = ccn(
+            "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+            serde_json::Value::Null,
+        );
+        value.as_object_mut().unwrap().remove("stakers");
+        let node: CcnInfo = serde_json::from_value(value).unwrap();
+        assert_eq!(node.total_staked(), 0.0);
+    }
+
+    #[test]
+    fn test_constructed_aggregate_round_trips() {
+        let ccn_hash =
+            NodeHash::from_str("deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef")
+                .unwrap();
+        let crn_hash =
+            NodeHash::from_str("feedfacefeedfacefeedfacefeedfacefeedfacefeedfacefeedfacefeedface")
+                .unwrap();
+        let owner = address!("0x0000000000000000000000000000000000000001");
+        let aggregate = CoreChannelAggregate::from(CoreChannelContent::new(
+            vec![
+                CcnInfo::new(ccn_hash, "ccn", owner.clone(), "/ip4/1.2.3.4/tcp/4025").with_stake(
+                    address!("0x0000000000000000000000000000000000000002"),
+                    200.0,
+                ),
+            ],
+            vec![
+                CrnInfo::new(crn_hash, "crn", owner.clone(), "https://crn.example.com")
+                    .linked_to(ccn_hash),
+                CrnInfo::new(crn_hash, "idle", owner, "https://idle.example.com"),
+            ],
+        ));
+
+        let json = serde_json::to_value(&aggregate).unwrap();
+        assert_eq!(json["corechannel"]["resource_nodes"][0]["status"], "linked");
+        let parsed: CoreChannelAggregate = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.corechannel.total_staked(), 200.0);
+        let linked: Vec<_> = parsed
+            .corechannel
+            .resource_nodes_of(ccn_hash)
+            .map(|crn| crn.name.as_str())
+            .collect();
+        assert_eq!(linked, ["crn"]);
+    }
+
+    #[test]
+    fn test_deserialize_crn_extended_fields() {
+        let crn: CrnInfo = serde_json::from_value(serde_json::json!({
+            "hash": "feedfacefeedfacefeedfacefeedfacefeedfacefeedfacefeedfacefeedface",
+            "name": "crn",
+            "time": 1700000000.0,
+            "type": "compute",
+            "owner": "0x0000000000000000000000000000000000000001",
+            "manager": "",
+            "reward": "0x0000000000000000000000000000000000000001",
+            "stream_reward": "0x0000000000000000000000000000000000000002",
+            "address": "https://crn.example.com",
+            "locked": true,
+            "authorized": ["0x0000000000000000000000000000000000000003"],
+            "status": "waiting",
+            "parent": null,
+            "score": 0.8,
+            "performance": 0.9,
+            "decentralization": 0.7,
+            "version": "1.5.1",
+            "registration_url": "https://example.com/register",
+            "terms_and_conditions": "",
+            "picture": "",
+            "banner": "",
+            "description": "GPU node",
+            "inactive_since": null,
+        }))
+        .unwrap();
+
+        assert_eq!(crn.node_type.as_deref(), Some("compute"));
+        assert_eq!(crn.manager, None);
+        assert!(crn.locked);
+        assert_eq!(crn.authorized.len(), 1);
+        assert_eq!(crn.performance, Some(0.9));
+        assert_eq!(crn.decentralization, Some(0.7));
+        assert_eq!(crn.version.as_deref(), Some("1.5.1"));
+        assert_eq!(
+            crn.registration_url.as_deref(),
+            Some("https://example.com/register")
+        );
+        assert_eq!(crn.terms_and_conditions, None);
+        assert_eq!(crn.description.as_deref(), Some("GPU node"));
+    }
+
     #[derive(Debug, Deserialize)]
     struct OptionalAddressWrapper {
         #[serde(default, deserialize_with = "deserialize_optional_address")]
@@ -3,6 +3,7 @@
 
 use aleph_types::address;
 use aleph_types::chain::Address;
+use aleph_types::message::execution::environment::Vcpus;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::LazyLock;
@@ -199,6 +200,21 @@ impl PricingPerEntity {
         })
     }
 
+    /// Compute units needed for `vcpus` with this entity's compute unit.
+    pub fn compute_units_for_vcpus(&self, vcpus: Vcpus) -> u32 {
+        vcpus.compute_units(self.compute_unit.vcpus)
+    }
+
+    /// The smallest tier providing at least `vcpus`, or `None` if they exceed
+    /// the largest tier.
+    pub fn tier_for_vcpus(&self, vcpus: Vcpus) -> Option<&Tier> {
+        let compute_units = self.compute_units_for_vcpus(vcpus);
+        self.tiers
+            .iter()
+            .filter(|tier| tier.compute_units >= compute_units)
+            .min_by_key(|tier| tier.compute_units)
+    }
+
     /// List all available slugs.
     pub fn available_slugs(&self) -> Vec<String> {
         self.tiers.iter().map(|tier| self.tier_slug(tier)).collect()
@@ -314,6 +330,17 @@ mod tests {
         assert!(pricing.find_tier_by_slug("3vcpu-6gb").is_none());
     }
 
+    #[test]
+    fn tier_for_vcpus_picks_smallest_fitting_tier() {
+        let pricing = test_pricing();
+        let vcpus = |n| Vcpus::new(n).unwrap();
+
+        assert_eq!(pricing.tier_for_vcpus(vcpus(4)).unwrap().id, "tier-3");
+        assert_eq!(pricing.tier_for_vcpus(vcpus(5)).unwrap().id, "tier-4");
+        assert!(pricing.tier_for_vcpus(vcpus(16)).is_none());
+        assert_eq!(pricing.compute_units_for_vcpus(vcpus(5)), 5);
+    }
+
     #[test]
     fn available_slugs_lists_all() {
         let pricing = test_pricing();
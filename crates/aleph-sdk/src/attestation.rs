@@ -0,0 +1,161 @@
+//! Launch attestation for confidential instances.
+//!
+//! Fetches the SEV launch measurement of a running VM from its CRN and checks
+//! it against the firmware and policy declared in the instance's
+//! `trusted_execution` settings, so a tenant can prove the VM launched
+//! untampered before handing it any secret. The crypto lives in
+//! [`crate::confidential`]; this module only adds the I/O around it.
+
+use crate::client::{AlephStorageClient, MessageError};
+use crate::confidential::{
+    ConfidentialError, DEFAULT_CONFIDENTIAL_FIRMWARE_HASH_HEX, SEVMeasurement, verify_measurement,
+};
+use crate::crn::{CrnClient, CrnError};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::execution::environment::TrustedExecutionEnvironment;
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttestationError {
+    #[error("failed to fetch the launch measurement: {0}")]
+    Crn(#[from] CrnError),
+    #[error("failed to download firmware {firmware}: {source}")]
+    FirmwareDownload {
+        firmware: ItemHash,
+        #[source]
+        source: MessageError,
+    },
+    #[error(transparent)]
+    Confidential(#[from] ConfidentialError),
+}
+
+/// A launch measurement that passed verification.
+#[derive(Debug, Clone)]
+pub struct Attestation {
+    /// The measurement as reported by the CRN.
+    pub measurement: SEVMeasurement,
+    /// SHA-256 of the OVMF firmware the measurement was checked against.
+    pub firmware_hash: [u8; 32],
+    /// The verified 32-byte VM measure, needed to build the secret packet.
+    pub vm_measure: [u8; 32],
+}
+
+/// Resolves the SHA-256 of the OVMF firmware a confidential instance boots.
+///
+/// `trusted_execution.firmware` is the hash of the STORE message that pinned
+/// the firmware blob: the blob is downloaded and hashed locally rather than
+/// trusting any digest served alongside it. When no firmware is set, the VM
+/// boots the platform default, [`DEFAULT_CONFIDENTIAL_FIRMWARE_HASH_HEX`].
+pub async fn resolve_firmware_hash<C>(
+    client: &C,
+    tee: &TrustedExecutionEnvironment,
+) -> Result<[u8; 32], AttestationError>
+where
+    C: AlephStorageClient + Sync,
+{
+    let Some(firmware) = &tee.firmware else {
+        return Ok(default_firmware_hash());
+    };
+    let download_error = |source| AttestationError::FirmwareDownload {
+        firmware: firmware.clone(),
+        source,
+    };
+
+    let download = client
+        .download_file_by_message_hash(firmware)
+        .await
+        .map_err(download_error)?;
+    let mut stream = download.into_stream();
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk
+            .map_err(reqwest_middleware::Error::from)
+            .map_err(|e| download_error(e.into()))?;
+        hasher.update(&chunk);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Fetches the launch measurement of `vm_id` and verifies it against the
+/// instance's declared firmware and SEV policy.
+///
+/// `tik` is the transport integrity key derived during session
+/// initialization (`sevctl session` writes it as `vm_tik.bin`).
+pub async fn attest_instance<C>(
+    crn: &CrnClient,
+    client: &C,
+    vm_id: &ItemHash,
+    tee: &TrustedExecutionEnvironment,
+    tik: &[u8; 16],
+) -> Result<Attestation, AttestationError>
+where
+    C: AlephStorageClient + Sync,
+{
+    let firmware_hash = resolve_firmware_hash(client, tee).await?;
+    let measurement = crn.get_measurement(vm_id).await?;
+    let vm_measure = verify_measurement(&measurement, tik, &firmware_hash, Some(tee.policy))?;
+    Ok(Attestation {
+        measurement,
+        firmware_hash,
+        vm_measure,
+    })
+}
+
+fn default_firmware_hash() -> [u8; 32] {
+    hex::decode(DEFAULT_CONFIDENTIAL_FIRMWARE_HASH_HEX)
+        .expect("default firmware hash is valid hex")
+        .try_into()
+        .expect("default firmware hash is 32 bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AlephClient;
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn resolve_firmware_hash_defaults_when_unset() {
+        let client = AlephClient::new(Url::parse("http://test.invalid").unwrap());
+        let tee = TrustedExecutionEnvironment {
+            firmware: None,
+            policy: 1,
+        };
+        let hash = resolve_firmware_hash(&client, &tee).await.unwrap();
+        assert_eq!(hex::encode(hash), DEFAULT_CONFIDENTIAL_FIRMWARE_HASH_HEX);
+    }
+
+    #[tokio::test]
+    async fn resolve_firmware_hash_hashes_downloaded_blob() {
+        let server = MockServer::start().await;
+        let firmware = ItemHash::from([1u8; 32]);
+        // SHA-256("abc")
+        let file_hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/storage/by-message-hash/{firmware}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ref": firmware.to_string(),
+                "owner": "0x0000000000000000000000000000000000000000",
+                "file_hash": file_hash,
+                "size": 3,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/storage/raw/{file_hash}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"abc".to_vec()))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let tee = TrustedExecutionEnvironment {
+            firmware: Some(firmware),
+            policy: 1,
+        };
+        let hash = resolve_firmware_hash(&client, &tee).await.unwrap();
+        assert_eq!(hex::encode(hash), file_hash);
+    }
+}
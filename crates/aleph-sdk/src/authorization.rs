@@ -59,9 +59,10 @@ pub trait AlephAuthorizationClient: AlephAggregateClient {
                 Ok(agg) => Ok(agg.security.authorizations),
                 // A missing security aggregate causes a deserialization failure
                 // (the response body doesn't match SecurityAggregate), which
-                // surfaces as HttpError. Treat that as "no authorizations yet".
-                // Other error variants (ApiError, Io, etc.) are propagated.
+                // surfaces as HttpError, or a 404 on some nodes. Treat both as
+                // "no authorizations yet". Other errors are propagated.
                 Err(MessageError::HttpError(_)) => Ok(vec![]),
+                Err(e) if e.is_not_found() => Ok(vec![]),
                 Err(other) => Err(other),
             }
         }
@@ -1,5 +1,6 @@
-use aleph_types::account::{Account, SignError, sign_message};
-use aleph_types::chain::Address;
+use aleph_types::account::{Account, SignError, sign_message_for_chain};
+use aleph_types::canonical_json::{self, JsonFormat};
+use aleph_types::chain::{Address, Chain};
 use aleph_types::channel::Channel;
 use aleph_types::item_hash::{AlephItemHash, ItemHash};
 use aleph_types::message::MessageType;
@@ -19,8 +20,12 @@ const DEFAULT_IPFS_CUTOFF: usize = 4 * 1024 * 1024; // 4 MiB
 /// JSON serialization, storage routing based on size cutoffs, hash
 /// computation (SHA-256 for inline/storage, IPFS CID for large content),
 /// and signing via the provided `Account`.
+///
+/// The message chain is the account's own unless overridden with
+/// [`chain`](Self::chain).
 pub struct MessageBuilder<'a, A: Account> {
     account: &'a A,
+    chain: Option<Chain>,
     owner: Option<Address>,
     message_type: MessageType,
     content: serde_json::Value,
@@ -29,12 +34,14 @@ pub struct MessageBuilder<'a, A: Account> {
     allow_inlining: bool,
     inline_cutoff: usize,
     ipfs_cutoff: usize,
+    json_format: JsonFormat,
 }
 
 impl<'a, A: Account> MessageBuilder<'a, A> {
     pub fn new(account: &'a A, message_type: MessageType, content: serde_json::Value) -> Self {
         Self {
             account,
+            chain: None,
             owner: None,
             message_type,
             content,
@@ -43,6 +50,7 @@ impl<'a, A: Account> MessageBuilder<'a, A> {
             allow_inlining: true,
             inline_cutoff: DEFAULT_INLINE_CUTOFF,
             ipfs_cutoff: DEFAULT_IPFS_CUTOFF,
+            json_format: JsonFormat::default(),
         }
     }
 
@@ -54,6 +62,14 @@ impl<'a, A: Account> MessageBuilder<'a, A> {
         self
     }
 
+    /// Sends the message from `chain` instead of the account's chain. The
+    /// chain must use the signature scheme of the account, or
+    /// [`build`](Self::build) fails with [`SignError::ChainMismatch`].
+    pub fn chain(mut self, chain: Chain) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
     pub fn channel(mut self, channel: Channel) -> Self {
         self.channel = Some(channel);
         self
@@ -79,10 +95,17 @@ impl<'a, A: Account> MessageBuilder<'a, A> {
         self
     }
 
+    /// Serializes the content in `format`. Defaults to the format of the
+    /// Python SDK, so that the same content gets the same item hash.
+    pub fn json_format(mut self, format: JsonFormat) -> Self {
+        self.json_format = format;
+        self
+    }
+
     /// Builds and signs the message, returning a `PendingMessage` ready for submission.
     ///
     /// 1. Injects `address` and `time` into the content envelope
-    /// 2. Serializes to compact JSON
+    /// 2. Serializes to JSON, see [`json_format`](Self::json_format)
     /// 3. Routes to inline/storage/IPFS based on size
     /// 4. Computes the appropriate hash
     /// 5. Signs using the account
@@ -106,7 +129,8 @@ impl<'a, A: Account> MessageBuilder<'a, A> {
             }
         }
 
-        let item_content = serde_json::to_string(&serde_json::Value::Object(envelope)).unwrap();
+        let item_content =
+            canonical_json::to_string(&serde_json::Value::Object(envelope), self.json_format);
         let len = item_content.len();
 
         let (item_type, item_hash) = if self.allow_inlining && len < self.inline_cutoff {
@@ -129,43 +153,19 @@ impl<'a, A: Account> MessageBuilder<'a, A> {
             channel: self.channel,
         };
 
-        sign_message(self.account, unsigned)
+        let chain = self.chain.unwrap_or_else(|| self.account.chain());
+        sign_message_for_chain(self.account, unsigned, chain)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aleph_types::account::{Account, SignError};
-    use aleph_types::chain::{Address, Chain, Signature};
+    use crate::test_utils::TestAccount;
+    use aleph_types::chain::{Address, Chain};
     use aleph_types::message::MessageType;
     use aleph_types::message::item_type::ItemType;
 
-    /// Minimal test account that produces a dummy signature.
-    struct TestAccount {
-        address: Address,
-    }
-
-    impl TestAccount {
-        fn new() -> Self {
-            Self {
-                address: Address::from("0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string()),
-            }
-        }
-    }
-
-    impl Account for TestAccount {
-        fn chain(&self) -> Chain {
-            Chain::Ethereum
-        }
-        fn address(&self) -> &Address {
-            &self.address
-        }
-        fn sign_raw(&self, _buffer: &[u8]) -> Result<Signature, SignError> {
-            Ok(Signature::from("0xDUMMY".to_string()))
-        }
-    }
-
     #[test]
     fn test_builder_inline_small_content() {
         let account = TestAccount::new();
@@ -233,6 +233,23 @@ mod tests {
         assert_eq!(pending.item_type, ItemType::Storage);
     }
 
+    #[test]
+    fn test_builder_chain_override() {
+        let account = TestAccount::new();
+        let content = serde_json::json!({"type": "test"});
+        let pending = MessageBuilder::new(&account, MessageType::Post, content.clone())
+            .chain(Chain::Base)
+            .build()
+            .unwrap();
+        assert_eq!(pending.chain, Chain::Base);
+
+        let err = MessageBuilder::new(&account, MessageType::Post, content)
+            .chain(Chain::Sol)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, SignError::ChainMismatch { .. }));
+    }
+
     #[test]
     fn test_builder_channel() {
         let account = TestAccount::new();
@@ -298,4 +315,28 @@ mod tests {
         );
         assert_eq!(pending.sender, account.address().clone());
     }
+
+    #[test]
+    fn test_builder_serializes_like_python() {
+        let account = TestAccount::new();
+        let content =
+            serde_json::json!({"type": "test", "content": {"body": "café", "ratio": 1e-5}});
+        let time = Timestamp::from(1762515431.653);
+        let pending = MessageBuilder::new(&account, MessageType::Post, content.clone())
+            .time(time.clone())
+            .build()
+            .unwrap();
+        // json.dumps(content, separators=(",", ":"), sort_keys=True)
+        assert_eq!(
+            pending.item_content,
+            r#"{"address":"0xB68B9D4f3771c246233823ed1D3Add451055F9Ef","content":{"body":"caf\u00e9","ratio":1e-05},"time":1762515431.653,"type":"test"}"#
+        );
+
+        let pending = MessageBuilder::new(&account, MessageType::Post, content)
+            .time(time)
+            .json_format(JsonFormat::COMPACT_UTF8)
+            .build()
+            .unwrap();
+        assert!(pending.item_content.contains(r#""body":"café""#));
+    }
 }
@@ -0,0 +1,125 @@
+//! Local verification of many messages at once.
+//!
+//! Indexers validating full channel histories mostly spend their time on
+//! signature recovery, which is CPU-bound. [`verify_all`] spreads it over the
+//! blocking thread pool of the tokio runtime, one batch per available core.
+//!
+//! Only checks that need no network access are performed: the item hash of
+//! inline messages and the signature of every message. Non-inline content has
+//! to be downloaded first; use
+//! [`get_messages_and_verify`](crate::client::AlephMessageClient::get_messages_and_verify)
+//! for that.
+
+use aleph_types::message::{Message, MessageVerificationError, SignatureVerificationError};
+use std::num::NonZeroUsize;
+
+/// Outcome of the local checks on one message.
+#[derive(Debug)]
+pub struct VerificationReport {
+    pub message: Message,
+    /// Result of the item hash check, or `None` for non-inline messages.
+    pub item_hash: Option<Result<(), MessageVerificationError>>,
+    pub signature: Result<(), SignatureVerificationError>,
+}
+
+impl VerificationReport {
+    fn new(message: Message) -> Self {
+        let item_hash = match message.verify_item_hash() {
+            Err(MessageVerificationError::NonInlineMessage) => None,
+            result => Some(result),
+        };
+        let signature = message.verify_signature();
+        Self {
+            message,
+            item_hash,
+            signature,
+        }
+    }
+
+    /// Whether every check that could be performed passed. Non-inline
+    /// messages only have their signature checked.
+    pub fn is_valid(&self) -> bool {
+        self.signature.is_ok() && self.item_hash.as_ref().is_none_or(Result::is_ok)
+    }
+}
+
+/// Verifies the item hash and signature of every message in parallel.
+///
+/// Reports are returned in the order of `messages`. Must be called from
+/// within a tokio runtime.
+pub async fn verify_all(messages: Vec<Message>) -> Vec<VerificationReport> {
+    let workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let batch_size = messages.len().div_ceil(workers).max(1);
+
+    let mut batches = Vec::with_capacity(workers);
+    let mut messages = messages.into_iter();
+    loop {
+        let batch: Vec<Message> = messages.by_ref().take(batch_size).collect();
+        if batch.is_empty() {
+            break;
+        }
+        batches.push(tokio::task::spawn_blocking(move || {
+            batch
+                .into_iter()
+                .map(VerificationReport::new)
+                .collect::<Vec<_>>()
+        }));
+    }
+
+    let mut reports = Vec::with_capacity(batches.len() * batch_size);
+    for batch in batches {
+        match batch.await {
+            Ok(batch) => reports.extend(batch),
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::chain::Address;
+    use aleph_types::message::ContentSource;
+
+    fn fixture(json: &str) -> Message {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn verify_all_reports_in_order() {
+        let post = fixture(include_str!("../../../fixtures/messages/post/post.json"));
+        let sol = fixture(include_str!(
+            "../../../fixtures/messages/post/post-sol.json"
+        ));
+        let mut stored = post.clone();
+        stored.content_source = ContentSource::Storage;
+        let mut forged = post.clone();
+        forged.sender = Address::from("0x0000000000000000000000000000000000000001".to_string());
+
+        let messages: Vec<_> = [post, sol, forged]
+            .into_iter()
+            .cycle()
+            .take(30)
+            .chain([stored])
+            .collect();
+        let expected: Vec<_> = messages.iter().map(|m| m.item_hash.clone()).collect();
+
+        let reports = verify_all(messages).await;
+        let hashes: Vec<_> = reports
+            .iter()
+            .map(|r| r.message.item_hash.clone())
+            .collect();
+        assert_eq!(hashes, expected);
+        for (i, report) in reports[..30].iter().enumerate() {
+            assert_eq!(report.is_valid(), i % 3 != 2, "report {i}");
+            assert!(matches!(report.item_hash, Some(Ok(()))));
+        }
+        assert!(reports[30].item_hash.is_none());
+    }
+
+    #[tokio::test]
+    async fn verify_all_accepts_empty_input() {
+        assert!(verify_all(Vec::new()).await.is_empty());
+    }
+}
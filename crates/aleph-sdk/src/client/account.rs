@@ -0,0 +1,805 @@
+//! Balances, credits and files of accounts.
+
+use super::error::CheckStatus;
+use super::pagination::{CURSOR_DEFAULT_PAGINATION, CURSOR_MAX_PAGINATION};
+use super::{AlephAccountClient, AlephClient, MessageError};
+use aleph_types::chain::Address;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::MessageType;
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use memsizes::Bytes;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct AccountBalance {
+    #[serde(rename = "balance")]
+    pub aleph_tokens: f64,
+    #[serde(rename = "locked_amount")]
+    pub locked_aleph_tokens: f64,
+    #[serde(default, rename = "credit_balance")]
+    pub credits: u64,
+}
+
+impl AccountBalance {
+    /// ALEPH tokens not already locked by the account's holder-tier
+    /// resources, i.e. what is left to hold new ones.
+    pub fn available_aleph_tokens(&self) -> f64 {
+        (self.aleph_tokens - self.locked_aleph_tokens).max(0.0)
+    }
+}
+
+/// One row of `/api/v0/addresses/{address}/credit_history`.
+///
+/// Mirrors `aleph.schemas.api.accounts.CreditHistoryResponseItem` in pyaleph.
+/// Optional fields reflect that purchase / transfer / expiration entries
+/// populate different subsets.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreditHistoryItem {
+    pub amount: i64,
+    /// Per-credit price at the time of the entry, serialized as a decimal
+    /// string (e.g. `"0.000001"`). `None` for non-purchase rows.
+    #[serde(default)]
+    pub price: Option<String>,
+    #[serde(default)]
+    pub bonus_amount: Option<i64>,
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub chain: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub origin: Option<String>,
+    #[serde(default)]
+    pub origin_ref: Option<String>,
+    #[serde(default)]
+    pub payment_method: Option<String>,
+    pub credit_ref: String,
+    pub credit_index: i64,
+    #[serde(default)]
+    pub expiration_date: Option<DateTime<Utc>>,
+    pub message_timestamp: DateTime<Utc>,
+}
+
+/// Page-paginated response from `/api/v0/addresses/{address}/credit_history`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreditHistoryResponse {
+    pub address: String,
+    pub credit_history: Vec<CreditHistoryItem>,
+    pub pagination_page: u32,
+    pub pagination_total: u64,
+    pub pagination_per_page: u32,
+}
+
+/// Sign-based classification of a credit history entry, mirroring pyaleph's
+/// `CreditFlow`. Incoming entries have a positive amount (distributions,
+/// received transfers); outgoing entries have a negative amount (expenses,
+/// sent transfers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreditDirection {
+    Incoming,
+    Outgoing,
+}
+
+impl CreditDirection {
+    /// The `direction` query-param value expected by pyaleph.
+    fn as_wire(self) -> &'static str {
+        match self {
+            CreditDirection::Incoming => "incoming",
+            CreditDirection::Outgoing => "outgoing",
+        }
+    }
+}
+
+/// Server-side filters shared by the credit history listing and summary
+/// endpoints. All fields are optional; the default value applies no filtering.
+#[derive(Debug, Default, Clone)]
+pub struct CreditHistoryFilters {
+    /// Inclusive lower bound on `message_timestamp`, as Unix seconds.
+    pub start_date: Option<i64>,
+    /// Inclusive upper bound on `message_timestamp`, as Unix seconds.
+    pub end_date: Option<i64>,
+    /// Restrict to incoming (top-ups) or outgoing (expenses) entries.
+    pub direction: Option<CreditDirection>,
+    /// Restrict to entries whose billed resource is one of these message
+    /// types (e.g. `STORE` for storage, `INSTANCE`/`PROGRAM` for compute).
+    pub resource_types: Vec<MessageType>,
+    /// Restrict to expenses billed to a single resource, identified by its
+    /// item hash (e.g. a VM/instance hash). Top-ups are never associated with
+    /// a resource, so this only ever narrows to outgoing entries.
+    pub resource: Option<String>,
+}
+
+impl CreditHistoryFilters {
+    /// Render the filters as repeated query parameters in pyaleph's camelCase
+    /// wire vocabulary. Empty/`None` fields are omitted.
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(start) = self.start_date {
+            params.push(("startDate", start.to_string()));
+        }
+        if let Some(end) = self.end_date {
+            params.push(("endDate", end.to_string()));
+        }
+        if let Some(direction) = self.direction {
+            params.push(("direction", direction.as_wire().to_string()));
+        }
+        if !self.resource_types.is_empty() {
+            let joined = self
+                .resource_types
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            params.push(("resourceTypes", joined));
+        }
+        if let Some(resource) = &self.resource {
+            params.push(("resource", resource.clone()));
+        }
+        params
+    }
+}
+
+/// Aggregate spend totals from
+/// `/api/v0/addresses/{address}/credit_history/summary`.
+///
+/// Mirrors `aleph.schemas.api.accounts.GetAccountCreditHistorySummaryResponse`
+/// in pyaleph. `total_incoming` is the sum of positive amounts (>= 0),
+/// `total_outgoing` the sum of negative amounts (<= 0), and `total_amount`
+/// their net.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreditHistorySummary {
+    pub address: String,
+    pub entry_count: i64,
+    pub total_amount: i64,
+    pub total_incoming: i64,
+    pub total_outgoing: i64,
+}
+
+/// One row of `/api/v0/addresses/{address}/files`.
+///
+/// `created` is a Unix-epoch timestamp (the cursor-mode shape used by the
+/// iterator). The non-cursor mode of the endpoint serializes `created` as
+/// an ISO string instead, but the SDK only ever reads cursor mode.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AccountFile {
+    pub file_hash: String,
+    pub size: Bytes,
+    /// Server-side storage backend tag (e.g. `"file"`, `"dir"`).
+    #[serde(rename = "type")]
+    pub storage_engine: String,
+    pub created: aleph_types::timestamp::Timestamp,
+    pub item_hash: ItemHash,
+}
+
+/// One cursor-mode page of `/api/v0/addresses/{address}/files`. Private:
+/// callers either walk the iterator or hit `get_total_storage_size` for the
+/// address-wide total.
+///
+/// `total_size` is read as `f64` because pyaleph emits it as a float in
+/// cursor mode (e.g. `78051738.0`); the wrapper rounds to the nearest byte.
+#[derive(Debug, Deserialize)]
+struct AccountFilesCursorResponse {
+    #[serde(default)]
+    files: Vec<AccountFile>,
+    #[serde(default)]
+    total_size: f64,
+    #[serde(default)]
+    next_cursor: Option<String>,
+}
+
+impl AccountFilesCursorResponse {
+    fn total_size_bytes(&self) -> Bytes {
+        Bytes::from(self.total_size.round().max(0.0) as u64)
+    }
+}
+
+impl AlephAccountClient for AlephClient {
+    async fn get_balance(&self, address: &Address) -> Result<AccountBalance, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/addresses/{}/balance", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+        let account_balance: AccountBalance = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        Ok(account_balance)
+    }
+
+    async fn get_total_storage_size(&self, address: &Address) -> Result<Bytes, MessageError> {
+        // Cursor mode with the smallest possible page still carries the
+        // address-wide `total_size`. Costs us one cheap row over the wire.
+        let page = self
+            .get_account_files_cursor(address, None, 1, None)
+            .await?;
+        Ok(page.total_size_bytes())
+    }
+
+    fn get_account_files_iterator(
+        &self,
+        address: &Address,
+        pagination: Option<u32>,
+        sort_order: Option<i8>,
+    ) -> impl Stream<Item = Result<AccountFile, MessageError>> + Send + '_ {
+        let pagination = pagination
+            .unwrap_or(CURSOR_DEFAULT_PAGINATION)
+            .min(CURSOR_MAX_PAGINATION);
+        let address = address.clone();
+        async_stream::try_stream! {
+            let mut cursor: Option<String> = None;
+            loop {
+                let response = self
+                    .get_account_files_cursor(&address, cursor.as_deref(), pagination, sort_order)
+                    .await?;
+                let next = response.next_cursor.clone();
+                for file in response.files {
+                    yield file;
+                }
+                cursor = match next {
+                    Some(c) => Some(c),
+                    None => break,
+                };
+            }
+        }
+    }
+
+    async fn get_vm_price(&self, item_hash: &ItemHash) -> Result<f64, MessageError> {
+        Ok(self.get_price(item_hash).await?.required_tokens)
+    }
+
+    async fn get_credit_history(
+        &self,
+        address: &Address,
+        page: u32,
+        page_size: Option<u32>,
+        filters: &CreditHistoryFilters,
+    ) -> Result<CreditHistoryResponse, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/addresses/{}/credit_history", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let mut request = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&[("page", page.to_string())])
+            .query(&filters.query_params());
+        if let Some(per_page) = page_size {
+            request = request.query(&[("pagination", per_page.to_string())]);
+        }
+
+        let response = request.send().await?;
+
+        // pyaleph returns 404 when the address has no credit history rather
+        // than an empty page. Surface that as an empty response so callers
+        // don't have to special-case the status code.
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(CreditHistoryResponse {
+                address: address.to_string(),
+                credit_history: Vec::new(),
+                pagination_page: page,
+                pagination_total: 0,
+                pagination_per_page: page_size.unwrap_or(0),
+            });
+        }
+
+        let response = response.check_status().await?;
+        let history: CreditHistoryResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(history)
+    }
+
+    async fn get_credit_history_summary(
+        &self,
+        address: &Address,
+        filters: &CreditHistoryFilters,
+    ) -> Result<CreditHistorySummary, MessageError> {
+        let path = format!("/api/v0/addresses/{}/credit_history/summary", address);
+        let url = self
+            .inner
+            .ccn_url
+            .join(&path)
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filters.query_params())
+            .send()
+            .await?;
+
+        // Unlike the listing endpoint, the summary endpoint always returns 200
+        // (with zeroed totals for an unknown address). A 404 therefore means
+        // the route does not exist, i.e. the CCN predates this endpoint, so we
+        // surface it as an error rather than silently reporting zero spend.
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(MessageError::ApiError {
+                status: StatusCode::NOT_FOUND.as_u16(),
+                body: format!(
+                    "credit history summary endpoint not found ({path}); \
+                     this CCN may be running a version without support for it"
+                ),
+            });
+        }
+
+        let response = response.check_status().await?;
+        let summary: CreditHistorySummary = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(summary)
+    }
+}
+
+impl AlephClient {
+    /// Fetch one cursor-mode page of `/api/v0/addresses/{address}/files`.
+    ///
+    /// Passing any value for `cursor` (including an empty string on the
+    /// first request) activates the server's cursor mode. Returns an empty
+    /// page when the address has no files (the endpoint responds with 404
+    /// in that case).
+    async fn get_account_files_cursor(
+        &self,
+        address: &Address,
+        cursor: Option<&str>,
+        pagination: u32,
+        sort_order: Option<i8>,
+    ) -> Result<AccountFilesCursorResponse, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/addresses/{}/files", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let mut req = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&[("cursor", cursor.unwrap_or(""))])
+            .query(&[("pagination", &pagination.to_string())]);
+        if let Some(order) = sort_order {
+            req = req.query(&[("sort_order", &order.to_string())]);
+        }
+
+        let response = req.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(AccountFilesCursorResponse {
+                files: Vec::new(),
+                total_size: 0.0,
+                next_cursor: None,
+            });
+        }
+        let response = response.check_status().await?;
+        let page: AccountFilesCursorResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::RejectedMessage;
+    use aleph_types::message::item_type::ItemType;
+    use futures_util::TryStreamExt;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn item_handles_missing_and_null_optionals() {
+        // Source-of-truth row with every optional field populated.
+        let purchase = serde_json::json!({
+            "amount": 1_000_000,
+            "price": "0.000001",
+            "bonus_amount": 50_000,
+            "tx_hash": "0xdeadbeef",
+            "token": "USDC",
+            "chain": "ETH",
+            "provider": "stripe",
+            "origin": "web",
+            "origin_ref": "ord_42",
+            "payment_method": "card",
+            "credit_ref": "purchase:0xdeadbeef:0",
+            "credit_index": 0,
+            "expiration_date": "2027-01-01T00:00:00Z",
+            "message_timestamp": "2026-05-01T12:00:00Z",
+        });
+        let _: CreditHistoryItem = serde_json::from_value(purchase).unwrap();
+
+        // Optional fields explicitly null (the shape the reviewer flagged).
+        let null_optionals = serde_json::json!({
+            "amount": -500,
+            "price": null,
+            "bonus_amount": null,
+            "tx_hash": null,
+            "token": null,
+            "chain": null,
+            "provider": null,
+            "origin": null,
+            "origin_ref": null,
+            "payment_method": null,
+            "credit_ref": "transfer:abc:0",
+            "credit_index": 0,
+            "expiration_date": null,
+            "message_timestamp": "2026-05-01T12:00:00Z",
+        });
+        let item: CreditHistoryItem = serde_json::from_value(null_optionals).unwrap();
+        assert!(item.expiration_date.is_none());
+        assert!(item.payment_method.is_none());
+        assert!(item.price.is_none());
+
+        // Optional fields entirely missing from the payload.
+        let missing_optionals = serde_json::json!({
+            "amount": 1,
+            "credit_ref": "x:0",
+            "credit_index": 1,
+            "message_timestamp": "2026-05-01T12:00:00Z",
+        });
+        let item: CreditHistoryItem = serde_json::from_value(missing_optionals).unwrap();
+        assert!(item.expiration_date.is_none());
+        assert!(item.payment_method.is_none());
+    }
+
+    #[test]
+    fn credit_direction_wire_values() {
+        assert_eq!(CreditDirection::Incoming.as_wire(), "incoming");
+        assert_eq!(CreditDirection::Outgoing.as_wire(), "outgoing");
+    }
+
+    #[test]
+    fn empty_filters_emit_no_query_params() {
+        assert!(CreditHistoryFilters::default().query_params().is_empty());
+    }
+
+    #[test]
+    fn filters_render_camelcase_query_params() {
+        let filters = CreditHistoryFilters {
+            start_date: Some(1_769_990_400),
+            end_date: Some(1_770_000_000),
+            direction: Some(CreditDirection::Outgoing),
+            resource_types: vec![MessageType::Store, MessageType::Program],
+            resource: None,
+        };
+        let params = filters.query_params();
+        assert_eq!(
+            params,
+            vec![
+                ("startDate", "1769990400".to_string()),
+                ("endDate", "1770000000".to_string()),
+                ("direction", "outgoing".to_string()),
+                ("resourceTypes", "STORE,PROGRAM".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_render_resource_query_param() {
+        let filters = CreditHistoryFilters {
+            resource: Some("vm-hash-abc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            filters.query_params(),
+            vec![("resource", "vm-hash-abc".to_string())]
+        );
+    }
+
+    #[test]
+    fn summary_deserializes() {
+        let body = serde_json::json!({
+            "address": "0xabc",
+            "entry_count": 42,
+            "total_amount": -1_250_000,
+            "total_incoming": 3_000_000,
+            "total_outgoing": -4_250_000,
+        });
+        let summary: CreditHistorySummary = serde_json::from_value(body).unwrap();
+        assert_eq!(summary.entry_count, 42);
+        assert_eq!(summary.total_amount, -1_250_000);
+        assert_eq!(summary.total_incoming, 3_000_000);
+        assert_eq!(summary.total_outgoing, -4_250_000);
+    }
+
+    #[tokio::test]
+    async fn summary_sends_filters_and_parses_totals() {
+        let server = MockServer::start().await;
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/api/v0/addresses/{addr}/credit_history/summary"
+            )))
+            .and(query_param("startDate", "1769990400"))
+            .and(query_param("direction", "outgoing"))
+            .and(query_param("resourceTypes", "STORE,PROGRAM"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": addr.to_string(),
+                "entry_count": 3,
+                "total_amount": -900,
+                "total_incoming": 0,
+                "total_outgoing": -900,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let filters = CreditHistoryFilters {
+            start_date: Some(1_769_990_400),
+            end_date: None,
+            direction: Some(CreditDirection::Outgoing),
+            resource_types: vec![MessageType::Store, MessageType::Program],
+            resource: None,
+        };
+        let summary = client
+            .get_credit_history_summary(&addr, &filters)
+            .await
+            .unwrap();
+        assert_eq!(summary.entry_count, 3);
+        assert_eq!(summary.total_outgoing, -900);
+    }
+
+    #[tokio::test]
+    async fn summary_404_is_an_error_not_silent_zeros() {
+        // An old CCN without the summary route 404s. We must not report that
+        // as zero spend; it has to surface as an error so the caller knows the
+        // server is unsupported.
+        let server = MockServer::start().await;
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/api/v0/addresses/{addr}/credit_history/summary"
+            )))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let err = client
+            .get_credit_history_summary(&addr, &CreditHistoryFilters::default())
+            .await
+            .expect_err("404 must be an error, not zeroed totals");
+        match err {
+            MessageError::ApiError { status, body } => {
+                assert_eq!(status, 404);
+                assert!(body.contains("summary endpoint not found"), "got: {body}");
+            }
+            other => panic!("expected ApiError, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn account_file_deserializes_cursor_mode() {
+        // Cursor-mode rows ship `created` as a unix-epoch float.
+        let body = json!({
+            "file_hash": "Qmabc",
+            "size": 1234,
+            "type": "file",
+            "created": 1779373210.7274384,
+            "item_hash": "4a0f62da42f4478544616519e6f5d58adb1096e069b392b151d47c3609492d0c"
+        });
+        let f: AccountFile = serde_json::from_value(body).unwrap();
+        assert_eq!(f.file_hash, "Qmabc");
+        assert_eq!(f.size.count(), 1234);
+        assert_eq!(f.storage_engine, "file");
+        assert!((f.created.as_f64() - 1779373210.7274384).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cursor_response_accepts_float_total_size() {
+        // pyaleph emits total_size as a float in cursor mode.
+        let body = json!({
+            "files": [],
+            "total_size": 78051738.0,
+            "next_cursor": null,
+        });
+        let r: AccountFilesCursorResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(r.total_size_bytes().count(), 78_051_738);
+    }
+
+    #[tokio::test]
+    async fn iterator_walks_cursor_until_exhausted() {
+        let server = MockServer::start().await;
+        let address = "0x0B8ee617A08AC051a8A3b430ACf7233a462A0187";
+
+        // First page: 2 rows + next_cursor=ABC
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/addresses/{address}/files")))
+            .and(query_param("cursor", ""))
+            .and(query_param("pagination", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [
+                    {"file_hash": "Qm1", "size": 100, "type": "file",
+                     "created": 1.0,
+                     "item_hash": "0000000000000000000000000000000000000000000000000000000000000001"},
+                    {"file_hash": "Qm2", "size": 200, "type": "file",
+                     "created": 2.0,
+                     "item_hash": "0000000000000000000000000000000000000000000000000000000000000002"},
+                ],
+                "total_size": 600.0,
+                "next_cursor": "ABC",
+            })))
+            .mount(&server)
+            .await;
+
+        // Second page: 1 row, no next_cursor.
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/addresses/{address}/files")))
+            .and(query_param("cursor", "ABC"))
+            .and(query_param("pagination", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [
+                    {"file_hash": "Qm3", "size": 300, "type": "file",
+                     "created": 3.0,
+                     "item_hash": "0000000000000000000000000000000000000000000000000000000000000003"},
+                ],
+                "total_size": 600.0,
+                "next_cursor": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let addr = Address::from(address.to_string());
+        let files: Vec<AccountFile> = client
+            .get_account_files_iterator(&addr, Some(2), None)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].file_hash, "Qm1");
+        assert_eq!(files[2].file_hash, "Qm3");
+    }
+
+    #[tokio::test]
+    async fn iterator_returns_empty_stream_on_404() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/api/v0/addresses/0x0000000000000000000000000000000000000001/files",
+            ))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let addr = Address::from("0x0000000000000000000000000000000000000001".to_string());
+        let files: Vec<AccountFile> = client
+            .get_account_files_iterator(&addr, Some(25), None)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_total_storage_size_reads_float_total_via_cursor() {
+        let server = MockServer::start().await;
+        let address = "0x0B8ee617A08AC051a8A3b430ACf7233a462A0187";
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/addresses/{address}/files")))
+            .and(query_param("cursor", ""))
+            .and(query_param("pagination", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [],
+                "total_size": 78_051_738.0,
+                "next_cursor": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let addr = Address::from(address.to_string());
+        let total = client.get_total_storage_size(&addr).await.unwrap();
+        assert_eq!(total.count(), 78_051_738);
+    }
+
+    #[tokio::test]
+    async fn get_total_storage_size_returns_zero_on_404() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/api/v0/addresses/0x0000000000000000000000000000000000000001/files",
+            ))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let addr = Address::from("0x0000000000000000000000000000000000000001".to_string());
+        assert_eq!(
+            client.get_total_storage_size(&addr).await.unwrap().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn rejected_message_deserializes_item_type_and_item_content_inline() {
+        let body = serde_json::json!({
+            "sender": "0xABCD",
+            "chain": "ETH",
+            "signature": "0xSIG",
+            "type": "POST",
+            "item_type": "inline",
+            "item_content": "{\"type\":\"test\"}",
+            "item_hash": "0".repeat(64),
+            "time": 1234.0,
+            "channel": "TEST",
+            "content": null,
+        });
+        let rejected: RejectedMessage = serde_json::from_value(body).unwrap();
+        assert_eq!(rejected.item_type, ItemType::Inline);
+        assert_eq!(
+            rejected.item_content.as_deref(),
+            Some("{\"type\":\"test\"}")
+        );
+    }
+
+    #[test]
+    fn rejected_message_deserializes_storage_with_null_item_content() {
+        let body = serde_json::json!({
+            "sender": "0xABCD",
+            "chain": "ETH",
+            "signature": "0xSIG",
+            "type": "STORE",
+            "item_type": "storage",
+            "item_content": null,
+            "item_hash": "0".repeat(64),
+            "time": 1234.0,
+            "channel": null,
+            "content": null,
+        });
+        let rejected: RejectedMessage = serde_json::from_value(body).unwrap();
+        assert_eq!(rejected.item_type, ItemType::Storage);
+        assert!(rejected.item_content.is_none());
+    }
+
+    const ADDRESS: &str = "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef";
+
+    #[tokio::test]
+    async fn balance_and_credits_are_typed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/addresses/{ADDRESS}/balance")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": ADDRESS,
+                "balance": 1500.5,
+                "locked_amount": 1000.0,
+                "credit_balance": 250_000,
+                "details": {"ETH": 1500.5},
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let address = Address::from(ADDRESS.to_string());
+        let balance = client.get_balance(&address).await.unwrap();
+        assert_eq!(balance.available_aleph_tokens(), 500.5);
+        assert_eq!(client.get_credits(&address).await.unwrap(), 250_000);
+    }
+}
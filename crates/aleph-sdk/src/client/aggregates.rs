@@ -0,0 +1,654 @@
+//! Helpers of the aggregate client.
+
+use super::error::CheckStatus;
+use super::{AlephAggregateClient, AlephClient, MessageError};
+use aleph_types::chain::Address;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// Shape of `/api/v0/aggregates/{address}.json` when fetching multiple
+/// keys (or every key) at once. Shared by `get_aggregates` and
+/// `get_all_aggregates`.
+#[derive(Deserialize)]
+struct AggregatesResponse {
+    data: HashMap<String, serde_json::Value>,
+}
+
+/// Applies the content of an AGGREGATE message to the value of its key, as the
+/// CCN does: top-level keys of `update` replace those of `value`.
+pub(super) fn merge_aggregate(
+    value: Option<serde_json::Value>,
+    update: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Value {
+    let mut merged = match value {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    merged.extend(update.iter().map(|(k, v)| (k.clone(), v.clone())));
+    serde_json::Value::Object(merged)
+}
+
+/// Maps a `404 Not Found` from `get_aggregate` to `Ok(None)` so callers that
+/// semantically treat "no aggregate stored" as empty don't need to discriminate
+/// between "200 with empty data" and "404 missing key".
+///
+/// The CCN's `/api/v0/aggregates/{address}.json?keys=...` endpoint is inconsistent:
+/// most deployments return `200 {"data": null}` for an unknown address+key pair, but
+/// some return `404`. Both should surface to callers as "no data". `get_aggregate`
+/// surfaces a 404 as `MessageError::ApiError`, which [`MessageError::is_not_found`]
+/// recognizes.
+///
+/// Only `404` is swallowed; other transport errors (timeouts, 5xx, decode failures)
+/// are propagated unchanged.
+pub(super) fn map_aggregate_404_to_empty(
+    result: Result<Option<serde_json::Value>, MessageError>,
+) -> Result<Option<serde_json::Value>, MessageError> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Descends through the inner aggregate envelope returned by `get_aggregate::<Option<Value>>`.
+///
+/// The CCN responds with `{"data": <inner>}`. `get_aggregate` strips the outer `data`
+/// wrapper, so `inner` is what we receive here. The full mapping from CCN response to
+/// helper output is:
+///
+/// * `200` with `data: null` (`raw == None`) -> `T::default()` (empty).
+/// * `200` with `data` an object that does not contain `key` -> `T::default()`.
+/// * `200` with `data[key]` explicitly `null` -> `T::default()`.
+/// * `200` with `data[key]` parseable as `T` -> that value.
+/// * `200` with `data[key]` present but not deserializable as `T` ->
+///   `MessageError::ApiError { status: 200, body: "invalid <key> aggregate: ..." }`.
+/// * `200` with a malformed envelope, i.e. `data` is something other than an object
+///   (e.g. a string or number) -> `T::default()`. This is intentional graceful
+///   degradation: a transiently broken CCN should not nuke a user-facing list
+///   command. Logging or alerting on this case belongs at a higher layer; this
+///   helper deliberately swallows it.
+pub(super) fn extract_aggregate_value<T>(
+    raw: Option<serde_json::Value>,
+    key: &str,
+) -> Result<T, MessageError>
+where
+    T: DeserializeOwned + Default,
+{
+    let mut map = match raw {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => return Ok(T::default()),
+    };
+
+    let inner = match map.remove(key) {
+        None | Some(serde_json::Value::Null) => return Ok(T::default()),
+        Some(value) => value,
+    };
+
+    decode_aggregate_value(inner, key)
+}
+
+/// Deserializes the value of the aggregate `key`, reporting a mismatch as an
+/// [`MessageError::ApiError`].
+pub(super) fn decode_aggregate_value<T: DeserializeOwned>(
+    value: serde_json::Value,
+    key: &str,
+) -> Result<T, MessageError> {
+    serde_json::from_value(value).map_err(|e| MessageError::ApiError {
+        status: 200,
+        body: format!("invalid {key} aggregate: {e}"),
+    })
+}
+
+impl AlephAggregateClient for AlephClient {
+    #[tracing::instrument(level = "debug", skip_all, fields(%address, key = key))]
+    async fn get_aggregate<T: DeserializeOwned>(
+        &self,
+        address: &Address,
+        key: &str,
+    ) -> Result<T, MessageError> {
+        #[derive(Deserialize)]
+        struct AggregateResponse<T> {
+            data: T,
+        }
+
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/aggregates/{}.json", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&[("keys", key)])
+            .send()
+            .await?
+            .check_status()
+            .await?;
+        let aggregate_response: AggregateResponse<T> = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        Ok(aggregate_response.data)
+    }
+
+    // get_websites_aggregate / get_domains_aggregate / get_port_forwarding_aggregate
+    // use the trait's default implementations (single-key `get_aggregate` reads
+    // with empty-response handling).
+
+    async fn get_aggregates(
+        &self,
+        address: &Address,
+        keys: &[&str],
+    ) -> Result<HashMap<String, serde_json::Value>, MessageError> {
+        if keys.is_empty() {
+            return Err(MessageError::ApiError {
+                status: 0,
+                body: "get_aggregates requires at least one key".to_string(),
+            });
+        }
+
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/aggregates/{}.json", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let keys_csv = keys.join(",");
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&[("keys", &keys_csv)])
+            .send()
+            .await?
+            .check_status()
+            .await?;
+
+        let aggregates_response: AggregatesResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        Ok(aggregates_response.data)
+    }
+
+    async fn get_all_aggregates(
+        &self,
+        address: &Address,
+    ) -> Result<HashMap<String, serde_json::Value>, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/aggregates/{}.json", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self.inner.http_client.get(url).send().await?;
+
+        // pyaleph returns 404 when the address has no aggregates rather than
+        // an empty data map. Treat it as an empty result so callers don't
+        // have to special-case the status code.
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(HashMap::new());
+        }
+
+        let response = response.check_status().await?;
+
+        let aggregates_response: AggregatesResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        Ok(aggregates_response.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate_models::corechannel::CORECHANNEL_ADDRESS;
+    use crate::aggregate_models::domains::{
+        DOMAINS_AGGREGATE_KEY, DomainTargetType, DomainsAggregate,
+    };
+    use crate::aggregate_models::websites::{WEBSITES_AGGREGATE_KEY, WebsitesAggregate};
+    use crate::client::{AlephMessageClient, RetryConfig};
+    use crate::test_utils::{MockClient, TestAccount};
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    #[ignore = "uses a remote CCN — requires corechannel data not in heph"]
+    async fn test_get_corechannel_aggregate() {
+        let client = AlephClient::new(Url::parse("https://api3.aleph.im").expect("valid url"));
+
+        let aggregate = client
+            .get_corechannel_aggregate(&CORECHANNEL_ADDRESS)
+            .await
+            .unwrap_or_else(|e| panic!("failed to fetch corechannel aggregate: {:?}", e));
+
+        assert!(
+            !aggregate.corechannel.nodes.is_empty(),
+            "should have at least one CCN"
+        );
+        assert!(
+            !aggregate.corechannel.resource_nodes.is_empty(),
+            "should have at least one CRN"
+        );
+    }
+
+    // --- extract_aggregate_value: pure JSON-descent logic ---
+
+    #[test]
+    fn extract_returns_empty_when_data_is_null() {
+        // CCN responded `{"data": null}` -> get_aggregate yields None.
+        let agg: WebsitesAggregate = extract_aggregate_value(None, WEBSITES_AGGREGATE_KEY)
+            .expect("null data should map to empty aggregate");
+        assert!(agg.is_empty());
+    }
+
+    #[test]
+    fn extract_returns_empty_when_inner_object_lacks_key() {
+        // CCN responded `{"data": {}}` (other keys but not ours).
+        let raw = serde_json::json!({"someOtherKey": {}});
+        let agg: WebsitesAggregate =
+            extract_aggregate_value(Some(raw), WEBSITES_AGGREGATE_KEY).unwrap();
+        assert!(agg.is_empty());
+    }
+
+    #[test]
+    fn extract_returns_empty_when_inner_key_is_null() {
+        // CCN responded `{"data": {"websites": null}}`.
+        let raw = serde_json::json!({ WEBSITES_AGGREGATE_KEY: serde_json::Value::Null });
+        let agg: WebsitesAggregate =
+            extract_aggregate_value(Some(raw), WEBSITES_AGGREGATE_KEY).unwrap();
+        assert!(agg.is_empty());
+    }
+
+    #[test]
+    fn extract_returns_empty_on_malformed_envelope() {
+        let bogus = Some(serde_json::Value::String("nope".into()));
+        let result: Result<WebsitesAggregate, _> =
+            extract_aggregate_value(bogus, WEBSITES_AGGREGATE_KEY);
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn extract_parses_real_websites_shape() {
+        let raw = serde_json::json!({
+            "websites": {
+                "my-site": {
+                    "metadata": { "name": "my-site", "tags": [], "framework": "nextjs" },
+                    "payment": { "chain": "ETH", "type": "hold" },
+                    "version": 2,
+                    "volume_id": "vol_abc",
+                    "history": {},
+                    "ens": [],
+                    "created_at": 1.0,
+                    "updated_at": 2.0
+                },
+                "deleted-site": null
+            }
+        });
+        let agg: WebsitesAggregate =
+            extract_aggregate_value(Some(raw), WEBSITES_AGGREGATE_KEY).unwrap();
+        let entry = agg.get("my-site").unwrap().as_ref().unwrap();
+        assert_eq!(entry.version, 2);
+        assert_eq!(entry.volume_id, "vol_abc");
+        assert!(agg.get("deleted-site").unwrap().is_none());
+    }
+
+    #[test]
+    fn extract_parses_real_domains_shape() {
+        let raw = serde_json::json!({
+            "domains": {
+                "site.example.com": {
+                    "type": "ipfs",
+                    "programType": "ipfs",
+                    "message_id": "vol1",
+                    "updated_at": 1.0
+                }
+            }
+        });
+        let agg: DomainsAggregate =
+            extract_aggregate_value(Some(raw), DOMAINS_AGGREGATE_KEY).unwrap();
+        let entry = agg.get("site.example.com").unwrap().as_ref().unwrap();
+        assert_eq!(entry.kind, DomainTargetType::Ipfs);
+        assert_eq!(entry.message_id, "vol1");
+    }
+
+    #[test]
+    fn extract_propagates_invalid_aggregate_as_api_error() {
+        // Inner key is present but the shape can't be deserialized.
+        let raw = serde_json::json!({
+            "websites": "not-an-object"
+        });
+        let err = extract_aggregate_value::<WebsitesAggregate>(Some(raw), WEBSITES_AGGREGATE_KEY)
+            .expect_err("non-object inner should fail to deserialize");
+        match err {
+            MessageError::ApiError { status, body } => {
+                assert_eq!(status, 200);
+                assert!(
+                    body.contains("invalid websites aggregate"),
+                    "body was: {body}"
+                );
+            }
+            other => panic!("expected ApiError, got: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_returns_empty_websites_aggregate_by_default() {
+        let client = MockClient::default();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+        let agg = client.get_websites_aggregate(&addr).await.unwrap();
+        assert!(agg.is_empty());
+    }
+
+    #[tokio::test]
+    async fn mock_returns_empty_domains_aggregate_by_default() {
+        let client = MockClient::default();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+        let agg = client.get_domains_aggregate(&addr).await.unwrap();
+        assert!(agg.is_empty());
+    }
+
+    #[tokio::test]
+    async fn mock_returns_empty_port_forwarding_aggregate_by_default() {
+        let client = MockClient::default();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+        let agg = client.get_port_forwarding_aggregate(&addr).await.unwrap();
+        assert!(agg.is_empty());
+    }
+
+    // --- map_aggregate_404_to_empty: 404 -> empty, others propagated ---
+
+    /// Spin up a TCP listener that responds to every request with the given
+    /// hand-rolled HTTP/1.1 response. Each accepted connection serves one
+    /// response and closes.
+    async fn start_canned_response_server(response: &'static [u8]) -> Url {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    break;
+                };
+                tokio::spawn(async move {
+                    // Drain request headers so close() is graceful on
+                    // Windows (otherwise the kernel sends RST instead of
+                    // FIN and the client never parses our response).
+                    let mut buf = [0u8; 1024];
+                    let mut req = Vec::with_capacity(1024);
+                    loop {
+                        match stream.read(&mut buf).await {
+                            Ok(0) => break,
+                            Ok(n) => {
+                                req.extend_from_slice(&buf[..n]);
+                                if req.windows(4).any(|w| w == b"\r\n\r\n") {
+                                    break;
+                                }
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                    let _ = stream.write_all(response).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        Url::parse(&format!("http://{addr}")).unwrap()
+    }
+
+    const HTTP_404_RESPONSE: &[u8] = b"HTTP/1.1 404 Not Found\r\n\
+        Content-Type: text/plain\r\n\
+        Content-Length: 9\r\n\
+        Connection: close\r\n\
+        \r\n\
+        not found";
+
+    #[tokio::test]
+    async fn get_websites_aggregate_returns_empty_on_404() {
+        let url = start_canned_response_server(HTTP_404_RESPONSE).await;
+        let client = AlephClient::builder(url)
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+
+        let agg = client
+            .get_websites_aggregate(&addr)
+            .await
+            .expect("404 should map to an empty aggregate, not an error");
+        assert!(agg.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_domains_aggregate_returns_empty_on_404() {
+        let url = start_canned_response_server(HTTP_404_RESPONSE).await;
+        let client = AlephClient::builder(url)
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+
+        let agg = client
+            .get_domains_aggregate(&addr)
+            .await
+            .expect("404 should map to an empty aggregate, not an error");
+        assert!(agg.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_port_forwarding_aggregate_returns_empty_on_404() {
+        let url = start_canned_response_server(HTTP_404_RESPONSE).await;
+        let client = AlephClient::builder(url)
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+
+        let agg = client
+            .get_port_forwarding_aggregate(&addr)
+            .await
+            .expect("404 should map to an empty aggregate, not an error");
+        assert!(agg.is_empty());
+    }
+
+    #[tokio::test]
+    async fn map_aggregate_404_to_empty_propagates_non_404_errors() {
+        // A 500 should NOT be swallowed — only 404 is special-cased.
+        const HTTP_500_RESPONSE: &[u8] = b"HTTP/1.1 500 Internal Server Error\r\n\
+            Content-Type: text/plain\r\n\
+            Content-Length: 5\r\n\
+            Connection: close\r\n\
+            \r\n\
+            boom!";
+
+        let url = start_canned_response_server(HTTP_500_RESPONSE).await;
+        let client = AlephClient::builder(url)
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+        let addr = aleph_types::address!("0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10");
+
+        let err = client
+            .get_websites_aggregate(&addr)
+            .await
+            .expect_err("5xx should propagate, not be swallowed as empty");
+        // The exact variant doesn't matter — what matters is that we did NOT
+        // get an Ok(empty) result for a non-404 transport failure.
+        assert!(
+            matches!(err, MessageError::ApiError { status: 500, .. }),
+            "got: {err:?}"
+        );
+    }
+
+    const OWNER: &str = "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef";
+
+    async fn mount_post(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "publication_status": {"status": "success", "failed": []},
+                "message_status": "processed",
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    /// Content of the AGGREGATE message posted to the server.
+    async fn posted_content(server: &MockServer) -> serde_json::Value {
+        let requests = server.received_requests().await.unwrap();
+        let post = requests
+            .iter()
+            .find(|r| r.method == http::Method::POST)
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&post.body).unwrap();
+        serde_json::from_str(body["message"]["item_content"].as_str().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_aggregate_rejects_non_object_content() {
+        let client = AlephClient::new(Url::parse("http://localhost:1").unwrap());
+        let account = TestAccount(Address::from(OWNER.to_string()));
+        let err = client
+            .create_aggregate(&account, "settings", json!([1, 2]), None, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MessageError::Build(crate::messages::MessageBuildError::AggregateNotObject)
+        ));
+    }
+
+    #[tokio::test]
+    async fn update_aggregate_publishes_the_modified_value() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{OWNER}.json")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"settings": {"theme": "dark", "count": 1}},
+            })))
+            .mount(&server)
+            .await;
+        mount_post(&server).await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let account = TestAccount(Address::from(OWNER.to_string()));
+        client
+            .update_aggregate(
+                &account,
+                "settings",
+                None,
+                |value: &mut serde_json::Map<String, serde_json::Value>| {
+                    value.insert("count".to_string(), json!(2));
+                },
+                true,
+            )
+            .await
+            .unwrap();
+
+        let content = posted_content(&server).await;
+        assert_eq!(content["key"], "settings");
+        assert_eq!(content["content"], json!({"theme": "dark", "count": 2}));
+        assert_eq!(content["address"], OWNER);
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Profile {
+        name: String,
+    }
+
+    const ADDRESS: &str = "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef";
+
+    #[tokio::test]
+    async fn fetch_aggregate_returns_the_typed_value() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{ADDRESS}.json")))
+            .and(query_param("keys", "profile"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": ADDRESS,
+                "data": {"profile": {"name": "alice"}},
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{ADDRESS}.json")))
+            .and(query_param("keys", "missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let address = Address::from(ADDRESS.to_string());
+        let profile: Option<Profile> = client.fetch_aggregate(&address, "profile").await.unwrap();
+        assert_eq!(
+            profile,
+            Some(Profile {
+                name: "alice".to_string()
+            })
+        );
+        let missing: Option<Profile> = client.fetch_aggregate(&address, "missing").await.unwrap();
+        assert_eq!(missing, None);
+    }
+
+    #[tokio::test]
+    async fn fetch_aggregates_deserializes_each_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{ADDRESS}.json")))
+            .and(query_param("keys", "alice,bob,carol"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": ADDRESS,
+                "data": {"alice": {"name": "alice"}, "bob": {"name": "bob"}},
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let profiles: HashMap<String, Profile> = client
+            .fetch_aggregates(
+                &Address::from(ADDRESS.to_string()),
+                &["alice", "bob", "carol"],
+            )
+            .await
+            .unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles["bob"].name, "bob");
+    }
+
+    #[tokio::test]
+    async fn fetch_aggregates_of_an_address_without_aggregates_is_empty() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{ADDRESS}.json")))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let profiles: HashMap<String, Profile> = client
+            .fetch_aggregates(&Address::from(ADDRESS.to_string()), &["alice"])
+            .await
+            .unwrap();
+        assert!(profiles.is_empty());
+    }
+}
@@ -0,0 +1,331 @@
+//! Activity statistics of channels.
+
+use super::error::CheckStatus;
+use super::{AlephClient, MessageError, MessageFilter, PaginationParams, SortBy, SortOrder};
+use aleph_types::channel::Channel;
+use aleph_types::message::{MessageStatus, MessageType};
+use aleph_types::timestamp::Timestamp;
+use futures_util::future::try_join_all;
+use futures_util::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Message counts on a channel, as computed by
+/// [`AlephClient::channel_stats`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelStats {
+    pub channel: Channel,
+    /// Start of the observed window (inclusive).
+    pub since: Timestamp,
+    /// End of the observed window (exclusive).
+    pub until: Timestamp,
+    /// Processed (or being removed) messages per message type.
+    pub by_type: BTreeMap<MessageType, u32>,
+    /// Messages per status, across all types.
+    pub by_status: Vec<(MessageStatus, u32)>,
+    /// Processed messages per time bucket, oldest first. Each entry is the
+    /// start of the bucket and its count.
+    pub buckets: Vec<(Timestamp, u32)>,
+}
+
+impl ChannelStats {
+    /// Total processed (or being removed) messages in the window.
+    pub fn total(&self) -> u32 {
+        self.by_type.values().sum()
+    }
+}
+
+/// A channel seen in recent messages, as listed by
+/// [`AlephClient::list_active_channels`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelActivity {
+    pub channel: Channel,
+    /// Messages sent on the channel since the start of the window.
+    pub messages: u64,
+    /// Time of the most recent of these messages.
+    pub last_seen: Timestamp,
+}
+
+impl AlephClient {
+    /// Counts the messages sent on `channel` since `since`, by type, by status
+    /// and per `bucket`-long time slice.
+    ///
+    /// Counts come from the `pagination_total` of one-item message queries, so
+    /// this issues one request per type, status and bucket rather than
+    /// downloading the messages. Type and bucket counts only include messages
+    /// the CCN lists by default (processed and removing).
+    pub async fn channel_stats(
+        &self,
+        channel: &Channel,
+        since: Timestamp,
+        bucket: Duration,
+    ) -> Result<ChannelStats, MessageError> {
+        const BUCKET_CONCURRENCY: usize = 8;
+        const TYPES: [MessageType; 6] = [
+            MessageType::Aggregate,
+            MessageType::Forget,
+            MessageType::Instance,
+            MessageType::Post,
+            MessageType::Program,
+            MessageType::Store,
+        ];
+        const STATUSES: [MessageStatus; 6] = [
+            MessageStatus::Pending,
+            MessageStatus::Processed,
+            MessageStatus::Removing,
+            MessageStatus::Removed,
+            MessageStatus::Forgotten,
+            MessageStatus::Rejected,
+        ];
+
+        let until = Timestamp::now();
+        let window = MessageFilter::default()
+            .channels([channel.clone()])
+            .time_range(Some(since.clone()), Some(until.clone()));
+
+        let by_type =
+            try_join_all(TYPES.map(|t| self.count_messages(window.clone().message_type(t))))
+                .await?;
+        let by_status = try_join_all(
+            STATUSES.map(|s| self.count_messages(window.clone().message_statuses([s]))),
+        )
+        .await?;
+
+        let step = bucket.as_secs_f64();
+        let mut starts = Vec::new();
+        let mut start = since.as_f64();
+        while step > 0.0 && start < until.as_f64() {
+            starts.push(start);
+            start += step;
+        }
+        // Short buckets over a long window make many requests: bound how many
+        // are queued at once.
+        let bucket_counts: Vec<u32> = futures_util::stream::iter(&starts)
+            .map(|&start| {
+                let end = (start + step).min(until.as_f64());
+                self.count_messages(
+                    MessageFilter::default()
+                        .channels([channel.clone()])
+                        .time_range(Some(Timestamp::from(start)), Some(Timestamp::from(end))),
+                )
+            })
+            .buffered(BUCKET_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(ChannelStats {
+            channel: channel.clone(),
+            since,
+            until,
+            by_type: TYPES.into_iter().zip(by_type).collect(),
+            by_status: STATUSES.into_iter().zip(by_status).collect(),
+            buckets: starts
+                .into_iter()
+                .map(Timestamp::from)
+                .zip(bucket_counts)
+                .collect(),
+        })
+    }
+
+    /// Lists the channels messages were sent on since `since`, busiest first.
+    ///
+    /// Known channels come from `GET /api/v0/channels/list.json`; each is then
+    /// counted with a one-item message query over the window, so this issues
+    /// one request per channel the CCN knows of. Channels without messages in
+    /// the window are skipped.
+    pub async fn list_active_channels(
+        &self,
+        since: Timestamp,
+    ) -> Result<Vec<ChannelActivity>, MessageError> {
+        const CONCURRENCY: usize = 8;
+
+        #[derive(Deserialize)]
+        struct ChannelList {
+            channels: Vec<Channel>,
+        }
+
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/channels/list.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+        let known: ChannelList = self
+            .inner
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .check_status()
+            .await?
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        let mut window = MessageFilter::default().time_range(Some(since), None);
+        window.sort_by = Some(SortBy::Time);
+        window.sort_order = Some(SortOrder::Desc);
+        let window = &window;
+        let latest_only = PaginationParams {
+            pagination: Some(1),
+            page: Some(1),
+        };
+        let latest_only = &latest_only;
+        let mut channels: Vec<ChannelActivity> = futures_util::stream::iter(known.channels)
+            .map(|channel| async move {
+                let filter = window.clone().channels([channel.clone()]);
+                let page = self.get_messages_raw(&filter, latest_only).await?;
+                Ok::<_, MessageError>(page.messages.into_iter().next().map(|latest| {
+                    ChannelActivity {
+                        channel,
+                        messages: page.pagination_total.into(),
+                        last_seen: latest.time,
+                    }
+                }))
+            })
+            .buffer_unordered(CONCURRENCY)
+            .try_filter_map(|activity| async move { Ok(activity) })
+            .try_collect()
+            .await?;
+
+        channels.sort_by(|a, b| {
+            b.messages
+                .cmp(&a.messages)
+                .then_with(|| a.channel.as_str().cmp(b.channel.as_str()))
+        });
+        Ok(channels)
+    }
+
+    async fn count_messages(&self, filter: MessageFilter) -> Result<u32, MessageError> {
+        let pagination = PaginationParams {
+            pagination: Some(1),
+            page: Some(1),
+        };
+        Ok(self
+            .get_messages_raw(&filter, &pagination)
+            .await?
+            .pagination_total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::channel;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn page(total: u32) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "messages": [],
+            "pagination_per_page": 1,
+            "pagination_page": 1,
+            "pagination_total": total,
+        }))
+    }
+
+    #[tokio::test]
+    async fn channel_stats_counts_types_statuses_and_buckets() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("channels", "TEST"))
+            .and(query_param("msgType", "POST"))
+            .respond_with(page(5))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("channels", "TEST"))
+            .and(query_param("msgStatuses", "rejected"))
+            .respond_with(page(1))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("channels", "TEST"))
+            .and(query_param("pagination", "1"))
+            .respond_with(page(0))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        // Slightly less than three hours, so the window splits into exactly
+        // three one-hour buckets.
+        let since = Timestamp::from(Timestamp::now().as_f64() - 3.0 * 3600.0 + 60.0);
+        let stats = client
+            .channel_stats(&channel!("TEST"), since, Duration::from_secs(3600))
+            .await
+            .unwrap();
+
+        assert_eq!(stats.by_type[&MessageType::Post], 5);
+        assert_eq!(stats.by_type[&MessageType::Store], 0);
+        assert_eq!(stats.total(), 5);
+        assert!(stats.by_status.contains(&(MessageStatus::Rejected, 1)));
+        assert_eq!(stats.buckets.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn active_channels_are_counted_busiest_first() {
+        let post: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../fixtures/messages/post/post.json"))
+                .unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/channels/list.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"channels": ["A", "B", "C"]})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        for (channel, total, time) in [("A", 1, 1_700_000_001.0), ("B", 2, 1_700_000_003.0)] {
+            let mut latest = post.clone();
+            latest["channel"] = json!(channel);
+            latest["time"] = json!(time);
+            Mock::given(method("GET"))
+                .and(path("/api/v0/messages.json"))
+                .and(query_param("channels", channel))
+                .and(query_param("pagination", "1"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "messages": [latest],
+                    "pagination_per_page": 1,
+                    "pagination_page": 1,
+                    "pagination_total": total,
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("channels", "C"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [],
+                "pagination_per_page": 1,
+                "pagination_page": 1,
+                "pagination_total": 0,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let channels = client
+            .list_active_channels(Timestamp::from(1_700_000_000.0))
+            .await
+            .unwrap();
+
+        let counts: Vec<_> = channels
+            .iter()
+            .map(|c| (c.channel.as_str(), c.messages))
+            .collect();
+        assert_eq!(counts, [("B", 2), ("A", 1)]);
+        assert_eq!(channels[0].last_seen.as_f64(), 1_700_000_003.0);
+    }
+}
@@ -0,0 +1,533 @@
+//! Errors returned by the client.
+
+use crate::response_limit::ResponseTooLarge;
+use aleph_types::chain::Address;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{FileRef, MessageStatus, MessageType, SignatureVerificationError};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum StorageError {
+    #[error("File not found: {0}")]
+    NotFound(ItemHash),
+    #[error("File reference not found: {0}")]
+    RefNotFound(FileRef),
+    #[error("failed to parse file size '{value}': {source}")]
+    InvalidSize {
+        value: String,
+        #[source]
+        source: std::num::ParseIntError,
+    },
+    #[error("Integrity verification failed: {0}")]
+    IntegrityError(#[from] crate::verify::VerifyError),
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    #[error("Insufficient balance")]
+    InsufficientBalance,
+    #[error("IPFS is disabled on this node")]
+    IpfsDisabled,
+    #[error("Invalid signature on STORE message")]
+    InvalidSignature,
+    #[error("Invalid upload metadata: {0}")]
+    InvalidMetadata(String),
+    #[error("File too large")]
+    FileTooLarge,
+    #[error("Upload failed: {0}")]
+    UploadFailed(reqwest_middleware::Error),
+    /// The node answered the upload with an error status that has no
+    /// dedicated variant.
+    #[error("Upload rejected with HTTP {status}: {body}")]
+    UploadRejected { status: u16, body: String },
+    /// The upload was aborted by the client-side timeout policy (an idle stall
+    /// or a total deadline). Distinct from [`Self::UploadFailed`], which is a
+    /// server or transport error. Transient; retry.
+    #[error("Upload timed out: {0}")]
+    UploadTimeout(String),
+    /// The node responded but the body could not be deserialized as JSON.
+    #[error("invalid response body from node")]
+    InvalidResponseBody(#[source] reqwest::Error),
+    /// The node responded with JSON but the embedded `hash` was not a valid item hash.
+    #[error("invalid item hash '{value}' in node response: {source}")]
+    InvalidResponseHash {
+        value: String,
+        #[source]
+        source: aleph_types::item_hash::ItemHashError,
+    },
+    /// IPFS gateway returned a body the SDK could not parse as a kubo add-response.
+    #[error("invalid IPFS gateway response")]
+    InvalidIpfsResponse(#[from] crate::ipfs::ParseRootError),
+    #[error("cannot upload empty folder: {0}")]
+    EmptyFolder(std::path::PathBuf),
+    #[error("non-UTF-8 path cannot be sent to IPFS gateway: {0}")]
+    NonUtf8Path(std::path::PathBuf),
+    #[error("upload integrity mismatch: locally computed {expected}, server returned {actual}")]
+    UploadIntegrityMismatch {
+        expected: ItemHash,
+        actual: ItemHash,
+    },
+    #[error("CID mismatch: gateway returned {remote} but local computation produced {local}")]
+    CidMismatch { local: ItemHash, remote: ItemHash },
+    /// 422: the CAR header's declared root does not match the metadata's
+    /// STORE item_hash. No IPFS side effects on the server. The two CID
+    /// strings come from the server's body and are not re-validated here.
+    #[error("CAR header root does not match metadata: car={car_root}, metadata={metadata_root}")]
+    CarHeaderRootMismatch {
+        car_root: String,
+        metadata_root: String,
+    },
+    /// 422: pyaleph imported the CAR successfully but the imported root
+    /// differs from the declared root (CAR header lied). The root is now
+    /// pinned on the server under the 24h grace period.
+    #[error(
+        "imported root does not match expected: kubo={kubo_root}, expected={expected_root}; root is pinned on the node under grace period"
+    )]
+    ImportedRootMismatch {
+        kubo_root: String,
+        expected_root: String,
+    },
+    /// 502/504: kubo or the pinning backend is unavailable or timed out.
+    /// Transient; retry.
+    #[error("IPFS backend unavailable: {0}")]
+    IpfsBackendUnavailable(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("local folder hash failed: {0}")]
+    FolderHashFailed(#[from] crate::folder_hash::FolderHashError),
+}
+
+impl StorageError {
+    /// The HTTP status of the answer of the node, if the error comes from
+    /// one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            StorageError::UploadRejected { status, .. } => Some(*status),
+            StorageError::UploadFailed(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MessageError {
+    #[error("Message not found: {0}")]
+    NotFound(ItemHash),
+    #[error("Expected message {item_hash} to be of type {expected}, got {actual}")]
+    InvalidType {
+        item_hash: ItemHash,
+        expected: MessageType,
+        actual: MessageType,
+    },
+    #[error("Unexpected message status for {item_hash}: {actual}, expected {expected}")]
+    UnexpectedStatus {
+        item_hash: ItemHash,
+        expected: MessageStatus,
+        actual: MessageStatus,
+    },
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+    #[error("Integrity verification failed: {0}")]
+    Integrity(#[from] IntegrityError),
+    /// The node answered with an error status. `body` is the body of the
+    /// answer, see [`MessageError::api_error`] for its parsed form.
+    #[error("API error (HTTP {status}): {body}")]
+    ApiError { status: u16, body: String },
+    /// A response body could not be deserialized.
+    #[error("failed to decode response body")]
+    Decode(#[source] serde_json::Error),
+    #[error(transparent)]
+    HttpError(reqwest_middleware::Error),
+    /// A response crossed its [`ResponseLimits`](crate::response_limit::ResponseLimits).
+    #[error(transparent)]
+    ResponseTooLarge(#[from] ResponseTooLarge),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The base URL had a scheme that has no websocket equivalent.
+    #[error("cannot derive a websocket scheme from the base URL")]
+    WebsocketBadScheme,
+    /// The message filter could not be serialized into a query string.
+    #[error("failed to serialize websocket query string")]
+    WebsocketSerializeFilter(#[source] serde_qs::Error),
+    /// The websocket handshake / TCP connect failed (initial or reconnect).
+    #[error("websocket connect failed")]
+    WebsocketConnect(#[source] Box<tokio_tungstenite::tungstenite::Error>),
+    /// An incoming websocket frame could not be deserialized as a [`Message`](aleph_types::message::Message).
+    #[error("failed to parse websocket message")]
+    WebsocketParse(#[source] serde_json::Error),
+    /// An error was reported by the websocket stream after connect.
+    #[error("websocket stream error")]
+    WebsocketStream(#[source] Box<tokio_tungstenite::tungstenite::Error>),
+    #[error("upload hash mismatch: expected {expected}, got {actual}")]
+    HashMismatch {
+        expected: ItemHash,
+        actual: ItemHash,
+    },
+    #[error("Message build error: {0}")]
+    Build(#[from] crate::messages::MessageBuildError),
+    #[error("Message cannot be broadcast: {0}")]
+    NotBroadcastable(#[from] aleph_types::message::pending::PendingConversionError),
+    #[error("Message {item_hash} was rejected by the node")]
+    Rejected { item_hash: ItemHash },
+    #[error("Message {item_hash} was still pending after {timeout:?}")]
+    WaitTimeout {
+        item_hash: ItemHash,
+        timeout: Duration,
+    },
+    #[error("Message {item_hash} belongs to {owner}, not {expected}")]
+    NotOwned {
+        item_hash: ItemHash,
+        owner: Address,
+        expected: Address,
+    },
+}
+
+impl From<reqwest_middleware::Error> for MessageError {
+    fn from(error: reqwest_middleware::Error) -> Self {
+        match ResponseTooLarge::find(&error) {
+            Some(too_large) => Self::ResponseTooLarge(too_large),
+            None => Self::HttpError(error),
+        }
+    }
+}
+
+impl MessageError {
+    /// Returns true if the error represents a 404 from the CCN, whether
+    /// surfaced as the typed `NotFound` variant, an `ApiError` with status
+    /// 404, or an `HttpError` wrapping a reqwest 404.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            MessageError::NotFound(_) => true,
+            MessageError::ApiError { status, .. } => *status == 404,
+            MessageError::HttpError(e) => e.status() == Some(StatusCode::NOT_FOUND),
+            _ => false,
+        }
+    }
+
+    /// The HTTP status of the answer of the node, if the error comes from
+    /// one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            MessageError::NotFound(_) => Some(404),
+            MessageError::ApiError { status, .. } => Some(*status),
+            MessageError::HttpError(e) => e.status().map(|status| status.as_u16()),
+            MessageError::Storage(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// The error reported by the node, parsed from the body of its answer.
+    pub fn api_error(&self) -> Option<ApiError> {
+        match self {
+            // Decode failures of successful answers are reported with status 200.
+            MessageError::ApiError { status, body } if *status >= 400 => {
+                Some(ApiError::parse(*status, body))
+            }
+            _ => None,
+        }
+    }
+
+    /// Which side the error comes from.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            MessageError::HttpError(e) | MessageError::Storage(StorageError::UploadFailed(e))
+                if e.status().is_none() =>
+            {
+                match e {
+                    reqwest_middleware::Error::Reqwest(e) if e.is_decode() || e.is_body() => {
+                        ErrorKind::Other
+                    }
+                    _ => ErrorKind::Network,
+                }
+            }
+            MessageError::WebsocketConnect(_) | MessageError::WebsocketStream(_) => {
+                ErrorKind::Network
+            }
+            MessageError::Storage(StorageError::UploadTimeout(_)) => ErrorKind::Network,
+            MessageError::Storage(StorageError::IpfsBackendUnavailable(_)) => ErrorKind::Server,
+            _ => match self.status() {
+                Some(status) if (400..500).contains(&status) => ErrorKind::Client,
+                Some(status) if status >= 500 => ErrorKind::Server,
+                _ => ErrorKind::Other,
+            },
+        }
+    }
+
+    /// Whether sending the same request again may succeed: network errors,
+    /// server errors, timeouts and rate limiting.
+    ///
+    /// Requests already go through the retry middleware of the client, so
+    /// this is for retries at a higher level, e.g. of a whole upload.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind() {
+            ErrorKind::Network => true,
+            // 501 and 505 will not change on retry.
+            ErrorKind::Server => !matches!(self.status(), Some(501 | 505)),
+            ErrorKind::Client => matches!(self.status(), Some(408 | 425 | 429)),
+            ErrorKind::Other => false,
+        }
+    }
+}
+
+/// Classification of a [`MessageError`], see [`MessageError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The node rejected the request (4xx).
+    Client,
+    /// The node failed to serve the request (5xx).
+    Server,
+    /// The node could not be reached, or the connection failed.
+    Network,
+    /// Local errors: invalid input, unexpected answers, I/O...
+    Other,
+}
+
+/// An error answered by a CCN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    /// Machine-readable error code, when the node sent one (the `error_code`
+    /// of rejected messages, for instance).
+    pub code: Option<String>,
+    /// Human-readable description of the error.
+    pub message: String,
+}
+
+impl ApiError {
+    /// Parses the body of an error answer of a CCN.
+    ///
+    /// Understands the shapes pyaleph uses, `{"error": {"code": ..., "message":
+    /// ...}}`, `{"error": "..."}`, `{"message": "..."}`, `{"detail": ...}`
+    /// and lists of validation errors, and falls back to the raw body.
+    pub fn parse(status: u16, body: &str) -> Self {
+        let body = body.trim();
+        let value = serde_json::from_str::<serde_json::Value>(body).unwrap_or_default();
+        let code = ["code", "error_code"]
+            .into_iter()
+            .find_map(|key| {
+                value
+                    .get("error")
+                    .and_then(|error| error.get(key))
+                    .or_else(|| value.get(key))
+            })
+            .and_then(json_scalar);
+        let message = value
+            .get("error")
+            .and_then(|error| error.get("message"))
+            .or_else(|| value.get("error"))
+            .or_else(|| value.get("message"))
+            .or_else(|| value.get("detail"))
+            .unwrap_or(&value);
+        let message = match message {
+            // Validation errors: [{"loc": [...], "msg": "...", ...}, ...].
+            serde_json::Value::Array(errors) => {
+                let messages: Vec<_> = errors
+                    .iter()
+                    .filter_map(|error| error.get("msg").and_then(json_scalar))
+                    .collect();
+                (!messages.is_empty()).then(|| messages.join("; "))
+            }
+            other => json_scalar(other),
+        };
+        Self {
+            status,
+            code,
+            message: message.unwrap_or_else(|| body.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP {}: {}", self.status, self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, " (code {code})")?;
+        }
+        Ok(())
+    }
+}
+
+/// String and number JSON values as text.
+fn json_scalar(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Checks the status of the answers of the node.
+pub(super) trait CheckStatus: Sized {
+    /// Fails on error statuses like `error_for_status`, but keeps the body of
+    /// the answer in [`MessageError::ApiError`].
+    fn check_status(self) -> impl Future<Output = Result<Self, MessageError>> + Send;
+}
+
+impl CheckStatus for reqwest::Response {
+    async fn check_status(self) -> Result<Self, MessageError> {
+        let status = self.status();
+        if status.is_client_error() || status.is_server_error() {
+            let body = self.text().await.unwrap_or_default();
+            return Err(MessageError::ApiError {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(self)
+    }
+}
+
+/// Error during message integrity verification.
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrityError {
+    /// The computed hash doesn't match the expected item hash.
+    #[error("Hash mismatch: expected {expected}, computed {actual}")]
+    HashMismatch {
+        expected: ItemHash,
+        actual: ItemHash,
+    },
+
+    /// The raw content passed hash verification but could not be deserialized as MessageContent.
+    #[error("Failed to deserialize verified content")]
+    ContentDeserializationFailed(#[source] serde_json::Error),
+
+    /// The cryptographic signature does not match the message sender.
+    #[error("Signature verification failed: {0}")]
+    SignatureVerificationFailed(#[from] SignatureVerificationError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{
+        AlephClient, AlephMessageClient, AlephStorageClient, MessageFilter, PaginationParams,
+        RetryConfig,
+    };
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_storage_error_display() {
+        assert_eq!(
+            StorageError::InsufficientBalance.to_string(),
+            "Insufficient balance"
+        );
+        assert_eq!(
+            StorageError::IpfsDisabled.to_string(),
+            "IPFS is disabled on this node"
+        );
+        assert_eq!(StorageError::FileTooLarge.to_string(), "File too large");
+    }
+
+    #[test]
+    fn parse_understands_ccn_error_bodies() {
+        let error = ApiError::parse(
+            422,
+            r#"{"error":{"code":503,"message":"forget address does not match"},"message_status":"rejected"}"#,
+        );
+        assert_eq!(error.code.as_deref(), Some("503"));
+        assert_eq!(error.message, "forget address does not match");
+
+        let error = ApiError::parse(
+            422,
+            r#"[{"loc":["body","sync"],"msg":"value is not a valid boolean","type":"type_error"}]"#,
+        );
+        assert_eq!(error.code, None);
+        assert_eq!(error.message, "value is not a valid boolean");
+
+        let error = ApiError::parse(400, r#"{"error_code": 5, "message": "bad request"}"#);
+        assert_eq!(error.code.as_deref(), Some("5"));
+        assert_eq!(error.message, "bad request");
+        assert_eq!(error.to_string(), "HTTP 400: bad request (code 5)");
+
+        let error = ApiError::parse(500, "500: Internal Server Error\n");
+        assert_eq!(error.message, "500: Internal Server Error");
+    }
+
+    #[tokio::test]
+    async fn error_answers_keep_their_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "Invalid start date",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/api/v0/price/d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c",
+            ))
+            .respond_with(ResponseTemplate::new(503).set_body_string("overloaded"))
+            .mount(&server)
+            .await;
+        let client = AlephClient::builder(Url::parse(&server.uri()).unwrap())
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+
+        let err = client
+            .get_messages(&MessageFilter::default(), PaginationParams::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.api_error().unwrap().message, "Invalid start date");
+        assert_eq!(err.kind(), ErrorKind::Client);
+        assert!(!err.is_retryable());
+
+        let err = client
+            .get_price(
+                &"d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+                    .parse()
+                    .unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(err.kind(), ErrorKind::Server);
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn upload_errors_are_classified() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/storage/add_file"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("disk full"))
+            .mount(&server)
+            .await;
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+        let err = client
+            .upload_to_storage(b"data", None, false)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, StorageError::UploadRejected { status: 500, body } if body == "disk full")
+        );
+        let err = MessageError::from(err);
+        assert_eq!(err.kind(), ErrorKind::Server);
+        assert!(err.is_retryable());
+
+        // Nothing listens on port 1.
+        let client = AlephClient::new(Url::parse("http://127.0.0.1:1").unwrap());
+        let err = client
+            .upload_to_storage(b"data", None, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::UploadFailed(_)));
+        let err = MessageError::from(err);
+        assert_eq!(err.kind(), ErrorKind::Network);
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn local_errors_are_not_retryable() {
+        let err = MessageError::WebsocketBadScheme;
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert!(!err.is_retryable());
+        assert!(err.api_error().is_none());
+    }
+}
@@ -0,0 +1,2317 @@
+mod account;
+mod aggregates;
+mod channels;
+mod error;
+mod node;
+mod pagination;
+mod posts;
+mod programs;
+mod retry;
+mod status;
+mod storage;
+mod verification;
+mod watch;
+
+use crate::aggregate_models::corechannel::CoreChannelAggregate;
+use crate::aggregate_models::domains::{DOMAINS_AGGREGATE_KEY, DomainsAggregate};
+use crate::aggregate_models::port_forwarding::{
+    PORT_FORWARDING_AGGREGATE_KEY, PortForwardingAggregate,
+};
+use crate::aggregate_models::pricing::{PRICING_ADDRESS, PricingAggregate};
+use crate::aggregate_models::settings::{SETTINGS_ADDRESS, SETTINGS_KEY, SettingsAggregate};
+use crate::aggregate_models::vm_images::{VM_IMAGES_KEY, VmImagesAggregate};
+use crate::aggregate_models::websites::{WEBSITES_AGGREGATE_KEY, WebsitesAggregate};
+use crate::authorization::{AlephAuthorizationClient, ReceivedAuthorization};
+use crate::messages::{InstanceBuilder, ProgramBuilder, StoreBuilder};
+use crate::network::Network;
+use crate::node_metrics::{
+    METRICS_POST_TYPE, NodeMetricsContent, SCORING_ADDRESS, SCORING_CHANNEL,
+};
+use crate::rate_limit::{RateLimit, RetryMiddleware};
+use crate::references::MessageReferences;
+use crate::response_limit::{ResponseLimitMiddleware, ResponseLimits};
+use crate::upload_timeout::UploadTimeout;
+use crate::verify::Hasher;
+use aleph_types::account::Account;
+use aleph_types::chain::Address;
+use aleph_types::channel::Channel;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::StorageEngine;
+use aleph_types::message::execution::volume::PersistentVolumeSize;
+use aleph_types::message::item_type::ItemType;
+use aleph_types::message::pending::PendingMessage;
+use aleph_types::message::{
+    FileRef, Message, MessageHeader, MessageStatus, MessageType, StoreContent, TypedContent,
+};
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use http::Extensions;
+use memsizes::Bytes;
+use reqwest::{Request, Response, StatusCode};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, Next};
+use reqwest_retry::policies::ExponentialBackoff;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::Instrument;
+use url::Url;
+
+pub use self::account::{
+    AccountBalance, AccountFile, CreditDirection, CreditHistoryFilters, CreditHistoryItem,
+    CreditHistoryResponse, CreditHistorySummary,
+};
+pub use self::channels::{ChannelActivity, ChannelStats};
+pub use self::error::{ApiError, ErrorKind, IntegrityError, MessageError, StorageError};
+pub use self::node::{
+    CrnCandidate, NodeInfo, NodeSyncStatus, PriceEstimate, PubsubPublishResponse, PubsubTransport,
+};
+pub use self::pagination::{
+    GetMessagesResponse, MessageFilter, PaginationParams, SortBy, SortOrder,
+};
+pub use self::posts::{
+    GetPostsV0Response, GetPostsV1Response, PostFilter, PostHistory, PostV0, PostV1, TypedPost,
+};
+pub use self::programs::{
+    CreatedProgram, DEFAULT_PROGRAM_GATEWAY_URL, ProgramEndpoint, ProgramHealth, ProgramRequest,
+    ProgramResponse,
+};
+pub use self::retry::{RetryConfig, TimeoutConfig};
+pub use self::status::{
+    ForgottenMessage, InstanceMessage, MessageWithStatus, PostMessage, ProgramMessage,
+    RawPendingMessage, RejectedMessage, RemovalReason, StoreMessage, TypedMessage,
+};
+pub use self::storage::{
+    DownloadProgress, FileDownload, FileMetadata, StoredFileMetadata, hash_file,
+};
+pub use self::verification::{
+    InvalidMessage, MessageVerification, UnsignedMessage, VerifiedMessage,
+};
+pub use self::watch::WATCH_BACKFILL_MARGIN;
+
+use self::aggregates::{
+    decode_aggregate_value, extract_aggregate_value, map_aggregate_404_to_empty,
+};
+use self::error::CheckStatus;
+use self::pagination::{CURSOR_DEFAULT_PAGINATION, CURSOR_MAX_PAGINATION};
+use self::storage::{GENERIC_CONTENT_TYPE, sniff_file_content_type};
+use self::verification::verify_message_header;
+
+/// Middleware that limits the number of concurrent HTTP requests.
+///
+/// Placed inside the retry middleware so that permits are held only during actual network I/O,
+/// not during backoff sleeps between retries.
+struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for ConcurrencyLimit {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        next.run(req, extensions).await
+    }
+}
+
+/// Middleware that runs each request in a `ccn_request` span carrying its
+/// method and URL, and logs its outcome.
+///
+/// Placed outside the retry middlewares so that all the attempts of a request
+/// share one span.
+struct RequestTracing;
+
+#[async_trait::async_trait]
+impl Middleware for RequestTracing {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let span = tracing::debug_span!("ccn_request", method = %req.method(), url = %req.url());
+        async move {
+            let started = std::time::Instant::now();
+            let result = next.run(req, extensions).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            match &result {
+                Ok(response) => tracing::debug!(
+                    status = response.status().as_u16(),
+                    elapsed_ms,
+                    "response received"
+                ),
+                Err(e) => tracing::debug!(error = %e, elapsed_ms, "request failed"),
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+/// Client for the API of a CCN.
+///
+/// Cloning is cheap: clones share the same connection pools and concurrency
+/// limit, so a single client can be handed to every task or request handler.
+#[derive(Clone)]
+pub struct AlephClient {
+    inner: Arc<ClientInner>,
+}
+
+#[derive(Clone)]
+struct ClientInner {
+    http_client: ClientWithMiddleware,
+    /// Plain client without retry middleware — used for uploads where the
+    /// request body (multipart) is not cloneable and therefore cannot be retried.
+    /// Carries no reqwest total timeout; upload deadlines are enforced per-request
+    /// by the [`AlephClientBuilder::upload_timeout`] policy via
+    /// [`crate::upload_timeout::run_upload`].
+    upload_client: reqwest::Client,
+    /// Policy governing how long an upload may run. See [`UploadTimeout`].
+    upload_timeout: UploadTimeout,
+    ccn_url: Url,
+    ipfs_gateway: Url,
+    /// Quota reported by the last response that carried rate-limit headers.
+    rate_limit: Arc<std::sync::Mutex<Option<RateLimit>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetMessageResponse {
+    #[serde(flatten)]
+    message: MessageWithStatus<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetMessageStatusResponse {
+    status: MessageStatus,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PublicationStatus {
+    pub status: String,
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PostMessageResponse {
+    pub publication_status: PublicationStatus,
+    pub message_status: String,
+}
+
+impl PostMessageResponse {
+    /// Status of the message on the node, parsed from `message_status`.
+    pub fn status(&self) -> Result<MessageStatus, MessageError> {
+        serde_json::from_value(serde_json::Value::String(self.message_status.clone()))
+            .map_err(MessageError::Decode)
+    }
+}
+
+/// Body for POSTing a message to a node via POST /api/v0/messages.
+#[derive(Serialize)]
+struct PostMessageBody<'a> {
+    sync: bool,
+    message: &'a PendingMessage,
+}
+
+pub trait AlephMessageClient {
+    fn get_message(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<MessageWithStatus<Message>, MessageError>> + Send;
+
+    /// Returns the status of a message without transferring its content.
+    ///
+    /// Meant for pollers that only need to know whether a message exists and
+    /// where it is in its lifecycle. The default implementation falls back to
+    /// [`get_message`](Self::get_message).
+    fn get_message_status(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<MessageStatus, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async move { Ok(self.get_message(item_hash).await?.status()) }
+    }
+
+    /// Polls [`get_message`](Self::get_message) every `poll_interval` until
+    /// the message leaves the pending state, and returns it with its final
+    /// status (processed, rejected, ...).
+    ///
+    /// Meant to be called right after broadcasting: a CCN that has not seen
+    /// the message yet answers 404, which is polled through like a pending
+    /// status. Fails with [`MessageError::WaitTimeout`] if the message is
+    /// still pending (or unknown) after `timeout`, including when a request
+    /// is still in flight at that point.
+    fn wait_for_message(
+        &self,
+        item_hash: &ItemHash,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> impl Future<Output = Result<MessageWithStatus<Message>, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        watch::wait_for_message(self, item_hash, timeout, poll_interval)
+    }
+
+    /// Fetches a message and checks that it carries content of type `C`.
+    ///
+    /// See [`TypedMessage::try_from_message`] for the accepted statuses.
+    fn get_typed_message<C: TypedContent>(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<TypedMessage<C>, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async move { TypedMessage::try_from_message(item_hash, self.get_message(item_hash).await?) }
+    }
+
+    /// Fetches the content of a message of type `C`, e.g.
+    /// `get_message_as::<ProgramContent>(&hash)`.
+    ///
+    /// Fails like [`get_typed_message`](Self::get_typed_message): with
+    /// [`MessageError::InvalidType`] if the message carries another type.
+    fn get_message_as<C: TypedContent>(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<C, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            self.get_typed_message::<C>(item_hash)
+                .await
+                .map(TypedMessage::into_content)
+        }
+    }
+
+    /// Fetches a PROGRAM message. Shorthand for
+    /// [`get_typed_message`](Self::get_typed_message).
+    fn get_program(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<ProgramMessage, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        self.get_typed_message(item_hash)
+    }
+
+    /// Fetches an INSTANCE message. Shorthand for
+    /// [`get_typed_message`](Self::get_typed_message).
+    fn get_instance(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<InstanceMessage, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        self.get_typed_message(item_hash)
+    }
+
+    /// Fetches a STORE message. Shorthand for
+    /// [`get_typed_message`](Self::get_typed_message).
+    fn get_store(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<StoreMessage, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        self.get_typed_message(item_hash)
+    }
+
+    /// Fetches a POST message. Shorthand for
+    /// [`get_typed_message`](Self::get_typed_message).
+    fn get_post(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<PostMessage, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        self.get_typed_message(item_hash)
+    }
+
+    /// Fetches a post and all the amends referencing it, ordered by time.
+    ///
+    /// `item_hash` may be the original post or one of its amends; in the
+    /// latter case the history of the amended post is returned.
+    fn get_post_history(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<PostHistory, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let mut original = self.get_post(item_hash).await?;
+            let amended = original
+                .content()
+                .is_amend()
+                .then(|| original.content().reference.as_deref())
+                .flatten()
+                .and_then(|r| r.parse::<ItemHash>().ok());
+            if let Some(amended) = amended {
+                original = self.get_post(&amended).await?;
+            }
+
+            let filter = MessageFilter::default()
+                .message_type(MessageType::Post)
+                .content_types(["amend"])
+                .refs([original.message().item_hash.clone()]);
+            let mut amends: Vec<Message> = self
+                .get_messages_iterator(filter, None)
+                .try_collect()
+                .await?;
+            amends.sort_by(|a, b| a.time.as_f64().total_cmp(&b.time.as_f64()));
+            Ok(PostHistory { original, amends })
+        }
+    }
+
+    fn get_messages(
+        &self,
+        filter: &MessageFilter,
+        pagination: PaginationParams,
+    ) -> impl Future<Output = Result<Vec<Message>, MessageError>> + Send;
+
+    /// Returns a stream that automatically paginates through all messages matching the filter.
+    ///
+    /// Items are yielded one at a time. Pages are fetched lazily — the first HTTP request
+    /// happens on the first `.next()` call. Uses cursor-based pagination internally.
+    ///
+    /// The stream terminates when all results have been consumed, or on the first error
+    /// (after the retry middleware has exhausted its retries).
+    /// `pagination` controls items per page (default 200, max 200).
+    fn get_messages_iterator(
+        &self,
+        filter: MessageFilter,
+        pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + '_;
+
+    /// Subscribes to the messages matching `filter` over the CCN websocket,
+    /// starting with the last `history` ones.
+    ///
+    /// The returned stream is live: it reconnects on its own and yields
+    /// connection errors without ending. Being a plain [`Stream`], it composes
+    /// with `StreamExt`/`TryStreamExt` combinators, e.g.
+    /// `.try_filter(...)`, `.take_until(shutdown)` or `tokio_stream`'s
+    /// `.timeout(...)`.
+    fn subscribe_to_messages(
+        &self,
+        filter: &MessageFilter,
+        history: Option<u32>,
+    ) -> impl Future<
+        Output = Result<
+            impl Stream<Item = Result<Message, MessageError>> + Send + Unpin,
+            MessageError,
+        >,
+    > + Send;
+
+    fn post_message(
+        &self,
+        message: &PendingMessage,
+        sync: bool,
+    ) -> impl Future<Output = Result<PostMessageResponse, MessageError>> + Send;
+
+    /// Submits a signed message to the network, uploading content to storage
+    /// or IPFS first if the message is non-inline.
+    ///
+    /// Prefer this over [`post_message`](Self::post_message) for messages built
+    /// with [`MessageBuilder`](crate::builder::MessageBuilder) or the typed builders,
+    /// as it handles the content upload step transparently.
+    fn submit_message(
+        &self,
+        message: &PendingMessage,
+        sync: bool,
+    ) -> impl Future<Output = Result<PostMessageResponse, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async move {
+            match message.item_type {
+                ItemType::Inline => {}
+                ItemType::Storage => {
+                    let uploaded = self
+                        .upload_to_storage(message.item_content.as_bytes(), None, false)
+                        .await?;
+                    if uploaded != message.item_hash {
+                        return Err(MessageError::HashMismatch {
+                            expected: message.item_hash.clone(),
+                            actual: uploaded,
+                        });
+                    }
+                }
+                ItemType::Ipfs => {
+                    let uploaded = self
+                        .upload_to_ipfs(message.item_content.as_bytes(), None, false)
+                        .await?;
+                    if uploaded != message.item_hash {
+                        return Err(MessageError::HashMismatch {
+                            expected: message.item_hash.clone(),
+                            actual: uploaded,
+                        });
+                    }
+                }
+            }
+            self.post_message(message, sync).await
+        }
+    }
+
+    /// Broadcasts an already signed message, e.g. one fetched from another
+    /// node, and returns its status on this node.
+    ///
+    /// The content of non-inline messages is not uploaded: it is expected to
+    /// be available on the network already. A message the node rejects fails
+    /// with [`MessageError::Rejected`].
+    fn broadcast_message(
+        &self,
+        message: &Message,
+        sync: bool,
+    ) -> impl Future<Output = Result<MessageStatus, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let pending = PendingMessage::try_from(message)?;
+            match self.post_message(&pending, sync).await?.status()? {
+                MessageStatus::Rejected => Err(MessageError::Rejected {
+                    item_hash: message.item_hash.clone(),
+                }),
+                status => Ok(status),
+            }
+        }
+    }
+
+    /// Builds, signs and submits a POST message in one call, returning its
+    /// item hash.
+    ///
+    /// This is a convenience over [`PostBuilder`](crate::messages::PostBuilder)
+    /// and [`submit_message`](Self::submit_message): content too large to be
+    /// inlined is uploaded to storage first. Use those directly to amend a
+    /// post, set a reference or post on behalf of another address. A post the
+    /// node rejects fails with [`MessageError::Rejected`].
+    fn create_post(
+        &self,
+        account: &impl Account,
+        post_type: impl Into<String> + Send,
+        content: impl Serialize + Send,
+        channel: Option<Channel>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async move {
+            let mut builder = crate::messages::PostBuilder::new(account, post_type, content)?;
+            if let Some(channel) = channel {
+                builder = builder.channel(channel);
+            }
+            let message = builder.build()?;
+            submit_accepted(self, &message, sync).await
+        }
+    }
+
+    /// Builds, signs and submits a FORGET message for `hashes` and whole
+    /// `aggregates`, returning its item hash.
+    ///
+    /// Every target is fetched first: a hash the node does not know fails
+    /// with [`MessageError::NotFound`], and a message owned by another
+    /// address than `owner` (the account by default) with
+    /// [`MessageError::NotOwned`], since the network would reject the
+    /// FORGET. Use [`ForgetBuilder`](crate::messages::ForgetBuilder) and
+    /// [`submit_message`](Self::submit_message) to skip these checks.
+    fn forget(
+        &self,
+        account: &impl Account,
+        hashes: Vec<ItemHash>,
+        aggregates: Vec<ItemHash>,
+        reason: Option<String>,
+        owner: Option<Address>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async move {
+            let expected = owner.clone().unwrap_or_else(|| account.address().clone());
+            for item_hash in hashes.iter().chain(&aggregates) {
+                let message = match self.get_message(item_hash).await? {
+                    MessageWithStatus::Processed { message }
+                    | MessageWithStatus::Removing { message, .. }
+                    | MessageWithStatus::Removed { message, .. } => message,
+                    // Nothing to check ownership against.
+                    _ => continue,
+                };
+                if !message.owner().same_as(&expected) {
+                    return Err(MessageError::NotOwned {
+                        item_hash: item_hash.clone(),
+                        owner: message.owner().clone(),
+                        expected,
+                    });
+                }
+            }
+
+            let mut builder =
+                crate::messages::ForgetBuilder::new(account, hashes).aggregates(aggregates);
+            if let Some(reason) = reason {
+                builder = builder.reason(reason);
+            }
+            if let Some(owner) = owner {
+                builder = builder.on_behalf_of(owner);
+            }
+            let message = builder.build()?;
+            submit_accepted(self, &message, sync).await
+        }
+    }
+
+    /// Builds, signs and submits an AGGREGATE message setting `key` to
+    /// `content`, returning its item hash.
+    ///
+    /// `content` must serialize to a JSON object. The network merges it into
+    /// the current value of the key: the top-level fields it contains are
+    /// replaced, the others are kept. `owner` writes the aggregate of another
+    /// address, which must have authorized the account.
+    fn create_aggregate(
+        &self,
+        account: &impl Account,
+        key: impl Into<String> + Send,
+        content: impl Serialize + Send,
+        owner: Option<Address>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async move {
+            let serde_json::Value::Object(content) =
+                serde_json::to_value(content).map_err(crate::messages::MessageBuildError::from)?
+            else {
+                return Err(crate::messages::MessageBuildError::AggregateNotObject.into());
+            };
+            let mut builder = crate::messages::AggregateBuilder::new(account, key, content);
+            if let Some(owner) = owner {
+                builder = builder.on_behalf_of(owner);
+            }
+            let message = builder.build()?;
+            submit_accepted(self, &message, sync).await
+        }
+    }
+
+    /// Read-modify-write of an aggregate key: fetches its current value (or
+    /// `T::default()` if unset), applies `update` and publishes the result
+    /// with [`create_aggregate`](Self::create_aggregate).
+    ///
+    /// Top-level fields removed by `update` are not deleted from the
+    /// aggregate, since the network merges updates; set them to `null`
+    /// instead. Concurrent writers are not detected.
+    fn update_aggregate<T, F>(
+        &self,
+        account: &impl Account,
+        key: &str,
+        owner: Option<Address>,
+        update: F,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephAggregateClient + AlephStorageClient + Sync,
+        T: DeserializeOwned + Serialize + Default + Send,
+        F: FnOnce(&mut T) + Send,
+    {
+        async move {
+            let address = owner.clone().unwrap_or_else(|| account.address().clone());
+            let raw = self
+                .get_aggregate::<Option<serde_json::Value>>(&address, key)
+                .await;
+            let mut value: T = extract_aggregate_value(map_aggregate_404_to_empty(raw)?, key)?;
+            update(&mut value);
+            self.create_aggregate(account, key, value, owner, sync)
+                .await
+        }
+    }
+
+    /// Deploys a program in one call: uploads the code archive with its STORE
+    /// message, then publishes a PROGRAM message running it.
+    ///
+    /// The program starts from the defaults of [`ProgramBuilder`] (zip
+    /// encoding, HTTP trigger, 1 vCPU, 128 MiB); `configure` adjusts them,
+    /// e.g. to set volumes, variables or a squashfs encoding. The STORE message
+    /// is always processed synchronously, so that the PROGRAM can reference it.
+    #[allow(clippy::too_many_arguments)]
+    fn create_program<A, F>(
+        &self,
+        account: &A,
+        code_archive: impl AsRef<std::path::Path> + Send,
+        storage_engine: StorageEngine,
+        entrypoint: impl Into<String> + Send,
+        runtime: ItemHash,
+        configure: F,
+        sync: bool,
+    ) -> impl Future<Output = Result<CreatedProgram, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+        A: Account,
+        F: FnOnce(ProgramBuilder<'_, A>) -> ProgramBuilder<'_, A> + Send,
+    {
+        async move {
+            let path = code_archive.as_ref();
+            let hasher = match storage_engine {
+                StorageEngine::Storage => Hasher::for_storage(),
+                StorageEngine::Ipfs => Hasher::for_ipfs(),
+            };
+            let file_hash = hash_file(path, hasher).await?;
+            let store = StoreBuilder::new(account, file_hash, storage_engine).build()?;
+            // `code.ref` is the STORE message, not the file: the CRN follows
+            // the message to the file.
+            let program = configure(ProgramBuilder::new(
+                account,
+                store.item_hash.clone(),
+                entrypoint,
+                runtime,
+            ))
+            .build()?;
+
+            match storage_engine {
+                StorageEngine::Storage => {
+                    self.upload_file_to_storage(path, Some(&store), true)
+                        .await?
+                }
+                StorageEngine::Ipfs => self.upload_file_to_ipfs(path, Some(&store), true).await?,
+            };
+            let program = submit_accepted(self, &program, sync).await?;
+            Ok(CreatedProgram {
+                code_store: store.item_hash,
+                program,
+            })
+        }
+    }
+
+    /// Publishes an INSTANCE message booting a copy of the `rootfs` image,
+    /// resized to `rootfs_size`.
+    ///
+    /// The instance starts from the defaults of [`InstanceBuilder`] (1 vCPU,
+    /// 128 MiB, internet access, no payment, i.e. paid by holding);
+    /// `configure` adjusts them, e.g. to set SSH keys, a hypervisor, volumes or
+    /// a pay-as-you-go payment.
+    fn create_instance<A, F>(
+        &self,
+        account: &A,
+        rootfs: ItemHash,
+        rootfs_size: PersistentVolumeSize,
+        configure: F,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+        A: Account,
+        F: FnOnce(InstanceBuilder<'_, A>) -> InstanceBuilder<'_, A> + Send,
+    {
+        async move {
+            let message = configure(InstanceBuilder::new(account, rootfs, rootfs_size)).build()?;
+            submit_accepted(self, &message, sync).await
+        }
+    }
+
+    /// Uploads a file and creates a STORE message in one call.
+    ///
+    /// This is a convenience that combines `upload_file_to_storage`/`upload_file_to_ipfs`,
+    /// `StoreBuilder`, and `post_message`. For more control (setting reference,
+    /// metadata, or channel), use those components directly.
+    ///
+    /// The file is hashed locally and uploaded together with the signed STORE
+    /// message in a single authenticated request, regardless of storage engine.
+    fn create_store(
+        &self,
+        account: &impl Account,
+        path: impl AsRef<std::path::Path> + Send,
+        storage_engine: StorageEngine,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async move {
+            let path = path.as_ref();
+            let hasher = match storage_engine {
+                StorageEngine::Storage => Hasher::for_storage(),
+                StorageEngine::Ipfs => Hasher::for_ipfs(),
+            };
+            let file_hash = hash_file(path, hasher).await?;
+            let message = StoreBuilder::new(account, file_hash.clone(), storage_engine).build()?;
+            match storage_engine {
+                StorageEngine::Storage => {
+                    self.upload_file_to_storage(path, Some(&message), sync)
+                        .await?;
+                }
+                StorageEngine::Ipfs => {
+                    self.upload_file_to_ipfs(path, Some(&message), sync).await?;
+                }
+            }
+            Ok(file_hash)
+        }
+    }
+
+    /// Like [`create_store`](Self::create_store), for content already in
+    /// memory.
+    fn create_store_from_bytes(
+        &self,
+        account: &impl Account,
+        data: &[u8],
+        storage_engine: StorageEngine,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async move {
+            let mut hasher = match storage_engine {
+                StorageEngine::Storage => Hasher::for_storage(),
+                StorageEngine::Ipfs => Hasher::for_ipfs(),
+            };
+            hasher.update(data);
+            let file_hash = hasher.finalize();
+            let message = StoreBuilder::new(account, file_hash.clone(), storage_engine).build()?;
+            let uploaded = match storage_engine {
+                StorageEngine::Storage => {
+                    self.upload_to_storage(data, Some(&message), sync).await?
+                }
+                StorageEngine::Ipfs => self.upload_to_ipfs(data, Some(&message), sync).await?,
+            };
+            if uploaded != file_hash {
+                return Err(MessageError::HashMismatch {
+                    expected: file_hash,
+                    actual: uploaded,
+                });
+            }
+            Ok(file_hash)
+        }
+    }
+
+    /// Verifies a fully-fetched [`Message`] by re-checking its raw content
+    /// and signature.
+    ///
+    /// Returns a [`MessageVerification`] describing the outcome:
+    /// [`Verified`](MessageVerification::Verified) (content hash + signature
+    /// both checked), [`Unsigned`](MessageVerification::Unsigned) (content
+    /// hash checked, signature absent), or
+    /// [`Invalid`](MessageVerification::Invalid) (integrity failure).
+    /// `Err(MessageError)` is reserved for transient failures (network, I/O).
+    fn verify_message(
+        &self,
+        message: Message,
+    ) -> impl Future<Output = Result<MessageVerification, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        verify_message_header(self, MessageHeader::from(message))
+    }
+
+    /// Fetches a single message and verifies its integrity.
+    ///
+    /// Verification is only performed for statuses that carry a full
+    /// [`Message`] (Processed, Removing, Removed); other statuses (Pending,
+    /// Forgotten, Rejected) are passed through unchanged. See
+    /// [`verify_message`](Self::verify_message) for the verification outcomes.
+    fn get_message_and_verify(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<MessageWithStatus<MessageVerification>, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        async {
+            self.get_message(item_hash)
+                .await?
+                .try_map_message_async(|msg| self.verify_message(msg))
+                .await
+        }
+    }
+
+    /// Fetches messages matching the filter and verifies each one's integrity.
+    ///
+    /// Messages are fetched as headers (without deserializing the CCN's `content` field),
+    /// then each message's raw content is obtained and verified:
+    /// - Inline messages: verified locally from `item_content`
+    /// - Non-inline messages: downloaded from `/api/v0/storage/raw/{item_hash}`
+    ///
+    /// Each message resolves to a [`MessageVerification`] — `Verified`,
+    /// `Unsigned`, or `Invalid`. The outer `Err(MessageError)` is reserved for
+    /// transient failures (network, 404) that abort the entire batch.
+    ///
+    /// **Note:** Non-inline messages require a sequential HTTP round-trip each to
+    /// `/storage/raw/{item_hash}`, so verifying a page of N non-inline messages incurs N
+    /// additional requests.
+    ///
+    /// ```ignore
+    /// let results = client.get_messages_and_verify(&filter).await?;
+    ///
+    /// for outcome in results {
+    ///     match outcome {
+    ///         MessageVerification::Verified(v) => use_signed(v),
+    ///         MessageVerification::Unsigned(u) => use_legacy(u), // see UnsignedMessage caveat
+    ///         MessageVerification::Invalid(i) => log::warn!("{}", i),
+    ///     }
+    /// }
+    /// ```
+    fn get_messages_and_verify(
+        &self,
+        filter: &MessageFilter,
+    ) -> impl Future<Output = Result<Vec<MessageVerification>, MessageError>> + Send
+    where
+        Self: AlephStorageClient + Sync;
+
+    /// Fetches every message whose `ref` points to `item_hash` and groups them
+    /// by relation (amendments, other posts, stores, forgets).
+    ///
+    /// Paginates through all results; see [`MessageReferences`] for the
+    /// classification rules.
+    fn get_messages_referencing(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<MessageReferences, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        let filter = MessageFilter::default().refs([item_hash.clone()]);
+        async move {
+            let messages: Vec<Message> = self
+                .get_messages_iterator(filter, None)
+                .try_collect()
+                .await?;
+            Ok(MessageReferences::classify(item_hash, messages))
+        }
+    }
+}
+
+pub trait AlephStorageClient {
+    fn get_file_size(
+        &self,
+        file_hash: &ItemHash,
+    ) -> impl Future<Output = Result<Bytes, MessageError>> + Send;
+
+    fn get_file_metadata_by_message_hash(
+        &self,
+        message_hash: &ItemHash,
+    ) -> impl Future<Output = Result<FileMetadata, MessageError>> + Send;
+
+    fn get_file_metadata_by_ref(
+        &self,
+        file_ref: &FileRef,
+    ) -> impl Future<Output = Result<FileMetadata, MessageError>> + Send;
+
+    fn download_file_by_hash(
+        &self,
+        file_hash: &ItemHash,
+    ) -> impl Future<Output = Result<FileDownload, MessageError>> + Send;
+
+    fn download_file_by_ref(
+        &self,
+        file_ref: &FileRef,
+    ) -> impl Future<Output = Result<FileDownload, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async {
+            let metadata = self.get_file_metadata_by_ref(file_ref).await?;
+            self.download_file_by_hash(&metadata.file_hash).await
+        }
+    }
+
+    fn download_file_by_message_hash(
+        &self,
+        message_hash: &ItemHash,
+    ) -> impl Future<Output = Result<FileDownload, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async {
+            let metadata = self.get_file_metadata_by_message_hash(message_hash).await?;
+            self.download_file_by_hash(&metadata.file_hash).await
+        }
+    }
+
+    /// Gathers in one call what is known about a stored file: its size,
+    /// content type and storage engine, and the STORE message that pinned it.
+    ///
+    /// Size and content type come from the STORE message when the CCN
+    /// recorded them there, the size otherwise from
+    /// [`get_file_size`](Self::get_file_size). A missing or generic content
+    /// type is replaced, on a best effort basis, by one sniffed from the first
+    /// bytes of the file.
+    ///
+    /// Fails with [`StorageError::NotFound`] if the CCN neither serves the
+    /// file nor knows a STORE message for it.
+    fn get_stored_file_metadata(
+        &self,
+        file_hash: &ItemHash,
+    ) -> impl Future<Output = Result<StoredFileMetadata, MessageError>> + Send
+    where
+        Self: AlephMessageClient + Sync,
+    {
+        async move {
+            let filter = MessageFilter::default()
+                .message_type(MessageType::Store)
+                .content_hashes([file_hash.clone()])
+                .sort(SortBy::Time, SortOrder::Asc);
+            let pagination = PaginationParams {
+                pagination: Some(1),
+                page: Some(1),
+            };
+            let store_message = self
+                .get_messages(&filter, pagination)
+                .await?
+                .into_iter()
+                .next();
+            let store = store_message
+                .as_ref()
+                .and_then(|message| message.typed_content::<StoreContent>());
+
+            let mut size = store.and_then(|store| store.size);
+            let mut content_type = store.and_then(|store| store.content_type.clone());
+            if size.is_none() {
+                match self.get_file_size(file_hash).await {
+                    Ok(file_size) => size = Some(file_size),
+                    Err(MessageError::ApiError { status: 404, .. })
+                    | Err(MessageError::Storage(StorageError::NotFound(_))) => {
+                        if store_message.is_none() {
+                            return Err(StorageError::NotFound(file_hash.clone()).into());
+                        }
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            if content_type
+                .as_deref()
+                .is_none_or(|t| t.starts_with(GENERIC_CONTENT_TYPE))
+                && let Some(sniffed) = sniff_file_content_type(self, file_hash).await
+            {
+                content_type = Some(sniffed.to_string());
+            }
+
+            Ok(StoredFileMetadata {
+                engine: match file_hash {
+                    ItemHash::Native(_) => StorageEngine::Storage,
+                    ItemHash::Ipfs(_) => StorageEngine::Ipfs,
+                },
+                file_hash: file_hash.clone(),
+                size,
+                content_type,
+                store_message,
+            })
+        }
+    }
+
+    /// Uploads raw bytes to the node's storage backend.
+    ///
+    /// Sends a `POST /api/v0/storage/add_file` multipart request and returns
+    /// the SHA-256 hash of the uploaded content.
+    ///
+    /// When `message` is provided, `sync` controls whether the server waits
+    /// for the STORE message to be processed before responding. Ignored when
+    /// no message is attached.
+    fn upload_to_storage(
+        &self,
+        data: &[u8],
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, StorageError>> + Send;
+
+    /// Uploads raw bytes to the node's IPFS backend.
+    ///
+    /// Sends a `POST /api/v0/ipfs/add_file` multipart request and returns
+    /// the IPFS CID of the pinned content.
+    ///
+    /// When `message` is `Some`, the multipart form includes a signed
+    /// STORE message in the `metadata` field. The message must be a STORE
+    /// with `content.item_type=ipfs` and `content.item_hash` set to the
+    /// CID the server will compute (callers compute it locally with
+    /// `Hasher::for_ipfs()`). The server pins, recomputes the CID, rejects
+    /// with 422 on mismatch, and processes the message inline. `sync`
+    /// controls whether the server waits for STORE message processing
+    /// before responding; ignored when `message` is `None`.
+    fn upload_to_ipfs(
+        &self,
+        data: &[u8],
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, StorageError>> + Send;
+
+    /// Uploads a file from disk to native storage, streaming without
+    /// loading the full file into memory. Returns the locally-computed
+    /// SHA-256 hash, verified against the server's response.
+    ///
+    /// When `message` is provided, `sync` controls whether the server waits
+    /// for the STORE message to be processed before responding. Ignored when
+    /// no message is attached.
+    fn upload_file_to_storage(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, StorageError>> + Send;
+
+    /// Uploads a file from disk to IPFS, streaming without loading the
+    /// full file into memory. Returns the locally-computed CID, verified
+    /// against the server's response.
+    ///
+    /// `message` and `sync` behave as for [`Self::upload_to_ipfs`].
+    fn upload_file_to_ipfs(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, StorageError>> + Send;
+}
+
+/// Methods used to query account properties, ex: their balance.
+pub trait AlephAccountClient {
+    /// Gets the balance of an Aleph account, in ALEPH tokens and credits.
+    fn get_balance(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<AccountBalance, MessageError>> + Send;
+
+    /// Gets the credit balance of an Aleph account, which pays for
+    /// pay-as-you-go VMs and storage.
+    #[allow(clippy::manual_async_fn)]
+    fn get_credits(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<u64, MessageError>> + Send {
+        let balance = self.get_balance(address);
+        async move { Ok(balance.await?.credits) }
+    }
+
+    /// Gets the total size of all files stored by the user.
+    fn get_total_storage_size(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<Bytes, MessageError>> + Send;
+
+    /// Returns a cursor-walking stream over the files stored by `address`.
+    ///
+    /// `pagination` caps the items per round-trip (defaults to the SDK's
+    /// cursor default, capped at the server's max). `sort_order` is `-1`
+    /// for newest first (server default) or `1` for oldest first; `None`
+    /// uses the server default.
+    fn get_account_files_iterator(
+        &self,
+        address: &Address,
+        pagination: Option<u32>,
+        sort_order: Option<i8>,
+    ) -> impl Stream<Item = Result<AccountFile, MessageError>> + Send + '_;
+
+    /// Gets the price of a VM in Aleph tokens using the holder tier, i.e. the minimum amount
+    /// of Aleph tokens that the user needs to hold in his account.
+    fn get_vm_price(
+        &self,
+        item_hash: &ItemHash,
+    ) -> impl Future<Output = Result<f64, MessageError>> + Send;
+
+    /// Returns a paginated history of credit-affecting events for the address
+    /// (purchases, transfers, expirations, etc.).
+    ///
+    /// Pages are 1-indexed to match the server. `page_size` is clamped by the
+    /// server side; pass `None` to use the server default. `filters` narrows
+    /// the rows by time range, direction, and billed resource type.
+    fn get_credit_history(
+        &self,
+        address: &Address,
+        page: u32,
+        page_size: Option<u32>,
+        filters: &CreditHistoryFilters,
+    ) -> impl Future<Output = Result<CreditHistoryResponse, MessageError>> + Send;
+
+    /// Returns aggregate spend totals for the address over the same filter
+    /// set as [`get_credit_history`](Self::get_credit_history). Unlike the
+    /// listing endpoint this always succeeds, returning zeroed totals for an
+    /// address with no matching entries.
+    fn get_credit_history_summary(
+        &self,
+        address: &Address,
+        filters: &CreditHistoryFilters,
+    ) -> impl Future<Output = Result<CreditHistorySummary, MessageError>> + Send;
+}
+
+pub trait AlephAggregateClient {
+    /// Returns the most recent version of an aggregate.
+    fn get_aggregate<T: DeserializeOwned>(
+        &self,
+        address: &Address,
+        key: &str,
+    ) -> impl Future<Output = Result<T, MessageError>> + Send;
+
+    /// Returns the value of the aggregate `key` of `address`, deserialized as
+    /// `T`, or `None` if the address has no such aggregate.
+    ///
+    /// Unlike [`get_aggregate`](Self::get_aggregate), which deserializes the
+    /// whole `{key: value}` envelope, `T` describes the value itself.
+    #[allow(clippy::manual_async_fn)]
+    fn fetch_aggregate<T: DeserializeOwned>(
+        &self,
+        address: &Address,
+        key: &str,
+    ) -> impl Future<Output = Result<Option<T>, MessageError>> + Send {
+        let raw = self.get_aggregate::<Option<serde_json::Value>>(address, key);
+        async move {
+            let raw = map_aggregate_404_to_empty(raw.await)?;
+            extract_aggregate_value(raw, key)
+        }
+    }
+
+    /// Returns the values of the aggregates `keys` of `address`, all
+    /// deserialized as `T`. Keys without an aggregate are omitted, and an
+    /// address without any aggregate yields an empty map.
+    #[allow(clippy::manual_async_fn)]
+    fn fetch_aggregates<T: DeserializeOwned>(
+        &self,
+        address: &Address,
+        keys: &[&str],
+    ) -> impl Future<Output = Result<HashMap<String, T>, MessageError>> + Send {
+        let raw = self.get_aggregates(address, keys);
+        async move {
+            let values = match raw.await {
+                Ok(values) => values,
+                Err(e) if e.is_not_found() => return Ok(HashMap::new()),
+                Err(e) => return Err(e),
+            };
+            values
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| {
+                    let value = decode_aggregate_value(value, &key)?;
+                    Ok((key, value))
+                })
+                .collect()
+        }
+    }
+
+    /// Returns the most recent version of the corechannel aggregate, i.e., the aggregate
+    /// that lists all the nodes on the network.
+    fn get_corechannel_aggregate(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<CoreChannelAggregate, MessageError>> + Send {
+        self.get_aggregate(address, "corechannel")
+    }
+
+    /// Returns the pricing aggregate that describes compute pricing tiers on the network.
+    fn get_pricing_aggregate(
+        &self,
+    ) -> impl Future<Output = Result<PricingAggregate, MessageError>> + Send {
+        self.get_aggregate(&PRICING_ADDRESS, "pricing")
+    }
+
+    /// Returns the `websites` aggregate for the given address.
+    ///
+    /// "No aggregate stored for this address" is mapped to an empty aggregate
+    /// regardless of how the CCN signals it:
+    ///
+    /// * `200` with `data: null`, `data: {}`, or `data: {"websites": null}` -> empty.
+    /// * `404 Not Found` -> empty.
+    ///
+    /// Other transport errors (timeouts, 5xx, parse failures, etc.) are propagated.
+    ///
+    /// Default implementation: a single-key `get_aggregate` read of the
+    /// `websites` key, with the empty-response handling described above.
+    #[allow(clippy::manual_async_fn)]
+    fn get_websites_aggregate(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<WebsitesAggregate, MessageError>> + Send {
+        // Build the inner future in the sync prefix so the async block captures
+        // only that (already-`Send`) future, not `&self` across the await; this
+        // keeps the returned future `Send` without a `Self: Sync` bound.
+        let raw = self.get_aggregate::<Option<serde_json::Value>>(address, WEBSITES_AGGREGATE_KEY);
+        async move {
+            let raw = map_aggregate_404_to_empty(raw.await)?;
+            extract_aggregate_value(raw, WEBSITES_AGGREGATE_KEY)
+        }
+    }
+
+    /// Returns the `domains` aggregate for the given address.
+    ///
+    /// "No aggregate stored for this address" is mapped to an empty aggregate
+    /// regardless of how the CCN signals it:
+    ///
+    /// * `200` with `data: null`, `data: {}`, or `data: {"domains": null}` -> empty.
+    /// * `404 Not Found` -> empty.
+    ///
+    /// Other transport errors (timeouts, 5xx, parse failures, etc.) are propagated.
+    ///
+    /// Default implementation: a single-key `get_aggregate` read of the
+    /// `domains` key, with the empty-response handling described above.
+    #[allow(clippy::manual_async_fn)]
+    fn get_domains_aggregate(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<DomainsAggregate, MessageError>> + Send {
+        let raw = self.get_aggregate::<Option<serde_json::Value>>(address, DOMAINS_AGGREGATE_KEY);
+        async move {
+            let raw = map_aggregate_404_to_empty(raw.await)?;
+            extract_aggregate_value(raw, DOMAINS_AGGREGATE_KEY)
+        }
+    }
+
+    /// Returns the `port-forwarding` aggregate for the given address.
+    ///
+    /// "No aggregate stored for this address" is mapped to an empty aggregate
+    /// regardless of how the CCN signals it:
+    ///
+    /// * `200` with `data: null`, `data: {}`, or `data: {"port-forwarding": null}` -> empty.
+    /// * `404 Not Found` -> empty.
+    ///
+    /// Other transport errors (timeouts, 5xx, parse failures, etc.) are propagated.
+    ///
+    /// Default implementation: a single-key `get_aggregate` read of the
+    /// `port-forwarding` key, with the empty-response handling described above.
+    #[allow(clippy::manual_async_fn)]
+    fn get_port_forwarding_aggregate(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<PortForwardingAggregate, MessageError>> + Send {
+        let raw =
+            self.get_aggregate::<Option<serde_json::Value>>(address, PORT_FORWARDING_AGGREGATE_KEY);
+        async move {
+            let raw = map_aggregate_404_to_empty(raw.await)?;
+            extract_aggregate_value(raw, PORT_FORWARDING_AGGREGATE_KEY)
+        }
+    }
+
+    /// Returns the most recent version of the vm-images aggregate that lists
+    /// rootfs presets, runtimes, and confidential UEFI firmware curated on the
+    /// network.
+    fn get_vm_images_aggregate(
+        &self,
+    ) -> impl Future<Output = Result<VmImagesAggregate, MessageError>> + Send {
+        self.get_aggregate(&PRICING_ADDRESS, VM_IMAGES_KEY)
+    }
+
+    /// Returns the most recent version of the foundation `settings` aggregate,
+    /// which lists (among other network settings) the GPU models compatible
+    /// with the network in `compatible_gpus`.
+    fn get_settings_aggregate(
+        &self,
+    ) -> impl Future<Output = Result<SettingsAggregate, MessageError>> + Send {
+        self.get_aggregate(&SETTINGS_ADDRESS, SETTINGS_KEY)
+    }
+
+    /// Returns the most recent version of multiple aggregates, keyed by their aggregate key.
+    ///
+    /// The result map only contains keys that exist on the server. Keys that
+    /// have no aggregate are silently omitted.
+    fn get_aggregates(
+        &self,
+        address: &Address,
+        keys: &[&str],
+    ) -> impl Future<Output = Result<HashMap<String, serde_json::Value>, MessageError>> + Send;
+
+    /// Returns every aggregate owned by `address`, keyed by their aggregate key.
+    ///
+    /// Use `get_aggregate` / `get_aggregates` when you already know the keys
+    /// you want; this method is for enumerating an address's aggregate
+    /// namespace (e.g. `aleph aggregate list`).
+    fn get_all_aggregates(
+        &self,
+        address: &Address,
+    ) -> impl Future<Output = Result<HashMap<String, serde_json::Value>, MessageError>> + Send;
+}
+
+pub trait AlephPostClient {
+    /// Queries posts matching the given filter using the v0 (legacy) format.
+    ///
+    /// The v0 format includes the full message envelope (chain, signature,
+    /// confirmations, etc.). Returns the full response including pagination metadata.
+    fn get_posts_v0(
+        &self,
+        filter: &PostFilter,
+        pagination: PaginationParams,
+    ) -> impl Future<Output = Result<GetPostsV0Response, MessageError>> + Send;
+
+    /// Queries posts matching the given filter using the v1 format.
+    ///
+    /// The v1 format is leaner: it omits message-level fields and uses ISO 8601
+    /// timestamps (`created`, `last_updated`) instead of unix floats.
+    fn get_posts_v1(
+        &self,
+        filter: &PostFilter,
+        pagination: PaginationParams,
+    ) -> impl Future<Output = Result<GetPostsV1Response, MessageError>> + Send;
+
+    /// Queries posts matching the given filter like
+    /// [`get_posts_v1`](Self::get_posts_v1), with their content deserialized
+    /// into `T`.
+    ///
+    /// A post whose content does not match `T` fails the whole query with
+    /// [`MessageError::Decode`]: restrict `filter.post_types` to the types
+    /// `T` models.
+    fn get_posts_as<T>(
+        &self,
+        filter: &PostFilter,
+        pagination: PaginationParams,
+    ) -> impl Future<Output = Result<Vec<TypedPost<T>>, MessageError>> + Send
+    where
+        Self: Sync,
+        T: DeserializeOwned + Send,
+    {
+        async move {
+            self.get_posts_v1(filter, pagination)
+                .await?
+                .posts
+                .into_iter()
+                .map(|post| TypedPost::try_from(post).map_err(MessageError::Decode))
+                .collect()
+        }
+    }
+
+    /// Returns a stream that automatically paginates through all posts matching the filter
+    /// using the v0 (legacy) format.
+    ///
+    /// Items are yielded one at a time. Pages are fetched lazily — the first HTTP request
+    /// happens on the first `.next()` call. Uses cursor-based pagination internally.
+    ///
+    /// The stream terminates when all results have been consumed, or on the first error
+    /// (after the retry middleware has exhausted its retries).
+    /// `pagination` controls items per page (default 200, max 200).
+    fn get_posts_v0_iterator(
+        &self,
+        filter: PostFilter,
+        pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<PostV0, MessageError>> + Send + '_;
+
+    /// Returns a stream that automatically paginates through all posts matching the filter
+    /// using the v1 format.
+    ///
+    /// Items are yielded one at a time. Pages are fetched lazily — the first HTTP request
+    /// happens on the first `.next()` call. Uses cursor-based pagination internally.
+    ///
+    /// The stream terminates when all results have been consumed, or on the first error
+    /// (after the retry middleware has exhausted its retries).
+    ///
+    /// `pagination` controls items per page (default 200, max 200).
+    fn get_posts_v1_iterator(
+        &self,
+        filter: PostFilter,
+        pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<PostV1, MessageError>> + Send + '_;
+
+    /// Returns the most recent node metrics published by the scoring service,
+    /// or `None` if no metrics post exists on this CCN.
+    ///
+    /// A metrics post whose content does not match [`NodeMetricsContent`]
+    /// surfaces as `MessageError::ApiError` with status 200.
+    fn get_latest_node_metrics(
+        &self,
+    ) -> impl Future<Output = Result<Option<NodeMetricsContent>, MessageError>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let filter = PostFilter {
+                addresses: Some(vec![SCORING_ADDRESS.clone()]),
+                post_types: Some(vec![METRICS_POST_TYPE.to_string()]),
+                channels: Some(vec![SCORING_CHANNEL.to_string()]),
+                sort_order: Some(SortOrder::Desc),
+                ..Default::default()
+            };
+            let pagination = PaginationParams {
+                pagination: Some(1),
+                page: Some(1),
+            };
+            let response = self.get_posts_v0(&filter, pagination).await?;
+            response
+                .posts
+                .first()
+                .map(|post| {
+                    post.content_as().map_err(|e| MessageError::ApiError {
+                        status: 200,
+                        body: format!("invalid {METRICS_POST_TYPE} post: {e}"),
+                    })
+                })
+                .transpose()
+        }
+    }
+}
+
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// `User-Agent` sent by default, identifying the SDK and its version.
+pub const DEFAULT_USER_AGENT: &str = concat!("aleph-sdk-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Builder for [`AlephClient`].
+///
+/// ```
+/// # use url::Url;
+/// # use aleph_sdk::client::{AlephClient, RetryConfig, TimeoutConfig};
+/// # use std::time::Duration;
+/// let client = AlephClient::builder(Url::parse("https://api3.aleph.im").unwrap())
+///     .max_concurrent_requests(32)
+///     .retry_config(RetryConfig::default().max_retries(5))
+///     .timeout_config(TimeoutConfig::default().connect_timeout(Duration::from_secs(5)))
+///     .build();
+/// ```
+pub struct AlephClientBuilder {
+    ccn_url: Url,
+    retry_config: RetryConfig,
+    timeout_config: TimeoutConfig,
+    max_concurrent_requests: usize,
+    ipfs_gateway: Url,
+    response_limits: ResponseLimits,
+    user_agent: http::HeaderValue,
+    default_headers: http::HeaderMap,
+    proxies: Vec<reqwest::Proxy>,
+    http_client: Option<reqwest::Client>,
+}
+
+impl AlephClientBuilder {
+    pub fn retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry_config = config;
+        self
+    }
+
+    pub fn timeout_config(mut self, config: TimeoutConfig) -> Self {
+        self.timeout_config = config;
+        self
+    }
+
+    /// Overrides the upload timeout policy (the `upload_timeout` field of
+    /// [`TimeoutConfig`]), leaving other timeout settings untouched.
+    pub fn upload_timeout(mut self, policy: UploadTimeout) -> Self {
+        self.timeout_config.upload_timeout = policy;
+        self
+    }
+
+    /// Sets the maximum number of concurrent HTTP requests. Default: 16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0 (would deadlock all requests).
+    pub fn max_concurrent_requests(mut self, n: usize) -> Self {
+        assert!(n > 0, "max_concurrent_requests must be > 0");
+        self.max_concurrent_requests = n;
+        self
+    }
+
+    /// Overrides the default IPFS gateway URL.
+    pub fn ipfs_gateway(mut self, gateway: Url) -> Self {
+        self.ipfs_gateway = gateway;
+        self
+    }
+
+    /// Caps the size of response bodies. Default: no limit.
+    ///
+    /// Downloads through `/api/v0/storage/raw` are capped too unless that
+    /// endpoint is given its own limit; since [`FileDownload`]s can stream
+    /// their body, lifting it there and bounding each download with
+    /// [`FileDownload::with_max_size`] is usually preferable.
+    pub fn response_limits(mut self, limits: ResponseLimits) -> Self {
+        self.response_limits = limits;
+        self
+    }
+
+    /// Overrides the `User-Agent` header. Default: [`DEFAULT_USER_AGENT`].
+    ///
+    /// Taking a [`HeaderValue`](http::HeaderValue) keeps invalid values out:
+    /// build it with `HeaderValue::from_static` or `HeaderValue::try_from`.
+    pub fn user_agent(mut self, user_agent: http::HeaderValue) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Headers added to every request, e.g. an API key expected by a
+    /// gateway in front of the CCN.
+    pub fn default_headers(mut self, headers: http::HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Routes requests through `proxy`. Can be called several times, e.g.
+    /// with one proxy per scheme. Without it, the `HTTP_PROXY`/`HTTPS_PROXY`
+    /// environment variables apply.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Sends requests through `client` instead of one built from this
+    /// builder, for settings it does not expose (TLS roots, DNS resolver,
+    /// ...).
+    ///
+    /// Timeouts, user agent, default headers and proxies are then those of
+    /// `client`, and apply to uploads too: avoid a total request timeout on
+    /// it if large files are uploaded. Retries, concurrency and response
+    /// limits still apply.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    pub fn build(self) -> AlephClient {
+        let retry_policy = ExponentialBackoff::builder()
+            .retry_bounds(self.retry_config.min_backoff, self.retry_config.max_backoff)
+            .build_with_max_retries(self.retry_config.max_retries);
+
+        let concurrency_limit = ConcurrencyLimit {
+            semaphore: Arc::new(Semaphore::new(self.max_concurrent_requests)),
+        };
+
+        let rate_limit = Arc::new(std::sync::Mutex::new(None));
+        let retry = RetryMiddleware {
+            policy: retry_policy,
+            max_wait: self.retry_config.max_retry_after,
+            last_rate_limit: rate_limit.clone(),
+        };
+
+        // General client: carries the per-request total and read timeouts.
+        let base_client = self.build_reqwest_client(
+            self.timeout_config.request_timeout,
+            self.timeout_config.read_timeout,
+        );
+
+        // Retry is the outer middleware: it retries network and server errors,
+        // waiting for as long as the CCN asked when it is rate-limited.
+        // ConcurrencyLimit is the inner middleware: each attempt (including retries)
+        // acquires a permit only for the duration of actual network I/O.
+        // Oversized responses are rejected below it, as fatal errors that are
+        // not retried. Request tracing wraps everything, so that retries are
+        // logged in the span of the request they belong to.
+        let http_client = ClientBuilder::new(base_client)
+            .with(RequestTracing)
+            .with(retry)
+            .with(concurrency_limit)
+            .with(ResponseLimitMiddleware {
+                limits: self.response_limits.clone(),
+            })
+            .build();
+
+        // Upload client: no retry middleware (multipart bodies are not cloneable)
+        // and no reqwest total timeout — a fixed deadline cuts large uploads on
+        // slow links. Upload deadlines are enforced per-request via
+        // `run_upload` using the `timeout` policy instead.
+        let upload_client = self.build_reqwest_client(None, None);
+
+        AlephClient {
+            inner: Arc::new(ClientInner {
+                http_client,
+                upload_client,
+                upload_timeout: self.timeout_config.upload_timeout,
+                ccn_url: self.ccn_url,
+                ipfs_gateway: self.ipfs_gateway,
+                rate_limit,
+            }),
+        }
+    }
+
+    /// Build a plain reqwest client with the configured connect timeout, user
+    /// agent, headers and proxies, and optional request and read timeouts.
+    fn build_reqwest_client(
+        &self,
+        request_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+    ) -> reqwest::Client {
+        if let Some(client) = &self.http_client {
+            return client.clone();
+        }
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.timeout_config.connect_timeout)
+            .user_agent(self.user_agent.clone())
+            .default_headers(self.default_headers.clone());
+        if let Some(timeout) = request_timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        for proxy in &self.proxies {
+            builder = builder.proxy(proxy.clone());
+        }
+        // Header values and proxies are validated when given to the builder:
+        // only the initialization of the TLS backend can fail here.
+        builder.build().expect("failed to build HTTP client")
+    }
+}
+
+impl AlephClient {
+    pub fn new(ccn_url: Url) -> Self {
+        Self::builder(ccn_url).build()
+    }
+
+    /// Creates a client for the CCN API of `network`, with default settings.
+    pub fn for_network(network: &Network) -> Self {
+        Self::new(network.ccn_url())
+    }
+
+    pub fn builder(ccn_url: Url) -> AlephClientBuilder {
+        AlephClientBuilder {
+            ccn_url,
+            retry_config: RetryConfig::default(),
+            timeout_config: TimeoutConfig::default(),
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            ipfs_gateway: Url::parse(crate::ipfs::DEFAULT_IPFS_GATEWAY)
+                .expect("DEFAULT_IPFS_GATEWAY is a valid URL"),
+            response_limits: ResponseLimits::default(),
+            user_agent: http::HeaderValue::from_static(DEFAULT_USER_AGENT),
+            default_headers: http::HeaderMap::new(),
+            proxies: Vec::new(),
+            http_client: None,
+        }
+    }
+
+    /// Request quota reported by the CCN in the last response that carried
+    /// rate-limit headers, if any. Shared by all clones of the client.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self
+            .inner
+            .rate_limit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Overrides the IPFS gateway URL on an existing client.
+    pub fn with_ipfs_gateway(mut self, gateway: Url) -> Self {
+        Arc::make_mut(&mut self.inner).ipfs_gateway = gateway;
+        self
+    }
+}
+
+impl AlephMessageClient for AlephClient {
+    /// Queries a message by item hash.
+    ///
+    /// Returns the message with its corresponding status.
+    #[tracing::instrument(level = "debug", skip_all, fields(%item_hash))]
+    async fn get_message(
+        &self,
+        item_hash: &ItemHash,
+    ) -> Result<MessageWithStatus<Message>, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/messages/{}", item_hash))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self.inner.http_client.get(url).send().await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(MessageError::NotFound(item_hash.clone()));
+        }
+        let response = response.check_status().await?;
+
+        let get_message_response: GetMessageResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(get_message_response.message)
+    }
+
+    /// Queries `/api/v0/messages/{hash}/status`, which only returns the status
+    /// and reception time of the message.
+    #[tracing::instrument(level = "debug", skip_all, fields(%item_hash))]
+    async fn get_message_status(
+        &self,
+        item_hash: &ItemHash,
+    ) -> Result<MessageStatus, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/messages/{}/status", item_hash))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self.inner.http_client.get(url).send().await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(MessageError::NotFound(item_hash.clone()));
+        }
+        let response = response.check_status().await?;
+
+        let status_response: GetMessageStatusResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(status_response.status)
+    }
+
+    async fn get_messages(
+        &self,
+        filter: &MessageFilter,
+        pagination: PaginationParams,
+    ) -> Result<Vec<Message>, MessageError> {
+        Ok(self.get_messages_raw(filter, &pagination).await?.messages)
+    }
+
+    fn get_messages_iterator(
+        &self,
+        filter: MessageFilter,
+        pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + '_ {
+        let pagination = pagination
+            .unwrap_or(CURSOR_DEFAULT_PAGINATION)
+            .min(CURSOR_MAX_PAGINATION);
+        async_stream::try_stream! {
+            let mut cursor: Option<String> = None;
+            loop {
+                let response = self
+                    .get_messages_cursor(&filter, cursor.as_deref(), pagination)
+                    .await?;
+                for message in response.messages {
+                    yield message;
+                }
+                cursor = match response.next_cursor {
+                    Some(c) => Some(c),
+                    None => break,
+                };
+            }
+        }
+    }
+
+    async fn subscribe_to_messages(
+        &self,
+        filter: &MessageFilter,
+        history: Option<u32>,
+    ) -> Result<impl Stream<Item = Result<Message, MessageError>> + Send + Unpin, MessageError>
+    {
+        crate::ws::subscribe(self.inner.ccn_url.clone(), filter, history).await
+    }
+
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(item_hash = %message.item_hash, message_type = %message.message_type, sync = sync)
+    )]
+    async fn post_message(
+        &self,
+        message: &PendingMessage,
+        sync: bool,
+    ) -> Result<PostMessageResponse, MessageError> {
+        let body = PostMessageBody { sync, message };
+
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/messages")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self.inner.http_client.post(url).json(&body).send().await?;
+
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            let body_text = response.text().await.unwrap_or_default();
+            return Err(MessageError::ApiError {
+                status: status.as_u16(),
+                body: body_text,
+            });
+        }
+
+        let response: PostMessageResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(response)
+    }
+
+    async fn get_messages_and_verify(
+        &self,
+        filter: &MessageFilter,
+    ) -> Result<Vec<MessageVerification>, MessageError> {
+        let headers = self.get_message_headers(filter).await?;
+
+        let verify_futures = headers
+            .into_iter()
+            .map(|header| verify_message_header(self, header));
+
+        // All verifications run concurrently. Inline messages complete instantly (no I/O);
+        // non-inline downloads are gated by the ConcurrencyLimit middleware.
+        futures_util::stream::iter(verify_futures)
+            .buffer_unordered(usize::MAX)
+            .try_collect()
+            .await
+    }
+}
+
+impl AlephAuthorizationClient for AlephClient {
+    async fn get_received_authorizations(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<ReceivedAuthorization>, MessageError> {
+        /// The CCN `authorizations` field is either an object keyed by granter
+        /// address (Python CCN) or an array of `ReceivedAuthorization` (heph).
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum AuthorizationsBody {
+            Grouped(HashMap<String, Vec<crate::authorization::AuthorizationRule>>),
+            List(Vec<ReceivedAuthorization>),
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            authorizations: AuthorizationsBody,
+        }
+
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/authorizations/received/{}.json", address))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&[("pagination", "200")])
+            .send()
+            .await?;
+
+        let parsed: Response = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        Ok(match parsed.authorizations {
+            AuthorizationsBody::Grouped(map) => map
+                .into_iter()
+                .map(|(granter, authorizations)| ReceivedAuthorization {
+                    granter: Address::from(granter),
+                    authorizations,
+                })
+                .collect(),
+            AuthorizationsBody::List(list) => list,
+        })
+    }
+}
+
+/// Submits `message`, failing with [`MessageError::Rejected`] if the node
+/// rejects it.
+async fn submit_accepted<C>(
+    client: &C,
+    message: &PendingMessage,
+    sync: bool,
+) -> Result<ItemHash, MessageError>
+where
+    C: AlephMessageClient + AlephStorageClient + Sync + ?Sized,
+{
+    let response = client.submit_message(message, sync).await?;
+    if response.status()? == MessageStatus::Rejected {
+        return Err(MessageError::Rejected {
+            item_hash: message.item_hash.clone(),
+        });
+    }
+    Ok(message.item_hash.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockClient, TestAccount};
+    use aleph_types::address;
+    use aleph_types::chain::{Chain, Signature};
+    use aleph_types::item_hash::{AlephItemHash, ItemHash};
+    use aleph_types::message::MessageType;
+    use aleph_types::message::execution::environment::{Hypervisor, MachineResources};
+    use aleph_types::message::item_type::ItemType;
+    use aleph_types::message::pending::PendingMessage;
+    use aleph_types::timestamp::Timestamp;
+    use serde_json::json;
+    use std::sync::atomic::Ordering;
+    use wiremock::matchers::{body_partial_json, header, method, path, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    #[ignore = "uses a remote CCN websocket — no heph equivalent yet"]
+    async fn test_subscribe_to_messages() {
+        use futures_util::StreamExt;
+
+        let client = AlephClient::new(Url::parse("https://api2.aleph.im").expect("valid url"));
+        let filter = MessageFilter {
+            message_type: Some(MessageType::Post),
+            ..Default::default()
+        };
+
+        let mut stream = client
+            .subscribe_to_messages(&filter, None)
+            .await
+            .expect("should connect");
+
+        // Read up to 5 historical messages
+        let mut count = 0;
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(msg) => {
+                    assert_eq!(msg.message_type, MessageType::Post);
+                    count += 1;
+                    if count >= 5 {
+                        break;
+                    }
+                }
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        }
+
+        assert!(count > 0, "should have received at least one message");
+    }
+
+    #[test]
+    #[should_panic(expected = "max_concurrent_requests must be > 0")]
+    fn test_builder_rejects_zero_concurrency() {
+        AlephClient::builder(Url::parse("https://api3.aleph.im").unwrap())
+            .max_concurrent_requests(0)
+            .build();
+    }
+
+    #[tokio::test]
+    async fn test_semaphore_limits_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let max_concurrency: usize = 3;
+        let total_tasks: usize = 20;
+
+        let semaphore = Arc::new(Semaphore::new(max_concurrency));
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_observed = Arc::new(AtomicUsize::new(0));
+
+        let futures = (0..total_tasks).map(|_| {
+            let semaphore = semaphore.clone();
+            let active = active.clone();
+            let max_observed = max_observed.clone();
+            async move {
+                let _permit = semaphore.acquire().await.unwrap();
+                let current = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_observed.fetch_max(current, Ordering::SeqCst);
+                // Simulate work
+                tokio::task::yield_now().await;
+                active.fetch_sub(1, Ordering::SeqCst);
+            }
+        });
+
+        futures_util::stream::iter(futures)
+            .buffer_unordered(usize::MAX)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert!(
+            max_observed.load(Ordering::SeqCst) <= max_concurrency,
+            "observed {} concurrent tasks, expected at most {}",
+            max_observed.load(Ordering::SeqCst),
+            max_concurrency,
+        );
+    }
+
+    const TEST_CONTENT: &str = r#"{"type":"test","address":"0xABCD","time":1234.0}"#;
+
+    fn make_pending(item_type: ItemType, item_hash: ItemHash) -> PendingMessage {
+        PendingMessage {
+            chain: Chain::Ethereum,
+            sender: address!("0xABCD"),
+            signature: Signature::from("0xSIG".to_string()),
+            message_type: MessageType::Post,
+            item_type,
+            item_content: TEST_CONTENT.to_string(),
+            item_hash,
+            time: Timestamp::from(1234.0),
+            channel: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_inline_skips_upload() {
+        let inline_hash = ItemHash::from(AlephItemHash::from_bytes(TEST_CONTENT.as_bytes()));
+        let client = MockClient::new(inline_hash.clone());
+        let msg = make_pending(ItemType::Inline, inline_hash);
+
+        let resp = client.submit_message(&msg, false).await.unwrap();
+        assert_eq!(resp.message_status, "processed");
+        assert!(client.post_called.load(Ordering::SeqCst));
+        assert!(!client.upload_storage_called.load(Ordering::SeqCst));
+        assert!(!client.upload_ipfs_called.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn submit_storage_uploads_then_posts() {
+        let storage_hash = ItemHash::from(AlephItemHash::from_bytes(TEST_CONTENT.as_bytes()));
+        let client = MockClient::new(storage_hash.clone());
+        let msg = make_pending(ItemType::Storage, storage_hash);
+
+        let resp = client.submit_message(&msg, false).await.unwrap();
+        assert_eq!(resp.message_status, "processed");
+        assert!(client.upload_storage_called.load(Ordering::SeqCst));
+        assert!(client.post_called.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn submit_ipfs_uploads_then_posts() {
+        let cid = crate::verify::compute_cid(TEST_CONTENT.as_bytes());
+        let ipfs_hash = ItemHash::Ipfs(cid);
+        let client = MockClient::new(ipfs_hash.clone());
+        let msg = make_pending(ItemType::Ipfs, ipfs_hash);
+
+        let resp = client.submit_message(&msg, false).await.unwrap();
+        assert_eq!(resp.message_status, "processed");
+        assert!(client.upload_ipfs_called.load(Ordering::SeqCst));
+        assert!(client.post_called.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn submit_storage_hash_mismatch_returns_error() {
+        let expected_hash = ItemHash::from(AlephItemHash::from_bytes(TEST_CONTENT.as_bytes()));
+        // Mock returns a different hash
+        let wrong_hash = ItemHash::from(AlephItemHash::from_bytes(b"wrong content"));
+        let client = MockClient::new(wrong_hash.clone());
+        let msg = make_pending(ItemType::Storage, expected_hash.clone());
+
+        let err = client.submit_message(&msg, false).await.unwrap_err();
+        match err {
+            MessageError::HashMismatch { expected, actual } => {
+                assert_eq!(expected, expected_hash);
+                assert_eq!(actual, wrong_hash);
+            }
+            other => panic!("expected HashMismatch, got: {other:?}"),
+        }
+        assert!(!client.post_called.load(Ordering::SeqCst));
+    }
+
+    const POST: &str = include_str!("../../../../fixtures/messages/post/post.json");
+
+    async fn mount(server: &MockServer, message_status: &str) {
+        let message: serde_json::Value = serde_json::from_str(POST).unwrap();
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .and(body_partial_json(json!({
+                "sync": true,
+                "message": {"item_hash": message["item_hash"]},
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "publication_status": {"status": "success", "failed": []},
+                "message_status": message_status,
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn broadcast_returns_the_message_status() {
+        let server = MockServer::start().await;
+        mount(&server, "processed").await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let message: Message = serde_json::from_str(POST).unwrap();
+        let status = client.broadcast_message(&message, true).await.unwrap();
+        assert_eq!(status, MessageStatus::Processed);
+    }
+
+    #[tokio::test]
+    async fn broadcast_surfaces_rejections() {
+        let server = MockServer::start().await;
+        mount(&server, "rejected").await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let message: Message = serde_json::from_str(POST).unwrap();
+        let err = client.broadcast_message(&message, true).await.unwrap_err();
+        assert!(
+            matches!(err, MessageError::Rejected { item_hash } if item_hash == message.item_hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn create_post_signs_and_submits_inline_posts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .and(body_partial_json(json!({
+                "sync": false,
+                "message": {
+                    "type": "POST",
+                    "item_type": "inline",
+                    "channel": "TEST",
+                    "sender": "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef",
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "publication_status": {"status": "success", "failed": []},
+                "message_status": "pending",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let account = TestAccount(Address::from(
+            "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string(),
+        ));
+        let item_hash = client
+            .create_post(
+                &account,
+                "chat",
+                json!({"body": "hello"}),
+                Some(Channel::from("TEST".to_string())),
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(item_hash, ItemHash::Native(_)));
+    }
+
+    async fn setup() -> (MockServer, Message) {
+        let message: Message = serde_json::from_str(POST).unwrap();
+        let body: serde_json::Value = serde_json::from_str(POST).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{}", message.item_hash)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"status": "processed", "message": body})),
+            )
+            .mount(&server)
+            .await;
+        (server, message)
+    }
+
+    #[tokio::test]
+    async fn forget_submits_after_checking_ownership() {
+        let (server, message) = setup().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "publication_status": {"status": "success", "failed": []},
+                "message_status": "processed",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        // Owner addresses are matched regardless of their checksum casing.
+        let account = TestAccount(Address::from(message.owner().as_str().to_lowercase()));
+        client
+            .forget(
+                &account,
+                vec![message.item_hash.clone()],
+                vec![],
+                Some("cleanup".to_string()),
+                None,
+                true,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn forget_refuses_messages_of_other_owners() {
+        let (server, message) = setup().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let account = TestAccount(Address::from(
+            "0x0000000000000000000000000000000000000001".to_string(),
+        ));
+        let err = client
+            .forget(
+                &account,
+                vec![message.item_hash.clone()],
+                vec![],
+                None,
+                None,
+                true,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, MessageError::NotOwned { item_hash, .. } if item_hash == message.item_hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn create_instance_broadcasts_the_configured_instance() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "publication_status": {"status": "success", "failed": []},
+                "message_status": "processed",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let account = TestAccount(Address::from(
+            "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string(),
+        ));
+        let rootfs = ItemHash::from([3u8; 32]);
+        let item_hash = client
+            .create_instance(
+                &account,
+                rootfs.clone(),
+                PersistentVolumeSize::try_from(20_480).unwrap(),
+                |instance| {
+                    instance
+                        .resources(MachineResources {
+                            memory: memsizes::MiB::from(2048),
+                            ..MachineResources::default()
+                        })
+                        .hypervisor(Hypervisor::Qemu)
+                        .ssh_keys(vec!["ssh-ed25519 AAAA user@host".to_string()])
+                },
+                true,
+            )
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["message"]["item_hash"], item_hash.to_string());
+        let content: serde_json::Value =
+            serde_json::from_str(body["message"]["item_content"].as_str().unwrap()).unwrap();
+        assert_eq!(content["rootfs"]["parent"]["ref"], rootfs.to_string());
+        assert_eq!(content["rootfs"]["size_mib"], 20_480);
+        assert_eq!(content["resources"]["vcpus"], 1);
+        assert_eq!(content["resources"]["memory"], 2048);
+        assert_eq!(content["environment"]["hypervisor"], "qemu");
+        assert_eq!(
+            content["authorized_keys"],
+            json!(["ssh-ed25519 AAAA user@host"])
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_carry_user_agent_and_default_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/api/v0/addresses/"))
+            .and(header("user-agent", "my-indexer/1.0"))
+            .and(header("x-api-key", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "balance": 1.0,
+                "locked_amount": 0.0,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-api-key", http::HeaderValue::from_static("secret"));
+        let client = AlephClient::builder(Url::parse(&server.uri()).unwrap())
+            .user_agent(http::HeaderValue::from_static("my-indexer/1.0"))
+            .default_headers(headers)
+            .build();
+        client
+            .get_balance(&Address::from(
+                "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string(),
+            ))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,679 @@
+//! Information about CCNs and CRNs, pubsub and price estimates.
+
+use super::error::CheckStatus;
+use super::{
+    AlephAggregateClient, AlephClient, GetMessagesResponse, MessageError, MessageFilter,
+    PaginationParams, RawPendingMessage,
+};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::MessageStatus;
+use aleph_types::message::execution::environment::{HostRequirements, MachineResources};
+use aleph_types::message::execution::volume::PersistentVolumeSize;
+use aleph_types::message::pending::PendingMessage;
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+/// How long [`AlephClient::find_crns`] waits for a CRN to report its
+/// resources before leaving it out.
+const CRN_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A CRN able to host a VM, see [`AlephClient::find_crns`].
+#[derive(Debug, Clone)]
+pub struct CrnCandidate {
+    /// The node as registered in the corechannel aggregate. Its `hash` is the
+    /// `node_hash` to require when creating the VM.
+    pub info: crate::aggregate_models::corechannel::CrnInfo,
+    pub url: Url,
+    /// Resources the node reported when it was probed.
+    pub usage: crate::crn::SystemUsage,
+}
+
+/// Pubsub network used to relay a topic message, see
+/// [`AlephClient::pubsub_publish`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PubsubTransport {
+    /// The CCN's libp2p node (`/api/v0/p2p/pubsub/pub`).
+    #[default]
+    P2p,
+    /// The CCN's IPFS daemon (`/api/v0/ipfs/pubsub/pub`).
+    Ipfs,
+}
+
+#[derive(Debug, Serialize)]
+struct PubsubPublishBody<'a> {
+    topic: &'a str,
+    data: &'a str,
+}
+
+/// Response of the CCN's pubsub publish endpoints.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PubsubPublishResponse {
+    pub status: String,
+    /// Peers or transports the message could not be relayed to.
+    #[serde(default)]
+    pub failed: Vec<String>,
+}
+
+/// Public information about a CCN, as gathered by
+/// [`AlephClient::get_node_info`].
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeInfo {
+    /// libp2p multiaddresses the node can be reached at.
+    pub multiaddresses: Vec<String>,
+    /// pyaleph version, if the node reports it.
+    pub version: Option<String>,
+    /// Synchronization status, if the node exposes its metrics.
+    pub sync: Option<NodeSyncStatus>,
+}
+
+/// Synchronization fields of a CCN's `/metrics.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeSyncStatus {
+    /// Messages waiting to be processed.
+    #[serde(default, rename = "pyaleph_status_sync_pending_messages_total")]
+    pub pending_messages: Option<u64>,
+    /// Chain transactions waiting to be processed.
+    #[serde(default, rename = "pyaleph_status_sync_pending_txs_total")]
+    pub pending_txs: Option<u64>,
+    /// Last Ethereum block the node has synced.
+    #[serde(default, rename = "pyaleph_status_chain_eth_last_committed_height")]
+    pub eth_height: Option<u64>,
+    /// Blocks the node lags behind the Ethereum chain head.
+    #[serde(default, rename = "pyaleph_status_chain_eth_height_remaining_total")]
+    pub eth_height_remaining: Option<i64>,
+    /// Messages the node has stored so far.
+    #[serde(default, rename = "pyaleph_status_sync_messages_total")]
+    pub messages_total: Option<u64>,
+}
+
+impl NodeSyncStatus {
+    /// Whether more than `max_pending` messages wait in the node's queue.
+    /// Nodes that do not report their queue are assumed not to be.
+    pub fn is_backed_up(&self, max_pending: u64) -> bool {
+        self.pending_messages
+            .is_some_and(|pending| pending > max_pending)
+    }
+
+    /// Whether the node lags behind the Ethereum chain head by more than
+    /// `max_blocks`.
+    pub fn is_lagging(&self, max_blocks: i64) -> bool {
+        self.eth_height_remaining
+            .is_some_and(|remaining| remaining > max_blocks)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicNodeInfoResponse {
+    node_multi_addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeVersionResponse {
+    version: String,
+}
+
+/// Cost of a message, as returned by `/api/v0/price/estimate` before it is
+/// published and by `/api/v0/price/{item_hash}` after.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceEstimate {
+    /// ALEPH tokens to hold (hold payment) or to stream per hour
+    /// (pay-as-you-go payments).
+    pub required_tokens: f64,
+    pub payment_type: String,
+    /// Cost as a decimal string, to avoid float rounding.
+    pub cost: Option<String>,
+}
+
+impl AlephClient {
+    /// Publishes a raw message on a pubsub topic through the CCN.
+    ///
+    /// Unlike [`post_message`](super::AlephMessageClient::post_message), `data` is
+    /// relayed as is: it is neither validated nor stored by the CCN, so
+    /// applications can use their own topics next to the Aleph message flow.
+    pub async fn pubsub_publish(
+        &self,
+        transport: PubsubTransport,
+        topic: &str,
+        data: &str,
+    ) -> Result<PubsubPublishResponse, MessageError> {
+        let path = match transport {
+            PubsubTransport::P2p => "/api/v0/p2p/pubsub/pub",
+            PubsubTransport::Ipfs => "/api/v0/ipfs/pubsub/pub",
+        };
+        let url = self
+            .inner
+            .ccn_url
+            .join(path)
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .post(url)
+            .json(&PubsubPublishBody { topic, data })
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            let body_text = response.text().await.unwrap_or_default();
+            return Err(MessageError::ApiError {
+                status: status.as_u16(),
+                body: body_text,
+            });
+        }
+
+        Ok(response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?)
+    }
+
+    /// Fetches the public information of the CCN: its multiaddresses from
+    /// `/api/v0/info/public.json`, its version and its sync status.
+    ///
+    /// Only the multiaddresses are required; nodes that do not expose their
+    /// version or metrics get `None` for those. Cheap enough to serve as a
+    /// health check.
+    pub async fn get_node_info(&self) -> Result<NodeInfo, MessageError> {
+        let (public, version, sync) = futures_util::join!(
+            self.get_node_json::<PublicNodeInfoResponse>("/api/v0/info/public.json"),
+            self.get_version(),
+            self.get_sync_status(),
+        );
+        Ok(NodeInfo {
+            multiaddresses: public?.node_multi_addresses,
+            version: version.ok(),
+            sync: sync.ok(),
+        })
+    }
+
+    /// Fetches the pyaleph version of the CCN from `/api/v0/version`.
+    pub async fn get_version(&self) -> Result<String, MessageError> {
+        Ok(self
+            .get_node_json::<NodeVersionResponse>("/api/v0/version")
+            .await?
+            .version)
+    }
+
+    /// Fetches the message queue depth and chain sync lag of the CCN from its
+    /// `/metrics.json`.
+    ///
+    /// Publishers sending many messages can poll it and slow down while
+    /// [`NodeSyncStatus::is_backed_up`].
+    pub async fn get_sync_status(&self) -> Result<NodeSyncStatus, MessageError> {
+        self.get_node_json("/metrics.json").await
+    }
+
+    /// Lists the CRNs able to host a VM with `requirements`, `resources` and
+    /// a root file system of `rootfs_size`, best score first.
+    ///
+    /// Linked CRNs of the corechannel aggregate are filtered on the node
+    /// requirements (owner, node hash, terms and conditions; the address
+    /// regex is not evaluated), then probed through their
+    /// `/about/usage/system` for CPU properties, free GPUs, cores, memory and
+    /// disk, see [`SystemUsage::satisfies`](crate::crn::SystemUsage::satisfies)
+    /// and [`SystemUsage::has_capacity`](crate::crn::SystemUsage::has_capacity).
+    /// Nodes that cannot be reached within a few seconds are left out.
+    pub async fn find_crns(
+        &self,
+        requirements: &HostRequirements,
+        resources: &MachineResources,
+        rootfs_size: PersistentVolumeSize,
+    ) -> Result<Vec<CrnCandidate>, MessageError> {
+        use crate::aggregate_models::corechannel::{CORECHANNEL_ADDRESS, CrnStatus};
+
+        let aggregate = self.get_corechannel_aggregate(&CORECHANNEL_ADDRESS).await?;
+        let node = requirements.node.as_ref();
+        let nodes = aggregate
+            .corechannel
+            .resource_nodes
+            .into_iter()
+            .filter(|crn| matches!(crn.status, CrnStatus::Linked { .. }))
+            .filter(|crn| crn.inactive_since.is_none())
+            .filter(|crn| {
+                node.is_none_or(|node| {
+                    node.owner
+                        .as_ref()
+                        .is_none_or(|owner| crn.owner.same_as(owner))
+                        && node
+                            .node_hash
+                            .as_ref()
+                            .is_none_or(|hash| *hash == crn.hash.to_string())
+                        && node.terms_and_conditions.as_ref().is_none_or(|terms| {
+                            crn.terms_and_conditions.as_deref() == Some(&terms.to_string())
+                        })
+                })
+            })
+            .filter_map(|crn| Url::parse(&crn.address).ok().map(|url| (crn, url)));
+
+        let http = &self.inner.upload_client;
+        let mut candidates: Vec<CrnCandidate> = futures_util::stream::iter(nodes)
+            .map(|(info, url)| async move {
+                let usage = tokio::time::timeout(
+                    CRN_PROBE_TIMEOUT,
+                    crate::crn::fetch_system_usage(http, &url),
+                )
+                .await
+                .ok()?
+                .ok()?;
+                Some(CrnCandidate { info, url, usage })
+            })
+            .buffer_unordered(32)
+            .filter_map(|candidate| async move {
+                candidate.filter(|candidate| {
+                    candidate.usage.satisfies(requirements)
+                        && candidate.usage.has_capacity(resources, rootfs_size)
+                })
+            })
+            .collect()
+            .await;
+        candidates.sort_by(|a, b| b.info.score.total_cmp(&a.info.score));
+        Ok(candidates)
+    }
+
+    /// Lists the messages matching `filter` that the CCN has received but not
+    /// processed yet.
+    ///
+    /// Any status set on `filter` is replaced by
+    /// [`MessageStatus::Pending`]. The total across pages is in
+    /// [`GetMessagesResponse::pagination_total`]; for the size of the whole
+    /// queue, [`get_sync_status`](Self::get_sync_status) is cheaper.
+    pub async fn get_pending_messages(
+        &self,
+        filter: &MessageFilter,
+        pagination: PaginationParams,
+    ) -> Result<GetMessagesResponse<RawPendingMessage>, MessageError> {
+        let filter = MessageFilter {
+            message_statuses: Some(vec![MessageStatus::Pending]),
+            ..filter.clone()
+        };
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/messages.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&pagination)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        crate::json::from_bytes(body).map_err(MessageError::Decode)
+    }
+
+    async fn get_node_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(path)
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+        Ok(response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?)
+    }
+
+    /// Estimate the cost of a message before submitting it.
+    ///
+    /// Calls `POST /api/v0/price/estimate` on the CCN.
+    pub async fn estimate_price(
+        &self,
+        message: &PendingMessage,
+    ) -> Result<PriceEstimate, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/price/estimate")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let body = serde_json::json!({ "message": message });
+
+        let response = self.inner.http_client.post(url).json(&body).send().await?;
+
+        let status = response.status();
+        if status.is_client_error() || status.is_server_error() {
+            let body_text = response.text().await.unwrap_or_default();
+            return Err(MessageError::ApiError {
+                status: status.as_u16(),
+                body: body_text,
+            });
+        }
+
+        let estimate: PriceEstimate = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(estimate)
+    }
+
+    /// Returns the cost of a published message.
+    ///
+    /// Calls `GET /api/v0/price/{item_hash}` on the CCN.
+    /// [`get_vm_price`](super::AlephAccountClient::get_vm_price) returns the
+    /// `required_tokens` of this estimate only.
+    pub async fn get_price(&self, item_hash: &ItemHash) -> Result<PriceEstimate, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/price/{item_hash}"))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+        Ok(response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::message::ContentSource;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const MULTIADDR: &str =
+        "/ip4/1.2.3.4/tcp/4025/p2p/QmZkurbY2G2hWay59yiTgQNaQxHSNzKZFt2jbnwJhQcKgV";
+
+    async fn mount(server: &MockServer, route: &str, body: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn node_info_combines_public_info_version_and_metrics() {
+        let server = MockServer::start().await;
+        mount(
+            &server,
+            "/api/v0/info/public.json",
+            json!({"node_multi_addresses": [MULTIADDR]}),
+        )
+        .await;
+        mount(&server, "/api/v0/version", json!({"version": "v0.5.8"})).await;
+        mount(
+            &server,
+            "/metrics.json",
+            json!({
+                "pyaleph_status_sync_pending_messages_total": 12,
+                "pyaleph_status_chain_eth_last_committed_height": 21000000,
+                "pyaleph_status_chain_eth_height_remaining_total": 3,
+            }),
+        )
+        .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let info = client.get_node_info().await.unwrap();
+        assert_eq!(info.multiaddresses, [MULTIADDR]);
+        assert_eq!(info.version.as_deref(), Some("v0.5.8"));
+        let sync = info.sync.unwrap();
+        assert_eq!(sync.pending_messages, Some(12));
+        assert_eq!(sync.eth_height, Some(21000000));
+        assert_eq!(sync.eth_height_remaining, Some(3));
+        assert_eq!(sync.pending_txs, None);
+        assert_eq!(client.get_version().await.unwrap(), "v0.5.8");
+    }
+
+    #[tokio::test]
+    async fn sync_status_reports_queue_depth() {
+        let server = MockServer::start().await;
+        mount(
+            &server,
+            "/metrics.json",
+            json!({
+                "pyaleph_status_sync_pending_messages_total": 2500,
+                "pyaleph_status_sync_messages_total": 1000000,
+                "pyaleph_status_chain_eth_height_remaining_total": 0,
+            }),
+        )
+        .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let sync = client.get_sync_status().await.unwrap();
+        assert_eq!(sync.messages_total, Some(1000000));
+        assert!(sync.is_backed_up(1000));
+        assert!(!sync.is_backed_up(5000));
+        assert!(!sync.is_lagging(10));
+        assert!(!NodeSyncStatus::default().is_backed_up(0));
+    }
+
+    #[tokio::test]
+    async fn find_crns_probes_linked_nodes_and_filters_on_cpu_features() {
+        use crate::aggregate_models::corechannel::{
+            CORECHANNEL_ADDRESS, CoreChannelAggregate, CoreChannelContent, CrnInfo, NodeHash,
+        };
+        use aleph_types::address;
+        use aleph_types::item_hash::AlephItemHash;
+
+        async fn crn_server(features: &[&str]) -> MockServer {
+            let server = MockServer::start().await;
+            mount(
+                &server,
+                "/about/usage/system",
+                json!({
+                    "cpu": {"count": 16},
+                    "mem": {"total_kB": 64000000, "available_kB": 32000000},
+                    "disk": {"total_kB": 900000000, "available_kB": 500000000},
+                    "properties": {"cpu": {"architecture": "x86_64", "features": features}},
+                }),
+            )
+            .await;
+            server
+        }
+
+        let sev = crn_server(&["sev", "sev_es"]).await;
+        let plain = crn_server(&[]).await;
+        let parent = NodeHash::from(AlephItemHash::new([9u8; 32]));
+        let crn = |byte: u8, url: &str, score: f64| {
+            let mut crn = CrnInfo::new(
+                NodeHash::from(AlephItemHash::new([byte; 32])),
+                format!("crn-{byte}"),
+                address!("0x0000000000000000000000000000000000000001"),
+                url,
+            )
+            .linked_to(parent);
+            crn.score = score;
+            crn
+        };
+        let aggregate = CoreChannelAggregate::from(CoreChannelContent::new(
+            vec![],
+            vec![
+                crn(1, &sev.uri(), 0.8),
+                crn(2, &plain.uri(), 0.9),
+                crn(3, "http://127.0.0.1:1", 0.95),
+                CrnInfo::new(
+                    NodeHash::from(AlephItemHash::new([4u8; 32])),
+                    "waiting",
+                    address!("0x0000000000000000000000000000000000000001"),
+                    sev.uri(),
+                ),
+            ],
+        ));
+
+        let ccn = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/api/v0/aggregates/{}.json",
+                *CORECHANNEL_ADDRESS
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"data": aggregate})))
+            .mount(&ccn)
+            .await;
+        let client = AlephClient::new(Url::parse(&ccn.uri()).unwrap());
+
+        let requirements: HostRequirements = serde_json::from_value(json!({
+            "cpu": {"architecture": "x86_64", "features": ["sev"]},
+        }))
+        .unwrap();
+        let resources = MachineResources::default();
+        let rootfs = PersistentVolumeSize::try_from(20_480).unwrap();
+        let crns = client
+            .find_crns(&requirements, &resources, rootfs)
+            .await
+            .unwrap();
+        assert_eq!(crns.len(), 1);
+        assert_eq!(crns[0].info.name, "crn-1");
+        assert_eq!(crns[0].usage.cpu.count, 16);
+
+        let any: HostRequirements = serde_json::from_value(json!({})).unwrap();
+        let names: Vec<_> = client
+            .find_crns(&any, &resources, rootfs)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|crn| crn.info.name)
+            .collect();
+        assert_eq!(names, ["crn-2", "crn-1"]);
+
+        // Both nodes have about 31 GiB of free memory.
+        let large = MachineResources {
+            memory: memsizes::MiB::from(65_536),
+            ..MachineResources::default()
+        };
+        assert!(
+            client
+                .find_crns(&any, &large, rootfs)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_messages_are_listed_with_their_count() {
+        use aleph_types::channel;
+        use wiremock::matchers::query_param;
+
+        let server = MockServer::start().await;
+        let pending: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../fixtures/api-responses/pending-message.json"
+        ))
+        .unwrap();
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("msgStatuses", "pending"))
+            .and(query_param("channels", "aleph-scoring"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": pending["messages"],
+                "pagination_per_page": 20,
+                "pagination_page": 1,
+                "pagination_total": 41,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let filter = MessageFilter::default()
+            .channels([channel!("aleph-scoring")])
+            .message_statuses([MessageStatus::Processed]);
+        let page = client
+            .get_pending_messages(&filter, PaginationParams::default())
+            .await
+            .unwrap();
+        assert_eq!(page.pagination_total, 41);
+        assert_eq!(page.messages.len(), 1);
+        assert_eq!(page.messages[0].content_source, ContentSource::Storage);
+    }
+
+    #[tokio::test]
+    async fn node_info_tolerates_missing_version_and_metrics() {
+        let server = MockServer::start().await;
+        mount(
+            &server,
+            "/api/v0/info/public.json",
+            json!({"node_multi_addresses": []}),
+        )
+        .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let info = client.get_node_info().await.unwrap();
+        assert!(info.multiaddresses.is_empty());
+        assert!(info.version.is_none());
+        assert!(info.sync.is_none());
+    }
+
+    #[tokio::test]
+    async fn pubsub_publish_posts_topic_and_data() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/p2p/pubsub/pub"))
+            .and(body_json(json!({"topic": "ALEPH-TEST", "data": "hello"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"status": "success", "failed": []})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/ipfs/pubsub/pub"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("forbidden"))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let response = client
+            .pubsub_publish(PubsubTransport::P2p, "ALEPH-TEST", "hello")
+            .await
+            .unwrap();
+        assert_eq!(response.status, "success");
+        assert!(response.failed.is_empty());
+
+        let err = client
+            .pubsub_publish(PubsubTransport::Ipfs, "ALEPH-TEST", "hello")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MessageError::ApiError { status: 403, .. }));
+    }
+
+    #[tokio::test]
+    async fn get_price_returns_the_payment_type() {
+        let item_hash = ItemHash::from([5u8; 32]);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/price/{item_hash}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "required_tokens": 0.0,
+                "payment_type": "credit",
+                "cost": "1425.00",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let price = client.get_price(&item_hash).await.unwrap();
+        assert_eq!(price.payment_type, "credit");
+        assert_eq!(price.cost.as_deref(), Some("1425.00"));
+    }
+}
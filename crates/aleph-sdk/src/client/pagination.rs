@@ -0,0 +1,693 @@
+//! Message filters, and page and cursor pagination of message lists.
+
+use super::error::CheckStatus;
+use super::{AlephClient, AlephMessageClient, MessageError};
+use aleph_types::chain::{Address, Chain};
+use aleph_types::channel::Channel;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{Message, MessageStatus, MessageType};
+use aleph_types::timestamp::Timestamp;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_with::{StringWithSeparator, formats::CommaSeparator, serde_as, skip_serializing_none};
+
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortBy {
+    Time,
+    TxTime,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn as_i8(self) -> i8 {
+        match self {
+            SortOrder::Asc => 1,
+            SortOrder::Desc => -1,
+        }
+    }
+}
+
+impl Serialize for SortOrder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i8(self.as_i8())
+    }
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        })
+    }
+}
+
+/// Pagination parameters for page-mode list endpoints.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PaginationParams {
+    /// Maximum number of items per page.
+    pub pagination: Option<u32>,
+    /// Page number (starts at 1).
+    pub page: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MessageFilter {
+    #[serde(rename = "msgType")]
+    pub message_type: Option<MessageType>,
+
+    #[serde(rename = "msgTypes")]
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, MessageType>>")]
+    pub message_types: Option<Vec<MessageType>>,
+
+    #[serde(rename = "contentTypes")]
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub content_types: Option<Vec<String>>,
+
+    #[serde(rename = "contentKeys")]
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub content_keys: Option<Vec<String>>,
+
+    #[serde(rename = "contentHashes")]
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, ItemHash>>")]
+    pub content_hashes: Option<Vec<ItemHash>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub refs: Option<Vec<String>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, Address>>")]
+    pub addresses: Option<Vec<Address>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, Address>>")]
+    pub owners: Option<Vec<Address>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub tags: Option<Vec<String>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, ItemHash>>")]
+    pub hashes: Option<Vec<ItemHash>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub channels: Option<Vec<String>>,
+
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub chains: Option<Vec<String>>,
+
+    #[serde(rename = "startDate")]
+    pub start_date: Option<Timestamp>,
+    #[serde(rename = "endDate")]
+    pub end_date: Option<Timestamp>,
+
+    #[serde(rename = "sortBy")]
+    pub sort_by: Option<SortBy>,
+    #[serde(rename = "sortOrder")]
+    pub sort_order: Option<SortOrder>,
+
+    #[serde(rename = "msgStatuses")]
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, MessageStatus>>")]
+    pub message_statuses: Option<Vec<MessageStatus>>,
+}
+
+/// Builder-style setters taking the crate's own types.
+///
+/// Each list setter appends to the values already set, so filters can be
+/// built incrementally:
+///
+/// ```ignore
+/// let filter = MessageFilter::default()
+///     .message_type(MessageType::Post)
+///     .addresses([owner])
+///     .channels([channel!("TEST")])
+///     .chains([Chain::Ethereum, Chain::Sol]);
+/// ```
+impl MessageFilter {
+    pub fn message_type(mut self, message_type: MessageType) -> Self {
+        self.message_type = Some(message_type);
+        self
+    }
+
+    pub fn message_types(mut self, types: impl IntoIterator<Item = MessageType>) -> Self {
+        extend_filter(&mut self.message_types, types);
+        self
+    }
+
+    pub fn content_types(mut self, types: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        extend_filter(&mut self.content_types, types.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn content_keys(mut self, keys: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        extend_filter(&mut self.content_keys, keys.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn content_hashes(mut self, hashes: impl IntoIterator<Item = ItemHash>) -> Self {
+        extend_filter(&mut self.content_hashes, hashes);
+        self
+    }
+
+    /// Only messages whose `ref` is one of `hashes`.
+    pub fn refs(mut self, hashes: impl IntoIterator<Item = ItemHash>) -> Self {
+        extend_filter(&mut self.refs, hashes.into_iter().map(|h| h.to_string()));
+        self
+    }
+
+    /// Only messages whose `ref` is one of the given user-defined references.
+    pub fn user_refs(mut self, refs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        extend_filter(&mut self.refs, refs.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn addresses(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        extend_filter(&mut self.addresses, addresses);
+        self
+    }
+
+    pub fn owners(mut self, owners: impl IntoIterator<Item = Address>) -> Self {
+        extend_filter(&mut self.owners, owners);
+        self
+    }
+
+    pub fn tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        extend_filter(&mut self.tags, tags.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn hashes(mut self, hashes: impl IntoIterator<Item = ItemHash>) -> Self {
+        extend_filter(&mut self.hashes, hashes);
+        self
+    }
+
+    pub fn channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        extend_filter(
+            &mut self.channels,
+            channels.into_iter().map(|c| c.as_str().to_string()),
+        );
+        self
+    }
+
+    pub fn chains(mut self, chains: impl IntoIterator<Item = Chain>) -> Self {
+        extend_filter(&mut self.chains, chains.into_iter().map(|c| c.to_string()));
+        self
+    }
+
+    /// Only messages sent in `[start, end)`. Either bound may be omitted.
+    pub fn time_range(mut self, start: Option<Timestamp>, end: Option<Timestamp>) -> Self {
+        self.start_date = start;
+        self.end_date = end;
+        self
+    }
+
+    pub fn sort(mut self, sort_by: SortBy, sort_order: SortOrder) -> Self {
+        self.sort_by = Some(sort_by);
+        self.sort_order = Some(sort_order);
+        self
+    }
+
+    pub fn message_statuses(mut self, statuses: impl IntoIterator<Item = MessageStatus>) -> Self {
+        extend_filter(&mut self.message_statuses, statuses);
+        self
+    }
+}
+
+fn extend_filter<T>(field: &mut Option<Vec<T>>, values: impl IntoIterator<Item = T>) {
+    field.get_or_insert_with(Vec::new).extend(values);
+}
+
+/// A page of messages, see [`AlephClient::get_messages_page`], or of
+/// pending messages, see [`AlephClient::get_pending_messages`].
+#[derive(Debug, Deserialize)]
+pub struct GetMessagesResponse<M = Message> {
+    pub messages: Vec<M>,
+    pub pagination_per_page: u32,
+    pub pagination_page: u32,
+    /// Messages matching the filter, across all pages.
+    pub pagination_total: u32,
+}
+
+impl<M> GetMessagesResponse<M> {
+    /// Number of pages of `pagination_per_page` messages matching the filter.
+    pub fn total_pages(&self) -> u32 {
+        match self.pagination_per_page {
+            // Pagination disabled: everything fits in one page.
+            0 => u32::from(self.pagination_total > 0),
+            per_page => self.pagination_total.div_ceil(per_page),
+        }
+    }
+
+    /// Whether pages follow this one.
+    pub fn has_next_page(&self) -> bool {
+        self.pagination_page < self.total_pages()
+    }
+}
+
+/// Maximum items per page in cursor mode (server caps at 200 too).
+pub(super) const CURSOR_MAX_PAGINATION: u32 = 200;
+
+pub(super) const CURSOR_DEFAULT_PAGINATION: u32 = 200;
+
+/// Cursor-mode response for messages. Private — only used by the iterators.
+#[derive(Debug, Deserialize)]
+pub(super) struct MessagesCursorResponse {
+    pub(super) messages: Vec<Message>,
+    pub(super) next_cursor: Option<String>,
+}
+
+impl AlephClient {
+    /// Fetches messages matching the filter, returning the full response including
+    /// pagination metadata.
+    ///
+    /// Used by [`get_messages`](AlephMessageClient::get_messages).
+    pub(super) async fn get_messages_raw(
+        &self,
+        filter: &MessageFilter,
+        pagination: &PaginationParams,
+    ) -> Result<GetMessagesResponse, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/messages.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&pagination)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        crate::json::from_bytes(body).map_err(MessageError::Decode)
+    }
+
+    /// Like [`get_messages_raw`] but uses cursor-based pagination.
+    ///
+    /// `cursor` is the opaque cursor from a previous response's `next_cursor`,
+    /// or `None` for the first request (sends `cursor=` empty string to activate
+    /// cursor mode on the server).
+    pub(super) async fn get_messages_cursor(
+        &self,
+        filter: &MessageFilter,
+        cursor: Option<&str>,
+        pagination: u32,
+    ) -> Result<MessagesCursorResponse, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/messages.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let req = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&[("cursor", cursor.unwrap_or(""))])
+            .query(&[("pagination", &pagination.to_string())]);
+
+        let response = req.send().await?.check_status().await?;
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        crate::json::from_bytes(body).map_err(MessageError::Decode)
+    }
+
+    /// Fetches one page of messages along with the pagination metadata that
+    /// [`get_messages`](AlephMessageClient::get_messages) drops.
+    pub async fn get_messages_page(
+        &self,
+        filter: &MessageFilter,
+        pagination: PaginationParams,
+    ) -> Result<GetMessagesResponse, MessageError> {
+        self.get_messages_raw(filter, &pagination).await
+    }
+
+    /// Fetches one page of messages, decoding each message as soon as it has
+    /// been received instead of buffering the whole response.
+    ///
+    /// Peak memory is bounded by the largest message rather than the page
+    /// size, which matters for 1000-message pages of large PROGRAM/INSTANCE
+    /// messages. Pagination metadata is not returned; use
+    /// [`get_messages`](AlephMessageClient::get_messages) when it is needed.
+    pub fn get_messages_stream(
+        &self,
+        filter: &MessageFilter,
+        pagination: PaginationParams,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + '_ {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/messages.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+        let request = self
+            .inner
+            .http_client
+            .get(url)
+            .query(filter)
+            .query(&pagination);
+
+        async_stream::try_stream! {
+            let response = request
+                .send()
+                .await?
+                .check_status()
+                .await?;
+
+            let mut body = response.bytes_stream();
+            let mut items = crate::json::ArrayItems::new("messages");
+            while let Some(chunk) = body.next().await {
+                items.extend(&chunk.map_err(reqwest_middleware::Error::from)?);
+                while let Some(item) = items.next_item() {
+                    yield crate::json::from_bytes::<Message>(item).map_err(MessageError::Decode)?;
+                }
+                if items.is_done() {
+                    break;
+                }
+            }
+            if !items.is_done() {
+                Err::<(), _>(MessageError::Decode(serde::de::Error::custom(
+                    "response ended before the end of the message list",
+                )))?;
+            }
+        }
+    }
+
+    /// Fetches every message matching `filter` in page mode, with up to
+    /// `concurrency` pages of `per_page` messages in flight at once.
+    ///
+    /// The first page gives `pagination_total`, from which the remaining
+    /// pages are requested concurrently and yielded in order. This is several
+    /// times faster than [`get_messages_iterator`] for bulk exports, but page
+    /// mode is not a snapshot: messages arriving during the export can shift
+    /// page boundaries. Requests still count against the client-wide
+    /// concurrency limit.
+    ///
+    /// [`get_messages_iterator`]: AlephMessageClient::get_messages_iterator
+    pub fn get_messages_concurrent(
+        &self,
+        filter: MessageFilter,
+        per_page: u32,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + '_ {
+        let per_page = per_page.max(1);
+        let page_params = move |page| PaginationParams {
+            pagination: Some(per_page),
+            page: Some(page),
+        };
+
+        async_stream::try_stream! {
+            let first = self.get_messages_raw(&filter, &page_params(1)).await?;
+            let pages = first.total_pages();
+            for message in first.messages {
+                yield message;
+            }
+
+            let filter = &filter;
+            let mut rest = std::pin::pin!(futures_util::stream::iter(2..=pages)
+                .map(|page| async move { self.get_messages_raw(filter, &page_params(page)).await })
+                .buffered(concurrency.max(1)));
+            while let Some(response) = rest.next().await {
+                for message in response?.messages {
+                    yield message;
+                }
+            }
+        }
+    }
+
+    /// Streams every message matching `filter`, following pagination.
+    ///
+    /// Without `concurrency`, pages of `per_page` messages (at most 200) are
+    /// fetched one after the other with a cursor, as
+    /// [`get_messages_iterator`](AlephMessageClient::get_messages_iterator)
+    /// does, which stays consistent while new messages arrive. With it, up to
+    /// that many pages are fetched at once as
+    /// [`get_messages_concurrent`](Self::get_messages_concurrent) does, based
+    /// on `pagination_total`.
+    pub fn iter_messages(
+        &self,
+        filter: MessageFilter,
+        per_page: u32,
+        concurrency: Option<usize>,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + '_ {
+        match concurrency {
+            None => self
+                .get_messages_iterator(filter, Some(per_page))
+                .left_stream(),
+            Some(concurrency) => self
+                .get_messages_concurrent(filter, per_page, concurrency)
+                .right_stream(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::{address, channel, item_hash};
+    use futures_util::TryStreamExt;
+    use serde_json::json;
+    use std::time::Duration;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn sort_by_serializes_kebab_case() {
+        assert_eq!(serde_json::to_value(SortBy::Time).unwrap(), "time");
+        assert_eq!(serde_json::to_value(SortBy::TxTime).unwrap(), "tx-time");
+    }
+
+    #[test]
+    fn sort_order_serializes_as_signed_int() {
+        assert_eq!(serde_json::to_value(SortOrder::Asc).unwrap(), 1);
+        assert_eq!(serde_json::to_value(SortOrder::Desc).unwrap(), -1);
+    }
+
+    #[test]
+    fn test_ws_message_filter_serialization() {
+        let filter = MessageFilter {
+            message_type: Some(MessageType::Post),
+            addresses: Some(vec![address!("0x1234")]),
+            channels: Some(vec!["TEST".to_string()]),
+            ..Default::default()
+        };
+
+        let query = serde_qs::to_string(&filter).unwrap();
+        assert!(query.contains("msgType=POST"));
+        assert!(query.contains("addresses=0x1234"));
+        assert!(query.contains("channels=TEST"));
+    }
+
+    #[test]
+    fn test_message_filter_builder() {
+        let hash = item_hash!("d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c");
+        let filter = MessageFilter::default()
+            .message_type(MessageType::Post)
+            .addresses([address!("0x1234")])
+            .addresses([address!("0x5678")])
+            .channels([channel!("TEST")])
+            .chains([Chain::Ethereum, Chain::Sol])
+            .refs([hash.clone()])
+            .message_statuses([MessageStatus::Processed]);
+
+        assert_eq!(
+            filter.addresses,
+            Some(vec![address!("0x1234"), address!("0x5678")])
+        );
+        let query = serde_json::to_value(&filter).unwrap();
+        assert_eq!(query["msgType"], "POST");
+        assert_eq!(query["addresses"], "0x1234,0x5678");
+        assert_eq!(query["channels"], "TEST");
+        assert_eq!(query["chains"], "ETH,SOL");
+        assert_eq!(query["refs"], hash.to_string());
+        assert_eq!(query["msgStatuses"], "processed");
+    }
+
+    const POST: &str = include_str!("../../../../fixtures/messages/post/post.json");
+
+    #[tokio::test]
+    async fn stream_yields_the_same_messages_as_get_messages() {
+        let post: serde_json::Value = serde_json::from_str(POST).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("pagination", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [post, post],
+                "pagination_per_page": 2,
+                "pagination_page": 1,
+                "pagination_total": 2,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let pagination = PaginationParams {
+            pagination: Some(2),
+            page: None,
+        };
+        let streamed: Vec<Message> = client
+            .get_messages_stream(&MessageFilter::default(), pagination.clone())
+            .try_collect()
+            .await
+            .unwrap();
+        let buffered = client
+            .get_messages(&MessageFilter::default(), pagination)
+            .await
+            .unwrap();
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(streamed, buffered);
+    }
+
+    #[tokio::test]
+    async fn stream_reports_truncated_bodies() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(format!(r#"{{"messages": [{POST}"#)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let items: Vec<_> = client
+            .get_messages_stream(&MessageFilter::default(), PaginationParams::default())
+            .collect()
+            .await;
+        assert!(matches!(items.as_slice(), [Err(MessageError::Decode(_))]));
+    }
+
+    fn page(page: u32, channels: &[&str], total: u32) -> ResponseTemplate {
+        let post: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../fixtures/messages/post/post.json"))
+                .unwrap();
+        let messages: Vec<_> = channels
+            .iter()
+            .map(|channel| {
+                let mut message = post.clone();
+                message["channel"] = json!(channel);
+                message
+            })
+            .collect();
+        ResponseTemplate::new(200).set_body_json(json!({
+            "messages": messages,
+            "pagination_per_page": 2,
+            "pagination_page": page,
+            "pagination_total": total,
+        }))
+    }
+
+    #[tokio::test]
+    async fn concurrent_pages_are_yielded_in_order() {
+        let server = MockServer::start().await;
+        let pages = [(1, vec!["A", "B"]), (2, vec!["C", "D"]), (3, vec!["E"])];
+        for (number, channels) in &pages {
+            let mut response = page(*number, channels, 5);
+            if *number == 2 {
+                // Make the middle page arrive last.
+                response = response.set_delay(Duration::from_millis(200));
+            }
+            Mock::given(method("GET"))
+                .and(path("/api/v0/messages.json"))
+                .and(query_param("page", number.to_string()))
+                .and(query_param("pagination", "2"))
+                .respond_with(response)
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let messages: Vec<Message> = client
+            .get_messages_concurrent(MessageFilter::default(), 2, 4)
+            .try_collect()
+            .await
+            .unwrap();
+        let channels: Vec<_> = messages
+            .iter()
+            .map(|m| m.channel.as_ref().unwrap().as_str().to_string())
+            .collect();
+        assert_eq!(channels, ["A", "B", "C", "D", "E"]);
+    }
+
+    #[tokio::test]
+    async fn get_messages_page_keeps_pagination() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("page", "2"))
+            .respond_with(page(2, &["C", "D"], 5))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let pagination = PaginationParams {
+            pagination: Some(2),
+            page: Some(2),
+        };
+        let response = client
+            .get_messages_page(&MessageFilter::default(), pagination)
+            .await
+            .unwrap();
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(response.pagination_total, 5);
+        assert_eq!(response.total_pages(), 3);
+        assert!(response.has_next_page());
+
+        let last = GetMessagesResponse::<Message> {
+            messages: vec![],
+            pagination_per_page: 2,
+            pagination_page: 3,
+            pagination_total: 5,
+        };
+        assert!(!last.has_next_page());
+    }
+
+    #[tokio::test]
+    async fn iter_messages_without_concurrency_follows_cursors() {
+        let server = MockServer::start().await;
+        let post: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../fixtures/messages/post/post.json"))
+                .unwrap();
+        for (cursor, next_cursor) in [("", Some("next")), ("next", None)] {
+            Mock::given(method("GET"))
+                .and(path("/api/v0/messages.json"))
+                .and(query_param("cursor", cursor))
+                .and(query_param("pagination", "50"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "messages": [post],
+                    "next_cursor": next_cursor,
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let messages: Vec<Message> = client
+            .iter_messages(MessageFilter::default(), 50, None)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 2);
+    }
+}
@@ -0,0 +1,653 @@
+//! Posts, the merged view of POST messages and their amendments.
+
+use super::error::CheckStatus;
+use super::pagination::{CURSOR_DEFAULT_PAGINATION, CURSOR_MAX_PAGINATION};
+use super::{
+    AlephClient, AlephPostClient, MessageError, PaginationParams, PostMessage, SortBy, SortOrder,
+};
+use aleph_types::chain::{Address, Chain};
+use aleph_types::channel::Channel;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{Message, MessageConfirmation};
+use aleph_types::timestamp::Timestamp;
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_with::{StringWithSeparator, formats::CommaSeparator, serde_as, skip_serializing_none};
+
+/// A post and every amendment made to it, oldest first.
+///
+/// Returned by [`AlephMessageClient::get_post_history`](super::AlephMessageClient::get_post_history). The CCN only applies
+/// amends made on behalf of the post's owner, by the owner or an authorized
+/// delegate; `amends` lists every amend referencing the post so that audit
+/// views can show rejected attempts too.
+#[derive(Debug)]
+pub struct PostHistory {
+    pub original: PostMessage,
+    pub amends: Vec<Message>,
+}
+
+impl PostHistory {
+    /// The most recent revision: the last amend applied to the post, or the
+    /// original post if it was never amended.
+    ///
+    /// The CCN accepted each amend only if its sender is its owner or a
+    /// delegate authorized by that owner, but applies it only if that owner
+    /// is the owner of the post. Amends made on behalf of anyone else are
+    /// skipped.
+    pub fn latest(&self) -> &Message {
+        let owner = self.original.message().owner();
+        self.amends
+            .iter()
+            .rev()
+            .find(|amend| amend.owner().same_as(owner))
+            .unwrap_or(self.original.message())
+    }
+
+    /// Every revision in order, starting with the original post.
+    pub fn revisions(&self) -> impl Iterator<Item = &Message> {
+        std::iter::once(self.original.message()).chain(&self.amends)
+    }
+}
+
+/// Query filter for GET /api/v0/posts.json and /api/v1/posts.json.
+///
+/// Posts are a higher-level view of POST messages: when a post is amended, the endpoint
+/// returns the merged result with the latest content and metadata from the amendment,
+/// while preserving a reference to the original post via `original_item_hash`.
+#[skip_serializing_none]
+#[serde_as]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PostFilter {
+    /// Filter by sender address.
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, Address>>")]
+    pub addresses: Option<Vec<Address>>,
+
+    /// Filter by item hash.
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, ItemHash>>")]
+    pub hashes: Option<Vec<ItemHash>>,
+
+    /// Filter by reference hash.
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub refs: Option<Vec<String>>,
+
+    /// Filter by post type (e.g., `"corechan-operation"`).
+    #[serde(rename = "types")]
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub post_types: Option<Vec<String>>,
+
+    /// Filter by tag.
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub tags: Option<Vec<String>>,
+
+    /// Filter by channel.
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, String>>")]
+    pub channels: Option<Vec<String>>,
+
+    /// Start date filter (inclusive).
+    #[serde(rename = "startDate")]
+    pub start_date: Option<Timestamp>,
+
+    /// End date filter (exclusive).
+    #[serde(rename = "endDate")]
+    pub end_date: Option<Timestamp>,
+
+    /// Sort key.
+    #[serde(rename = "sortBy")]
+    pub sort_by: Option<SortBy>,
+
+    /// Sort order.
+    #[serde(rename = "sortOrder")]
+    pub sort_order: Option<SortOrder>,
+}
+
+/// A merged post as returned by GET /api/v0/posts.json.
+///
+/// This is the legacy format that includes the full message envelope (chain, signature,
+/// confirmations, etc.). When a post has been amended, the response shows the latest
+/// version with `original_item_hash` pointing to the original post.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PostV0 {
+    /// The blockchain used for this post.
+    pub chain: Chain,
+    /// Hash of the current version (may be an amendment).
+    pub item_hash: ItemHash,
+    /// Address of the sender.
+    pub sender: Address,
+    /// Post type (e.g., `"corechan-operation"`).
+    #[serde(rename = "type")]
+    pub post_type: String,
+    /// Channel of the message.
+    #[serde(default)]
+    pub channel: Option<Channel>,
+    /// Whether the post has on-chain confirmations.
+    #[serde(default)]
+    pub confirmed: bool,
+    /// The user-defined content of the post (arbitrary JSON).
+    pub content: serde_json::Value,
+    /// Timestamp of the post.
+    pub time: Timestamp,
+    /// On-chain confirmations.
+    #[serde(default)]
+    pub confirmations: Vec<MessageConfirmation>,
+    /// Hash of the original post (before any amendments).
+    pub original_item_hash: ItemHash,
+    /// Type of the original post.
+    #[serde(default)]
+    pub original_type: Option<String>,
+    /// Alias for `original_item_hash`.
+    pub hash: ItemHash,
+    /// Address of the post owner.
+    pub address: Address,
+    /// Reference hash (for amendments, points to the amended post).
+    #[serde(default, rename = "ref")]
+    pub reference: Option<String>,
+}
+
+impl PostV0 {
+    /// Deserializes the content into a typed struct.
+    pub fn content_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.content.clone())
+    }
+    /// Whether the post has been amended, i.e. the content is that of an
+    /// amend rather than of `original_item_hash`.
+    pub fn is_amended(&self) -> bool {
+        self.item_hash != self.original_item_hash
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetPostsV0Response {
+    pub posts: Vec<PostV0>,
+    pub pagination_per_page: u32,
+    pub pagination_page: u32,
+    pub pagination_total: u32,
+}
+
+/// A merged post as returned by GET /api/v1/posts.json.
+///
+/// This is the leaner format that omits message-level fields (chain, signature,
+/// confirmations, etc.) and uses ISO 8601 timestamps instead of unix floats.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PostV1 {
+    /// Hash of the current version (may be an amendment).
+    pub item_hash: ItemHash,
+    /// The user-defined content of the post (arbitrary JSON).
+    pub content: serde_json::Value,
+    /// Hash of the original post (before any amendments).
+    pub original_item_hash: ItemHash,
+    /// Type of the original post.
+    #[serde(default)]
+    pub original_type: Option<String>,
+    /// Address of the post owner.
+    pub address: Address,
+    /// Reference hash (for amendments, points to the amended post).
+    #[serde(default, rename = "ref")]
+    pub reference: Option<String>,
+    /// Channel of the message.
+    #[serde(default)]
+    pub channel: Option<Channel>,
+    /// When the post was first created (ISO 8601).
+    pub created: DateTime<Utc>,
+    /// When the post was last updated (ISO 8601), i.e. the time of the latest amendment.
+    pub last_updated: DateTime<Utc>,
+}
+
+impl PostV1 {
+    /// Deserializes the content into a typed struct.
+    pub fn content_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.content.clone())
+    }
+    /// Whether the post has been amended, i.e. the content is that of an
+    /// amend rather than of `original_item_hash`.
+    pub fn is_amended(&self) -> bool {
+        self.item_hash != self.original_item_hash
+    }
+}
+
+/// A [`PostV1`] whose content is deserialized into an application type.
+///
+/// The post keeps its JSON content, so [`post`](Self::post) still gives
+/// access to fields `T` does not model.
+#[derive(Debug, Clone)]
+pub struct TypedPost<T> {
+    post: PostV1,
+    content: T,
+}
+
+impl<T: DeserializeOwned> TryFrom<PostV1> for TypedPost<T> {
+    type Error = serde_json::Error;
+
+    fn try_from(post: PostV1) -> Result<Self, Self::Error> {
+        Ok(Self {
+            content: post.content_as()?,
+            post,
+        })
+    }
+}
+
+impl<T> TypedPost<T> {
+    pub fn content(&self) -> &T {
+        &self.content
+    }
+
+    /// Drops the post and returns the content.
+    pub fn into_content(self) -> T {
+        self.content
+    }
+
+    pub fn post(&self) -> &PostV1 {
+        &self.post
+    }
+
+    pub fn into_post(self) -> PostV1 {
+        self.post
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetPostsV1Response {
+    pub posts: Vec<PostV1>,
+    pub pagination_per_page: u32,
+    pub pagination_page: u32,
+    pub pagination_total: u32,
+}
+
+/// Cursor-mode response for v0 posts. Private — only used by the iterators.
+#[derive(Debug, Deserialize)]
+struct PostsV0CursorResponse {
+    posts: Vec<PostV0>,
+    next_cursor: Option<String>,
+}
+
+/// Cursor-mode response for v1 posts. Private — only used by the iterators.
+#[derive(Debug, Deserialize)]
+struct PostsV1CursorResponse {
+    posts: Vec<PostV1>,
+    next_cursor: Option<String>,
+}
+
+impl AlephClient {
+    async fn get_posts_v0_cursor(
+        &self,
+        filter: &PostFilter,
+        cursor: Option<&str>,
+        pagination: u32,
+    ) -> Result<PostsV0CursorResponse, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/posts.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let req = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&[("cursor", cursor.unwrap_or(""))])
+            .query(&[("pagination", &pagination.to_string())]);
+
+        let response = req.send().await?.check_status().await?;
+
+        let resp: PostsV0CursorResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(resp)
+    }
+
+    async fn get_posts_v1_cursor(
+        &self,
+        filter: &PostFilter,
+        cursor: Option<&str>,
+        pagination: u32,
+    ) -> Result<PostsV1CursorResponse, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v1/posts.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let req = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&[("cursor", cursor.unwrap_or(""))])
+            .query(&[("pagination", &pagination.to_string())]);
+
+        let response = req.send().await?.check_status().await?;
+
+        let resp: PostsV1CursorResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(resp)
+    }
+}
+
+impl AlephPostClient for AlephClient {
+    async fn get_posts_v0(
+        &self,
+        filter: &PostFilter,
+        pagination: PaginationParams,
+    ) -> Result<GetPostsV0Response, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/posts.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&pagination)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+
+        let posts_response: GetPostsV0Response = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(posts_response)
+    }
+
+    async fn get_posts_v1(
+        &self,
+        filter: &PostFilter,
+        pagination: PaginationParams,
+    ) -> Result<GetPostsV1Response, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v1/posts.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .query(&pagination)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+
+        let posts_response: GetPostsV1Response = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(posts_response)
+    }
+
+    fn get_posts_v0_iterator(
+        &self,
+        filter: PostFilter,
+        pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<PostV0, MessageError>> + Send + '_ {
+        let pagination = pagination
+            .unwrap_or(CURSOR_DEFAULT_PAGINATION)
+            .min(CURSOR_MAX_PAGINATION);
+        async_stream::try_stream! {
+            let mut cursor: Option<String> = None;
+            loop {
+                let response = self
+                    .get_posts_v0_cursor(&filter, cursor.as_deref(), pagination)
+                    .await?;
+                for post in response.posts {
+                    yield post;
+                }
+                cursor = match response.next_cursor {
+                    Some(c) => Some(c),
+                    None => break,
+                };
+            }
+        }
+    }
+
+    fn get_posts_v1_iterator(
+        &self,
+        filter: PostFilter,
+        pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<PostV1, MessageError>> + Send + '_ {
+        let pagination = pagination
+            .unwrap_or(CURSOR_DEFAULT_PAGINATION)
+            .min(CURSOR_MAX_PAGINATION);
+        async_stream::try_stream! {
+            let mut cursor: Option<String> = None;
+            loop {
+                let response = self
+                    .get_posts_v1_cursor(&filter, cursor.as_deref(), pagination)
+                    .await?;
+                for post in response.posts {
+                    yield post;
+                }
+                cursor = match response.next_cursor {
+                    Some(c) => Some(c),
+                    None => break,
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AlephMessageClient;
+    use crate::test_utils::inline_message_json;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn get_posts_as_deserializes_content() {
+        #[derive(Debug, Deserialize)]
+        struct Chat {
+            body: String,
+        }
+
+        let hash = ItemHash::from([3u8; 32]);
+        let post = |content: serde_json::Value| {
+            json!({
+                "item_hash": hash.to_string(),
+                "content": content,
+                "original_item_hash": hash.to_string(),
+                "original_type": "chat",
+                "address": "0x0000000000000000000000000000000000000001",
+                "ref": null,
+                "channel": "TEST",
+                "created": "2024-01-01T00:00:00Z",
+                "last_updated": "2024-01-01T00:00:00Z",
+            })
+        };
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/posts.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "posts": [post(json!({"body": "hello"}))],
+                "pagination_per_page": 20,
+                "pagination_page": 1,
+                "pagination_total": 1,
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/posts.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "posts": [post(json!({"title": "no body"}))],
+                "pagination_per_page": 20,
+                "pagination_page": 1,
+                "pagination_total": 1,
+            })))
+            .mount(&server)
+            .await;
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+        let posts = client
+            .get_posts_as::<Chat>(&PostFilter::default(), PaginationParams::default())
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].content().body, "hello");
+        assert!(!posts[0].post().is_amended());
+        assert!(matches!(
+            client
+                .get_posts_as::<Chat>(&PostFilter::default(), PaginationParams::default())
+                .await,
+            Err(MessageError::Decode(_))
+        ));
+    }
+
+    const OWNER: &str = "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef";
+
+    fn post(
+        hash: &ItemHash,
+        post_type: &str,
+        reference: Option<&ItemHash>,
+        time: f64,
+    ) -> serde_json::Value {
+        post_from(OWNER, hash, post_type, reference, time)
+    }
+
+    fn post_from(
+        address: &str,
+        hash: &ItemHash,
+        post_type: &str,
+        reference: Option<&ItemHash>,
+        time: f64,
+    ) -> serde_json::Value {
+        let content = json!({
+            "address": address,
+            "time": time,
+            "type": post_type,
+            "ref": reference.map(ToString::to_string),
+            "content": {"rev": time},
+        });
+        let mut message = inline_message_json("POST", content);
+        message["sender"] = address.into();
+        message["item_hash"] = hash.to_string().into();
+        message["time"] = time.into();
+        message
+    }
+
+    #[tokio::test]
+    async fn get_post_history_resolves_original_and_sorts_amends() {
+        let server = MockServer::start().await;
+        let original = ItemHash::from([1u8; 32]);
+        let first = ItemHash::from([2u8; 32]);
+        let second = ItemHash::from([3u8; 32]);
+
+        for (hash, body) in [
+            (&original, post(&original, "note", None, 100.0)),
+            (&second, post(&second, "amend", Some(&original), 300.0)),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/api/v0/messages/{hash}")))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({"status": "processed", "message": body})),
+                )
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("refs", original.to_string()))
+            .and(query_param("contentTypes", "amend"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [
+                    post(&second, "amend", Some(&original), 300.0),
+                    post(&first, "amend", Some(&original), 200.0),
+                ],
+                "next_cursor": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        // Starting from an amend still yields the full history.
+        let history = client.get_post_history(&second).await.unwrap();
+        let revisions: Vec<_> = history.revisions().map(|m| m.item_hash.clone()).collect();
+        assert_eq!(revisions, vec![original, first, second.clone()]);
+        assert_eq!(history.latest().item_hash, second);
+    }
+
+    #[tokio::test]
+    async fn latest_skips_amends_of_other_owners() {
+        let server = MockServer::start().await;
+        let original = ItemHash::from([1u8; 32]);
+        let amend = ItemHash::from([2u8; 32]);
+        let foreign = ItemHash::from([3u8; 32]);
+
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{original}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                json!({"status": "processed", "message": post(&original, "note", None, 100.0)}),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [
+                    // Same owner, with a lowercase address.
+                    post_from(
+                        "0xb68b9d4f3771c246233823ed1d3add451055f9ef",
+                        &amend,
+                        "amend",
+                        Some(&original),
+                        200.0,
+                    ),
+                    post_from(
+                        "0x0000000000000000000000000000000000000002",
+                        &foreign,
+                        "amend",
+                        Some(&original),
+                        300.0,
+                    ),
+                ],
+                "next_cursor": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let history = client.get_post_history(&original).await.unwrap();
+        assert_eq!(history.amends.len(), 2);
+        assert_eq!(history.latest().item_hash, amend);
+    }
+
+    fn post_v1(item_hash: ItemHash, original_item_hash: ItemHash) -> PostV1 {
+        serde_json::from_value(json!({
+            "item_hash": item_hash,
+            "content": {"body": "hello"},
+            "original_item_hash": original_item_hash,
+            "original_type": "chat",
+            "address": "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef",
+            "ref": original_item_hash.to_string(),
+            "channel": "TEST",
+            "created": "2024-01-01T00:00:00Z",
+            "last_updated": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn is_amended_compares_with_the_original() {
+        let original = ItemHash::from([1u8; 32]);
+        assert!(!post_v1(original.clone(), original.clone()).is_amended());
+        let amended = post_v1(ItemHash::from([2u8; 32]), original);
+        assert!(amended.is_amended());
+        assert_eq!(amended.original_type.as_deref(), Some("chat"));
+    }
+}
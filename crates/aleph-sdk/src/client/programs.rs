@@ -0,0 +1,381 @@
+//! Health checks and requests to running programs.
+
+use super::{AlephClient, MessageError};
+use aleph_types::item_hash::ItemHash;
+use serde::Serialize;
+use std::time::Duration;
+use url::Url;
+
+/// Gateway routing requests to whichever CRN runs a program.
+pub const DEFAULT_PROGRAM_GATEWAY_URL: &str = "https://aleph.sh";
+
+/// How long [`AlephClient::check_program`] and [`AlephClient::run_program`]
+/// wait for a program to answer, cold start included.
+const PROGRAM_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where [`AlephClient::check_program_at`] reaches a program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ProgramEndpoint {
+    /// The aleph.sh gateway (`https://aleph.sh/vm/{hash}`).
+    #[default]
+    Gateway,
+    /// A specific CRN (`{crn_url}/vm/{hash}`), to check one node rather than
+    /// whichever the gateway picks.
+    Crn(Url),
+}
+
+/// Outcome of a program health check.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgramHealth {
+    pub url: Url,
+    /// HTTP status of the answer, or `None` if the program was unreachable.
+    pub status: Option<u16>,
+    /// Time until the response headers were received, or until the failure.
+    pub latency: Duration,
+    /// Why the program could not be reached.
+    pub error: Option<String>,
+}
+
+/// URL of `path` under the root of a program.
+fn program_url(endpoint: &ProgramEndpoint, item_hash: &ItemHash, path: &str) -> Url {
+    let base = match endpoint {
+        ProgramEndpoint::Gateway => {
+            Url::parse(DEFAULT_PROGRAM_GATEWAY_URL).expect("valid gateway url")
+        }
+        ProgramEndpoint::Crn(crn_url) => crn_url.clone(),
+    };
+    let separator = if path.is_empty() || path.starts_with('/') {
+        ""
+    } else {
+        "/"
+    };
+    base.join(&format!("/vm/{item_hash}{separator}{path}"))
+        .unwrap_or_else(|e| panic!("invalid url: {e}"))
+}
+
+impl ProgramHealth {
+    /// Whether the program answered with a success status.
+    pub fn is_live(&self) -> bool {
+        self.status
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// An HTTP request to send to a program, see [`AlephClient::run_program`].
+///
+/// `path` is relative to the root of the program and may carry a query
+/// string, e.g. `/items?page=2`.
+#[derive(Debug, Clone)]
+pub struct ProgramRequest {
+    pub method: reqwest::Method,
+    pub path: String,
+    pub headers: reqwest::header::HeaderMap,
+    pub body: Option<bytes::Bytes>,
+}
+
+impl ProgramRequest {
+    pub fn new(method: reqwest::Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            headers: reqwest::header::HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(reqwest::Method::GET, path)
+    }
+
+    pub fn post(path: impl Into<String>, body: impl Into<bytes::Bytes>) -> Self {
+        Self::new(reqwest::Method::POST, path).body(body)
+    }
+
+    pub fn header(
+        mut self,
+        name: reqwest::header::HeaderName,
+        value: reqwest::header::HeaderValue,
+    ) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<bytes::Bytes>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+/// The answer of a program to a [`ProgramRequest`].
+#[derive(Debug, Clone)]
+pub struct ProgramResponse {
+    pub url: Url,
+    pub status: reqwest::StatusCode,
+    pub headers: reqwest::header::HeaderMap,
+    pub body: bytes::Bytes,
+}
+
+/// Messages published by
+/// [`create_program`](super::AlephMessageClient::create_program).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedProgram {
+    /// The STORE message of the code archive, referenced by the program.
+    pub code_store: ItemHash,
+    /// The PROGRAM message.
+    pub program: ItemHash,
+}
+
+impl AlephClient {
+    /// Checks that a program answers on its public endpoint through the
+    /// aleph.sh gateway. See [`check_program_at`](Self::check_program_at).
+    pub async fn check_program(&self, item_hash: &ItemHash) -> ProgramHealth {
+        self.check_program_at(item_hash, &ProgramEndpoint::Gateway)
+            .await
+    }
+
+    /// Sends a `GET` to the root of a program and reports whether it answered,
+    /// with which status and how fast.
+    ///
+    /// Transient failures are retried like any other request of the client,
+    /// see [`RetryConfig`](super::RetryConfig), and the latency covers every
+    /// attempt; the first call after publishing includes the cold start of the
+    /// VM. Failures to
+    /// connect are reported in the result, not as errors.
+    pub async fn check_program_at(
+        &self,
+        item_hash: &ItemHash,
+        endpoint: &ProgramEndpoint,
+    ) -> ProgramHealth {
+        let url = program_url(endpoint, item_hash, "");
+
+        let start = std::time::Instant::now();
+        let result = self
+            .inner
+            .http_client
+            .get(url.clone())
+            .timeout(PROGRAM_CHECK_TIMEOUT)
+            .send()
+            .await;
+        let latency = start.elapsed();
+
+        match result {
+            Ok(response) => ProgramHealth {
+                url,
+                status: Some(response.status().as_u16()),
+                latency,
+                error: None,
+            },
+            Err(e) => ProgramHealth {
+                url,
+                status: None,
+                latency,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Calls a deployed program through the aleph.sh gateway. See
+    /// [`run_program_at`](Self::run_program_at).
+    pub async fn run_program(
+        &self,
+        item_hash: &ItemHash,
+        request: ProgramRequest,
+    ) -> Result<ProgramResponse, MessageError> {
+        self.run_program_at(item_hash, &ProgramEndpoint::Gateway, request)
+            .await
+    }
+
+    /// Sends `request` to a program and returns its answer, whatever its
+    /// status: error statuses come from the program, not from the SDK.
+    ///
+    /// Unlike [`check_program_at`](Self::check_program_at), the request is not
+    /// retried, since programs are free to handle non-idempotent requests.
+    pub async fn run_program_at(
+        &self,
+        item_hash: &ItemHash,
+        endpoint: &ProgramEndpoint,
+        request: ProgramRequest,
+    ) -> Result<ProgramResponse, MessageError> {
+        let url = program_url(endpoint, item_hash, &request.path);
+        let mut builder = self
+            .inner
+            .upload_client
+            .request(request.method, url.clone())
+            .headers(request.headers)
+            .timeout(PROGRAM_CHECK_TIMEOUT);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .bytes()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(ProgramResponse {
+            url,
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{AlephMessageClient, RetryConfig, hash_file};
+    use crate::test_utils::TestAccount;
+    use crate::verify::Hasher;
+    use aleph_types::chain::Address;
+    use aleph_types::message::StorageEngine;
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Client checking each program once.
+    fn client_without_retries() -> AlephClient {
+        AlephClient::builder(Url::parse("http://localhost:1").unwrap())
+            .retry_config(RetryConfig::default().max_retries(0))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn check_program_reports_status_of_crn() {
+        let server = MockServer::start().await;
+        let live = ItemHash::from([1u8; 32]);
+        let broken = ItemHash::from([2u8; 32]);
+        Mock::given(method("GET"))
+            .and(path(format!("/vm/{live}")))
+            .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/vm/{broken}")))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_without_retries();
+        let crn = ProgramEndpoint::Crn(Url::parse(&server.uri()).unwrap());
+
+        let health = client.check_program_at(&live, &crn).await;
+        assert!(health.is_live());
+        assert_eq!(health.url.path(), format!("/vm/{live}"));
+
+        let health = client.check_program_at(&broken, &crn).await;
+        assert!(!health.is_live());
+        assert_eq!(health.status, Some(502));
+        assert!(health.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn run_program_forwards_method_path_and_body() {
+        let server = MockServer::start().await;
+        let program = ItemHash::from([1u8; 32]);
+        Mock::given(method("POST"))
+            .and(path(format!("/vm/{program}/items")))
+            .and(query_param("dry", "1"))
+            .and(wiremock::matchers::header(
+                "content-type",
+                "application/json",
+            ))
+            .and(wiremock::matchers::body_string("{\"name\":\"a\"}"))
+            .respond_with(ResponseTemplate::new(201).set_body_string("created"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse("http://localhost:1").unwrap());
+        let crn = ProgramEndpoint::Crn(Url::parse(&server.uri()).unwrap());
+        let request = ProgramRequest::post("items?dry=1", "{\"name\":\"a\"}").header(
+            reqwest::header::CONTENT_TYPE,
+            reqwest::header::HeaderValue::from_static("application/json"),
+        );
+
+        let response = client
+            .run_program_at(&program, &crn, request)
+            .await
+            .unwrap();
+        assert_eq!(response.status, reqwest::StatusCode::CREATED);
+        assert_eq!(response.body, "created");
+        assert_eq!(response.url.path(), format!("/vm/{program}/items"));
+    }
+
+    #[tokio::test]
+    async fn check_program_reports_unreachable_nodes() {
+        let client = client_without_retries();
+        let crn = ProgramEndpoint::Crn(Url::parse("http://127.0.0.1:1").unwrap());
+
+        let health = client
+            .check_program_at(&ItemHash::from([1u8; 32]), &crn)
+            .await;
+        assert!(!health.is_live());
+        assert!(health.status.is_none());
+        assert!(health.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn create_program_references_the_code_store_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("code.zip");
+        std::fs::write(&archive, b"not really a zip").unwrap();
+        let file_hash = hash_file(&archive, Hasher::for_storage()).await.unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/storage/add_file"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"status": "success", "hash": file_hash.to_string()})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "publication_status": {"status": "success", "failed": []},
+                "message_status": "pending",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let account = TestAccount(Address::from(
+            "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string(),
+        ));
+        let runtime = ItemHash::from([7u8; 32]);
+        let created = client
+            .create_program(
+                &account,
+                &archive,
+                StorageEngine::Storage,
+                "main:app",
+                runtime,
+                |program| program.internet(false),
+                false,
+            )
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let program_request = requests
+            .iter()
+            .find(|r| r.url.path() == "/api/v0/messages")
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&program_request.body).unwrap();
+        assert_eq!(body["message"]["item_hash"], created.program.to_string());
+        let content: serde_json::Value =
+            serde_json::from_str(body["message"]["item_content"].as_str().unwrap()).unwrap();
+        assert_eq!(content["code"]["ref"], created.code_store.to_string());
+        assert_eq!(content["environment"]["internet"], false);
+    }
+}
@@ -0,0 +1,268 @@
+//! Retry and timeout policies of the HTTP client.
+
+use crate::upload_timeout::UploadTimeout;
+use std::time::Duration;
+
+/// Configuration for HTTP retry behavior on transient errors (429, 5xx).
+///
+/// ```
+/// # use aleph_sdk::client::RetryConfig;
+/// # use std::time::Duration;
+/// let config = RetryConfig::default()
+///     .max_retries(5)
+///     .max_retry_after(Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RetryConfig {
+    /// Maximum number of retry attempts, rate-limited ones included. Default: 3.
+    pub max_retries: u32,
+    /// Minimum backoff duration between retries. Default: 500ms.
+    pub min_backoff: Duration,
+    /// Maximum backoff duration between retries. Default: 30s.
+    pub max_backoff: Duration,
+    /// Longest wait honored when a rate-limited CCN sends `Retry-After`;
+    /// longer requested delays are cut to this. Default: 60s.
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn min_backoff(mut self, min_backoff: Duration) -> Self {
+        self.min_backoff = min_backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+}
+
+/// Configuration for HTTP timeouts.
+///
+/// ```
+/// # use aleph_sdk::client::TimeoutConfig;
+/// # use std::time::Duration;
+/// let config = TimeoutConfig::default()
+///     .connect_timeout(Duration::from_secs(5))
+///     .read_timeout(Some(Duration::from_secs(30)));
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TimeoutConfig {
+    /// Timeout for establishing a TCP connection. Default: 10s.
+    pub connect_timeout: Duration,
+    /// Overall timeout for an individual non-upload HTTP request (including
+    /// reading the response body). Default: 120s. Set to `None` via
+    /// [`TimeoutConfig::no_request_timeout`] to disable.
+    ///
+    /// Uploads do not use this: they are governed by [`Self::upload_timeout`],
+    /// because a fixed total deadline cuts large uploads on slow-but-healthy links.
+    pub request_timeout: Option<Duration>,
+    /// Policy governing how long an upload may run. Default: [`UploadTimeout::Idle`]
+    /// of 60s, so a slow upload that keeps making progress is never cut.
+    pub upload_timeout: UploadTimeout,
+    /// Longest wait for the next bytes of a non-upload response, however long
+    /// the response takes overall. Default: none.
+    pub read_timeout: Option<Duration>,
+}
+
+impl TimeoutConfig {
+    /// Returns a config with no per-request timeout (connect timeout still applies).
+    /// Useful for long-running streaming downloads.
+    pub fn no_request_timeout() -> Self {
+        Self {
+            request_timeout: None,
+            ..Default::default()
+        }
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    pub fn upload_timeout(mut self, policy: UploadTimeout) -> Self {
+        self.upload_timeout = policy;
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Some(Duration::from_secs(120)),
+            upload_timeout: UploadTimeout::default(),
+            read_timeout: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{AlephClient, AlephMessageClient, AlephStorageClient, StorageError};
+    use url::Url;
+
+    /// Spin up a TCP listener that accepts connections but never sends a response,
+    /// simulating a stalled server. Returns the URL to connect to.
+    async fn start_stalling_server() -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                // Accept the connection and hold it open without responding.
+                let Ok((_stream, _)) = listener.accept().await else {
+                    break;
+                };
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+        });
+        Url::parse(&format!("http://{addr}")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_fires_on_stalled_server() {
+        let url = start_stalling_server().await;
+        let client = AlephClient::builder(url)
+            .timeout_config(TimeoutConfig {
+                connect_timeout: Duration::from_secs(5),
+                request_timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            })
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+
+        let start = std::time::Instant::now();
+        let hash = aleph_types::item_hash!(
+            "0000000000000000000000000000000000000000000000000000000000000000"
+        );
+        let result = client.get_message(&hash).await;
+        let elapsed = start.elapsed();
+
+        assert!(result.is_err(), "should fail with timeout");
+        // The server sleeps for an hour, so any return well under that proves the
+        // 200ms request timeout fired. The margin is deliberately generous: loaded
+        // CI runners (especially Windows) can starve the single-threaded test
+        // runtime for several seconds before the timer is serviced.
+        assert!(
+            elapsed < Duration::from_secs(60),
+            "should bail out via the request timeout (took {elapsed:?}), not hang indefinitely"
+        );
+    }
+
+    /// An upload with a total policy aborts a stalled request as
+    /// `StorageError::UploadTimeout`, distinct from a transport `UploadFailed`.
+    #[tokio::test]
+    async fn upload_total_timeout_fires_on_stalled_server() {
+        let url = start_stalling_server().await;
+        let client = AlephClient::builder(url)
+            .upload_timeout(UploadTimeout::Total(Duration::from_millis(200)))
+            .build();
+
+        let result = client.upload_to_ipfs(b"payload", None, false).await;
+
+        match result {
+            Err(StorageError::UploadTimeout(msg)) => {
+                assert!(msg.contains("total"), "unexpected message: {msg}");
+            }
+            other => panic!("expected UploadTimeout, got {other:?}"),
+        }
+    }
+
+    /// The idle policy aborts a stalled upload once no bytes have moved for the
+    /// idle window. The tiny body is flushed to the socket immediately, then the
+    /// server never responds, so the idle watchdog trips.
+    #[tokio::test]
+    async fn upload_idle_timeout_fires_on_stalled_server() {
+        let url = start_stalling_server().await;
+        let client = AlephClient::builder(url)
+            .upload_timeout(UploadTimeout::Idle(Duration::from_millis(200)))
+            .build();
+
+        let start = std::time::Instant::now();
+        let result = client.upload_to_ipfs(b"payload", None, false).await;
+        let elapsed = start.elapsed();
+
+        match result {
+            Err(StorageError::UploadTimeout(msg)) => {
+                assert!(msg.contains("no data sent"), "unexpected message: {msg}");
+            }
+            other => panic!("expected UploadTimeout, got {other:?}"),
+        }
+        // The server holds the connection for an hour; returning well under that
+        // proves the idle watchdog fired. Generous margin for loaded CI runners.
+        assert!(
+            elapsed < Duration::from_secs(60),
+            "should bail out via the idle timeout (took {elapsed:?})"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_fires_on_unreachable_host() {
+        // 192.0.2.1 is TEST-NET-1 (RFC 5737) — routable nowhere, will time out.
+        let client = AlephClient::builder(Url::parse("http://192.0.2.1:1").unwrap())
+            .timeout_config(TimeoutConfig {
+                connect_timeout: Duration::from_millis(200),
+                request_timeout: Some(Duration::from_secs(120)),
+                ..Default::default()
+            })
+            .retry_config(RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build();
+
+        let start = std::time::Instant::now();
+        let hash = aleph_types::item_hash!(
+            "0000000000000000000000000000000000000000000000000000000000000000"
+        );
+        let result = client.get_message(&hash).await;
+        let elapsed = start.elapsed();
+
+        assert!(result.is_err(), "should fail with connect timeout");
+        // Returning well under the 120s request budget proves the 200ms connect
+        // timeout is what fired. The margin is generous because loaded CI runners
+        // (especially Windows) can starve the single-threaded test runtime for
+        // several seconds before the timer is serviced.
+        assert!(
+            elapsed < Duration::from_secs(30),
+            "should bail out via the connect timeout (took {elapsed:?})"
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Messages as served by the CCN, with their status.
+
+use super::MessageError;
+use aleph_types::chain::{Address, Chain, Signature};
+use aleph_types::channel::Channel;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::item_type::ItemType;
+use aleph_types::message::{
+    ContentSource, InstanceContent, Message, MessageStatus, MessageType, PostContent,
+    ProgramContent, StoreContent, TypedContent,
+};
+use aleph_types::timestamp::Timestamp;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalReason {
+    BalanceInsufficient,
+    CreditInsufficient,
+}
+
+/// A pending message as returned by the CCN API (message status endpoint).
+/// Not to be confused with `aleph_types::message::pending::PendingMessage`
+/// which is the outgoing message type used for submission.
+///
+/// Time is typed as `DateTime<Utc>` (rather than `Timestamp`) because the CCN
+/// emits ISO-8601 datetime strings for pending messages, like `ForgottenMessage`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawPendingMessage {
+    pub sender: Address,
+    pub chain: Chain,
+    pub signature: Option<Signature>,
+    #[serde(flatten)]
+    pub content_source: ContentSource,
+    #[serde(rename = "type")]
+    pub message_type: MessageType,
+    pub item_hash: ItemHash,
+    pub time: DateTime<Utc>,
+    pub channel: Option<Channel>,
+    pub content: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RejectedMessage {
+    pub sender: Address,
+    pub chain: Chain,
+    pub signature: Option<Signature>,
+    #[serde(rename = "type")]
+    pub message_type: MessageType,
+    pub item_type: ItemType,
+    #[serde(default)]
+    pub item_content: Option<String>,
+    pub item_hash: ItemHash,
+    pub time: Timestamp,
+    pub channel: Option<Channel>,
+    pub content: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForgottenMessage {
+    pub sender: Address,
+    pub chain: Chain,
+    pub signature: Option<Signature>,
+    #[serde(rename = "type")]
+    pub message_type: MessageType,
+    pub item_hash: ItemHash,
+    pub time: DateTime<Utc>,
+    pub channel: Option<Channel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum MessageWithStatus<M> {
+    // More than one message with the same item hash can be pending at the same time.
+    Pending {
+        messages: Vec<RawPendingMessage>,
+    },
+    Processed {
+        message: M,
+    },
+    Removing {
+        message: M,
+        reason: RemovalReason,
+    },
+    Removed {
+        message: M,
+        reason: RemovalReason,
+    },
+    Forgotten {
+        message: ForgottenMessage,
+        forgotten_by: Vec<ItemHash>,
+    },
+    Rejected {
+        message: RejectedMessage,
+        error_code: i64,
+    },
+}
+
+impl<M> MessageWithStatus<M> {
+    pub fn status(&self) -> MessageStatus {
+        match self {
+            MessageWithStatus::Pending { .. } => MessageStatus::Pending,
+            MessageWithStatus::Processed { .. } => MessageStatus::Processed,
+            MessageWithStatus::Removing { .. } => MessageStatus::Removing,
+            MessageWithStatus::Removed { .. } => MessageStatus::Removed,
+            MessageWithStatus::Forgotten { .. } => MessageStatus::Forgotten,
+            MessageWithStatus::Rejected { .. } => MessageStatus::Rejected,
+        }
+    }
+
+    /// Applies a fallible transformation to the message in variants that carry one
+    /// (Processed, Removing, Removed). Other variants are passed through unchanged.
+    pub async fn try_map_message_async<N, E, F, Fut>(self, f: F) -> Result<MessageWithStatus<N>, E>
+    where
+        F: FnOnce(M) -> Fut,
+        Fut: Future<Output = Result<N, E>>,
+    {
+        Ok(match self {
+            MessageWithStatus::Processed { message } => MessageWithStatus::Processed {
+                message: f(message).await?,
+            },
+            MessageWithStatus::Removing { message, reason } => MessageWithStatus::Removing {
+                message: f(message).await?,
+                reason,
+            },
+            MessageWithStatus::Removed { message, reason } => MessageWithStatus::Removed {
+                message: f(message).await?,
+                reason,
+            },
+            MessageWithStatus::Pending { messages } => MessageWithStatus::Pending { messages },
+            MessageWithStatus::Forgotten {
+                message,
+                forgotten_by,
+            } => MessageWithStatus::Forgotten {
+                message,
+                forgotten_by,
+            },
+            MessageWithStatus::Rejected {
+                message,
+                error_code,
+            } => MessageWithStatus::Rejected {
+                message,
+                error_code,
+            },
+        })
+    }
+}
+
+/// A message known to carry content of type `C`, with its status.
+///
+/// Returned by [`AlephMessageClient::get_typed_message`](super::AlephMessageClient::get_typed_message)
+/// and its per-type shorthands
+/// ([`get_program`](super::AlephMessageClient::get_program), ...). Only
+/// statuses that carry the full message (processed, removing, removed) can be
+/// represented.
+#[derive(Debug)]
+pub struct TypedMessage<C> {
+    message: Message,
+    status: MessageStatus,
+    content_type: PhantomData<fn() -> C>,
+}
+
+pub type ProgramMessage = TypedMessage<ProgramContent>;
+
+pub type InstanceMessage = TypedMessage<InstanceContent>;
+
+pub type StoreMessage = TypedMessage<StoreContent>;
+
+pub type PostMessage = TypedMessage<PostContent>;
+
+impl<C: TypedContent> TypedMessage<C> {
+    /// Checks that `message` has a full message of type `C`.
+    ///
+    /// Fails with [`MessageError::InvalidType`] on a type mismatch and with
+    /// [`MessageError::UnexpectedStatus`] if the message is pending, forgotten
+    /// or rejected.
+    pub fn try_from_message(
+        item_hash: &ItemHash,
+        message: MessageWithStatus<Message>,
+    ) -> Result<Self, MessageError> {
+        let status = message.status();
+        let message = match message {
+            MessageWithStatus::Processed { message }
+            | MessageWithStatus::Removing { message, .. }
+            | MessageWithStatus::Removed { message, .. } => message,
+            _ => {
+                return Err(MessageError::UnexpectedStatus {
+                    item_hash: item_hash.clone(),
+                    expected: MessageStatus::Processed,
+                    actual: status,
+                });
+            }
+        };
+        if message.typed_content::<C>().is_none() {
+            return Err(MessageError::InvalidType {
+                item_hash: item_hash.clone(),
+                expected: C::MESSAGE_TYPE,
+                actual: message.message_type,
+            });
+        }
+        Ok(Self {
+            message,
+            status,
+            content_type: PhantomData,
+        })
+    }
+
+    pub fn content(&self) -> &C {
+        self.message
+            .typed_content()
+            .expect("content type is checked on construction")
+    }
+
+    /// Drops the envelope and returns the content.
+    pub fn into_content(self) -> C {
+        match C::try_from_content(self.message.content.content) {
+            Ok(content) => content,
+            Err(_) => unreachable!("content type is checked on construction"),
+        }
+    }
+}
+
+impl<C> TypedMessage<C> {
+    pub fn status(&self) -> &MessageStatus {
+        &self.status
+    }
+
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    pub fn into_message(self) -> Message {
+        self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{AlephClient, AlephMessageClient};
+    use crate::test_utils::processed_post_json;
+    use aleph_types::{address, channel, item_hash};
+    use serde_json::json;
+    use std::str::FromStr;
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const FORGOTTEN_MESSAGE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../fixtures/api-responses/forgotten-message.json"
+    ));
+
+    const PENDING_MESSAGE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../fixtures/api-responses/pending-message.json"
+    ));
+
+    #[test]
+    fn test_deserialize_pending_message() {
+        let message: MessageWithStatus<Message> = serde_json::from_str(PENDING_MESSAGE).unwrap();
+
+        match message {
+            MessageWithStatus::Pending { messages } => {
+                assert_eq!(messages.len(), 1);
+                let pending = &messages[0];
+                assert_eq!(pending.chain, Chain::Ethereum);
+                assert_eq!(
+                    pending.item_hash,
+                    item_hash!("cab98cd9e1f957bd99259acff3eb35d960436121c7f567a2c9cb941c24e0c01b")
+                );
+                assert_eq!(
+                    pending.sender,
+                    address!("0x4D52380D3191274a04846c89c069E6C3F2Ed94e4")
+                );
+                assert_eq!(pending.message_type, MessageType::Post);
+                assert_eq!(pending.content_source, ContentSource::Storage);
+                assert_eq!(pending.channel, Some(channel!("aleph-scoring")));
+                assert!(pending.content.is_none());
+            }
+            _ => panic!("Expected Pending variant"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_forgotten_message() {
+        let message: MessageWithStatus<Message> = serde_json::from_str(FORGOTTEN_MESSAGE).unwrap();
+
+        match message {
+            MessageWithStatus::Forgotten {
+                message,
+                forgotten_by,
+            } => {
+                assert_eq!(message.chain, Chain::Ethereum);
+                assert_eq!(
+                    message.item_hash,
+                    item_hash!("821d7b01866bdfafc8d07539d6191061ab5858dfbfcab046d7b799e5e01da51f")
+                );
+                assert_eq!(
+                    message.sender,
+                    address!("0xCBD8c644d5628DB7Fd6D600681E15bcF82d79274")
+                );
+                assert_eq!(message.message_type, MessageType::Store);
+                assert_eq!(message.channel, Some(channel!("TEST")));
+
+                assert_eq!(forgotten_by.len(), 1);
+                assert_eq!(
+                    forgotten_by[0],
+                    item_hash!("3292ebfacccf1315ad21615101661b147dabfb2e1f97d7c46262a528a3e22852")
+                );
+            }
+            _ => panic!("Expected Forgotten variant"),
+        }
+    }
+
+    #[tokio::test]
+    async fn get_message_status_uses_status_endpoint() {
+        let server = MockServer::start().await;
+        let hash = ItemHash::from([7u8; 32]);
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}/status")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "processed",
+                "item_hash": hash.to_string(),
+                "reception_time": "2024-01-01T00:00:00Z",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let status = client.get_message_status(&hash).await.unwrap();
+        assert_eq!(status, MessageStatus::Processed);
+    }
+
+    #[tokio::test]
+    async fn get_message_status_maps_404_to_not_found() {
+        let server = MockServer::start().await;
+        let hash = ItemHash::from([7u8; 32]);
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}/status")))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let err = client.get_message_status(&hash).await.unwrap_err();
+        assert!(matches!(err, MessageError::NotFound(h) if h == hash));
+    }
+
+    async fn client_serving(hash: &ItemHash, body: serde_json::Value) -> (MockServer, AlephClient) {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn get_post_returns_typed_content() {
+        let hash = ItemHash::from([3u8; 32]);
+        let (_server, client) = client_serving(&hash, processed_post_json(&hash)).await;
+
+        let post = client.get_post(&hash).await.unwrap();
+        assert_eq!(post.status(), &MessageStatus::Processed);
+        assert_eq!(post.content().post_type, "chat");
+    }
+
+    #[tokio::test]
+    async fn get_message_as_returns_content_or_invalid_type() {
+        let hash = ItemHash::from([3u8; 32]);
+        let (_server, client) = client_serving(&hash, processed_post_json(&hash)).await;
+
+        let post = client.get_message_as::<PostContent>(&hash).await.unwrap();
+        assert_eq!(post.post_type, "chat");
+        assert!(matches!(
+            client.get_message_as::<ProgramContent>(&hash).await,
+            Err(MessageError::InvalidType {
+                expected: MessageType::Program,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_program_rejects_other_types() {
+        let hash = ItemHash::from([3u8; 32]);
+        let (_server, client) = client_serving(&hash, processed_post_json(&hash)).await;
+
+        let err = client.get_program(&hash).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MessageError::InvalidType {
+                expected: MessageType::Program,
+                actual: MessageType::Post,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_store_rejects_forgotten_messages() {
+        let hash =
+            ItemHash::from_str("821d7b01866bdfafc8d07539d6191061ab5858dfbfcab046d7b799e5e01da51f")
+                .unwrap();
+        let body: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../fixtures/api-responses/forgotten-message.json"
+        )))
+        .unwrap();
+        let (_server, client) = client_serving(&hash, body).await;
+
+        let err = client.get_store(&hash).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MessageError::UnexpectedStatus {
+                actual: MessageStatus::Forgotten,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,1510 @@
+//! File downloads and uploads to the storage and IPFS endpoints.
+
+use super::error::CheckStatus;
+use super::{AlephClient, AlephStorageClient, MessageError, StorageError};
+use crate::response_limit::{ResponseTooLarge, limit_body};
+use crate::upload_timeout::{UploadActivity, bytes_stream, run_upload, track_activity};
+use crate::verify::Hasher;
+use aleph_types::chain::Address;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::StorageEngine;
+use aleph_types::message::pending::PendingMessage;
+use aleph_types::message::{FileRef, Message, RawFileRef, StoreContent};
+use futures_util::StreamExt;
+use memsizes::Bytes;
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::Instrument;
+use url::Url;
+
+/// Read a file in chunks and compute its hash using the given Hasher.
+#[tracing::instrument(level = "debug", skip_all, fields(path = %path.display()))]
+pub async fn hash_file(
+    path: &std::path::Path,
+    mut hasher: Hasher,
+) -> Result<ItemHash, StorageError> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buf = vec![0u8; 64 * 1024]; // 64 KiB chunks
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let item_hash = hasher.finalize();
+    tracing::debug!(%item_hash, "file hashed");
+    Ok(item_hash)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileMetadata {
+    #[serde(rename = "ref")]
+    pub reference: RawFileRef,
+    pub owner: Address,
+    pub file_hash: ItemHash,
+    pub size: Bytes,
+}
+
+/// Everything known about a stored file, see
+/// [`AlephStorageClient::get_stored_file_metadata`].
+#[derive(Debug)]
+pub struct StoredFileMetadata {
+    pub file_hash: ItemHash,
+    /// Native storage for SHA-256 hashes, IPFS for CIDs.
+    pub engine: StorageEngine,
+    pub size: Option<Bytes>,
+    pub content_type: Option<String>,
+    /// The earliest STORE message pinning the file, if any.
+    pub store_message: Option<Message>,
+}
+
+/// Content type served for files whose type the CCN does not know.
+pub(super) const GENERIC_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Number of leading bytes inspected by [`sniff_content_type`].
+const SNIFF_LEN: usize = 512;
+
+/// Guesses a content type from the first bytes of a file: common binary
+/// formats by their magic number, then JSON and plain text.
+fn sniff_content_type(head: &[u8]) -> Option<&'static str> {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\0asm", "application/wasm"),
+        (b"hsqs", "application/vnd.squashfs"),
+    ];
+    if let Some((_, content_type)) = MAGIC.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return Some(*content_type);
+    }
+    if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+
+    // The sample may end in the middle of a multi-byte character.
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).ok()?,
+        Err(_) => return None,
+    };
+    if text.is_empty() || text.contains('\0') {
+        return None;
+    }
+    let trimmed = text.trim_start();
+    let lower = trimmed
+        .chars()
+        .take(16)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if trimmed.starts_with(['{', '[']) {
+        Some("application/json")
+    } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        Some("text/html")
+    } else {
+        Some("text/plain")
+    }
+}
+
+/// Reads the first bytes of a file to guess its type. Errors yield `None`.
+pub(super) async fn sniff_file_content_type<C: AlephStorageClient + Sync + ?Sized>(
+    client: &C,
+    file_hash: &ItemHash,
+) -> Option<&'static str> {
+    // Only the sample is read: the rest of the body is dropped with the stream.
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    let mut body = client
+        .download_file_by_hash(file_hash)
+        .await
+        .ok()?
+        .into_stream();
+    while head.len() < SNIFF_LEN
+        && let Some(chunk) = body.next().await
+    {
+        head.extend_from_slice(&chunk.ok()?);
+    }
+    head.truncate(SNIFF_LEN);
+    sniff_content_type(&head)
+}
+
+/// Callback reporting the bytes received so far and, if the server announced
+/// it, the total size of the file.
+pub type DownloadProgress = Box<dyn FnMut(u64, Option<u64>) + Send>;
+
+pub struct FileDownload {
+    response: reqwest::Response,
+    expected_hash: ItemHash,
+    verify: bool,
+    max_size: Option<u64>,
+    progress: Option<DownloadProgress>,
+}
+
+impl FileDownload {
+    pub(crate) fn new(response: reqwest::Response, expected_hash: ItemHash) -> Self {
+        Self {
+            response,
+            expected_hash,
+            verify: false,
+            max_size: None,
+            progress: None,
+        }
+    }
+
+    /// Calls `progress` after each chunk written by
+    /// [`to_writer()`](Self::to_writer) or [`to_file()`](Self::to_file).
+    pub fn with_progress(
+        mut self,
+        progress: impl FnMut(u64, Option<u64>) + Send + 'static,
+    ) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Fails the download once more than `max_bytes` have been received.
+    ///
+    /// [`bytes()`](Self::bytes) and [`to_file()`](Self::to_file) fail upfront
+    /// with [`MessageError::ResponseTooLarge`] if the server announces a
+    /// larger size. Otherwise, and with [`into_stream()`](Self::into_stream),
+    /// the body errors out as soon as it crosses the limit.
+    pub fn with_max_size(mut self, max_bytes: u64) -> Self {
+        self.max_size = Some(max_bytes);
+        self
+    }
+
+    /// The response, with its body capped to `max_size` if set.
+    fn limited_response(
+        response: reqwest::Response,
+        max_size: Option<u64>,
+    ) -> Result<reqwest::Response, ResponseTooLarge> {
+        let Some(limit) = max_size else {
+            return Ok(response);
+        };
+        match response.content_length() {
+            Some(content_length) if content_length > limit => Err(ResponseTooLarge {
+                url: response.url().clone(),
+                limit,
+                content_length: Some(content_length),
+            }),
+            _ => Ok(limit_body(response, limit)),
+        }
+    }
+
+    /// Enables integrity verification for this download.
+    ///
+    /// When enabled, [`bytes()`](Self::bytes) and [`to_file()`](Self::to_file) will verify the
+    /// downloaded content matches the expected hash. Note that [`to_file()`](Self::to_file) writes
+    /// data to disk as it streams — if verification fails, the partial file remains on disk and the
+    /// caller is responsible for cleanup.
+    ///
+    /// Verification is **not** applied by [`into_stream()`](Self::into_stream).
+    pub fn with_verification(mut self) -> Self {
+        self.verify = true;
+        self
+    }
+
+    pub async fn bytes(self) -> Result<bytes::Bytes, MessageError> {
+        let content = Self::limited_response(self.response, self.max_size)?
+            .bytes()
+            .await
+            .map_err(reqwest_middleware::Error::from)
+            .map_err(MessageError::from)?;
+
+        if self.verify {
+            let mut verifier = crate::verify::HashVerifier::new(&self.expected_hash)
+                .map_err(StorageError::IntegrityError)?;
+            verifier.update(&content);
+            verifier.finalize().map_err(StorageError::IntegrityError)?;
+        }
+
+        Ok(content)
+    }
+
+    pub async fn to_file(self, path: impl AsRef<std::path::Path>) -> Result<(), MessageError> {
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(MessageError::Io)?;
+        self.to_writer(&mut file).await?;
+        Ok(())
+    }
+
+    /// Streams the file into `writer` without buffering it in memory, and
+    /// returns the number of bytes written.
+    ///
+    /// As with [`to_file()`](Self::to_file), a failed verification leaves
+    /// the written data in place.
+    pub async fn to_writer<W>(mut self, writer: &mut W) -> Result<u64, MessageError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let total = self.response.content_length();
+        let response = Self::limited_response(self.response, self.max_size)?;
+        let mut stream = response.bytes_stream();
+        let mut written = 0u64;
+
+        let mut verifier = if self.verify {
+            Some(
+                crate::verify::HashVerifier::new(&self.expected_hash)
+                    .map_err(StorageError::IntegrityError)?,
+            )
+        } else {
+            None
+        };
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(reqwest_middleware::Error::from)?;
+            if let Some(ref mut v) = verifier {
+                v.update(&chunk);
+            }
+            writer.write_all(&chunk).await.map_err(MessageError::Io)?;
+            written += chunk.len() as u64;
+            if let Some(progress) = self.progress.as_mut() {
+                progress(written, total);
+            }
+        }
+        writer.flush().await.map_err(MessageError::Io)?;
+
+        if let Some(v) = verifier {
+            v.finalize().map_err(StorageError::IntegrityError)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Returns the raw response body as a byte stream.
+    ///
+    /// Verification is **not** performed; the `with_verification()` flag is ignored.
+    pub fn into_stream(
+        self,
+    ) -> impl futures_util::Stream<Item = Result<bytes::Bytes, reqwest::Error>> {
+        match self.max_size {
+            Some(limit) => limit_body(self.response, limit).bytes_stream(),
+            None => self.response.bytes_stream(),
+        }
+    }
+}
+
+impl AlephClient {
+    /// Public URL the file of a STORE message can be downloaded from.
+    ///
+    /// See [`file_hash_url`](Self::file_hash_url).
+    pub fn file_url(&self, store: &StoreContent) -> Url {
+        self.file_hash_url(&store.file_hash())
+    }
+
+    /// Public URL a file can be downloaded from: the CCN's raw storage
+    /// endpoint for native files, the configured IPFS gateway for CIDs.
+    pub fn file_hash_url(&self, file_hash: &ItemHash) -> Url {
+        match file_hash {
+            ItemHash::Native(_) => self
+                .inner
+                .ccn_url
+                .join(&format!("/api/v0/storage/raw/{file_hash}")),
+            ItemHash::Ipfs(_) => self.inner.ipfs_gateway.join(&format!("/ipfs/{file_hash}")),
+        }
+        .unwrap_or_else(|e| panic!("invalid url: {e}"))
+    }
+
+    /// Send a prepared upload request under the configured [`UploadTimeout`]
+    /// policy, mapping a policy abort to [`StorageError::UploadTimeout`] and a
+    /// transport error to [`StorageError::UploadFailed`].
+    ///
+    /// `activity` must be the counter feeding the request body (via
+    /// [`track_activity`]); for [`UploadTimeout::Idle`] the watchdog observes
+    /// progress through it. When the body carries no activity signal, `Idle(d)`
+    /// degrades to a total deadline of `d`.
+    async fn send_upload(
+        &self,
+        request: reqwest::RequestBuilder,
+        activity: UploadActivity,
+    ) -> Result<Response, StorageError> {
+        let upload_failed = |e| StorageError::UploadFailed(reqwest_middleware::Error::from(e));
+        let (client, request) = request.build_split();
+        let request = request.map_err(upload_failed)?;
+        // The upload client has no middleware: trace the request here.
+        let span =
+            tracing::debug_span!("ccn_upload", method = %request.method(), url = %request.url());
+        async move {
+            let response = run_upload(self.inner.upload_timeout, activity, client.execute(request))
+                .await
+                .map_err(|fired| StorageError::UploadTimeout(fired.to_string()))?
+                .map_err(upload_failed);
+            match &response {
+                Ok(r) => tracing::debug!(status = r.status().as_u16(), "upload response received"),
+                Err(e) => tracing::debug!(error = %e, "upload failed"),
+            }
+            response
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    hash: String,
+}
+
+/// Classifies an HTTP error response from the storage / IPFS upload
+/// endpoints into the right `StorageError` variant.
+///
+/// Returns `None` for success codes and for unmapped non-success codes
+/// (which the caller maps to `UploadRejected`).
+fn classify_status_and_body(status: reqwest::StatusCode, body: &str) -> Option<StorageError> {
+    use reqwest::StatusCode;
+    match status {
+        StatusCode::PAYMENT_REQUIRED => Some(StorageError::InsufficientBalance),
+        StatusCode::FORBIDDEN if body.contains("IPFS is disabled on this node") => {
+            Some(StorageError::IpfsDisabled)
+        }
+        StatusCode::FORBIDDEN => Some(StorageError::InvalidSignature),
+        StatusCode::PAYLOAD_TOO_LARGE => Some(StorageError::FileTooLarge),
+        StatusCode::UNPROCESSABLE_ENTITY => {
+            if body.contains("Root CID does not match")
+                && let Some((car_root, metadata_root)) = parse_cid_pair(body)
+            {
+                return Some(StorageError::CarHeaderRootMismatch {
+                    car_root,
+                    metadata_root,
+                });
+            }
+            if body.contains("Imported root does not match expected")
+                && let Some((kubo_root, expected_root)) = parse_cid_pair(body)
+            {
+                return Some(StorageError::ImportedRootMismatch {
+                    kubo_root,
+                    expected_root,
+                });
+            }
+            Some(StorageError::InvalidMetadata(body.to_string()))
+        }
+        StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT => {
+            Some(StorageError::IpfsBackendUnavailable(body.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Parse `"... (A != B)..."` and return (A, B). Tolerates surrounding text.
+fn parse_cid_pair(body: &str) -> Option<(String, String)> {
+    let open = body.find('(')?;
+    let close = body[open..].find(')')?;
+    let inner = &body[open + 1..open + close];
+    let (a, b) = inner.split_once(" != ")?;
+    Some((a.trim().to_string(), b.trim().to_string()))
+}
+
+/// Inspects an upload response: returns the success response unchanged,
+/// or maps non-success status codes to a `StorageError`.
+///
+/// 403, 422, 502, and 504 require body inspection; other statuses do not.
+/// Unmapped non-success codes fall back to `UploadRejected`, with the body of
+/// the answer.
+async fn handle_storage_response(
+    response: reqwest::Response,
+) -> Result<reqwest::Response, StorageError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(
+        classify_status_and_body(status, &body).unwrap_or(StorageError::UploadRejected {
+            status: status.as_u16(),
+            body,
+        }),
+    )
+}
+
+/// Serializes the storage upload metadata field as JSON.
+///
+/// Both the storage and IPFS authenticated upload endpoints accept the
+/// same `metadata` multipart field: a JSON object with the signed STORE
+/// message and a `sync` flag.
+fn serialize_storage_metadata(message: &PendingMessage, sync: bool) -> String {
+    serde_json::json!({"message": message, "sync": sync}).to_string()
+}
+
+/// Build a multipart part from an in-memory buffer, streamed in chunks so the
+/// upload idle-timeout watchdog sees incremental progress via `activity`.
+fn tracked_bytes_part(
+    data: Vec<u8>,
+    file_name: &str,
+    mime: &str,
+    activity: &UploadActivity,
+) -> reqwest::multipart::Part {
+    let len = data.len() as u64;
+    let body = reqwest::Body::wrap_stream(track_activity(bytes_stream(data), activity.clone()));
+    reqwest::multipart::Part::stream_with_length(body, len)
+        .file_name(file_name.to_string())
+        .mime_str(mime)
+        .expect("valid mime type")
+}
+
+/// Build a multipart part that streams `path` from disk, bumping `activity` as
+/// chunks are consumed (for the idle-timeout watchdog) and optionally invoking a
+/// user progress callback. `Content-Length` is preserved.
+async fn tracked_file_part(
+    path: &std::path::Path,
+    file_name: String,
+    activity: &UploadActivity,
+    progress: Option<Box<dyn FnMut(u64, u64) + Send>>,
+) -> std::io::Result<reqwest::multipart::Part> {
+    let total = tokio::fs::metadata(path).await?.len();
+    let file = tokio::fs::File::open(path).await?;
+    let stream = track_activity(tokio_util::io::ReaderStream::new(file), activity.clone());
+    let body = match progress {
+        Some(on_tick) => reqwest::Body::wrap_stream(crate::progress::report_upload_progress(
+            stream, total, on_tick,
+        )),
+        None => reqwest::Body::wrap_stream(stream),
+    };
+    Ok(reqwest::multipart::Part::stream_with_length(body, total)
+        .file_name(file_name)
+        .mime_str("application/octet-stream")
+        .expect("valid mime type"))
+}
+
+fn build_storage_metadata_part(message: &PendingMessage, sync: bool) -> reqwest::multipart::Part {
+    reqwest::multipart::Part::text(serialize_storage_metadata(message, sync))
+        .mime_str("application/json")
+        .expect("application/json is a valid mime type")
+}
+
+/// Read a CARv1 body file fully and chain it after the in-memory header
+/// bytes, returning a single `reqwest::Body`.
+///
+/// The CARv1 producer in `upload_folder_to_ipfs_authenticated` writes the
+/// block-frame section to a tempfile during the DAG walk; this helper
+/// prepends the header (built in memory after the walk completes) and
+/// hands the combined body to reqwest. Buffers the whole CAR into memory
+/// at upload time; streaming-first is a future optimization (the 4 GiB
+/// server cap bounds memory usage).
+async fn build_car_upload_body(
+    header_bytes: Vec<u8>,
+    car_body_path: &std::path::Path,
+) -> std::io::Result<Vec<u8>> {
+    use tokio::io::AsyncReadExt;
+    let file_size = tokio::fs::metadata(car_body_path).await?.len() as usize;
+    let mut file = tokio::fs::File::open(car_body_path).await?;
+    let mut body = Vec::with_capacity(header_bytes.len() + file_size);
+    body.extend_from_slice(&header_bytes);
+    file.read_to_end(&mut body).await?;
+    Ok(body)
+}
+
+/// Extract the file CID from a STORE `PendingMessage`.
+///
+/// For STORE messages the file hash is embedded in `item_content` as the
+/// `"item_hash"` JSON field, not in `PendingMessage::item_hash` (which is the
+/// SHA-256 of the serialized message body). This helper deserializes that JSON
+/// and returns the file's `ItemHash`.
+fn extract_message_item_hash(message: &PendingMessage) -> Result<ItemHash, StorageError> {
+    let content: serde_json::Value = serde_json::from_str(&message.item_content).map_err(|e| {
+        StorageError::InvalidMetadata(format!("STORE message item_content is not JSON: {e}"))
+    })?;
+    let raw = content
+        .get("item_hash")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            StorageError::InvalidMetadata("STORE message item_content is missing item_hash".into())
+        })?;
+    raw.parse::<ItemHash>().map_err(|source| {
+        StorageError::InvalidMetadata(format!(
+            "STORE message item_hash '{}' is not a valid ItemHash: {}",
+            raw, source,
+        ))
+    })
+}
+
+impl AlephStorageClient for AlephClient {
+    async fn get_file_size(&self, file_hash: &ItemHash) -> Result<Bytes, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/storage/raw/{}", file_hash))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .head(url)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+        let headers = response.headers();
+        let content_length = headers
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok());
+
+        content_length
+            .ok_or_else(|| StorageError::NotFound(file_hash.clone()))
+            .and_then(|s| {
+                s.parse::<u64>()
+                    .map(Bytes::from)
+                    .map_err(|source| StorageError::InvalidSize {
+                        value: s.to_string(),
+                        source,
+                    })
+            })
+            .map_err(MessageError::Storage)
+    }
+
+    async fn get_file_metadata_by_message_hash(
+        &self,
+        message_hash: &ItemHash,
+    ) -> Result<FileMetadata, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/storage/by-message-hash/{}", message_hash))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self.inner.http_client.get(url).send().await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(MessageError::NotFound(message_hash.clone()));
+        }
+        let response = response.check_status().await?;
+
+        let file_metadata: FileMetadata = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(file_metadata)
+    }
+
+    async fn get_file_metadata_by_ref(
+        &self,
+        file_ref: &FileRef,
+    ) -> Result<FileMetadata, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/storage/by-ref/{}", file_ref))
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self.inner.http_client.get(url).send().await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(StorageError::RefNotFound(file_ref.clone()).into());
+        }
+        let response = response.check_status().await?;
+
+        let file_metadata: FileMetadata = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(file_metadata)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(item_hash = %file_hash))]
+    async fn download_file_by_hash(
+        &self,
+        file_hash: &ItemHash,
+    ) -> Result<FileDownload, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join(&format!("/api/v0/storage/raw/{}", file_hash))
+            .map_err(StorageError::InvalidUrl)?;
+
+        let response = self.inner.http_client.get(url).send().await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(StorageError::NotFound(file_hash.clone()).into());
+        }
+        let response = response.check_status().await?;
+
+        Ok(FileDownload::new(response, file_hash.clone()))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(size = data.len(), sync = sync))]
+    async fn upload_to_storage(
+        &self,
+        data: &[u8],
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> Result<ItemHash, StorageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/storage/add_file")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let activity = UploadActivity::new();
+        let part = tracked_bytes_part(
+            data.to_vec(),
+            "upload",
+            "application/octet-stream",
+            &activity,
+        );
+        let mut form = reqwest::multipart::Form::new().part("file", part);
+
+        if let Some(msg) = message {
+            form = form.part("metadata", build_storage_metadata_part(msg, sync));
+        }
+
+        // Use the plain client — multipart bodies are not cloneable, so the
+        // retry middleware would fail with "Request object is not cloneable".
+        let response = self
+            .send_upload(self.inner.upload_client.post(url).multipart(form), activity)
+            .await?;
+
+        let response = handle_storage_response(response).await?;
+
+        let upload: UploadResponse = response
+            .json()
+            .await
+            .map_err(StorageError::InvalidResponseBody)?;
+
+        upload
+            .hash
+            .parse::<ItemHash>()
+            .map_err(|source| StorageError::InvalidResponseHash {
+                value: upload.hash.clone(),
+                source,
+            })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(size = data.len(), sync = sync))]
+    async fn upload_to_ipfs(
+        &self,
+        data: &[u8],
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> Result<ItemHash, StorageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/ipfs/add_file")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let activity = UploadActivity::new();
+        let part = tracked_bytes_part(
+            data.to_vec(),
+            "upload",
+            "application/octet-stream",
+            &activity,
+        );
+        let mut form = reqwest::multipart::Form::new().part("file", part);
+
+        if let Some(msg) = message {
+            form = form.part("metadata", build_storage_metadata_part(msg, sync));
+        }
+
+        // Use the plain client — multipart bodies are not cloneable, so the
+        // retry middleware would fail with "Request object is not cloneable".
+        let response = self
+            .send_upload(self.inner.upload_client.post(url).multipart(form), activity)
+            .await?;
+
+        let response = handle_storage_response(response).await?;
+
+        let upload: UploadResponse = response
+            .json()
+            .await
+            .map_err(StorageError::InvalidResponseBody)?;
+
+        upload
+            .hash
+            .parse::<ItemHash>()
+            .map_err(|source| StorageError::InvalidResponseHash {
+                value: upload.hash.clone(),
+                source,
+            })
+    }
+
+    async fn upload_file_to_storage(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> Result<ItemHash, StorageError> {
+        self.upload_file_streaming(
+            "/api/v0/storage/add_file",
+            Hasher::for_storage(),
+            path.as_ref(),
+            message,
+            sync,
+            None,
+        )
+        .await
+    }
+
+    async fn upload_file_to_ipfs(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> Result<ItemHash, StorageError> {
+        self.upload_file_streaming(
+            "/api/v0/ipfs/add_file",
+            Hasher::for_ipfs(),
+            path.as_ref(),
+            message,
+            sync,
+            None,
+        )
+        .await
+    }
+}
+
+impl AlephClient {
+    /// Shared body for [`AlephStorageClient::upload_file_to_storage`] /
+    /// `upload_file_to_ipfs` and their `_with_progress` variants.
+    ///
+    /// The file is always streamed from disk through an activity tracker (so the
+    /// upload idle timeout observes progress). With `Some(on_tick)` it is also
+    /// wrapped in [`crate::progress::report_upload_progress`] so the caller
+    /// observes upload progress. In both cases the locally-computed hash is
+    /// verified against the server's response.
+    #[tracing::instrument(
+        level = "debug",
+        skip(self, hasher, path, message, progress),
+        fields(path = %path.display())
+    )]
+    async fn upload_file_streaming(
+        &self,
+        endpoint_path: &str,
+        hasher: Hasher,
+        path: &std::path::Path,
+        message: Option<&PendingMessage>,
+        sync: bool,
+        progress: Option<Box<dyn FnMut(u64, u64) + Send>>,
+    ) -> Result<ItemHash, StorageError> {
+        // Pass 1: stream file to compute the content hash locally.
+        let local_hash = hash_file(path, hasher).await?;
+
+        // Pass 2: upload the file.
+        let url = self
+            .inner
+            .ccn_url
+            .join(endpoint_path)
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        // Always stream from disk through an activity tracker so the upload
+        // idle-timeout watchdog sees progress, layering the optional user
+        // progress callback on top. `tracked_file_part` keeps Content-Length set.
+        let activity = UploadActivity::new();
+        let file_name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("file")
+            .to_string();
+        let part = tracked_file_part(path, file_name, &activity, progress).await?;
+        let mut form = reqwest::multipart::Form::new().part("file", part);
+
+        if let Some(msg) = message {
+            form = form.part("metadata", build_storage_metadata_part(msg, sync));
+        }
+
+        let response = self
+            .send_upload(self.inner.upload_client.post(url).multipart(form), activity)
+            .await?;
+
+        let response = handle_storage_response(response).await?;
+
+        let upload: UploadResponse = response
+            .json()
+            .await
+            .map_err(StorageError::InvalidResponseBody)?;
+
+        let server_hash = upload.hash.parse::<ItemHash>().map_err(|source| {
+            StorageError::InvalidResponseHash {
+                value: upload.hash.clone(),
+                source,
+            }
+        })?;
+
+        if local_hash != server_hash {
+            tracing::warn!(%local_hash, %server_hash, "uploaded file hash mismatch");
+            return Err(StorageError::UploadIntegrityMismatch {
+                expected: local_hash,
+                actual: server_hash,
+            });
+        }
+
+        Ok(local_hash)
+    }
+
+    /// Like [`AlephStorageClient::upload_file_to_storage`] but reports upload
+    /// progress: `on_tick(sent, total)` is called roughly every 500 ms and once
+    /// more when the upload completes.
+    pub async fn upload_file_to_storage_with_progress(
+        &self,
+        path: &std::path::Path,
+        message: Option<&PendingMessage>,
+        sync: bool,
+        on_tick: impl FnMut(u64, u64) + Send + 'static,
+    ) -> Result<ItemHash, StorageError> {
+        self.upload_file_streaming(
+            "/api/v0/storage/add_file",
+            Hasher::for_storage(),
+            path,
+            message,
+            sync,
+            Some(Box::new(on_tick)),
+        )
+        .await
+    }
+
+    /// Like [`AlephStorageClient::upload_file_to_ipfs`] but reports upload
+    /// progress; see [`Self::upload_file_to_storage_with_progress`].
+    pub async fn upload_file_to_ipfs_with_progress(
+        &self,
+        path: &std::path::Path,
+        message: Option<&PendingMessage>,
+        sync: bool,
+        on_tick: impl FnMut(u64, u64) + Send + 'static,
+    ) -> Result<ItemHash, StorageError> {
+        self.upload_file_streaming(
+            "/api/v0/ipfs/add_file",
+            Hasher::for_ipfs(),
+            path,
+            message,
+            sync,
+            Some(Box::new(on_tick)),
+        )
+        .await
+    }
+
+    /// Uploads a directory tree to the configured IPFS gateway and returns
+    /// the root directory CID.
+    ///
+    /// The caller is responsible for posting any STORE message that references
+    /// the returned hash.
+    ///
+    /// # Symlinks
+    ///
+    /// Symlinks inside `path` are dereferenced (per `collect_folder_files`
+    /// `follow_symlinks=true` default) and their target's bytes are uploaded as
+    /// regular files. The resulting CID will NOT match `ipfs add -r` on the
+    /// same source folder if it contains symlinks — kubo's recursive add would
+    /// build UnixFS `Symlink` nodes whereas this function uploads the resolved
+    /// file bytes. Both the local hash and the gateway response agree on the
+    /// dereferenced representation, so verification still succeeds.
+    pub async fn upload_folder_to_ipfs(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        opts: crate::ipfs::UploadFolderOptions,
+    ) -> Result<ItemHash, StorageError> {
+        use crate::ipfs::{CollectError, build_add_query, collect_folder_files, parse_ndjson_root};
+
+        let path = path.as_ref();
+        let entries = collect_folder_files(path, opts.follow_symlinks).map_err(|e| match e {
+            CollectError::Empty(p) => StorageError::EmptyFolder(p),
+            CollectError::NonUtf8(p) => StorageError::NonUtf8Path(p),
+            CollectError::Walk { source, .. } => StorageError::Io(source.into()),
+            // `CollectError` is non-exhaustive now that it lives in `aleph-cid`.
+            other => StorageError::Io(std::io::Error::other(other.to_string())),
+        })?;
+
+        let local_cid = ItemHash::Ipfs(crate::folder_hash::hash_folder_root(&entries, &opts)?);
+
+        let activity = UploadActivity::new();
+        let mut form = reqwest::multipart::Form::new();
+        for entry in entries {
+            let part =
+                tracked_file_part(&entry.absolute_path, entry.relative_path, &activity, None)
+                    .await?;
+            form = form.part("file", part);
+        }
+
+        let query = build_add_query(&opts);
+        let url = self
+            .inner
+            .ipfs_gateway
+            .join(&format!("/api/v0/add?{query}"))
+            .map_err(StorageError::InvalidUrl)?;
+
+        let response = self
+            .send_upload(self.inner.upload_client.post(url).multipart(form), activity)
+            .await?;
+
+        match response.status() {
+            StatusCode::FORBIDDEN => return Err(StorageError::IpfsDisabled),
+            status if !status.is_success() => {
+                return Err(StorageError::UploadRejected {
+                    status: status.as_u16(),
+                    body: response.text().await.unwrap_or_default(),
+                });
+            }
+            _ => {}
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| StorageError::UploadFailed(reqwest_middleware::Error::from(e)))?;
+
+        let cid = parse_ndjson_root(&body)?;
+        let remote_cid = ItemHash::Ipfs(cid);
+        if local_cid != remote_cid {
+            return Err(StorageError::CidMismatch {
+                local: local_cid,
+                remote: remote_cid,
+            });
+        }
+        Ok(remote_cid)
+    }
+
+    /// Upload a directory to pyaleph's authenticated IPFS CAR endpoint.
+    ///
+    /// The SDK rebuilds the UnixFS DAG locally, asserts its root matches
+    /// `message.item_hash` (fails with `CidMismatch` if not), writes
+    /// the DAG into a CARv1 temp file, and posts CAR + signed STORE metadata
+    /// to `/api/v0/ipfs/add_car`. Returns the root `ItemHash` on success.
+    pub async fn upload_folder_to_ipfs_authenticated(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: &PendingMessage,
+        sync: bool,
+        opts: crate::ipfs::UploadFolderOptions,
+    ) -> Result<ItemHash, StorageError> {
+        use crate::car::{write_block_frame, write_carv1_header};
+        use crate::folder_hash::build_folder_dag;
+        use crate::ipfs::{CollectError, collect_folder_files};
+        use std::io::Write;
+
+        let path = path.as_ref();
+        let entries = collect_folder_files(path, opts.follow_symlinks).map_err(|e| match e {
+            CollectError::Empty(p) => StorageError::EmptyFolder(p),
+            CollectError::NonUtf8(p) => StorageError::NonUtf8Path(p),
+            CollectError::Walk { source, .. } => StorageError::Io(source.into()),
+            // `CollectError` is non-exhaustive now that it lives in `aleph-cid`.
+            other => StorageError::Io(std::io::Error::other(other.to_string())),
+        })?;
+
+        // 1. Walk the DAG, stream block frames into a tempfile body.
+        let mut body_tmp = tempfile::NamedTempFile::new()?;
+        let mut last_cid_bytes: Option<Vec<u8>> = None;
+        let local_root = build_folder_dag(&entries, &opts, &mut |cid, block| {
+            write_block_frame(&mut body_tmp, cid, block)?;
+            last_cid_bytes = Some(cid.to_vec());
+            Ok(())
+        })?;
+        body_tmp.flush()?;
+        let local_root = ItemHash::Ipfs(local_root);
+        let root_cid_bytes =
+            last_cid_bytes.expect("build_folder_dag always emits at least the root");
+
+        // 2. Sanity check the local root against metadata.item_hash.
+        let metadata_root = extract_message_item_hash(message)?;
+        if local_root != metadata_root {
+            return Err(StorageError::CidMismatch {
+                local: local_root,
+                remote: metadata_root,
+            });
+        }
+
+        // 3. Build the CARv1 header bytes.
+        let mut header_bytes = Vec::new();
+        write_carv1_header(&mut header_bytes, &root_cid_bytes)?;
+
+        // 4. Construct the multipart body.
+        let activity = UploadActivity::new();
+        let body = build_car_upload_body(header_bytes, body_tmp.path()).await?;
+        let file_part =
+            tracked_bytes_part(body, "upload.car", "application/vnd.ipld.car", &activity);
+        let form = reqwest::multipart::Form::new()
+            .part("file", file_part)
+            .part("metadata", build_storage_metadata_part(message, sync));
+
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/ipfs/add_car")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        // 5. POST and classify the response.
+        let response = self
+            .send_upload(self.inner.upload_client.post(url).multipart(form), activity)
+            .await?;
+
+        let response = handle_storage_response(response).await?;
+        let upload: UploadResponse = response
+            .json()
+            .await
+            .map_err(StorageError::InvalidResponseBody)?;
+        let server_hash = upload.hash.parse::<ItemHash>().map_err(|source| {
+            StorageError::InvalidResponseHash {
+                value: upload.hash.clone(),
+                source,
+            }
+        })?;
+        if server_hash != local_root {
+            return Err(StorageError::UploadIntegrityMismatch {
+                expected: local_root,
+                actual: server_hash,
+            });
+        }
+        Ok(local_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AlephMessageClient;
+    use crate::messages::StoreBuilder;
+    use crate::test_utils::TestAccount;
+    use aleph_types::item_hash;
+    use aleph_types::message::{StorageBackend, StorageEngine};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{body_string_contains, method, path, path_regex, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    #[ignore = "uses a remote CCN with IPFS — no heph equivalent yet"]
+    async fn test_download_cidv0_with_verification() {
+        let client = AlephClient::new(Url::parse("https://api3.aleph.im").expect("valid url"));
+        let file_hash = item_hash!("QmQKPXPMENCLL7HfyPiTZkmyX5iHp5QrYdWWMeP6pEhiS4");
+
+        let download = client
+            .download_file_by_hash(&file_hash)
+            .await
+            .expect("download should succeed");
+
+        let content = download
+            .with_verification()
+            .bytes()
+            .await
+            .expect("CIDv0 verified download should succeed");
+        assert!(!content.is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "uses a remote CCN with IPFS — no heph equivalent yet"]
+    async fn test_download_large_cidv0_with_verification() {
+        let client = AlephClient::new(Url::parse("https://api3.aleph.im").expect("valid url"));
+        let file_hash = item_hash!("QmdFaKjHBGsU525nHD6fgH7o1YnGZgfNo1x9jspzwCaR9N");
+
+        let download = client
+            .download_file_by_hash(&file_hash)
+            .await
+            .expect("download should succeed");
+
+        let content = download
+            .with_verification()
+            .bytes()
+            .await
+            .expect("large CIDv0 verified download should succeed");
+        assert!(!content.is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "uses a remote CCN with IPFS — no heph equivalent yet"]
+    async fn test_upload_to_ipfs() {
+        let client = AlephClient::new(Url::parse("https://api3.aleph.im").expect("valid url"));
+        let data = b"hello aleph ipfs";
+
+        let hash = client
+            .upload_to_ipfs(data, None, false)
+            .await
+            .expect("upload should succeed");
+
+        // Verify the hash is an IPFS CID, not a native hash
+        assert!(matches!(hash, ItemHash::Ipfs(_)));
+
+        // Verify the file is retrievable
+        let size = client
+            .get_file_size(&hash)
+            .await
+            .expect("file should exist");
+        assert_eq!(size, Bytes::from(data.len() as u64));
+    }
+
+    #[test]
+    fn serialize_storage_metadata_emits_message_and_sync() {
+        let account = TestAccount::new();
+        let hash = aleph_types::item_hash!("QmYULJoNGPDmoRq4WNWTDTUvJGJv1hosox8H6vVd1kCsY8");
+        let msg = StoreBuilder::new(&account, hash, StorageEngine::Ipfs)
+            .build()
+            .unwrap();
+
+        let json_str = serialize_storage_metadata(&msg, true);
+        let v: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["sync"], serde_json::Value::Bool(true));
+        assert!(v["message"].is_object(), "message should be an object");
+        assert_eq!(v["message"]["type"], "STORE");
+    }
+
+    #[test]
+    fn serialize_storage_metadata_respects_sync_false() {
+        let account = TestAccount::new();
+        let hash = aleph_types::item_hash!("QmYULJoNGPDmoRq4WNWTDTUvJGJv1hosox8H6vVd1kCsY8");
+        let msg = StoreBuilder::new(&account, hash, StorageEngine::Ipfs)
+            .build()
+            .unwrap();
+        let json_str = serialize_storage_metadata(&msg, false);
+        let v: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["sync"], serde_json::Value::Bool(false));
+    }
+
+    #[test]
+    fn classify_status_402_insufficient_balance() {
+        let err = classify_status_and_body(reqwest::StatusCode::PAYMENT_REQUIRED, "");
+        assert!(matches!(err, Some(StorageError::InsufficientBalance)));
+    }
+
+    #[test]
+    fn classify_status_403_ipfs_disabled() {
+        let err = classify_status_and_body(
+            reqwest::StatusCode::FORBIDDEN,
+            "403: IPFS is disabled on this node",
+        );
+        assert!(matches!(err, Some(StorageError::IpfsDisabled)));
+    }
+
+    #[test]
+    fn classify_status_403_invalid_signature_when_body_does_not_match() {
+        let err = classify_status_and_body(reqwest::StatusCode::FORBIDDEN, "");
+        assert!(matches!(err, Some(StorageError::InvalidSignature)));
+        let err = classify_status_and_body(reqwest::StatusCode::FORBIDDEN, "Invalid signature");
+        assert!(matches!(err, Some(StorageError::InvalidSignature)));
+    }
+
+    #[test]
+    fn classify_status_403_partial_substring_does_not_match_ipfs_disabled() {
+        // Adversarial / proxy-injected body that quotes the fragment but is not
+        // the canonical pyaleph "IPFS is disabled on this node" reason — must
+        // fall through to InvalidSignature, not IpfsDisabled.
+        let err = classify_status_and_body(
+            reqwest::StatusCode::FORBIDDEN,
+            "User said: 'IPFS is disabled' is wrong",
+        );
+        assert!(matches!(err, Some(StorageError::InvalidSignature)));
+    }
+
+    #[test]
+    fn classify_status_403_aiohttp_default_body_is_invalid_signature() {
+        let err = classify_status_and_body(reqwest::StatusCode::FORBIDDEN, "403: Forbidden");
+        assert!(matches!(err, Some(StorageError::InvalidSignature)));
+    }
+
+    #[test]
+    fn classify_status_413_file_too_large() {
+        let err = classify_status_and_body(reqwest::StatusCode::PAYLOAD_TOO_LARGE, "anything");
+        assert!(matches!(err, Some(StorageError::FileTooLarge)));
+    }
+
+    #[test]
+    fn classify_status_422_invalid_metadata_carries_body() {
+        let body = "File hash does not match (X != Y)";
+        let err = classify_status_and_body(reqwest::StatusCode::UNPROCESSABLE_ENTITY, body);
+        match err {
+            Some(StorageError::InvalidMetadata(s)) => assert_eq!(s, body),
+            other => panic!("expected InvalidMetadata, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_status_422_car_header_root_mismatch() {
+        let body = "Root CID does not match (bafy1 != bafy2)";
+        let err = classify_status_and_body(reqwest::StatusCode::UNPROCESSABLE_ENTITY, body);
+        match err {
+            Some(StorageError::CarHeaderRootMismatch {
+                car_root,
+                metadata_root,
+            }) => {
+                assert_eq!(car_root, "bafy1");
+                assert_eq!(metadata_root, "bafy2");
+            }
+            other => panic!("expected CarHeaderRootMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_status_422_imported_root_mismatch() {
+        let body = "Imported root does not match expected (bafy3 != bafy4); CAR header declared a root that does not correspond to the imported DAG";
+        let err = classify_status_and_body(reqwest::StatusCode::UNPROCESSABLE_ENTITY, body);
+        match err {
+            Some(StorageError::ImportedRootMismatch {
+                kubo_root,
+                expected_root,
+            }) => {
+                assert_eq!(kubo_root, "bafy3");
+                assert_eq!(expected_root, "bafy4");
+            }
+            other => panic!("expected ImportedRootMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_status_422_unknown_body_falls_back_to_invalid_metadata() {
+        let body = "some other 422 reason";
+        let err = classify_status_and_body(reqwest::StatusCode::UNPROCESSABLE_ENTITY, body);
+        match err {
+            Some(StorageError::InvalidMetadata(s)) => assert_eq!(s, body),
+            other => panic!("expected InvalidMetadata, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_status_502_bad_gateway_is_ipfs_backend_unavailable() {
+        let err = classify_status_and_body(
+            reqwest::StatusCode::BAD_GATEWAY,
+            "Failed to import CAR into IPFS: kubo unreachable",
+        );
+        match err {
+            Some(StorageError::IpfsBackendUnavailable(s)) => {
+                assert!(s.contains("kubo unreachable"));
+            }
+            other => panic!("expected IpfsBackendUnavailable, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_status_504_gateway_timeout_is_ipfs_backend_unavailable() {
+        let err = classify_status_and_body(
+            reqwest::StatusCode::GATEWAY_TIMEOUT,
+            "Timed out waiting for IPFS stat",
+        );
+        match err {
+            Some(StorageError::IpfsBackendUnavailable(s)) => {
+                assert!(s.contains("Timed out"));
+            }
+            other => panic!("expected IpfsBackendUnavailable, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn classify_status_returns_none_for_success_and_unknown() {
+        assert!(classify_status_and_body(reqwest::StatusCode::OK, "").is_none());
+        assert!(classify_status_and_body(reqwest::StatusCode::INTERNAL_SERVER_ERROR, "").is_none());
+    }
+
+    #[test]
+    fn default_client_uses_default_ipfs_gateway() {
+        let client = AlephClient::new(Url::parse("https://example.com").unwrap());
+        // Url normalizes to include a trailing slash for the empty-path root.
+        assert_eq!(
+            client.inner.ipfs_gateway.as_str(),
+            "https://ipfs.aleph.cloud/"
+        );
+    }
+
+    #[test]
+    fn with_ipfs_gateway_overrides() {
+        let client = AlephClient::new(Url::parse("https://example.com").unwrap())
+            .with_ipfs_gateway(Url::parse("http://localhost:5001").unwrap());
+        assert_eq!(client.inner.ipfs_gateway.as_str(), "http://localhost:5001/");
+    }
+
+    #[test]
+    fn clones_share_state_until_modified() {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<AlephClient>();
+
+        let client = AlephClient::new(Url::parse("https://example.com").unwrap());
+        let clone = client.clone();
+        assert!(Arc::ptr_eq(&client.inner, &clone.inner));
+
+        let clone = clone.with_ipfs_gateway(Url::parse("http://localhost:5001").unwrap());
+        assert!(!Arc::ptr_eq(&client.inner, &clone.inner));
+        assert_eq!(
+            client.inner.ipfs_gateway.as_str(),
+            "https://ipfs.aleph.cloud/"
+        );
+    }
+
+    fn client() -> AlephClient {
+        AlephClient::new(Url::parse("https://ccn.example.org").unwrap())
+            .with_ipfs_gateway(Url::parse("https://gateway.example.org").unwrap())
+    }
+
+    #[test]
+    fn native_files_resolve_to_ccn_raw_storage() {
+        let hash = item_hash!("d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c");
+        assert_eq!(
+            client().file_hash_url(&hash).as_str(),
+            format!("https://ccn.example.org/api/v0/storage/raw/{hash}")
+        );
+    }
+
+    #[test]
+    fn ipfs_files_resolve_to_gateway() {
+        let hash = item_hash!("QmPZ9gcCEpqKTo6aq61g2nXGUhM4iCL3ewB6LDXZCtioEB");
+        let ItemHash::Ipfs(cid) = hash.clone() else {
+            panic!("expected an IPFS hash");
+        };
+        let store = StoreContent::new(StorageBackend::Ipfs { item_hash: cid }, None, None, None);
+        assert_eq!(
+            client().file_url(&store).as_str(),
+            format!("https://gateway.example.org/ipfs/{hash}")
+        );
+    }
+
+    const STORE_FIXTURE: &str = include_str!("../../../../fixtures/messages/store/store-ipfs.json");
+
+    const CID: &str = "QmYULJoNGPDmoRq4WNWTDTUvJGJv1hosox8H6vVd1kCsY8";
+
+    #[test]
+    fn sniff_recognizes_common_formats() {
+        assert_eq!(
+            sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0"),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_content_type(b"hsqs\x01\0\0\0"),
+            Some("application/vnd.squashfs")
+        );
+        assert_eq!(
+            sniff_content_type(b"  {\"key\": 1}"),
+            Some("application/json")
+        );
+        assert_eq!(
+            sniff_content_type(b"<!DOCTYPE html><html>"),
+            Some("text/html")
+        );
+        // Cut in the middle of "\u{e9}".
+        assert_eq!(sniff_content_type(b"caf\xc3"), Some("text/plain"));
+        assert_eq!(sniff_content_type(b"\0\x01\x02"), None);
+        assert_eq!(sniff_content_type(b""), None);
+    }
+
+    #[tokio::test]
+    async fn get_file_metadata_combines_store_message_and_headers() {
+        let server = MockServer::start().await;
+        let store: serde_json::Value = serde_json::from_str(STORE_FIXTURE).unwrap();
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("contentHashes", CID))
+            .and(query_param("msgType", "STORE"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [store],
+                "pagination_per_page": 1,
+                "pagination_page": 1,
+                "pagination_total": 1,
+            })))
+            .mount(&server)
+            .await;
+        for verb in ["HEAD", "GET"] {
+            Mock::given(method(verb))
+                .and(path(format!("/api/v0/storage/raw/{CID}")))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(b"{\"a\": 1}".to_vec()))
+                .mount(&server)
+                .await;
+        }
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let metadata = client
+            .get_stored_file_metadata(&CID.parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(metadata.engine, StorageEngine::Ipfs);
+        assert_eq!(metadata.size, Some(Bytes::from(8)));
+        assert_eq!(metadata.content_type.as_deref(), Some("application/json"));
+        assert_eq!(
+            metadata.store_message.unwrap().item_hash.to_string(),
+            "afe106f1fd70b6b806e0452cc2f9485e518143581ffd046ae19fc64af7b6bbaa"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_file_metadata_fails_for_unknown_files() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": [],
+                "pagination_per_page": 1,
+                "pagination_page": 1,
+                "pagination_total": 0,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path(format!("/api/v0/storage/raw/{CID}")))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let err = client
+            .get_stored_file_metadata(&CID.parse().unwrap())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, MessageError::Storage(StorageError::NotFound(_))),
+            "got: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn create_store_from_bytes_uploads_with_the_store_message() {
+        let data = b"create_store_from_bytes test";
+        let mut hasher = Hasher::for_storage();
+        hasher.update(data);
+        let expected = hasher.finalize();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v0/storage/add_file"))
+            .and(body_string_contains("\"type\":\"STORE\""))
+            .and(body_string_contains(expected.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "success",
+                "hash": expected.to_string(),
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let account = TestAccount(Address::from(
+            "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string(),
+        ));
+        let file_hash = client
+            .create_store_from_bytes(&account, data, StorageEngine::Storage, true)
+            .await
+            .unwrap();
+        assert_eq!(file_hash, expected);
+    }
+
+    #[tokio::test]
+    async fn to_writer_streams_the_verified_file_and_reports_progress() {
+        let data = vec![7u8; 300_000];
+        let mut hasher = Hasher::for_storage();
+        hasher.update(&data);
+        let hash = hasher.finalize();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/api/v0/storage/raw/"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(data.clone()))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let mut out = Vec::new();
+        let written = client
+            .download_file_by_hash(&hash)
+            .await
+            .unwrap()
+            .with_verification()
+            .with_progress(move |received, total| sink.lock().unwrap().push((received, total)))
+            .to_writer(&mut out)
+            .await
+            .unwrap();
+
+        assert_eq!(written, data.len() as u64);
+        assert_eq!(out, data);
+        let reports = reports.lock().unwrap();
+        assert_eq!(
+            reports.last(),
+            Some(&(data.len() as u64, Some(data.len() as u64)))
+        );
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
@@ -0,0 +1,337 @@
+//! Verification of the integrity and signature of fetched messages.
+
+use super::error::CheckStatus;
+use super::{
+    AlephClient, AlephStorageClient, IntegrityError, MessageError, MessageFilter, StorageError,
+};
+use aleph_types::message::{
+    ContentSource, Message, MessageContent, MessageContentEnum, MessageHeader,
+    SignatureVerificationError,
+};
+use serde::Deserialize;
+
+/// A message that passed full verification: content hash matched AND the
+/// signature was checked against the sender.
+#[derive(Debug)]
+pub struct VerifiedMessage {
+    message: Message,
+}
+
+impl From<VerifiedMessage> for Message {
+    fn from(v: VerifiedMessage) -> Self {
+        v.message
+    }
+}
+
+impl VerifiedMessage {
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    pub fn content(&self) -> &MessageContentEnum {
+        self.message.content()
+    }
+}
+
+/// A message whose content hash was verified but whose signature could not be
+/// checked because it is absent (`signature: null`). Pyaleph emits such
+/// messages for a small set of pre-signature-enforcement-era mainnet entries,
+/// notably smart-contract-originated messages.
+///
+/// **Authentication caveat.** A correctly-behaving CCN only serves `signature:
+/// null` for messages whose authenticity is anchored elsewhere (on-chain TX
+/// data). The current `/api/v0/messages` response does not surface the
+/// provenance discriminant, so a malicious CCN could in principle strip
+/// signatures and serve unsigned forgeries. Callers MUST decide for themselves
+/// whether they trust their CCN before treating `UnsignedMessage` as
+/// authentic; out-of-band verification (e.g. fetching the on-chain TX
+/// referenced in `confirmations`) is the only way to authenticate without
+/// trusting the CCN.
+#[derive(Debug)]
+pub struct UnsignedMessage {
+    message: Message,
+}
+
+impl From<UnsignedMessage> for Message {
+    fn from(u: UnsignedMessage) -> Self {
+        u.message
+    }
+}
+
+impl UnsignedMessage {
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    pub fn content(&self) -> &MessageContentEnum {
+        self.message.content()
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidMessage {
+    pub header: MessageHeader,
+    pub error: IntegrityError,
+}
+
+impl std::fmt::Display for InvalidMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "integrity check failed for {}: {}",
+            self.header.item_hash, self.error
+        )
+    }
+}
+
+impl std::error::Error for InvalidMessage {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Outcome of verifying a single message.
+///
+/// Distinguishes three cases that callers typically need to treat differently:
+/// - [`Verified`](Self::Verified): content hash and signature both checked.
+/// - [`Unsigned`](Self::Unsigned): content hash checked, but signature is
+///   absent (legacy pre-enforcement-era data). See [`UnsignedMessage`] for
+///   the authentication caveat.
+/// - [`Invalid`](Self::Invalid): an integrity check (hash mismatch,
+///   deserialization failure, bad signature) failed.
+///
+/// Network and I/O errors from fetching non-inline content are returned as
+/// `Err(MessageError)` from the verifying APIs, not as an `Invalid` variant.
+#[derive(Debug)]
+pub enum MessageVerification {
+    Verified(VerifiedMessage),
+    Unsigned(UnsignedMessage),
+    Invalid(InvalidMessage),
+}
+
+impl MessageVerification {
+    /// Returns the underlying `Message` if verification succeeded (including
+    /// the unsigned case), or `None` for `Invalid`.
+    pub fn message(&self) -> Option<&Message> {
+        match self {
+            MessageVerification::Verified(v) => Some(v.message()),
+            MessageVerification::Unsigned(u) => Some(u.message()),
+            MessageVerification::Invalid(_) => None,
+        }
+    }
+
+    /// Consuming counterpart to [`message`](Self::message).
+    pub fn into_message(self) -> Option<Message> {
+        match self {
+            MessageVerification::Verified(v) => Some(v.into()),
+            MessageVerification::Unsigned(u) => Some(u.into()),
+            MessageVerification::Invalid(_) => None,
+        }
+    }
+}
+
+/// Verifies a [`MessageHeader`] and resolves it to a [`MessageVerification`].
+///
+/// Performs, in order:
+/// 1. **Signature check.** If the signature is present and valid: continue.
+///    If it is present but invalid: return [`MessageVerification::Invalid`].
+///    If it is absent (`signature: null` from legacy pyaleph data): track that
+///    fact and continue to content verification.
+/// 2. **Content hash check.** Inline messages are hashed locally; non-inline
+///    messages (storage/ipfs) are downloaded from
+///    `/api/v0/storage/raw/{item_hash}` and their hash is verified against
+///    `item_hash`.
+/// 3. **Content deserialization.** Always performed from the verified raw
+///    bytes, never from the CCN's pre-deserialized `content` field.
+///
+/// Returns [`MessageVerification::Verified`] for messages that passed every
+/// step, [`MessageVerification::Unsigned`] for messages that passed the hash
+/// check but had no signature, and [`MessageVerification::Invalid`] for any
+/// integrity failure. Network and I/O errors propagate via `Err(MessageError)`.
+pub(super) async fn verify_message_header<C: AlephStorageClient + Sync + ?Sized>(
+    client: &C,
+    header: MessageHeader,
+) -> Result<MessageVerification, MessageError> {
+    // Signature check — cheap, no I/O. Missing is not an error here: legacy
+    // pre-enforcement-era pyaleph messages are unsigned by design, and we
+    // still want to integrity-check their content.
+    let signed = match header.verify_signature() {
+        Ok(()) => true,
+        Err(SignatureVerificationError::MissingSignature) => false,
+        Err(e) => {
+            return Ok(MessageVerification::Invalid(InvalidMessage {
+                header,
+                error: IntegrityError::SignatureVerificationFailed(e),
+            }));
+        }
+    };
+
+    let content = match &header.content_source {
+        ContentSource::Inline { item_content } => {
+            if let Some(Err((expected, actual))) =
+                header.content_source.verify_inline_hash(&header.item_hash)
+            {
+                return Ok(MessageVerification::Invalid(InvalidMessage {
+                    header,
+                    error: IntegrityError::HashMismatch { expected, actual },
+                }));
+            }
+            MessageContent::deserialize_with_type(header.message_type, item_content.as_bytes())
+        }
+        ContentSource::Storage | ContentSource::Ipfs => {
+            let download = client.download_file_by_hash(&header.item_hash).await?;
+            let raw_bytes = match download.with_verification().bytes().await {
+                Ok(bytes) => bytes,
+                Err(MessageError::Storage(StorageError::IntegrityError(
+                    crate::verify::VerifyError::IntegrityMismatch { expected, actual },
+                ))) => {
+                    return Ok(MessageVerification::Invalid(InvalidMessage {
+                        header,
+                        error: IntegrityError::HashMismatch { expected, actual },
+                    }));
+                }
+                Err(e) => return Err(e),
+            };
+            MessageContent::deserialize_with_type(header.message_type, &raw_bytes)
+        }
+    };
+
+    let content = match content {
+        Ok(c) => c,
+        Err(e) => {
+            return Ok(MessageVerification::Invalid(InvalidMessage {
+                header,
+                error: IntegrityError::ContentDeserializationFailed(e),
+            }));
+        }
+    };
+
+    let message = header.with_content(content);
+    Ok(if signed {
+        MessageVerification::Verified(VerifiedMessage { message })
+    } else {
+        MessageVerification::Unsigned(UnsignedMessage { message })
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct GetMessageHeadersResponse {
+    messages: Vec<MessageHeader>,
+}
+
+impl AlephClient {
+    /// Fetches messages matching the filter, returning only the headers (without content).
+    ///
+    /// Used by [`get_messages_and_verify`](AlephMessageClient::get_messages_and_verify) to avoid
+    /// deserializing the CCN's `content` field, which is discarded in favor of content
+    /// deserialized from verified raw bytes.
+    pub(super) async fn get_message_headers(
+        &self,
+        filter: &MessageFilter,
+    ) -> Result<Vec<MessageHeader>, MessageError> {
+        let url = self
+            .inner
+            .ccn_url
+            .join("/api/v0/messages.json")
+            .unwrap_or_else(|e| panic!("invalid url: {e}"));
+
+        let response = self
+            .inner
+            .http_client
+            .get(url)
+            .query(&filter)
+            .send()
+            .await?
+            .check_status()
+            .await?;
+
+        let response: GetMessageHeadersResponse = response
+            .json()
+            .await
+            .map_err(reqwest_middleware::Error::from)?;
+        Ok(response.messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::item_hash;
+    use url::Url;
+
+    /// Inline POST message with `signature: null`. Content hash matches
+    /// `item_hash`. Used to exercise the unsigned-but-integrity-checked path.
+    const INLINE_UNSIGNED_POST: &str = r#"{
+        "sender": "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef",
+        "chain": "ETH",
+        "signature": null,
+        "type": "POST",
+        "item_content": "{\"type\":\"05567c5b-0606-4a6e-a639-25734c06e2a0\",\"address\":\"0xB68B9D4f3771c246233823ed1D3Add451055F9Ef\",\"content\":{\"body\":\"Hello World\"},\"time\":1762515431.653}",
+        "item_type": "inline",
+        "item_hash": "d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c",
+        "time": 1762515431.653,
+        "channel": "TEST",
+        "content": {
+            "address": "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef",
+            "time": 1762515431.653,
+            "content": { "body": "Hello World" },
+            "ref": null,
+            "type": "05567c5b-0606-4a6e-a639-25734c06e2a0"
+        },
+        "confirmed": false,
+        "confirmations": []
+    }"#;
+
+    fn inline_only_client() -> AlephClient {
+        // Inline verification never touches the network; any URL works.
+        AlephClient::new(Url::parse("http://test.invalid").unwrap())
+    }
+
+    #[tokio::test]
+    async fn unsigned_inline_message_passes_integrity_and_resolves_to_unsigned() {
+        let message: Message = serde_json::from_str(INLINE_UNSIGNED_POST).unwrap();
+        assert!(message.signature.is_none());
+
+        let header = MessageHeader::from(message);
+        let outcome = verify_message_header(&inline_only_client(), header)
+            .await
+            .expect("inline verification should not perform I/O");
+
+        match outcome {
+            MessageVerification::Unsigned(u) => {
+                assert_eq!(
+                    u.message().item_hash,
+                    item_hash!("d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c")
+                );
+            }
+            other => panic!("expected Unsigned, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn unsigned_inline_message_with_tampered_content_is_invalid() {
+        let message: Message = serde_json::from_str(INLINE_UNSIGNED_POST).unwrap();
+        let mut header = MessageHeader::from(message);
+        // Tamper item_content — its hash will no longer match item_hash.
+        if let ContentSource::Inline {
+            ref mut item_content,
+        } = header.content_source
+        {
+            item_content.push_str(" tampered");
+        } else {
+            panic!("fixture must be inline");
+        }
+
+        let outcome = verify_message_header(&inline_only_client(), header)
+            .await
+            .expect("inline verification should not perform I/O");
+
+        assert!(matches!(
+            outcome,
+            MessageVerification::Invalid(InvalidMessage {
+                error: IntegrityError::HashMismatch { .. },
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,587 @@
+//! Waiting for messages and following message and aggregate updates.
+
+use super::aggregates::{decode_aggregate_value, merge_aggregate};
+use super::{
+    AlephAggregateClient, AlephClient, AlephMessageClient, MessageError, MessageFilter,
+    MessageWithStatus, SortBy, SortOrder,
+};
+use aleph_types::chain::Address;
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{AggregateContent, Message, MessageType};
+use aleph_types::timestamp::Timestamp;
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// How far before the last message seen
+/// [`watch_messages`](AlephClient::watch_messages) backfills after a
+/// reconnection.
+pub const WATCH_BACKFILL_MARGIN: Duration = Duration::from_secs(60);
+
+/// Item hashes remembered by [`watch_messages`](AlephClient::watch_messages)
+/// to deduplicate messages.
+const WATCH_DEDUP_CAPACITY: usize = 10_000;
+
+/// Body of [`AlephMessageClient::wait_for_message`].
+pub(super) async fn wait_for_message<C: AlephMessageClient + Sync + ?Sized>(
+    client: &C,
+    item_hash: &ItemHash,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<MessageWithStatus<Message>, MessageError> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let timed_out = || MessageError::WaitTimeout {
+        item_hash: item_hash.clone(),
+        timeout,
+    };
+    loop {
+        match tokio::time::timeout_at(deadline, client.get_message(item_hash)).await {
+            Ok(Ok(MessageWithStatus::Pending { .. })) => {}
+            Ok(Err(e)) if e.is_not_found() => {}
+            Ok(result) => return result,
+            Err(_) => return Err(timed_out()),
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Err(timed_out());
+        }
+        tokio::time::sleep(poll_interval.min(deadline - now)).await;
+    }
+}
+
+impl AlephClient {
+    /// Streams the value of the aggregate `key` of `address`: its current
+    /// value first, then the new value every time an AGGREGATE message
+    /// updates it.
+    ///
+    /// The websocket subscription is opened before the initial fetch so that
+    /// no update is missed in between. Updates are merged into the last value
+    /// locally, the way the CCN merges them: the keys of each update replace
+    /// the keys of the value. Updates sent before the initial fetch or before
+    /// the last merged update, and the first update after a subscription
+    /// error, trigger a fetch of the aggregate instead, since they may be
+    /// applied out of order. The aggregate is also fetched after each
+    /// reconnection of the websocket, which does not replay the updates sent
+    /// while it was down. Updates leaving the value unchanged are skipped.
+    /// If the aggregate does not exist yet, the stream starts with its first
+    /// value.
+    ///
+    /// Subscription and fetch errors are yielded without ending the stream, as
+    /// are values that do not deserialize to `T`.
+    pub async fn watch_aggregate<T>(
+        &self,
+        address: &Address,
+        key: &str,
+    ) -> Result<impl Stream<Item = Result<T, MessageError>> + Send + Unpin + '_, MessageError>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let filter = MessageFilter::default()
+            .message_type(MessageType::Aggregate)
+            .owners([address.clone()])
+            .content_keys([key]);
+        let updates =
+            crate::ws::subscribe_events(self.inner.ccn_url.clone(), &filter, None).await?;
+        let fetched_at = Timestamp::now();
+        let initial = self
+            .fetch_aggregate::<serde_json::Value>(address, key)
+            .await?;
+        Ok(self.aggregate_values(
+            address.clone(),
+            key.to_string(),
+            initial,
+            fetched_at,
+            updates,
+        ))
+    }
+
+    /// Body of [`watch_aggregate`](Self::watch_aggregate), once subscribed.
+    fn aggregate_values<T>(
+        &self,
+        address: Address,
+        key: String,
+        initial: Option<serde_json::Value>,
+        fetched_at: Timestamp,
+        mut updates: impl Stream<Item = Result<crate::ws::SubscriptionEvent, MessageError>>
+        + Send
+        + Unpin
+        + 'static,
+    ) -> impl Stream<Item = Result<T, MessageError>> + Send + Unpin + '_
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        Box::pin(async_stream::stream! {
+            let mut last = initial.clone();
+            if let Some(value) = initial {
+                yield decode_aggregate_value::<T>(value, &key);
+            }
+            // Time of the most recent update included in `last`.
+            let mut latest = fetched_at.as_f64();
+            let mut resync = false;
+            while let Some(event) = updates.next().await {
+                let message = match event {
+                    Ok(crate::ws::SubscriptionEvent::Message(message)) => Some(message),
+                    // Updates sent while the connection was down are lost.
+                    Ok(crate::ws::SubscriptionEvent::Reconnected) => {
+                        resync = true;
+                        None
+                    }
+                    Err(e) => {
+                        resync = true;
+                        yield Err(e);
+                        continue;
+                    }
+                };
+                let update = match &message {
+                    // The CCN filters on the same criteria; this only guards
+                    // against nodes ignoring part of the filter.
+                    Some(message) => match message.typed_content::<AggregateContent>() {
+                        Some(content)
+                            if content.key() == key && message.owner().same_as(&address) =>
+                        {
+                            Some((message.sent_at().as_f64(), content))
+                        }
+                        _ => continue,
+                    },
+                    None => None,
+                };
+
+                let value = match update {
+                    Some((sent_at, content)) if !resync && sent_at >= latest => {
+                        latest = sent_at;
+                        Some(merge_aggregate(last.clone(), &content.content))
+                    }
+                    _ => match self
+                        .fetch_aggregate::<serde_json::Value>(&address, &key)
+                        .await
+                    {
+                        Ok(value) => {
+                            resync = false;
+                            if let Some((sent_at, _)) = update {
+                                latest = latest.max(sent_at);
+                            }
+                            value
+                        }
+                        Err(e) => {
+                            resync = true;
+                            yield Err(e);
+                            continue;
+                        }
+                    },
+                };
+                match value {
+                    Some(value) if last.as_ref() != Some(&value) => {
+                        last = Some(value.clone());
+                        yield decode_aggregate_value::<T>(value, &key);
+                    }
+                    _ => {}
+                }
+            }
+        })
+    }
+
+    /// Streams the messages matching `filter` as they arrive, without gaps.
+    ///
+    /// Like [`subscribe_to_messages`](AlephMessageClient::subscribe_to_messages),
+    /// except that after each websocket reconnection the messages sent while
+    /// the connection was down are fetched through the REST API and yielded
+    /// first. Messages are deduplicated, so one delivered by both paths is
+    /// yielded once; their order is only guaranteed within each path.
+    ///
+    /// The backfill starts [`WATCH_BACKFILL_MARGIN`] before the time of the
+    /// last message seen, since message times are set by their senders.
+    /// Connection and backfill errors are yielded without ending the stream.
+    pub async fn watch_messages(
+        &self,
+        filter: MessageFilter,
+    ) -> Result<impl Stream<Item = Result<Message, MessageError>> + Send + Unpin + '_, MessageError>
+    {
+        let started = Timestamp::now();
+        let live = crate::ws::subscribe_events(self.inner.ccn_url.clone(), &filter, None).await?;
+        Ok(self.gap_free_messages(filter, started, live))
+    }
+
+    /// Body of [`watch_messages`](Self::watch_messages), once subscribed.
+    fn gap_free_messages(
+        &self,
+        filter: MessageFilter,
+        started: Timestamp,
+        mut live: impl Stream<Item = Result<crate::ws::SubscriptionEvent, MessageError>>
+        + Send
+        + Unpin
+        + 'static,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + Unpin + '_ {
+        Box::pin(async_stream::stream! {
+            let mut last_seen = started.as_f64();
+            let mut seen = std::collections::HashSet::new();
+            let mut seen_order = std::collections::VecDeque::new();
+            // Records `message` as seen, returning false if it already was.
+            let mut first_sighting = |message: &Message, last_seen: &mut f64| {
+                if !seen.insert(message.item_hash.clone()) {
+                    return false;
+                }
+                seen_order.push_back(message.item_hash.clone());
+                if seen_order.len() > WATCH_DEDUP_CAPACITY
+                    && let Some(oldest) = seen_order.pop_front()
+                {
+                    seen.remove(&oldest);
+                }
+                *last_seen = last_seen.max(message.time.as_f64());
+                true
+            };
+
+            while let Some(event) = live.next().await {
+                match event {
+                    Ok(crate::ws::SubscriptionEvent::Message(message)) => {
+                        if first_sighting(&message, &mut last_seen) {
+                            yield Ok(message);
+                        }
+                    }
+                    Ok(crate::ws::SubscriptionEvent::Reconnected) => {
+                        let since = last_seen - WATCH_BACKFILL_MARGIN.as_secs_f64();
+                        let missed = filter
+                            .clone()
+                            .time_range(Some(Timestamp::from(since)), None)
+                            .sort(SortBy::Time, SortOrder::Asc);
+                        let mut missed = std::pin::pin!(self.get_messages_iterator(missed, None));
+                        while let Some(message) = missed.next().await {
+                            match message {
+                                Ok(message) => {
+                                    if first_sighting(&message, &mut last_seen) {
+                                        yield Ok(message);
+                                    }
+                                }
+                                Err(e) => {
+                                    yield Err(e);
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => yield Err(e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::processed_post_json;
+    use crate::ws::SubscriptionEvent;
+    use aleph_types::item_hash::ItemHash;
+    use aleph_types::message::MessageStatus;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn wait_for_message_polls_until_processed() {
+        let hash = ItemHash::from([3u8; 32]);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../../fixtures/api-responses/pending-message.json"
+                ))),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(processed_post_json(&hash)))
+            .mount(&server)
+            .await;
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+        let message = client
+            .wait_for_message(&hash, Duration::from_secs(5), Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(message.status(), MessageStatus::Processed);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn wait_for_message_times_out_while_pending() {
+        let hash = ItemHash::from([3u8; 32]);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+        let err = client
+            .wait_for_message(&hash, Duration::from_millis(30), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MessageError::WaitTimeout { .. }));
+    }
+
+    #[tokio::test]
+    async fn wait_for_message_times_out_during_a_slow_request() {
+        let hash = ItemHash::from([3u8; 32]);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(processed_post_json(&hash))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&server)
+            .await;
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+        let started = std::time::Instant::now();
+        let err = client
+            .wait_for_message(&hash, Duration::from_millis(50), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MessageError::WaitTimeout { .. }));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    const OWNER: &str = "0xa1B3bb7d2332383D96b7796B908fB7f7F3c2Be10";
+
+    type Event = Result<crate::ws::SubscriptionEvent, MessageError>;
+
+    fn update(key: &str) -> Event {
+        let mut message: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../fixtures/messages/aggregate/aggregate.json"
+        ))
+        .unwrap();
+        message["content"]["key"] = key.into();
+        Ok(crate::ws::SubscriptionEvent::Message(
+            serde_json::from_value(message).unwrap(),
+        ))
+    }
+
+    fn update_at(time: f64, content: serde_json::Value) -> Event {
+        let mut message: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../fixtures/messages/aggregate/aggregate.json"
+        ))
+        .unwrap();
+        message["content"]["time"] = time.into();
+        message["content"]["content"] = content;
+        Ok(crate::ws::SubscriptionEvent::Message(
+            serde_json::from_value(message).unwrap(),
+        ))
+    }
+
+    async fn mount_value(server: &MockServer, value: serde_json::Value, times: u64) {
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{OWNER}.json")))
+            .and(query_param("keys", "corechannel"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"data": {"corechannel": value}})),
+            )
+            .up_to_n_times(times)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn aggregate_values_merges_updates_locally() {
+        let server = MockServer::start().await;
+        // Only the out-of-order update is fetched.
+        mount_value(&server, json!({"version": 5, "nodes": ["b"]}), 1).await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let owner = Address::from(OWNER.to_string());
+        let updates = futures_util::stream::iter(vec![
+            update_at(2000.0, json!({"version": 2})),
+            update_at(2001.0, json!({"version": 2})),
+            update_at(2002.0, json!({"nodes": ["b"]})),
+            update_at(1500.0, json!({"version": 5})),
+            update_at(2003.0, json!({"version": 6})),
+        ]);
+        let values: Vec<serde_json::Value> = client
+            .aggregate_values(
+                owner,
+                "corechannel".to_string(),
+                Some(json!({"version": 1, "nodes": ["a"]})),
+                Timestamp::from(1000.0),
+                updates,
+            )
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(
+            values,
+            vec![
+                json!({"version": 1, "nodes": ["a"]}),
+                json!({"version": 2, "nodes": ["a"]}),
+                json!({"version": 2, "nodes": ["b"]}),
+                json!({"version": 5, "nodes": ["b"]}),
+                json!({"version": 6, "nodes": ["b"]}),
+            ]
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn aggregate_values_fetches_after_reconnection() {
+        let server = MockServer::start().await;
+        // The value after the updates missed while disconnected.
+        mount_value(&server, json!({"version": 5, "nodes": ["b"]}), 1).await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let owner = Address::from(OWNER.to_string());
+        let updates = futures_util::stream::iter(vec![
+            Ok(crate::ws::SubscriptionEvent::Reconnected),
+            update_at(2000.0, json!({"version": 6})),
+        ]);
+        let values: Vec<serde_json::Value> = client
+            .aggregate_values(
+                owner,
+                "corechannel".to_string(),
+                Some(json!({"version": 1, "nodes": ["a"]})),
+                Timestamp::from(1000.0),
+                updates,
+            )
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(
+            values,
+            vec![
+                json!({"version": 1, "nodes": ["a"]}),
+                json!({"version": 5, "nodes": ["b"]}),
+                json!({"version": 6, "nodes": ["b"]}),
+            ]
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn aggregate_values_yields_changed_values_only() {
+        let server = MockServer::start().await;
+        // Served in mount order: one fetch per value, the last one repeatedly.
+        mount_value(&server, json!({"version": 2}), 1).await;
+        mount_value(&server, json!({"version": 3}), u64::MAX).await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let owner = Address::from(OWNER.to_string());
+        let updates = futures_util::stream::iter(vec![
+            update("corechannel"),
+            update("other"),
+            Err(MessageError::WebsocketBadScheme),
+            update("corechannel"),
+            update("corechannel"),
+        ]);
+        let values: Vec<Result<serde_json::Value, MessageError>> = client
+            .aggregate_values(
+                owner,
+                "corechannel".to_string(),
+                Some(json!({"version": 1})),
+                Timestamp::now(),
+                updates,
+            )
+            .collect()
+            .await;
+
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0].as_ref().unwrap(), &json!({"version": 1}));
+        assert_eq!(values[1].as_ref().unwrap(), &json!({"version": 2}));
+        assert!(matches!(values[2], Err(MessageError::WebsocketBadScheme)));
+        assert_eq!(values[3].as_ref().unwrap(), &json!({"version": 3}));
+    }
+
+    #[tokio::test]
+    async fn missing_aggregates_are_none() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{OWNER}.json")))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let owner = Address::from(OWNER.to_string());
+        assert_eq!(
+            client
+                .fetch_aggregate::<serde_json::Value>(&owner, "corechannel")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    fn post(channel: &str, time: f64) -> Message {
+        let mut message: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../fixtures/messages/post/post.json"))
+                .unwrap();
+        message["channel"] = channel.into();
+        message["time"] = time.into();
+        // Distinct hashes for deduplication; signatures are not checked here.
+        message["item_hash"] = ItemHash::from([channel.as_bytes()[0]; 32])
+            .to_string()
+            .into();
+        serde_json::from_value(message).unwrap()
+    }
+
+    fn channels(messages: &[Result<Message, MessageError>]) -> Vec<String> {
+        messages
+            .iter()
+            .map(|m| {
+                m.as_ref()
+                    .unwrap()
+                    .channel
+                    .as_ref()
+                    .unwrap()
+                    .as_str()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reconnection_backfills_missed_messages_once() {
+        let server = MockServer::start().await;
+        let missed = [post("B", 1010.0), post("C", 1020.0)];
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("sortOrder", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": missed,
+                "next_cursor": null,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let live = futures_util::stream::iter(vec![
+            Ok(SubscriptionEvent::Message(post("A", 1000.0))),
+            Err(MessageError::WebsocketBadScheme),
+            Ok(SubscriptionEvent::Reconnected),
+            // Sent right after the reconnection, also returned by the backfill.
+            Ok(SubscriptionEvent::Message(post("C", 1020.0))),
+            Ok(SubscriptionEvent::Message(post("D", 1030.0))),
+        ]);
+        let messages: Vec<_> = client
+            .gap_free_messages(MessageFilter::default(), Timestamp::from(900.0), live)
+            .collect()
+            .await;
+
+        assert!(matches!(messages[1], Err(MessageError::WebsocketBadScheme)));
+        let messages: Vec<_> = messages.into_iter().filter(|m| m.is_ok()).collect();
+        assert_eq!(channels(&messages), ["A", "B", "C", "D"]);
+    }
+}
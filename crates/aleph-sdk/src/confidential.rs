@@ -42,6 +42,10 @@ pub enum ConfidentialError {
         "launch_measure has unexpected length: expected 48 bytes (32 measure + 16 nonce), got {0}"
     )]
     InvalidLaunchMeasureLength(usize),
+    #[error("SEV policy mismatch: expected {expected:#x}, VM was launched with {actual:#x}")]
+    PolicyMismatch { expected: u32, actual: u32 },
+    #[error("launch measurement does not match the expected firmware")]
+    MeasurementMismatch,
 }
 
 impl SEVMeasurement {
@@ -81,6 +85,41 @@ pub fn compute_expected_measure(
     mac.finalize().into_bytes().into()
 }
 
+/// Validates a CRN-reported launch measurement against the expected firmware
+/// and, optionally, the expected SEV policy.
+///
+/// The measurement HMAC covers the `sev_info` the CRN reports (including the
+/// policy), so a match proves the VM launched with exactly that firmware and
+/// policy; `expected_policy` then pins the policy to the one the tenant asked
+/// for (`TrustedExecutionEnvironment::policy`). Pass `None` to accept whatever
+/// policy the measurement binds.
+///
+/// The comparison is constant-time. Returns the 32-byte VM measure, which
+/// [`build_secret_packet`] needs to authenticate the injected secret.
+pub fn verify_measurement(
+    measurement: &SEVMeasurement,
+    tik: &[u8; 16],
+    firmware_hash: &[u8; 32],
+    expected_policy: Option<u32>,
+) -> Result<[u8; 32], ConfidentialError> {
+    use subtle::ConstantTimeEq;
+
+    let (vm_measure, nonce) = measurement.split_launch_measure()?;
+    if let Some(expected) = expected_policy
+        && expected != measurement.sev_info.policy
+    {
+        return Err(ConfidentialError::PolicyMismatch {
+            expected,
+            actual: measurement.sev_info.policy,
+        });
+    }
+    let expected = compute_expected_measure(&measurement.sev_info, tik, firmware_hash, &nonce);
+    if expected.ct_eq(&vm_measure).unwrap_u8() == 0 {
+        return Err(ConfidentialError::MeasurementMismatch);
+    }
+    Ok(vm_measure)
+}
+
 /// SEV-ES launch secret injection. AMD SEV API specification, LAUNCH_SECRET command.
 ///
 /// Returns `(packet_header_b64, encrypted_secret_b64)`.
@@ -306,6 +345,57 @@ mod tests {
         ));
     }
 
+    fn fixture_measurement() -> (SEVMeasurement, [u8; 16], [u8; 32]) {
+        let measurement = SEVMeasurement {
+            sev_info: SEVInfo {
+                api_major: 1,
+                api_minor: 55,
+                build_id: 24,
+                policy: 1,
+            },
+            launch_measure: "ls2jv10V3HVShVI/RHCo/a43WO0soLZf0huU9ZZstIxRFA2okCqH/Z6nh2uPH9e8"
+                .to_string(),
+        };
+        let tik: [u8; 16] = hex::decode("9e939311ce26b5119f5df07e1ba10177")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let firmware_hash: [u8; 32] =
+            hex::decode("d06471f485c0a61aba5a431ec136b947be56907acf6ed96afb11788ae4525aeb")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        (measurement, tik, firmware_hash)
+    }
+
+    #[test]
+    fn verify_measurement_accepts_matching_firmware_and_policy() {
+        let (measurement, tik, firmware_hash) = fixture_measurement();
+        let vm_measure = verify_measurement(&measurement, &tik, &firmware_hash, Some(1)).unwrap();
+        let (expected, _) = measurement.split_launch_measure().unwrap();
+        assert_eq!(vm_measure, expected);
+    }
+
+    #[test]
+    fn verify_measurement_rejects_wrong_firmware() {
+        let (measurement, tik, _) = fixture_measurement();
+        let err = verify_measurement(&measurement, &tik, &[0u8; 32], None).unwrap_err();
+        assert!(matches!(err, ConfidentialError::MeasurementMismatch));
+    }
+
+    #[test]
+    fn verify_measurement_rejects_unexpected_policy() {
+        let (measurement, tik, firmware_hash) = fixture_measurement();
+        let err = verify_measurement(&measurement, &tik, &firmware_hash, Some(0x5)).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfidentialError::PolicyMismatch {
+                expected: 0x5,
+                actual: 0x1
+            }
+        ));
+    }
+
     #[test]
     fn build_secret_packet_table_layout() {
         use base64::Engine;
@@ -0,0 +1,265 @@
+//! Boot handshake for confidential instances.
+//!
+//! A confidential VM is allocated on its CRN in a paused state and only boots
+//! once the tenant has (1) uploaded a launch session bound to the platform's
+//! certificate chain, (2) checked the resulting launch measurement and (3)
+//! injected the disk-decryption secret. Session derivation itself is done by
+//! AMD's `sevctl`, which the SDK never invokes; this module consumes the files
+//! `sevctl session --name <prefix>` writes and drives the CRN side of the flow.
+
+use crate::confidential::{ConfidentialError, build_secret_packet, verify_measurement};
+use crate::crn::{CrnClient, CrnError};
+use aleph_types::item_hash::ItemHash;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{} must be {expected} bytes (got {actual})", path.display())]
+    InvalidKeyLength {
+        path: PathBuf,
+        expected: usize,
+        actual: usize,
+    },
+    #[error(transparent)]
+    Crn(#[from] CrnError),
+    #[error(transparent)]
+    Confidential(#[from] ConfidentialError),
+}
+
+/// The launch session blobs uploaded to the CRN: the guest owner's
+/// Diffie-Hellman certificate (`<prefix>_godh.b64`) and the session
+/// parameters (`<prefix>_session.b64`).
+#[derive(Debug, Clone)]
+pub struct LaunchSession {
+    pub godh: Vec<u8>,
+    pub session: Vec<u8>,
+}
+
+impl LaunchSession {
+    /// Reads the session blobs written by `sevctl session --name <prefix>`.
+    pub fn load(prefix: &Path) -> Result<Self, SessionError> {
+        Ok(Self {
+            godh: read(&with_suffix(prefix, "_godh.b64"))?,
+            session: read(&with_suffix(prefix, "_session.b64"))?,
+        })
+    }
+}
+
+/// Transport keys shared with the platform firmware: the TEK encrypts the
+/// injected secret, the TIK authenticates the measurement and the packet.
+/// Never leave the tenant's machine, and are wiped from memory on drop.
+#[derive(Clone)]
+pub struct SessionKeys {
+    pub tek: [u8; 16],
+    pub tik: [u8; 16],
+}
+
+impl std::fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionKeys").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SessionKeys {
+    fn drop(&mut self) {
+        self.tek.zeroize();
+        self.tik.zeroize();
+    }
+}
+
+impl SessionKeys {
+    /// Reads `<prefix>_tek.bin` and `<prefix>_tik.bin` as written by
+    /// `sevctl session --name <prefix>`.
+    pub fn load(prefix: &Path) -> Result<Self, SessionError> {
+        Ok(Self {
+            tek: read_key(&with_suffix(prefix, "_tek.bin"))?,
+            tik: read_key(&with_suffix(prefix, "_tik.bin"))?,
+        })
+    }
+}
+
+/// Uploads the launch session to the CRN, which starts the VM's firmware up to
+/// the point where it can be measured.
+pub async fn initialize_session(
+    crn: &CrnClient,
+    vm_id: &ItemHash,
+    session: &LaunchSession,
+) -> Result<(), SessionError> {
+    crn.initialize_confidential(vm_id, &session.session, &session.godh)
+        .await?;
+    Ok(())
+}
+
+/// Validates the launch measurement of `vm_id` and, only if it matches,
+/// injects `secret` so the VM can unlock its encrypted disk and finish booting.
+///
+/// `firmware_hash` is the SHA-256 of the expected OVMF blob (see
+/// [`crate::attestation::resolve_firmware_hash`]); `expected_policy` is
+/// forwarded to [`verify_measurement`]. Nothing is sent to the CRN when
+/// verification fails.
+pub async fn inject_disk_secret(
+    crn: &CrnClient,
+    vm_id: &ItemHash,
+    keys: &SessionKeys,
+    firmware_hash: &[u8; 32],
+    expected_policy: Option<u32>,
+    secret: &str,
+) -> Result<(), SessionError> {
+    let measurement = crn.get_measurement(vm_id).await?;
+    let vm_measure = verify_measurement(&measurement, &keys.tik, firmware_hash, expected_policy)?;
+
+    let mut iv = [0u8; 16];
+    OsRng.fill_bytes(&mut iv);
+    let (packet_header, encrypted_secret) =
+        build_secret_packet(&keys.tek, &keys.tik, &vm_measure, secret, iv);
+    crn.inject_secret(vm_id, &packet_header, &encrypted_secret)
+        .await?;
+    Ok(())
+}
+
+fn with_suffix(prefix: &Path, suffix: &str) -> PathBuf {
+    let mut path = prefix.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, SessionError> {
+    std::fs::read(path).map_err(|source| SessionError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn read_key(path: &Path) -> Result<[u8; 16], SessionError> {
+    let bytes = Zeroizing::new(read(path)?);
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| SessionError::InvalidKeyLength {
+            path: path.to_path_buf(),
+            expected: 16,
+            actual: bytes.len(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_files_load_from_sevctl_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = dir.path().join("vm");
+        std::fs::write(dir.path().join("vm_godh.b64"), b"godh").unwrap();
+        std::fs::write(dir.path().join("vm_session.b64"), b"session").unwrap();
+        std::fs::write(dir.path().join("vm_tek.bin"), [1u8; 16]).unwrap();
+        std::fs::write(dir.path().join("vm_tik.bin"), [2u8; 16]).unwrap();
+
+        let session = LaunchSession::load(&prefix).unwrap();
+        assert_eq!(session.godh, b"godh");
+        assert_eq!(session.session, b"session");
+
+        let keys = SessionKeys::load(&prefix).unwrap();
+        assert_eq!(keys.tek, [1u8; 16]);
+        assert_eq!(keys.tik, [2u8; 16]);
+    }
+
+    #[test]
+    fn session_keys_reject_wrong_length() {
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = dir.path().join("vm");
+        std::fs::write(dir.path().join("vm_tek.bin"), [1u8; 15]).unwrap();
+        std::fs::write(dir.path().join("vm_tik.bin"), [2u8; 16]).unwrap();
+
+        let err = SessionKeys::load(&prefix).unwrap_err();
+        assert!(matches!(
+            err,
+            SessionError::InvalidKeyLength {
+                expected: 16,
+                actual: 15,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "account-evm")]
+    #[tokio::test]
+    async fn inject_disk_secret_rejects_a_mismatched_policy() {
+        use aleph_types::account::EvmAccount;
+        use aleph_types::chain::Chain;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let vm_id: ItemHash = "1111111111111111111111111111111111111111111111111111111111111111"
+            .parse()
+            .unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/control/machine/{vm_id}/confidential/measurement"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sev_info": {"api_major": 1, "api_minor": 55, "build_id": 24, "policy": 1},
+                "launch_measure": "ls2jv10V3HVShVI/RHCo/a43WO0soLZf0huU9ZZstIxRFA2okCqH/Z6nh2uPH9e8"
+            })))
+            .mount(&server)
+            .await;
+        // Only the call with the matching policy injects the secret.
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/control/machine/{vm_id}/confidential/inject_secret"
+            )))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let account = EvmAccount::new(Chain::Ethereum, &[1u8; 32]).unwrap();
+        let crn = CrnClient::new(&account, server.uri().parse().unwrap()).unwrap();
+        let keys = SessionKeys {
+            tek: [0u8; 16],
+            tik: hex::decode("9e939311ce26b5119f5df07e1ba10177")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        };
+        let firmware_hash: [u8; 32] =
+            hex::decode("d06471f485c0a61aba5a431ec136b947be56907acf6ed96afb11788ae4525aeb")
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        let err = inject_disk_secret(&crn, &vm_id, &keys, &firmware_hash, Some(0x5), "secret")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SessionError::Confidential(ConfidentialError::PolicyMismatch {
+                expected: 0x5,
+                actual: 1,
+            })
+        ));
+
+        inject_disk_secret(&crn, &vm_id, &keys, &firmware_hash, Some(1), "secret")
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn session_keys_debug_redacts_key_material() {
+        let keys = SessionKeys {
+            tek: [0xab; 16],
+            tik: [0xcd; 16],
+        };
+        assert_eq!(format!("{keys:?}"), "SessionKeys { .. }");
+    }
+}
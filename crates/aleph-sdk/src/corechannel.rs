@@ -216,35 +216,10 @@ pub fn amend_node<A: Account>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aleph_types::account::{Account, SignError};
-    use aleph_types::chain::{Address, Chain, Signature};
+    use crate::test_utils::TestAccount;
     use aleph_types::message::MessageType;
     use std::str::FromStr;
 
-    struct TestAccount {
-        address: Address,
-    }
-
-    impl TestAccount {
-        fn new() -> Self {
-            Self {
-                address: Address::from("0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string()),
-            }
-        }
-    }
-
-    impl Account for TestAccount {
-        fn chain(&self) -> Chain {
-            Chain::Ethereum
-        }
-        fn address(&self) -> &Address {
-            &self.address
-        }
-        fn sign_raw(&self, _buffer: &[u8]) -> Result<Signature, SignError> {
-            Ok(Signature::from("0xDUMMY".to_string()))
-        }
-    }
-
     fn test_node_hash() -> NodeHash {
         NodeHash::from_str("a75e0d10aec10614553ed00070147dd288aa4f510346cf4f5c13a826ae9f2d77")
             .unwrap()
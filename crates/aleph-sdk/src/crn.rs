@@ -2,9 +2,14 @@ use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use crate::confidential::SEVMeasurement;
+use crate::crns_list::Gpu;
 
 use aleph_types::account::{Account, SignError};
 use aleph_types::item_hash::ItemHash;
+use aleph_types::message::execution::environment::{
+    GpuProperties, HostRequirements, MachineResources,
+};
+use aleph_types::message::execution::volume::PersistentVolumeSize;
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use chrono::Utc;
 use futures_util::{SinkExt, Stream, StreamExt};
@@ -54,15 +59,15 @@ fn crn_error_message(body: String) -> String {
     }
 }
 
-/// A log entry from the stream_logs WebSocket.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+/// A log entry from the stream_logs WebSocket or the logs endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct LogEntry {
     #[serde(rename = "type")]
     pub log_type: LogType,
     pub message: String,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum LogType {
     Stdout,
@@ -203,6 +208,222 @@ pub struct MappedPort {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// GPU section of a CRN's `/about/usage/system`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GpuUsage {
+    /// Every GPU installed on the node.
+    #[serde(default)]
+    pub devices: Vec<Gpu>,
+    /// GPUs not reserved by any VM.
+    #[serde(default)]
+    pub available_devices: Vec<Gpu>,
+}
+
+/// The GPU section alone, for callers that only need GPU availability.
+#[derive(Debug, Deserialize)]
+struct SystemUsageGpu {
+    #[serde(default)]
+    gpu: GpuUsage,
+}
+
+/// Resources of a CRN, as reported by its `/about/usage/system`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SystemUsage {
+    pub cpu: CpuUsage,
+    pub mem: StorageUsage,
+    pub disk: StorageUsage,
+    #[serde(default)]
+    pub gpu: GpuUsage,
+    #[serde(default)]
+    pub properties: Option<MachineProperties>,
+    /// Whether the node accepts new VMs.
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl SystemUsage {
+    /// Whether the node can host a VM with these requirements: same CPU
+    /// architecture and vendor, every required CPU feature, and a distinct
+    /// free GPU for each required one. Node constraints (owner, hash, ...)
+    /// are checked against the corechannel aggregate, not here.
+    pub fn satisfies(&self, requirements: &HostRequirements) -> bool {
+        if !self.active {
+            return false;
+        }
+        if let Some(cpu) = &requirements.cpu {
+            let Some(node_cpu) = self.properties.as_ref().map(|p| &p.cpu) else {
+                return false;
+            };
+            if node_cpu.architecture.as_deref() != wire_name(&cpu.architecture).as_deref() {
+                return false;
+            }
+            if let Some(vendor) = &cpu.vendor
+                && node_cpu.vendor.as_deref() != wire_name(vendor).as_deref()
+            {
+                return false;
+            }
+            let has_features = cpu.features.iter().all(|feature| {
+                wire_name(feature).is_some_and(|feature| node_cpu.features.contains(&feature))
+            });
+            if !has_features {
+                return false;
+            }
+        }
+        if let Some(gpus) = &requirements.gpu {
+            let mut free: Vec<&Gpu> = self.gpu.available_devices.iter().collect();
+            for wanted in gpus {
+                let Some(i) = free
+                    .iter()
+                    .position(|gpu| gpu.device_id.eq_ignore_ascii_case(&wanted.device_id))
+                else {
+                    return false;
+                };
+                free.swap_remove(i);
+            }
+        }
+        true
+    }
+
+    /// Whether the node has room for a VM with `resources` and a root file
+    /// system of `rootfs_size`: enough logical cores, available memory and
+    /// available disk.
+    pub fn has_capacity(
+        &self,
+        resources: &MachineResources,
+        rootfs_size: PersistentVolumeSize,
+    ) -> bool {
+        self.cpu.count >= resources.vcpus.get()
+            && self.mem.available_kb / 1024 >= resources.memory.count()
+            && self.disk.available_kb / 1024 >= u64::from(rootfs_size)
+    }
+}
+
+/// The string a unit enum or a string newtype is serialized as.
+fn wire_name(value: &impl Serialize) -> Option<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => Some(name),
+        _ => None,
+    }
+}
+
+/// Hardware properties of a CRN. Values are kept as the node reports them so
+/// that a node running newer hardware than this SDK knows still parses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MachineProperties {
+    pub cpu: MachineCpuProperties,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MachineCpuProperties {
+    /// e.g. `x86_64`.
+    #[serde(default)]
+    pub architecture: Option<String>,
+    /// e.g. `AuthenticAMD`.
+    #[serde(default)]
+    pub vendor: Option<String>,
+    /// e.g. `sev`, `sev_es`.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CpuUsage {
+    /// Number of logical cores.
+    pub count: u32,
+    #[serde(default)]
+    pub load_average: Option<LoadAverage>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct LoadAverage {
+    pub load1: f64,
+    pub load5: f64,
+    pub load15: f64,
+}
+
+/// Memory or disk space, in kilobytes.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct StorageUsage {
+    #[serde(rename = "total_kB")]
+    pub total_kb: u64,
+    #[serde(rename = "available_kB")]
+    pub available_kb: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuState {
+    Free,
+    /// Attached to a VM.
+    Reserved,
+}
+
+/// A GPU installed on a CRN and whether it can be allocated.
+#[derive(Debug, Clone)]
+pub struct GpuSlot {
+    pub gpu: Gpu,
+    pub state: GpuState,
+}
+
+/// GPUs of a CRN, as returned by [`CrnClient::get_gpu_availability`].
+#[derive(Debug, Clone, Default)]
+pub struct GpuAvailability {
+    pub gpus: Vec<GpuSlot>,
+}
+
+impl GpuAvailability {
+    /// Classifies the installed GPUs: those not listed as available are
+    /// reserved. GPUs are identified by their PCI host address.
+    pub fn from_usage(usage: GpuUsage) -> Self {
+        let gpus = usage
+            .devices
+            .into_iter()
+            .map(|gpu| {
+                let free = usage
+                    .available_devices
+                    .iter()
+                    .any(|available| available.pci_host == gpu.pci_host);
+                let state = if free {
+                    GpuState::Free
+                } else {
+                    GpuState::Reserved
+                };
+                GpuSlot { gpu, state }
+            })
+            .collect();
+        Self { gpus }
+    }
+
+    pub fn free(&self) -> impl Iterator<Item = &Gpu> {
+        self.in_state(GpuState::Free)
+    }
+
+    pub fn reserved(&self) -> impl Iterator<Item = &Gpu> {
+        self.in_state(GpuState::Reserved)
+    }
+
+    /// Free GPUs that can be attached to a VM requesting `requested`: same
+    /// PCI vendor and device ids, compatible with passthrough.
+    pub fn free_matching<'a>(
+        &'a self,
+        requested: &'a GpuProperties,
+    ) -> impl Iterator<Item = &'a Gpu> {
+        self.free().filter(|gpu| {
+            gpu.compatible && gpu.device_id.eq_ignore_ascii_case(&requested.device_id)
+        })
+    }
+
+    fn in_state(&self, state: GpuState) -> impl Iterator<Item = &Gpu> {
+        self.gpus
+            .iter()
+            .filter(move |slot| slot.state == state)
+            .map(|slot| &slot.gpu)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CrnError {
     #[error(transparent)]
@@ -590,6 +811,53 @@ impl CrnClient {
         Ok(response.json::<ActiveVmList>().await?)
     }
 
+    /// Lists the GPUs of the node and whether each one is free or reserved.
+    ///
+    /// Calls `GET /about/usage/system`. No auth; this endpoint is public.
+    pub async fn get_gpu_availability(&self) -> Result<GpuAvailability, CrnError> {
+        fetch_gpu_availability(&self.http_client, &self.crn_url).await
+    }
+
+    /// Reports the CPU, memory, disk and GPU resources of the node.
+    ///
+    /// Calls `GET /about/usage/system`. No auth; this endpoint is public.
+    pub async fn get_system_usage(&self) -> Result<SystemUsage, CrnError> {
+        fetch_system_usage(&self.http_client, &self.crn_url).await
+    }
+
+    /// Lists the VMs running on the node, keyed by item hash.
+    ///
+    /// Calls `GET /about/executions/list`. No auth; this endpoint is public.
+    pub async fn get_executions(&self) -> Result<HashMap<String, ExecutionInfo>, CrnError> {
+        fetch_executions(&self.http_client, &self.crn_url).await
+    }
+
+    /// `GET /control/machine/<vm>/logs`
+    ///
+    /// Returns the logs the VM has written so far; see
+    /// [`stream_logs`](Self::stream_logs) to follow them. Signed.
+    pub async fn get_logs(&self, vm_id: &ItemHash) -> Result<Vec<LogEntry>, CrnError> {
+        let path = format!("/control/machine/{vm_id}/logs");
+        let url = self.crn_url.join(&path).expect("valid path");
+
+        let mut request = self.http_client.get(url);
+        for (name, value) in self.auth_headers("GET", &path) {
+            request = request.header(name, value);
+        }
+        let response = request.send().await?;
+
+        let status = response.status().as_u16();
+        match status {
+            200..=299 => Ok(response.json::<Vec<LogEntry>>().await?),
+            403 => Err(CrnError::Unauthorized(response.text().await?)),
+            404 => Err(CrnError::VmNotFound(vm_id.clone())),
+            _ => Err(CrnError::Api {
+                status,
+                body: response.text().await?,
+            }),
+        }
+    }
+
     pub async fn create_backup(
         &self,
         vm_id: &ItemHash,
@@ -855,6 +1123,39 @@ pub async fn fetch_active_vms(
     Ok(resp.json::<ActiveVmList>().await?)
 }
 
+/// Free-function variant of `CrnClient::get_gpu_availability`, for callers
+/// that probe many CRNs without an account.
+pub async fn fetch_gpu_availability(
+    http: &reqwest::Client,
+    crn_url: &Url,
+) -> Result<GpuAvailability, CrnError> {
+    let url = crn_url.join("/about/usage/system").expect("valid path");
+    let resp = http.get(url).send().await?;
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(CrnError::Api { status, body });
+    }
+    let usage: SystemUsageGpu = resp.json().await?;
+    Ok(GpuAvailability::from_usage(usage.gpu))
+}
+
+/// Free-function variant of `CrnClient::get_system_usage`, for callers that
+/// probe many CRNs without an account.
+pub async fn fetch_system_usage(
+    http: &reqwest::Client,
+    crn_url: &Url,
+) -> Result<SystemUsage, CrnError> {
+    let url = crn_url.join("/about/usage/system").expect("valid path");
+    let resp = http.get(url).send().await?;
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(CrnError::Api { status, body });
+    }
+    Ok(resp.json().await?)
+}
+
 /// Free-function variant of `CrnClient::get_platform_certificate`. Useful for
 /// tests that don't need to construct a full `CrnClient`.
 pub async fn fetch_platform_certificate(
@@ -943,6 +1244,56 @@ mod tests {
     use super::*;
     use aleph_types::chain::Chain;
 
+    #[test]
+    fn gpu_availability_splits_free_and_reserved() {
+        let gpu = |pci_host: &str, device_id: &str| {
+            serde_json::json!({
+                "vendor": "NVIDIA",
+                "model": "H100",
+                "device_name": "GH100 [H100 SXM5 80GB]",
+                "device_class": "0302",
+                "pci_host": pci_host,
+                "device_id": device_id,
+                "compatible": true,
+            })
+        };
+        let usage: SystemUsageGpu = serde_json::from_value(serde_json::json!({
+            "cpu": {"count": 64},
+            "gpu": {
+                "devices": [gpu("01:00.0", "10de:2330"), gpu("02:00.0", "10de:2330"), gpu("03:00.0", "10de:2204")],
+                "available_devices": [gpu("02:00.0", "10de:2330"), gpu("03:00.0", "10de:2204")],
+            },
+        }))
+        .unwrap();
+        let availability = GpuAvailability::from_usage(usage.gpu);
+
+        let reserved: Vec<_> = availability
+            .reserved()
+            .map(|g| g.pci_host.as_str())
+            .collect();
+        assert_eq!(reserved, ["01:00.0"]);
+        assert_eq!(availability.free().count(), 2);
+
+        let requested: GpuProperties = serde_json::from_value(serde_json::json!({
+            "vendor": "NVIDIA",
+            "device_name": "GH100 [H100 SXM5 80GB]",
+            "device_class": "0302",
+            "device_id": "10DE:2330",
+        }))
+        .unwrap();
+        let matching: Vec<_> = availability
+            .free_matching(&requested)
+            .map(|g| g.pci_host.as_str())
+            .collect();
+        assert_eq!(matching, ["02:00.0"]);
+    }
+
+    #[test]
+    fn gpu_availability_defaults_to_no_gpus() {
+        let usage: SystemUsageGpu = serde_json::from_str(r#"{"cpu": {"count": 4}}"#).unwrap();
+        assert!(GpuAvailability::from_usage(usage.gpu).gpus.is_empty());
+    }
+
     #[test]
     fn deserialize_allocation_response_success() {
         let json = r#"{
@@ -1848,4 +2199,104 @@ mod tests {
             .unwrap_err();
         assert!(matches!(err, CrnError::VmNotFound(_)));
     }
+
+    #[tokio::test]
+    async fn fetch_system_usage_decodes_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/about/usage/system"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "cpu": {
+                    "count": 32,
+                    "load_average": {"load1": 1.5, "load5": 1.25, "load15": 1.0},
+                    "core_frequencies": {"min": 1500.0, "max": 3400.0},
+                },
+                "mem": {"total_kB": 131072000, "available_kB": 65536000},
+                "disk": {"total_kB": 2000000000, "available_kB": 1500000000},
+                "period": {"start_timestamp": "2026-01-01T00:00:00Z", "duration_seconds": 60},
+                "properties": {"cpu": {"architecture": "x86_64", "vendor": "AuthenticAMD"}},
+            })))
+            .mount(&server)
+            .await;
+
+        let url = Url::parse(&server.uri()).unwrap();
+        let usage = fetch_system_usage(&reqwest::Client::new(), &url)
+            .await
+            .unwrap();
+        assert_eq!(usage.cpu.count, 32);
+        assert_eq!(usage.cpu.load_average.unwrap().load5, 1.25);
+        assert_eq!(usage.mem.available_kb, 65536000);
+        assert_eq!(usage.disk.total_kb, 2000000000);
+        assert!(usage.gpu.devices.is_empty());
+        assert!(usage.active);
+    }
+
+    #[cfg(feature = "account-evm")]
+    #[tokio::test]
+    async fn get_logs_returns_past_entries() {
+        use aleph_types::account::EvmAccount;
+        use wiremock::matchers::{header_exists, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let vm = "5a586d6f59f6c2e6862f155204626dcf01a6ec1107e7aba67063cd48ffe41d99";
+        Mock::given(method("GET"))
+            .and(path(format!("/control/machine/{vm}/logs")))
+            .and(header_exists("X-SignedOperation"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"type": "stdout", "message": "booting"},
+                {"type": "stderr", "message": "oops"},
+            ])))
+            .mount(&server)
+            .await;
+
+        let account = EvmAccount::new(Chain::Ethereum, &[1u8; 32]).unwrap();
+        let client = CrnClient::new(&account, Url::parse(&server.uri()).unwrap()).unwrap();
+        let logs = client.get_logs(&vm.parse().unwrap()).await.unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].log_type, LogType::Stderr);
+        assert_eq!(logs[0].message, "booting");
+    }
+
+    #[test]
+    fn system_usage_satisfies_cpu_and_gpu_requirements() {
+        let gpu = |device_id: &str| {
+            serde_json::json!({
+                "vendor": "NVIDIA", "model": "RTX 4090", "device_name": "AD102",
+                "device_class": "0300", "pci_host": format!("01:00.{device_id}"),
+                "device_id": device_id, "compatible": true,
+            })
+        };
+        let usage: SystemUsage = serde_json::from_value(serde_json::json!({
+            "cpu": {"count": 8},
+            "mem": {"total_kB": 1, "available_kB": 1},
+            "disk": {"total_kB": 1, "available_kB": 1},
+            "gpu": {"devices": [gpu("10de:2684")], "available_devices": [gpu("10de:2684")]},
+            "properties": {"cpu": {"architecture": "x86_64", "vendor": "AuthenticAMD", "features": ["sev"]}},
+        }))
+        .unwrap();
+        let requirements = |value: serde_json::Value| -> HostRequirements {
+            serde_json::from_value(value).unwrap()
+        };
+        let rtx = serde_json::json!({
+            "vendor": "NVIDIA", "device_name": "AD102", "device_class": "0300", "device_id": "10DE:2684",
+        });
+
+        assert!(usage.satisfies(&requirements(serde_json::json!({}))));
+        assert!(usage.satisfies(&requirements(serde_json::json!({
+            "cpu": {"architecture": "x86_64", "vendor": "AuthenticAMD", "features": ["sev"]},
+            "gpu": [rtx],
+        }))));
+        assert!(!usage.satisfies(&requirements(serde_json::json!({
+            "cpu": {"architecture": "x86_64", "features": ["sev_snp"]},
+        }))));
+        assert!(!usage.satisfies(&requirements(serde_json::json!({
+            "cpu": {"architecture": "arm64", "features": []},
+        }))));
+        // A single free GPU cannot serve two requested ones.
+        assert!(!usage.satisfies(&requirements(serde_json::json!({"gpu": [rtx, rtx]}))));
+    }
 }
@@ -0,0 +1,438 @@
+//! DNS checks for custom domains.
+//!
+//! Registering a domain in the `domains` aggregate only tells the Aleph
+//! gateways which website, program or instance it serves. Requests reach them
+//! once the owner has created the DNS records they expect: a CNAME to the
+//! gateway and a `_control` TXT record proving ownership of the aggregate
+//! entry. [`check_domain_dns`] resolves those records and reports, for each
+//! one, what is missing or wrong.
+//!
+//! Resolution goes through the [`DnsResolver`] trait; the `dns` feature
+//! provides [`SystemResolver`], backed by the system resolver configuration.
+
+use crate::aggregate_models::domains::DomainTargetType;
+use aleph_types::chain::Address;
+use serde::Serialize;
+use std::fmt;
+
+/// Gateway serving websites stored on IPFS.
+pub const DNS_IPFS_DOMAIN: &str = "ipfs.public.aleph.sh";
+/// Zone holding the DNSLink records of websites.
+pub const DNS_STATIC_DOMAIN: &str = "static.public.aleph.sh";
+/// Zone routing domains to programs.
+pub const DNS_PROGRAM_DOMAIN: &str = "program.public.aleph.sh";
+/// Zone routing domains to instances.
+pub const DNS_INSTANCE_DOMAIN: &str = "instance.public.aleph.sh";
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("DNS lookup of {name} failed: {message}")]
+pub struct DnsLookupError {
+    pub name: String,
+    pub message: String,
+}
+
+/// Resolves the record types the gateways rely on.
+///
+/// Names are passed without a trailing dot. A name without records of the
+/// requested type resolves to an empty list, not to an error.
+#[async_trait::async_trait]
+pub trait DnsResolver {
+    async fn cname(&self, name: &str) -> Result<Vec<String>, DnsLookupError>;
+    async fn txt(&self, name: &str) -> Result<Vec<String>, DnsLookupError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DnsRecordType {
+    #[serde(rename = "CNAME")]
+    Cname,
+    #[serde(rename = "TXT")]
+    Txt,
+}
+
+impl fmt::Display for DnsRecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsRecordType::Cname => f.pad("CNAME"),
+            DnsRecordType::Txt => f.pad("TXT"),
+        }
+    }
+}
+
+/// A DNS record the gateways expect for a domain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DnsRule {
+    pub record_type: DnsRecordType,
+    pub name: String,
+    pub expected: String,
+}
+
+/// Records to create so that `domain` serves a target of type `kind` owned by
+/// `owner`.
+pub fn required_dns_records(domain: &str, kind: DomainTargetType, owner: &Address) -> Vec<DnsRule> {
+    let domain = normalize(domain);
+    let cname = |name: String, expected: String| DnsRule {
+        record_type: DnsRecordType::Cname,
+        name,
+        expected,
+    };
+
+    let mut rules = match kind {
+        DomainTargetType::Ipfs => vec![
+            cname(domain.clone(), DNS_IPFS_DOMAIN.to_string()),
+            cname(
+                format!("_dnslink.{domain}"),
+                format!("_dnslink.{domain}.{DNS_STATIC_DOMAIN}"),
+            ),
+        ],
+        DomainTargetType::Program => vec![cname(
+            domain.clone(),
+            format!("{domain}.{DNS_PROGRAM_DOMAIN}"),
+        )],
+        DomainTargetType::Instance => vec![cname(
+            domain.clone(),
+            format!("{domain}.{DNS_INSTANCE_DOMAIN}"),
+        )],
+    };
+    rules.push(DnsRule {
+        record_type: DnsRecordType::Txt,
+        name: format!("_control.{domain}"),
+        expected: owner.to_string(),
+    });
+    rules
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DnsCheckStatus {
+    Ok,
+    /// The name has no record of the expected type.
+    Missing,
+    /// The name has records of the expected type, none with the expected
+    /// value.
+    Mismatch,
+    LookupFailed {
+        message: String,
+    },
+}
+
+/// Outcome of the check of one [`DnsRule`].
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsRecordCheck {
+    pub rule: DnsRule,
+    /// Values currently published for the rule's name and type.
+    pub found: Vec<String>,
+    #[serde(flatten)]
+    pub status: DnsCheckStatus,
+}
+
+impl DnsRecordCheck {
+    pub fn is_ok(&self) -> bool {
+        self.status == DnsCheckStatus::Ok
+    }
+
+    /// What to change in the DNS zone, or `None` if the record is correct.
+    pub fn hint(&self) -> Option<String> {
+        let DnsRule {
+            record_type,
+            name,
+            expected,
+        } = &self.rule;
+        match &self.status {
+            DnsCheckStatus::Ok => None,
+            DnsCheckStatus::Missing => Some(format!(
+                "create a {record_type} record on {name} with value {expected}"
+            )),
+            DnsCheckStatus::Mismatch => Some(format!(
+                "{record_type} record on {name} is {}, change it to {expected}",
+                self.found.join(", ")
+            )),
+            DnsCheckStatus::LookupFailed { message } => Some(format!(
+                "could not resolve {record_type} {name} ({message}), retry once DNS has propagated"
+            )),
+        }
+    }
+}
+
+/// Result of [`check_domain_dns`].
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainDnsReport {
+    pub domain: String,
+    pub checks: Vec<DnsRecordCheck>,
+}
+
+impl DomainDnsReport {
+    /// Whether every required record is in place.
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(DnsRecordCheck::is_ok)
+    }
+
+    /// Hints of the failed checks, in the order of the checks.
+    pub fn hints(&self) -> Vec<String> {
+        self.checks
+            .iter()
+            .filter_map(DnsRecordCheck::hint)
+            .collect()
+    }
+}
+
+/// Checks that the DNS records of `domain` point at the Aleph gateway serving
+/// targets of type `kind`, and that the `_control` record names `owner`.
+///
+/// Can run before publishing the `domains` aggregate, to catch mistakes
+/// early, and after, to tell DNS propagation issues apart from aggregate ones.
+pub async fn check_domain_dns<R: DnsResolver + ?Sized>(
+    resolver: &R,
+    domain: &str,
+    kind: DomainTargetType,
+    owner: &Address,
+) -> DomainDnsReport {
+    let rules = required_dns_records(domain, kind, owner);
+    let mut checks = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let lookup = match rule.record_type {
+            DnsRecordType::Cname => resolver.cname(&rule.name).await,
+            DnsRecordType::Txt => resolver.txt(&rule.name).await,
+        };
+        let check = match lookup {
+            Ok(found) => {
+                let status = if found.is_empty() {
+                    DnsCheckStatus::Missing
+                } else if found.iter().any(|value| record_matches(&rule, value)) {
+                    DnsCheckStatus::Ok
+                } else {
+                    DnsCheckStatus::Mismatch
+                };
+                DnsRecordCheck {
+                    rule,
+                    found,
+                    status,
+                }
+            }
+            Err(e) => DnsRecordCheck {
+                rule,
+                found: Vec::new(),
+                status: DnsCheckStatus::LookupFailed { message: e.message },
+            },
+        };
+        checks.push(check);
+    }
+    DomainDnsReport {
+        domain: normalize(domain),
+        checks,
+    }
+}
+
+/// Host names compare case-insensitively and with or without the root dot.
+/// TXT values are compared as is, except for EVM addresses whose checksum
+/// casing is optional.
+fn record_matches(rule: &DnsRule, value: &str) -> bool {
+    match rule.record_type {
+        DnsRecordType::Cname => normalize(value) == normalize(&rule.expected),
+        DnsRecordType::Txt if rule.expected.starts_with("0x") => {
+            value.trim().eq_ignore_ascii_case(&rule.expected)
+        }
+        DnsRecordType::Txt => value.trim() == rule.expected,
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// [`DnsResolver`] using the system DNS configuration.
+#[cfg(feature = "dns")]
+pub struct SystemResolver(hickory_resolver::TokioResolver);
+
+#[cfg(feature = "dns")]
+impl SystemResolver {
+    /// Reads `/etc/resolv.conf` (or the platform equivalent).
+    pub fn new() -> Result<Self, DnsLookupError> {
+        let resolver = hickory_resolver::TokioResolver::builder_tokio()
+            .map_err(|e| DnsLookupError {
+                name: String::new(),
+                message: e.to_string(),
+            })?
+            .build();
+        Ok(Self(resolver))
+    }
+
+    fn lookup_error(name: &str, e: hickory_resolver::ResolveError) -> DnsLookupError {
+        DnsLookupError {
+            name: name.to_string(),
+            message: e.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "dns")]
+#[async_trait::async_trait]
+impl DnsResolver for SystemResolver {
+    async fn cname(&self, name: &str) -> Result<Vec<String>, DnsLookupError> {
+        use hickory_resolver::proto::rr::{RData, RecordType};
+
+        match self.0.lookup(name, RecordType::CNAME).await {
+            Ok(lookup) => Ok(lookup
+                .record_iter()
+                .filter_map(|record| match record.data() {
+                    RData::CNAME(target) => Some(normalize(&target.0.to_string())),
+                    _ => None,
+                })
+                .collect()),
+            Err(e) if e.is_no_records_found() => Ok(Vec::new()),
+            Err(e) => Err(Self::lookup_error(name, e)),
+        }
+    }
+
+    async fn txt(&self, name: &str) -> Result<Vec<String>, DnsLookupError> {
+        match self.0.txt_lookup(name).await {
+            Ok(lookup) => Ok(lookup
+                .iter()
+                .map(|txt| {
+                    txt.txt_data()
+                        .iter()
+                        .map(|chunk| String::from_utf8_lossy(chunk))
+                        .collect()
+                })
+                .collect()),
+            Err(e) if e.is_no_records_found() => Ok(Vec::new()),
+            Err(e) => Err(Self::lookup_error(name, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const OWNER: &str = "0x238224C744F4b90b4494516e074D2676ECfC6803";
+
+    #[derive(Default)]
+    struct StaticResolver {
+        cname: HashMap<&'static str, Vec<&'static str>>,
+        txt: HashMap<&'static str, Vec<&'static str>>,
+    }
+
+    fn lookup(
+        records: &HashMap<&'static str, Vec<&'static str>>,
+        name: &str,
+    ) -> Result<Vec<String>, DnsLookupError> {
+        if name.starts_with("broken.") || name.contains(".broken.") {
+            return Err(DnsLookupError {
+                name: name.to_string(),
+                message: "SERVFAIL".to_string(),
+            });
+        }
+        Ok(records
+            .get(name)
+            .map(|values| values.iter().map(|v| v.to_string()).collect())
+            .unwrap_or_default())
+    }
+
+    #[async_trait::async_trait]
+    impl DnsResolver for StaticResolver {
+        async fn cname(&self, name: &str) -> Result<Vec<String>, DnsLookupError> {
+            lookup(&self.cname, name)
+        }
+
+        async fn txt(&self, name: &str) -> Result<Vec<String>, DnsLookupError> {
+            lookup(&self.txt, name)
+        }
+    }
+
+    fn owner() -> Address {
+        Address::from(OWNER.to_string())
+    }
+
+    #[test]
+    fn required_records_depend_on_target_type() {
+        let rules = required_dns_records("Site.Example.com.", DomainTargetType::Ipfs, &owner());
+        let names: Vec<_> = rules.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "site.example.com",
+                "_dnslink.site.example.com",
+                "_control.site.example.com"
+            ]
+        );
+        assert_eq!(
+            rules[1].expected,
+            "_dnslink.site.example.com.static.public.aleph.sh"
+        );
+
+        let rules = required_dns_records("app.example.com", DomainTargetType::Program, &owner());
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].expected, "app.example.com.program.public.aleph.sh");
+        assert_eq!(rules[1].expected, OWNER);
+    }
+
+    #[tokio::test]
+    async fn check_reports_valid_website_domain() {
+        let resolver = StaticResolver {
+            cname: HashMap::from([
+                ("site.example.com", vec!["IPFS.public.aleph.sh."]),
+                (
+                    "_dnslink.site.example.com",
+                    vec!["_dnslink.site.example.com.static.public.aleph.sh."],
+                ),
+            ]),
+            txt: HashMap::from([(
+                "_control.site.example.com",
+                vec!["0x238224c744f4b90b4494516e074d2676ecfc6803"],
+            )]),
+        };
+
+        let report = check_domain_dns(
+            &resolver,
+            "site.example.com",
+            DomainTargetType::Ipfs,
+            &owner(),
+        )
+        .await;
+        assert!(report.is_valid(), "{:?}", report.hints());
+    }
+
+    #[tokio::test]
+    async fn check_explains_missing_and_wrong_records() {
+        let resolver = StaticResolver {
+            cname: HashMap::from([("app.example.com", vec!["ipfs.public.aleph.sh"])]),
+            ..Default::default()
+        };
+
+        let report = check_domain_dns(
+            &resolver,
+            "app.example.com",
+            DomainTargetType::Program,
+            &owner(),
+        )
+        .await;
+        assert!(!report.is_valid());
+        assert_eq!(report.checks[0].status, DnsCheckStatus::Mismatch);
+        assert_eq!(report.checks[1].status, DnsCheckStatus::Missing);
+        assert_eq!(
+            report.hints(),
+            [
+                "CNAME record on app.example.com is ipfs.public.aleph.sh, change it to app.example.com.program.public.aleph.sh".to_string(),
+                format!("create a TXT record on _control.app.example.com with value {OWNER}"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn check_reports_lookup_failures() {
+        let report = check_domain_dns(
+            &StaticResolver::default(),
+            "broken.example.com",
+            DomainTargetType::Instance,
+            &owner(),
+        )
+        .await;
+        assert!(
+            report
+                .checks
+                .iter()
+                .all(|check| matches!(check.status, DnsCheckStatus::LookupFailed { .. }))
+        );
+    }
+}
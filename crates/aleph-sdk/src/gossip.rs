@@ -0,0 +1,198 @@
+//! Ingestion of messages received over pubsub.
+//!
+//! CCNs relay every new message on a pubsub topic (`aleph.queue_topic` in the
+//! pyaleph configuration, `ALEPH-TEST` by default) before processing it. An
+//! indexer subscribed to that topic, through its own libp2p or IPFS node, sees
+//! messages without going through any CCN HTTP API.
+//!
+//! [`GossipIngest`] turns the raw pubsub payloads into messages: it drops the
+//! duplicates that gossip inevitably delivers and the messages that fail the
+//! checks a CCN would run first (signature, and item hash for inline content).
+//! It is transport-agnostic: the pubsub subscription itself is left to the
+//! caller, or to the libp2p node of
+//! [`gossip_node`](crate::gossip_node) with the `gossip-node` feature.
+
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{
+    ContentSource, Message, MessageContent, MessageHeader, SignatureVerificationError,
+};
+use std::collections::{HashSet, VecDeque};
+
+/// Topic on which CCNs relay new messages, unless configured otherwise.
+pub const DEFAULT_QUEUE_TOPIC: &str = "ALEPH-TEST";
+
+/// Number of item hashes remembered for deduplication by default.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 100_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GossipError {
+    #[error("invalid message payload")]
+    Parse(#[source] serde_json::Error),
+    #[error("signature pre-check failed for {item_hash}")]
+    Signature {
+        item_hash: ItemHash,
+        #[source]
+        source: SignatureVerificationError,
+    },
+    #[error("item hash mismatch: expected {expected}, got {actual}")]
+    ItemHash {
+        expected: ItemHash,
+        actual: ItemHash,
+    },
+    #[error("invalid inline content for {item_hash}")]
+    Content {
+        item_hash: ItemHash,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// A message accepted from pubsub.
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum GossipMessage {
+    /// Inline message: signature and content hash checked, content decoded
+    /// from `item_content`.
+    Inline(Message),
+    /// Storage or IPFS message: only the signature could be checked. Its
+    /// content has to be fetched and verified separately, e.g. with
+    /// [`download_file_by_hash`](crate::client::AlephStorageClient::download_file_by_hash).
+    Remote(MessageHeader),
+}
+
+impl GossipMessage {
+    pub fn item_hash(&self) -> &ItemHash {
+        match self {
+            GossipMessage::Inline(message) => &message.item_hash,
+            GossipMessage::Remote(header) => &header.item_hash,
+        }
+    }
+}
+
+/// Validates and deduplicates pubsub payloads.
+pub struct GossipIngest {
+    capacity: usize,
+    seen: HashSet<ItemHash>,
+    /// Insertion order of `seen`, to forget the oldest hashes first.
+    order: VecDeque<ItemHash>,
+}
+
+impl Default for GossipIngest {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_CAPACITY)
+    }
+}
+
+impl GossipIngest {
+    /// Creates an ingester remembering the last `capacity` item hashes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Validates one pubsub payload.
+    ///
+    /// Returns `Ok(None)` for a message that was already accepted. Invalid
+    /// messages are not remembered, so that a forged copy cannot shadow the
+    /// genuine message carrying the same item hash.
+    pub fn ingest(&mut self, payload: &[u8]) -> Result<Option<GossipMessage>, GossipError> {
+        let header: MessageHeader = serde_json::from_slice(payload).map_err(GossipError::Parse)?;
+        if self.seen.contains(&header.item_hash) {
+            return Ok(None);
+        }
+
+        header
+            .verify_signature()
+            .map_err(|source| GossipError::Signature {
+                item_hash: header.item_hash.clone(),
+                source,
+            })?;
+
+        let message = match &header.content_source {
+            ContentSource::Inline { item_content } => {
+                if let Some(Err((expected, actual))) =
+                    header.content_source.verify_inline_hash(&header.item_hash)
+                {
+                    return Err(GossipError::ItemHash { expected, actual });
+                }
+                let content = MessageContent::deserialize_with_type(
+                    header.message_type,
+                    item_content.as_bytes(),
+                )
+                .map_err(|source| GossipError::Content {
+                    item_hash: header.item_hash.clone(),
+                    source,
+                })?;
+                GossipMessage::Inline(header.with_content(content))
+            }
+            ContentSource::Storage | ContentSource::Ipfs => GossipMessage::Remote(header),
+        };
+
+        self.remember(message.item_hash().clone());
+        Ok(Some(message))
+    }
+
+    fn remember(&mut self, item_hash: ItemHash) {
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.seen.insert(item_hash.clone());
+        self.order.push_back(item_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::message::pending::PendingMessage;
+
+    /// The pubsub payload of a fixture message, as its sender posted it.
+    fn payload(json: &str) -> Vec<u8> {
+        let message: Message = serde_json::from_str(json).unwrap();
+        serde_json::to_vec(&PendingMessage::try_from(&message).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn ingest_accepts_valid_messages_once() {
+        let post = payload(include_str!("../../../fixtures/messages/post/post.json"));
+        let mut ingest = GossipIngest::default();
+
+        let message = ingest.ingest(&post).unwrap().unwrap();
+        assert!(matches!(message, GossipMessage::Inline(_)));
+        assert!(ingest.ingest(&post).unwrap().is_none());
+    }
+
+    #[test]
+    fn ingest_rejects_forgeries_without_remembering_them() {
+        let json = include_str!("../../../fixtures/messages/post/post.json");
+        let mut forged: serde_json::Value = serde_json::from_slice(&payload(json)).unwrap();
+        forged["sender"] = "0x0000000000000000000000000000000000000001".into();
+        let forged = serde_json::to_vec(&forged).unwrap();
+
+        let mut ingest = GossipIngest::default();
+        assert!(matches!(
+            ingest.ingest(&forged),
+            Err(GossipError::Signature { .. })
+        ));
+        assert!(ingest.ingest(&payload(json)).unwrap().is_some());
+        assert!(matches!(ingest.ingest(b"{}"), Err(GossipError::Parse(_))));
+    }
+
+    #[test]
+    fn dedup_forgets_oldest_hashes_beyond_capacity() {
+        let post = payload(include_str!("../../../fixtures/messages/post/post.json"));
+        let sol = payload(include_str!(
+            "../../../fixtures/messages/post/post-sol.json"
+        ));
+        let mut ingest = GossipIngest::new(1);
+
+        assert!(ingest.ingest(&post).unwrap().is_some());
+        assert!(ingest.ingest(&sol).unwrap().is_some());
+        assert!(ingest.ingest(&post).unwrap().is_some());
+    }
+}
@@ -0,0 +1,240 @@
+//! A minimal libp2p node following the Aleph gossip topics.
+//!
+//! [`GossipNode`] joins the pubsub topics on which CCNs relay new messages and
+//! yields the messages that pass [`GossipIngest`]: duplicates are dropped and
+//! forgeries rejected before they reach the caller. Messages are received
+//! from the P2P network directly, so an indexer keeps ingesting while the
+//! HTTP API of its CCN is down.
+//!
+//! The node speaks TCP with noise and yamux, like the P2P service of CCNs,
+//! and validates messages before gossipsub forwards them: rejected payloads
+//! are not propagated to other peers.
+
+use crate::gossip::{
+    DEFAULT_DEDUP_CAPACITY, DEFAULT_QUEUE_TOPIC, GossipError, GossipIngest, GossipMessage,
+};
+use futures_util::{Stream, StreamExt};
+use libp2p::gossipsub::{self, IdentTopic, MessageAcceptance, MessageAuthenticity, ValidationMode};
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::SwarmEvent;
+use libp2p::{Multiaddr, PeerId, Swarm, noise, tcp, yamux};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+const CHANNEL_BUFFER_SIZE: usize = 100;
+/// Connections to bootstrap peers carry the gossip: keep them open while
+/// topics are quiet.
+const IDLE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, thiserror::Error)]
+pub enum GossipNodeError {
+    #[error("failed to set up the libp2p transport: {0}")]
+    Transport(String),
+    #[error("invalid gossipsub configuration: {0}")]
+    Behaviour(String),
+    #[error("failed to subscribe to topic {topic}: {reason}")]
+    Subscribe { topic: String, reason: String },
+    #[error("failed to listen on {address}")]
+    Listen {
+        address: Multiaddr,
+        #[source]
+        source: libp2p::TransportError<std::io::Error>,
+    },
+    #[error("failed to dial {address}")]
+    Dial {
+        address: Multiaddr,
+        #[source]
+        source: libp2p::swarm::DialError,
+    },
+}
+
+/// Configuration of a gossip node; [`GossipNode::subscribe`] starts it.
+///
+/// Bootstrap peers are typically CCNs, whose P2P addresses are listed by
+/// [`AlephClient::get_node_info`](crate::client::AlephClient::get_node_info).
+#[derive(Debug, Clone)]
+pub struct GossipNode {
+    bootstrap: Vec<Multiaddr>,
+    topics: Vec<String>,
+    listen: Vec<Multiaddr>,
+    dedup_capacity: usize,
+}
+
+impl GossipNode {
+    /// A node joining the network through `bootstrap` peers and following
+    /// [`DEFAULT_QUEUE_TOPIC`].
+    pub fn new(bootstrap: impl IntoIterator<Item = Multiaddr>) -> Self {
+        Self {
+            bootstrap: bootstrap.into_iter().collect(),
+            topics: vec![DEFAULT_QUEUE_TOPIC.to_string()],
+            listen: Vec::new(),
+            dedup_capacity: DEFAULT_DEDUP_CAPACITY,
+        }
+    }
+
+    /// Topics to follow instead of [`DEFAULT_QUEUE_TOPIC`].
+    pub fn topics<S: Into<String>>(mut self, topics: impl IntoIterator<Item = S>) -> Self {
+        self.topics = topics.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Also accepts inbound connections on `address`. By default the node only
+    /// dials its bootstrap peers.
+    pub fn listen_on(mut self, address: Multiaddr) -> Self {
+        self.listen.push(address);
+        self
+    }
+
+    /// Number of item hashes remembered for deduplication, see
+    /// [`GossipIngest::new`].
+    pub fn dedup_capacity(mut self, capacity: usize) -> Self {
+        self.dedup_capacity = capacity;
+        self
+    }
+
+    /// Starts the node and returns the stream of the messages it accepts.
+    ///
+    /// Invalid payloads are yielded as errors without ending the stream.
+    /// Dropping the stream stops the node.
+    pub async fn subscribe(
+        self,
+    ) -> Result<
+        impl Stream<Item = Result<GossipMessage, GossipError>> + Send + Unpin + 'static,
+        GossipNodeError,
+    > {
+        let mut swarm = build_swarm()?;
+
+        for topic in &self.topics {
+            swarm
+                .behaviour_mut()
+                .subscribe(&IdentTopic::new(topic))
+                .map_err(|e| GossipNodeError::Subscribe {
+                    topic: topic.clone(),
+                    reason: e.to_string(),
+                })?;
+        }
+        for address in self.listen {
+            swarm
+                .listen_on(address.clone())
+                .map_err(|source| GossipNodeError::Listen { address, source })?;
+        }
+        for address in self.bootstrap {
+            // Explicit peers receive every message of the topics, even
+            // outside of the mesh: a light node with few peers relies on them.
+            if let Some(peer_id) = peer_id(&address) {
+                swarm.behaviour_mut().add_explicit_peer(&peer_id);
+            }
+            swarm
+                .dial(address.clone())
+                .map_err(|source| GossipNodeError::Dial { address, source })?;
+        }
+
+        let (tx, rx) = mpsc::channel(CHANNEL_BUFFER_SIZE);
+        tokio::spawn(run_node(swarm, GossipIngest::new(self.dedup_capacity), tx));
+        Ok(ReceiverStream::new(rx))
+    }
+}
+
+fn build_swarm() -> Result<Swarm<gossipsub::Behaviour>, GossipNodeError> {
+    Ok(libp2p::SwarmBuilder::with_new_identity()
+        .with_tokio()
+        .with_tcp(
+            tcp::Config::default(),
+            noise::Config::new,
+            yamux::Config::default,
+        )
+        .map_err(|e| GossipNodeError::Transport(e.to_string()))?
+        .with_dns()
+        .map_err(|e| GossipNodeError::Transport(e.to_string()))?
+        .with_behaviour(
+            |key| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+                // Messages are forwarded only once `run_node` has validated them.
+                let config = gossipsub::ConfigBuilder::default()
+                    .validation_mode(ValidationMode::Strict)
+                    .validate_messages()
+                    .build()
+                    .map_err(|e| e.to_string())?;
+                Ok(gossipsub::Behaviour::new(
+                    MessageAuthenticity::Signed(key.clone()),
+                    config,
+                )?)
+            },
+        )
+        .map_err(|e| GossipNodeError::Behaviour(e.to_string()))?
+        .with_swarm_config(|config| config.with_idle_connection_timeout(IDLE_CONNECTION_TIMEOUT))
+        .build())
+}
+
+/// The peer ID at the end of `address`, if any.
+fn peer_id(address: &Multiaddr) -> Option<PeerId> {
+    address.iter().find_map(|protocol| match protocol {
+        Protocol::P2p(peer_id) => Some(peer_id),
+        _ => None,
+    })
+}
+
+async fn run_node(
+    mut swarm: Swarm<gossipsub::Behaviour>,
+    mut ingest: GossipIngest,
+    tx: mpsc::Sender<Result<GossipMessage, GossipError>>,
+) {
+    loop {
+        let event = tokio::select! {
+            event = swarm.select_next_some() => event,
+            // Receiver dropped: stop the node.
+            _ = tx.closed() => return,
+        };
+        match event {
+            SwarmEvent::Behaviour(gossipsub::Event::Message {
+                propagation_source,
+                message_id,
+                message,
+            }) => {
+                let (acceptance, item) = match ingest.ingest(&message.data) {
+                    Ok(Some(message)) => (MessageAcceptance::Accept, Some(Ok(message))),
+                    Ok(None) => (MessageAcceptance::Ignore, None),
+                    Err(e) => (MessageAcceptance::Reject, Some(Err(e))),
+                };
+                let _ = swarm.behaviour_mut().report_message_validation_result(
+                    &message_id,
+                    &propagation_source,
+                    acceptance,
+                );
+                if let Some(item) = item
+                    && tx.send(item).await.is_err()
+                {
+                    return;
+                }
+            }
+            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                tracing::debug!(%peer_id, "gossip peer connected");
+            }
+            SwarmEvent::ConnectionClosed { peer_id, .. } => {
+                tracing::debug!(%peer_id, "gossip peer disconnected");
+            }
+            SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
+                tracing::warn!(?peer_id, %error, "failed to connect to gossip peer");
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peer_id_is_read_from_the_p2p_component() {
+        let address: Multiaddr =
+            "/ip4/1.2.3.4/tcp/4025/p2p/QmZkurbY2G2hWay59yiTgQNaQxHSNzKZFt2jbnwJhQcKgV"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            peer_id(&address).unwrap().to_string(),
+            "QmZkurbY2G2hWay59yiTgQNaQxHSNzKZFt2jbnwJhQcKgV"
+        );
+        assert!(peer_id(&"/ip4/1.2.3.4/tcp/4025".parse().unwrap()).is_none());
+    }
+}
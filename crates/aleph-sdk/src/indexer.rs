@@ -0,0 +1,453 @@
+//! Indexing of message histories.
+//!
+//! An [`Indexer`] feeds the messages matching a filter to a [`MessageHandler`]:
+//! first the history, paginated through the REST API in chronological order,
+//! then the new messages as they arrive over the websocket. Its progress is
+//! saved in a [`CheckpointStore`] so that a restarted indexer resumes where
+//! the previous one stopped instead of replaying the whole history.
+//!
+//! ```ignore
+//! use aleph_sdk::indexer::{FileCheckpoint, HandlerError, Indexer, MessageHandler};
+//! use aleph_types::message::{Message, PostContent};
+//!
+//! struct Posts;
+//!
+//! #[async_trait::async_trait]
+//! impl MessageHandler for Posts {
+//!     async fn on_post(&mut self, message: &Message, post: &PostContent) -> Result<(), HandlerError> {
+//!         println!("{}: {}", message.item_hash, post.post_type);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! Indexer::new(&client, filter)
+//!     .checkpoints(FileCheckpoint::new("posts.checkpoint.json"))
+//!     .run(&mut Posts)
+//!     .await?;
+//! ```
+//!
+//! Delivery is at least once: on resume, the history is fetched again from
+//! [`WATCH_BACKFILL_MARGIN`] before the checkpoint since message times are
+//! set by their senders, so handlers must tolerate seeing a message twice.
+//! Within one run, each message is handled once.
+
+use crate::client::{
+    AlephClient, AlephMessageClient, MessageError, MessageFilter, SortBy, SortOrder,
+    WATCH_BACKFILL_MARGIN,
+};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{
+    AggregateContent, ForgetContent, InstanceContent, Message, MessageContentEnum, PostContent,
+    ProgramContent, StoreContent,
+};
+use aleph_types::timestamp::Timestamp;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Messages handled between two checkpoints while going through the history.
+const CHECKPOINT_INTERVAL: usize = 200;
+
+/// Error returned by handlers. It stops the indexer.
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    #[error(transparent)]
+    Client(#[from] MessageError),
+    #[error("handler failed on message {item_hash}: {source}")]
+    Handler {
+        item_hash: ItemHash,
+        #[source]
+        source: HandlerError,
+    },
+    #[error("checkpoint error: {0}")]
+    Checkpoint(#[from] std::io::Error),
+}
+
+/// Receives the messages of an [`Indexer`], one method per message type.
+///
+/// All methods default to ignoring their message. A failing handler stops the
+/// indexer before its message is checkpointed, so the message is handled
+/// again on the next run.
+#[async_trait::async_trait]
+pub trait MessageHandler: Send {
+    async fn on_aggregate(
+        &mut self,
+        _message: &Message,
+        _content: &AggregateContent,
+    ) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    async fn on_forget(
+        &mut self,
+        _message: &Message,
+        _content: &ForgetContent,
+    ) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    async fn on_instance(
+        &mut self,
+        _message: &Message,
+        _content: &InstanceContent,
+    ) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    async fn on_post(
+        &mut self,
+        _message: &Message,
+        _content: &PostContent,
+    ) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    async fn on_program(
+        &mut self,
+        _message: &Message,
+        _content: &ProgramContent,
+    ) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    async fn on_store(
+        &mut self,
+        _message: &Message,
+        _content: &StoreContent,
+    ) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    /// Called with the errors of the live subscription, which reconnects on
+    /// its own. They do not stop the indexer.
+    async fn on_subscription_error(&mut self, _error: &MessageError) {}
+}
+
+/// Progress of an indexer: the time of the most recent message handled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub last_time: Timestamp,
+}
+
+/// Where an [`Indexer`] saves its progress.
+#[async_trait::async_trait]
+pub trait CheckpointStore: Send + Sync {
+    async fn load(&self) -> Result<Option<Checkpoint>, std::io::Error>;
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<(), std::io::Error>;
+}
+
+/// Keeps the checkpoint in memory: each process starts from the beginning of
+/// the history.
+#[derive(Debug, Default)]
+pub struct MemoryCheckpoint(Mutex<Option<Checkpoint>>);
+
+#[async_trait::async_trait]
+impl CheckpointStore for MemoryCheckpoint {
+    async fn load(&self) -> Result<Option<Checkpoint>, std::io::Error> {
+        Ok(self.0.lock().expect("checkpoint mutex poisoned").clone())
+    }
+
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<(), std::io::Error> {
+        *self.0.lock().expect("checkpoint mutex poisoned") = Some(checkpoint.clone());
+        Ok(())
+    }
+}
+
+/// Keeps the checkpoint in a JSON file, replaced atomically on each save.
+#[derive(Debug, Clone)]
+pub struct FileCheckpoint {
+    path: PathBuf,
+}
+
+impl FileCheckpoint {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointStore for FileCheckpoint {
+    async fn load(&self) -> Result<Option<Checkpoint>, std::io::Error> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(std::io::Error::other),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<(), std::io::Error> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let bytes = serde_json::to_vec(checkpoint).map_err(std::io::Error::other)?;
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, &self.path).await
+    }
+}
+
+/// Feeds the history and the live messages matching a filter to a
+/// [`MessageHandler`]. See the [module docs](self).
+pub struct Indexer<'a, S = MemoryCheckpoint> {
+    client: &'a AlephClient,
+    filter: MessageFilter,
+    checkpoints: S,
+}
+
+impl<'a> Indexer<'a> {
+    /// Indexes the messages matching `filter`, keeping progress in memory.
+    /// The sort order of `filter` is ignored.
+    pub fn new(client: &'a AlephClient, filter: MessageFilter) -> Self {
+        Self {
+            client,
+            filter,
+            checkpoints: MemoryCheckpoint::default(),
+        }
+    }
+}
+
+impl<'a, S: CheckpointStore> Indexer<'a, S> {
+    /// Saves progress in `checkpoints`, and resumes from the checkpoint it
+    /// holds.
+    pub fn checkpoints<T: CheckpointStore>(self, checkpoints: T) -> Indexer<'a, T> {
+        Indexer {
+            client: self.client,
+            filter: self.filter,
+            checkpoints,
+        }
+    }
+
+    /// The checkpoint store of the indexer.
+    pub fn checkpoint_store(&self) -> &S {
+        &self.checkpoints
+    }
+
+    /// Handles the history, then the live messages until the subscription
+    /// ends, which it only does when dropped. Wrap the future in a
+    /// `select!` or a timeout to stop the indexer.
+    ///
+    /// The subscription is opened before going through the history, so that
+    /// no message is missed in between.
+    pub async fn run<H: MessageHandler>(&self, handler: &mut H) -> Result<(), IndexerError> {
+        let started = Timestamp::now();
+        let live = self.client.watch_messages(self.filter.clone()).await?;
+        self.run_with(handler, started, live).await
+    }
+
+    /// Body of [`run`](Self::run), once subscribed at `started`.
+    async fn run_with<H: MessageHandler>(
+        &self,
+        handler: &mut H,
+        started: Timestamp,
+        live: impl Stream<Item = Result<Message, MessageError>> + Send,
+    ) -> Result<(), IndexerError> {
+        let mut checkpoint = self.checkpoints.load().await?;
+        let mut history = self.filter.clone().sort(SortBy::Time, SortOrder::Asc);
+        if let Some(checkpoint) = &checkpoint {
+            let since = checkpoint.last_time.as_f64() - WATCH_BACKFILL_MARGIN.as_secs_f64();
+            history = history.time_range(Some(Timestamp::from(since)), None);
+        }
+
+        // Messages of the history the subscription may deliver again.
+        let overlap_start = started.as_f64() - WATCH_BACKFILL_MARGIN.as_secs_f64();
+        let mut overlap = HashSet::new();
+        let mut pending = 0;
+        let mut history = std::pin::pin!(self.client.get_messages_iterator(history, None));
+        while let Some(message) = history.next().await {
+            let message = message?;
+            handle(handler, &message).await?;
+            if message.time.as_f64() >= overlap_start {
+                overlap.insert(message.item_hash.clone());
+            }
+            advance(&mut checkpoint, &message);
+            pending += 1;
+            if pending == CHECKPOINT_INTERVAL {
+                self.save(&checkpoint).await?;
+                pending = 0;
+            }
+        }
+        self.save(&checkpoint).await?;
+
+        let mut live = std::pin::pin!(live);
+        while let Some(message) = live.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    handler.on_subscription_error(&e).await;
+                    continue;
+                }
+            };
+            if overlap.remove(&message.item_hash) {
+                continue;
+            }
+            handle(handler, &message).await?;
+            advance(&mut checkpoint, &message);
+            self.save(&checkpoint).await?;
+        }
+        Ok(())
+    }
+
+    async fn save(&self, checkpoint: &Option<Checkpoint>) -> Result<(), IndexerError> {
+        if let Some(checkpoint) = checkpoint {
+            self.checkpoints.save(checkpoint).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Moves `checkpoint` forward to `message`. Live messages are not ordered, so
+/// it never moves back.
+fn advance(checkpoint: &mut Option<Checkpoint>, message: &Message) {
+    if checkpoint
+        .as_ref()
+        .is_none_or(|checkpoint| checkpoint.last_time < message.time)
+    {
+        *checkpoint = Some(Checkpoint {
+            last_time: message.time.clone(),
+        });
+    }
+}
+
+async fn handle<H: MessageHandler>(handler: &mut H, message: &Message) -> Result<(), IndexerError> {
+    let result = match message.content() {
+        MessageContentEnum::Aggregate(content) => handler.on_aggregate(message, content).await,
+        MessageContentEnum::Forget(content) => handler.on_forget(message, content).await,
+        MessageContentEnum::Instance(content) => handler.on_instance(message, content).await,
+        MessageContentEnum::Post(content) => handler.on_post(message, content).await,
+        MessageContentEnum::Program(content) => handler.on_program(message, content).await,
+        MessageContentEnum::Store(content) => handler.on_store(message, content).await,
+    };
+    result.map_err(|source| IndexerError::Handler {
+        item_hash: message.item_hash.clone(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const POST_FIXTURE: &str = include_str!("../../../fixtures/messages/post/post.json");
+    const AGGREGATE_FIXTURE: &str =
+        include_str!("../../../fixtures/messages/aggregate/aggregate.json");
+
+    #[derive(Default)]
+    struct Recorder {
+        handled: Vec<String>,
+        errors: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl MessageHandler for Recorder {
+        async fn on_aggregate(
+            &mut self,
+            message: &Message,
+            content: &AggregateContent,
+        ) -> Result<(), HandlerError> {
+            self.handled
+                .push(format!("aggregate {} {}", content.key(), message.item_hash));
+            Ok(())
+        }
+
+        async fn on_post(
+            &mut self,
+            message: &Message,
+            _: &PostContent,
+        ) -> Result<(), HandlerError> {
+            self.handled.push(format!("post {}", message.item_hash));
+            Ok(())
+        }
+
+        async fn on_subscription_error(&mut self, _error: &MessageError) {
+            self.errors += 1;
+        }
+    }
+
+    async fn mount_history(server: &MockServer, messages: Vec<serde_json::Value>) {
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "messages": messages,
+                "next_cursor": null,
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn run_handles_history_then_live_messages_once() {
+        let server = MockServer::start().await;
+        let post: serde_json::Value = serde_json::from_str(POST_FIXTURE).unwrap();
+        let aggregate: Message = serde_json::from_str(AGGREGATE_FIXTURE).unwrap();
+        mount_history(&server, vec![post.clone()]).await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let indexer = Indexer::new(&client, MessageFilter::default());
+        let live = futures_util::stream::iter(vec![
+            // Already handled from the history.
+            Ok(serde_json::from_value::<Message>(post.clone()).unwrap()),
+            Err(MessageError::WebsocketBadScheme),
+            Ok(aggregate.clone()),
+        ]);
+        let mut recorder = Recorder::default();
+        let post_time = Timestamp::from(post["time"].as_f64().unwrap());
+        indexer
+            .run_with(&mut recorder, post_time, live)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            recorder.handled,
+            vec![
+                format!("post {}", post["item_hash"].as_str().unwrap()),
+                format!("aggregate corechannel {}", aggregate.item_hash),
+            ]
+        );
+        assert_eq!(recorder.errors, 1);
+        assert_eq!(
+            indexer.checkpoint_store().load().await.unwrap(),
+            Some(Checkpoint {
+                last_time: aggregate.time
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn run_resumes_from_the_checkpoint() {
+        let server = MockServer::start().await;
+        mount_history(&server, vec![]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoints = FileCheckpoint::new(dir.path().join("checkpoint.json"));
+        assert_eq!(checkpoints.load().await.unwrap(), None);
+        checkpoints
+            .save(&Checkpoint {
+                last_time: Timestamp::from(1_700_000_060.0),
+            })
+            .await
+            .unwrap();
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let indexer = Indexer::new(&client, MessageFilter::default()).checkpoints(checkpoints);
+        indexer
+            .run_with(
+                &mut Recorder::default(),
+                Timestamp::now(),
+                futures_util::stream::empty(),
+            )
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let query = requests[0].url.query().unwrap_or_default().to_string();
+        assert!(query.contains("startDate=1700000000"), "{query}");
+        assert!(query.contains("sortOrder=1"), "{query}");
+    }
+}
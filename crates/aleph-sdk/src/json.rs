@@ -0,0 +1,247 @@
+//! JSON decoding of message payloads.
+//!
+//! Message list bodies and websocket frames go through [`from_bytes`]. With the
+//! `simd-json` feature they are parsed with simd-json, which is markedly faster
+//! on the large pages fetched by indexers. The decoded types and the error type
+//! are the same either way.
+//!
+//! [`ArrayItems`] splits the items of a large array out of a response body as
+//! it is received, so that they can be decoded one at a time.
+
+use bytes::{Bytes, BytesMut};
+use serde::de::DeserializeOwned;
+
+/// Decodes a JSON document.
+#[cfg(not(feature = "simd-json"))]
+pub(crate) fn from_bytes<T: DeserializeOwned>(bytes: Bytes) -> Result<T, serde_json::Error> {
+    serde_json::from_slice(&bytes)
+}
+
+/// Decodes a JSON document.
+///
+/// simd-json parses in place, so the buffer is taken by value; converting a
+/// uniquely owned `Bytes` into a `Vec` does not copy.
+#[cfg(feature = "simd-json")]
+pub(crate) fn from_bytes<T: DeserializeOwned>(bytes: Bytes) -> Result<T, serde_json::Error> {
+    let mut buffer = Vec::from(bytes);
+    simd_json::serde::from_slice(&mut buffer).map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Looking for the key of the array in the top-level object.
+    Seeking,
+    /// Saw the key and its colon; the array should come next.
+    AwaitArray,
+    /// Inside the array.
+    InArray,
+    /// Past the end of the array.
+    Done,
+}
+
+/// Incrementally extracts the items of the array stored under `key` in a
+/// top-level JSON object.
+///
+/// Feed the body chunk by chunk with [`extend`](Self::extend) and drain
+/// complete items with [`next_item`](Self::next_item). Only the item being
+/// received is buffered once the array has been reached; everything else in
+/// the document is skipped without being parsed. The splitter only tracks
+/// nesting and strings: items are validated when they are decoded.
+pub(crate) struct ArrayItems {
+    key: &'static [u8],
+    buffer: BytesMut,
+    /// Next byte of `buffer` to scan.
+    pos: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    string_start: usize,
+    key_matches: bool,
+    item_start: Option<usize>,
+    state: State,
+}
+
+impl ArrayItems {
+    pub(crate) fn new(key: &'static str) -> Self {
+        Self {
+            key: key.as_bytes(),
+            buffer: BytesMut::new(),
+            pos: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            string_start: 0,
+            key_matches: false,
+            item_start: None,
+            state: State::Seeking,
+        }
+    }
+
+    pub(crate) fn extend(&mut self, chunk: &[u8]) {
+        if self.state != State::Done {
+            self.buffer.extend_from_slice(chunk);
+        }
+    }
+
+    /// Whether the closing bracket of the array has been reached.
+    pub(crate) fn is_done(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// Returns the next complete item, or `None` if more input is needed or
+    /// the array is over.
+    pub(crate) fn next_item(&mut self) -> Option<Bytes> {
+        while let Some(&byte) = self.buffer.get(self.pos) {
+            let i = self.pos;
+            self.pos += 1;
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                    if self.depth == 1 && self.state == State::Seeking {
+                        self.key_matches = self.buffer[self.string_start + 1..i] == *self.key;
+                    }
+                }
+                continue;
+            }
+            if byte.is_ascii_whitespace() {
+                continue;
+            }
+            if self.state == State::AwaitArray {
+                if byte == b'[' {
+                    self.state = State::InArray;
+                    self.depth += 1;
+                    // Nothing before the array is needed anymore.
+                    let _ = self.buffer.split_to(self.pos);
+                    self.pos = 0;
+                    continue;
+                }
+                self.state = State::Seeking;
+            }
+
+            let at_item_level = self.state == State::InArray && self.depth == 2;
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    self.string_start = i;
+                }
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => self.depth = self.depth.saturating_sub(1),
+                b':' if self.depth == 1 && self.state == State::Seeking && self.key_matches => {
+                    self.state = State::AwaitArray;
+                }
+                _ => {}
+            }
+
+            if at_item_level {
+                if byte == b',' || byte == b']' {
+                    if byte == b']' {
+                        self.state = State::Done;
+                    }
+                    if let Some(start) = self.item_start.take() {
+                        return Some(self.take_item(start, i));
+                    }
+                    if self.is_done() {
+                        return None;
+                    }
+                } else if self.item_start.is_none() {
+                    self.item_start = Some(i);
+                }
+            }
+        }
+        None
+    }
+
+    /// Splits `buffer[start..end]` off as an item, dropping everything before.
+    fn take_item(&mut self, start: usize, end: usize) -> Bytes {
+        let consumed = self.buffer.split_to(end + 1).freeze();
+        self.pos = 0;
+        let item = &consumed[start..end];
+        let len = item.len()
+            - item
+                .iter()
+                .rev()
+                .take_while(|b| b.is_ascii_whitespace())
+                .count();
+        consumed.slice(start..start + len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GetMessagesResponse;
+    use aleph_types::message::Message;
+
+    #[test]
+    fn decodes_messages() {
+        let json = include_str!("../../../fixtures/messages/post/post.json");
+        let message: Message = from_bytes(Bytes::from_static(json.as_bytes())).unwrap();
+        assert_eq!(message, serde_json::from_str::<Message>(json).unwrap());
+    }
+
+    #[test]
+    fn decodes_message_lists() {
+        let json = include_str!("../../../fixtures/messages/post/post.json");
+        let message: serde_json::Value = serde_json::from_str(json).unwrap();
+        let body = serde_json::json!({
+            "messages": [message, message],
+            "pagination_per_page": 20,
+            "pagination_page": 1,
+            "pagination_total": 2,
+        });
+        let page: GetMessagesResponse =
+            from_bytes(Bytes::from(serde_json::to_vec(&body).unwrap())).unwrap();
+        assert_eq!(page.pagination_total, 2);
+        assert_eq!(
+            page.messages,
+            vec![serde_json::from_str::<Message>(json).unwrap(); 2]
+        );
+    }
+
+    fn split(body: &[u8], chunk_size: usize) -> (Vec<Bytes>, bool) {
+        let mut splitter = ArrayItems::new("messages");
+        let mut items = Vec::new();
+        for chunk in body.chunks(chunk_size) {
+            splitter.extend(chunk);
+            while let Some(item) = splitter.next_item() {
+                items.push(item);
+            }
+        }
+        (items, splitter.is_done())
+    }
+
+    #[test]
+    fn splits_array_items_across_chunks() {
+        let body = br#"{"pagination_page": 1, "note": "messages: [", "nested": {"messages": [9]},
+            "messages": [ {"a": "x,]}\"", "b": [1, 2]} , {"c": {}}, "s", 3 ],
+            "pagination_total": 4}"#;
+        let expected: Vec<&[u8]> = vec![
+            br#"{"a": "x,]}\"", "b": [1, 2]}"#,
+            br#"{"c": {}}"#,
+            br#""s""#,
+            b"3",
+        ];
+        for chunk_size in [1, 2, 7, body.len()] {
+            let (items, done) = split(body, chunk_size);
+            assert!(done);
+            assert_eq!(items, expected);
+        }
+    }
+
+    #[test]
+    fn empty_and_missing_arrays() {
+        assert_eq!(split(br#"{"messages": []}"#, 3), (vec![], true));
+        assert_eq!(split(br#"{"posts": [1]}"#, 3), (vec![], false));
+        assert!(!split(br#"{"messages": [{"a": 1}"#, 3).1);
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert!(from_bytes::<Message>(Bytes::from_static(b"{\"chain\":")).is_err());
+    }
+}
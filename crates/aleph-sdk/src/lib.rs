@@ -1,22 +1,44 @@
 pub mod aggregate_models;
+pub mod attestation;
 pub mod authorization;
 pub mod builder;
+pub mod bulk_verify;
 pub mod caching_aggregate_client;
 pub mod client;
 pub mod confidential;
+pub mod confidential_session;
 pub mod corechannel;
 #[cfg(feature = "credits")]
 pub mod credit;
 pub mod credit_transfer;
 pub mod crn;
 pub mod crns_list;
+pub mod domain_dns;
+pub mod gossip;
+#[cfg(feature = "gossip-node")]
+pub mod gossip_node;
+pub mod indexer;
 pub mod ipfs;
+mod json;
+#[cfg(feature = "aleph-cache")]
+pub mod message_cache;
 pub mod messages;
+pub mod network;
+pub mod node_metrics;
+pub mod prelude;
 pub mod progress;
+pub mod provenance;
+pub mod rate_limit;
+pub mod references;
+pub mod remote_signer;
+pub mod response_limit;
 pub mod scheduler;
 pub mod ssh;
+pub mod stream;
 #[cfg(feature = "swap")]
 pub mod swap;
+#[cfg(test)]
+mod test_utils;
 pub mod upload_timeout;
 pub mod verify;
 pub mod ws;
@@ -0,0 +1,559 @@
+//! A local SQLite cache of messages.
+//!
+//! [`CachedClient`] wraps an [`AlephMessageClient`] and stores every message
+//! it fetches into a SQLite database. Messages are immutable once processed,
+//! so [`get_message`](CachedClient::get_message) serves repeat lookups
+//! locally, and [`cached_messages`](CachedClient::cached_messages) answers
+//! queries from the database alone, without network access.
+//!
+//! [`sync`](CachedClient::sync) keeps the database up to date with a filter
+//! (the messages of an address or of a channel, for instance): the first call
+//! fetches the whole history, the next ones only the messages since the
+//! previous sync. Progress is stored in the database, so a sync resumes where
+//! the last one stopped across restarts.
+//!
+//! Only processed messages are cached. The cache does not follow FORGET
+//! messages: a forgotten message stays in the database until
+//! [`remove`](CachedClient::remove) is called.
+
+use crate::client::{
+    AlephMessageClient, MessageError, MessageFilter, MessageWithStatus, PaginationParams, SortBy,
+    SortOrder, WATCH_BACKFILL_MARGIN,
+};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::Message;
+use aleph_types::timestamp::Timestamp;
+use futures_util::StreamExt;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Messages stored per transaction during a sync, and fetched per request.
+const SYNC_BATCH_SIZE: usize = 200;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS messages (
+    item_hash TEXT PRIMARY KEY,
+    message_type TEXT NOT NULL,
+    chain TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    channel TEXT,
+    time REAL NOT NULL,
+    message TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS messages_sender ON messages (sender, time);
+CREATE INDEX IF NOT EXISTS messages_owner ON messages (owner, time);
+CREATE INDEX IF NOT EXISTS messages_channel ON messages (channel, time);
+CREATE TABLE IF NOT EXISTS syncs (
+    filter TEXT PRIMARY KEY,
+    last_time REAL NOT NULL
+);
+";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error(transparent)]
+    Client(#[from] MessageError),
+    #[error("cache database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("invalid cached message: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the cache cannot filter messages on {0}")]
+    UnsupportedFilter(&'static str),
+    #[error("failed to serialize the sync filter: {0}")]
+    SyncFilter(#[source] serde_qs::Error),
+}
+
+/// Wraps an [`AlephMessageClient`] with a SQLite message cache. See the
+/// [module docs](self).
+pub struct CachedClient<C> {
+    inner: C,
+    db: Arc<Mutex<Connection>>,
+}
+
+impl<C: AlephMessageClient + Sync> CachedClient<C> {
+    /// Wraps `inner`, caching messages in the database at `path`, which is
+    /// created if needed.
+    pub async fn open(inner: C, path: impl AsRef<Path>) -> Result<Self, CacheError> {
+        let path = path.as_ref().to_path_buf();
+        let db = tokio::task::spawn_blocking(move || -> Result<_, CacheError> {
+            let db = Connection::open(path)?;
+            db.execute_batch(SCHEMA)?;
+            Ok(db)
+        })
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))?;
+        Ok(Self::with_connection(inner, db))
+    }
+
+    /// Wraps `inner`, caching messages in memory for the lifetime of the
+    /// client.
+    pub fn in_memory(inner: C) -> Result<Self, CacheError> {
+        let db = Connection::open_in_memory()?;
+        db.execute_batch(SCHEMA)?;
+        Ok(Self::with_connection(inner, db))
+    }
+
+    fn with_connection(inner: C, db: Connection) -> Self {
+        Self {
+            inner,
+            db: Arc::new(Mutex::new(db)),
+        }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Fetches a message, from the cache if it holds it. Processed messages
+    /// fetched from the network are cached.
+    pub async fn get_message(
+        &self,
+        item_hash: &ItemHash,
+    ) -> Result<MessageWithStatus<Message>, CacheError> {
+        if let Some(message) = self.cached_message(item_hash).await? {
+            return Ok(MessageWithStatus::Processed { message });
+        }
+        let message = self.inner.get_message(item_hash).await?;
+        if let MessageWithStatus::Processed { message } = &message {
+            self.store(std::slice::from_ref(message)).await?;
+        }
+        Ok(message)
+    }
+
+    /// Fetches a page of messages from the network and caches them.
+    pub async fn get_messages(
+        &self,
+        filter: &MessageFilter,
+        pagination: PaginationParams,
+    ) -> Result<Vec<Message>, CacheError> {
+        let messages = self.inner.get_messages(filter, pagination).await?;
+        self.store(&messages).await?;
+        Ok(messages)
+    }
+
+    /// Brings the cache up to date with the messages matching `filter`, and
+    /// returns the number of messages added to it.
+    ///
+    /// Fetches the messages sent since the last sync of the same filter, or
+    /// all of them on the first sync. As with
+    /// [`watch_messages`](crate::client::AlephClient::watch_messages), the
+    /// fetch starts [`WATCH_BACKFILL_MARGIN`] before the last message seen
+    /// since message times are set by their senders. Progress is saved after
+    /// each page, so an interrupted sync resumes from there.
+    pub async fn sync(&self, filter: &MessageFilter) -> Result<usize, CacheError> {
+        let sync_key = sync_key(filter)?;
+        let last_time: Option<f64> = self
+            .with_db({
+                let sync_key = sync_key.clone();
+                move |db| {
+                    Ok(db
+                        .query_row(
+                            "SELECT last_time FROM syncs WHERE filter = ?1",
+                            params![sync_key],
+                            |row| row.get(0),
+                        )
+                        .optional()?)
+                }
+            })
+            .await?;
+
+        let mut query = filter.clone().sort(SortBy::Time, SortOrder::Asc);
+        if let Some(last_time) = last_time {
+            let since = last_time - WATCH_BACKFILL_MARGIN.as_secs_f64();
+            query = query.time_range(Some(Timestamp::from(since)), None);
+        }
+
+        let mut added = 0;
+        let mut batch = Vec::with_capacity(SYNC_BATCH_SIZE);
+        let mut failure = None;
+        let mut messages = std::pin::pin!(
+            self.inner
+                .get_messages_iterator(query, Some(SYNC_BATCH_SIZE as u32))
+        );
+        while let Some(message) = messages.next().await {
+            match message {
+                Ok(message) => batch.push(message),
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+            if batch.len() == SYNC_BATCH_SIZE {
+                added += self.checkpoint(&sync_key, &mut batch).await?;
+            }
+        }
+        // Keeps what was fetched before a failure.
+        added += self.checkpoint(&sync_key, &mut batch).await?;
+        match failure {
+            Some(e) => Err(e.into()),
+            None => Ok(added),
+        }
+    }
+
+    /// Stores `batch` and records the sync of `sync_key` as done up to its
+    /// last message, in one transaction.
+    async fn checkpoint(
+        &self,
+        sync_key: &str,
+        batch: &mut Vec<Message>,
+    ) -> Result<usize, CacheError> {
+        let Some(latest) = batch
+            .iter()
+            .map(|message| message.time.as_f64())
+            .reduce(f64::max)
+        else {
+            return Ok(0);
+        };
+        let rows = batch.iter().map(Row::new).collect::<Result<Vec<_>, _>>()?;
+        let sync_key = sync_key.to_string();
+        let added = self
+            .with_db(move |db| {
+                let transaction = db.transaction()?;
+                let added = insert(&transaction, &rows)?;
+                transaction.execute(
+                    "INSERT INTO syncs (filter, last_time) VALUES (?1, ?2)
+                     ON CONFLICT (filter) DO UPDATE SET last_time = max(last_time, excluded.last_time)",
+                    params![sync_key, latest],
+                )?;
+                transaction.commit()?;
+                Ok(added)
+            })
+            .await?;
+        batch.clear();
+        Ok(added)
+    }
+
+    /// Returns a message from the cache, without network access.
+    pub async fn cached_message(
+        &self,
+        item_hash: &ItemHash,
+    ) -> Result<Option<Message>, CacheError> {
+        let item_hash = item_hash.to_string();
+        self.with_db(move |db| {
+            let json: Option<String> = db
+                .query_row(
+                    "SELECT message FROM messages WHERE item_hash = ?1",
+                    params![item_hash],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+        })
+        .await
+    }
+
+    /// Returns the cached messages matching `filter`, without network access.
+    ///
+    /// The cache indexes messages on their type, chain, sender, owner,
+    /// channel, item hash and time, and fails with
+    /// [`CacheError::UnsupportedFilter`] if `filter` sets another criterion.
+    /// Messages are sorted by time, newest first unless the filter asks for
+    /// ascending order.
+    pub async fn cached_messages(
+        &self,
+        filter: &MessageFilter,
+    ) -> Result<Vec<Message>, CacheError> {
+        let unsupported = [
+            ("content types", filter.content_types.is_some()),
+            ("content keys", filter.content_keys.is_some()),
+            ("content hashes", filter.content_hashes.is_some()),
+            ("refs", filter.refs.is_some()),
+            ("tags", filter.tags.is_some()),
+            ("message statuses", filter.message_statuses.is_some()),
+        ];
+        if let Some((criterion, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(CacheError::UnsupportedFilter(criterion));
+        }
+
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        let mut any_of = |column: &str, items: Vec<String>| {
+            let placeholders = vec!["?"; items.len()].join(", ");
+            conditions.push(format!("{column} IN ({placeholders})"));
+            values.extend(items.into_iter().map(Value::Text));
+        };
+        let message_types = filter
+            .message_type
+            .iter()
+            .chain(filter.message_types.iter().flatten())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if !message_types.is_empty() {
+            any_of("message_type", message_types);
+        }
+        if let Some(chains) = &filter.chains {
+            any_of("chain", chains.clone());
+        }
+        if let Some(addresses) = &filter.addresses {
+            any_of(
+                "sender",
+                addresses.iter().map(ToString::to_string).collect(),
+            );
+        }
+        if let Some(owners) = &filter.owners {
+            any_of("owner", owners.iter().map(ToString::to_string).collect());
+        }
+        if let Some(channels) = &filter.channels {
+            any_of("channel", channels.clone());
+        }
+        if let Some(hashes) = &filter.hashes {
+            any_of(
+                "item_hash",
+                hashes.iter().map(ToString::to_string).collect(),
+            );
+        }
+        if let Some(start) = &filter.start_date {
+            conditions.push("time >= ?".to_string());
+            values.push(Value::Real(start.as_f64()));
+        }
+        if let Some(end) = &filter.end_date {
+            conditions.push("time < ?".to_string());
+            values.push(Value::Real(end.as_f64()));
+        }
+
+        let mut sql = "SELECT message FROM messages".to_string();
+        if !conditions.is_empty() {
+            sql = format!("{sql} WHERE {}", conditions.join(" AND "));
+        }
+        let order = match filter.sort_order {
+            Some(SortOrder::Asc) => "ASC",
+            _ => "DESC",
+        };
+        sql = format!("{sql} ORDER BY time {order}");
+
+        self.with_db(move |db| {
+            let mut statement = db.prepare(&sql)?;
+            let rows = statement.query_map(rusqlite::params_from_iter(values), |row| {
+                row.get::<_, String>(0)
+            })?;
+            rows.map(|json| Ok(serde_json::from_str(&json?)?)).collect()
+        })
+        .await
+    }
+
+    /// Removes a message from the cache, e.g. after it was forgotten.
+    pub async fn remove(&self, item_hash: &ItemHash) -> Result<bool, CacheError> {
+        let item_hash = item_hash.to_string();
+        self.with_db(move |db| {
+            let removed = db.execute(
+                "DELETE FROM messages WHERE item_hash = ?1",
+                params![item_hash],
+            )?;
+            Ok(removed > 0)
+        })
+        .await
+    }
+
+    /// Stores `messages`, returning how many were not cached yet.
+    async fn store(&self, messages: &[Message]) -> Result<usize, CacheError> {
+        let rows = messages
+            .iter()
+            .map(Row::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.with_db(move |db| {
+            let transaction = db.transaction()?;
+            let added = insert(&transaction, &rows)?;
+            transaction.commit()?;
+            Ok(added)
+        })
+        .await
+    }
+
+    /// Runs `f` on the database from the blocking thread pool, so that
+    /// SQLite I/O does not stall the async runtime.
+    async fn with_db<T, F>(&self, f: F) -> Result<T, CacheError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, CacheError> + Send + 'static,
+    {
+        let db = Arc::clone(&self.db);
+        tokio::task::spawn_blocking(move || {
+            let mut db = db.lock().expect("message cache mutex poisoned");
+            f(&mut db)
+        })
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+}
+
+/// The columns of a cached message.
+struct Row {
+    item_hash: String,
+    message_type: String,
+    chain: String,
+    sender: String,
+    owner: String,
+    channel: Option<String>,
+    time: f64,
+    message: String,
+}
+
+impl Row {
+    fn new(message: &Message) -> Result<Self, CacheError> {
+        Ok(Self {
+            item_hash: message.item_hash.to_string(),
+            message_type: message.message_type.to_string(),
+            chain: message.chain.to_string(),
+            sender: message.sender.to_string(),
+            owner: message.owner().to_string(),
+            channel: message
+                .channel
+                .as_ref()
+                .map(|channel| channel.as_str().to_string()),
+            time: message.time.as_f64(),
+            message: serde_json::to_string(message)?,
+        })
+    }
+}
+
+/// Inserts `rows`, returning how many were not cached yet.
+fn insert(db: &Connection, rows: &[Row]) -> Result<usize, rusqlite::Error> {
+    let mut statement = db.prepare_cached(
+        "INSERT OR IGNORE INTO messages
+         (item_hash, message_type, chain, sender, owner, channel, time, message)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    let mut added = 0;
+    for row in rows {
+        added += statement.execute(params![
+            row.item_hash,
+            row.message_type,
+            row.chain,
+            row.sender,
+            row.owner,
+            row.channel,
+            row.time,
+            row.message,
+        ])?;
+    }
+    Ok(added)
+}
+
+/// Identifies the syncs of `filter`: its query string without the sort and
+/// time criteria that [`CachedClient::sync`] sets itself.
+fn sync_key(filter: &MessageFilter) -> Result<String, CacheError> {
+    let mut filter = filter.clone();
+    filter.sort_by = None;
+    filter.sort_order = None;
+    filter.start_date = None;
+    filter.end_date = None;
+    serde_qs::to_string(&filter).map_err(CacheError::SyncFilter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AlephClient;
+    use aleph_types::chain::Address;
+    use aleph_types::channel;
+    use aleph_types::message::MessageType;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const POST_FIXTURE: &str = include_str!("../../../fixtures/messages/post/post.json");
+
+    fn post() -> serde_json::Value {
+        serde_json::from_str(POST_FIXTURE).unwrap()
+    }
+
+    fn page(messages: Vec<serde_json::Value>) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "messages": messages,
+            "next_cursor": null,
+        }))
+    }
+
+    fn client(server: &MockServer) -> CachedClient<AlephClient> {
+        CachedClient::in_memory(AlephClient::new(Url::parse(&server.uri()).unwrap())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_message_is_served_from_the_cache() {
+        let server = MockServer::start().await;
+        let message = post();
+        let item_hash: ItemHash = message["item_hash"].as_str().unwrap().parse().unwrap();
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{item_hash}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "processed",
+                "item_hash": item_hash.to_string(),
+                "reception_time": "2024-01-01T00:00:00Z",
+                "message": message,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        let fetched = client.get_message(&item_hash).await.unwrap();
+        let cached = client.get_message(&item_hash).await.unwrap();
+        assert!(matches!(
+            (fetched, cached),
+            (MessageWithStatus::Processed { message: a }, MessageWithStatus::Processed { message: b })
+                if a == b
+        ));
+    }
+
+    #[tokio::test]
+    async fn sync_fetches_new_messages_only() {
+        let server = MockServer::start().await;
+        let first = post();
+        let mut second = post();
+        second["item_hash"] =
+            "8a9bd4a8f8f1c7ac4a3f7a65bea3e2cc4a5b7e0f6ea1cf3a92d1b5cdf6e4b0c9".into();
+        second["time"] = (first["time"].as_f64().unwrap() + 3600.0).into();
+        // The first sync has no time range, the next one starts from the
+        // last message seen. Mocks are matched in mount order.
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param_is_missing("startDate"))
+            .respond_with(page(vec![first.clone()]))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .and(query_param("sortOrder", "1"))
+            .respond_with(page(vec![first.clone(), second.clone()]))
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        let sender = Address::from(first["sender"].as_str().unwrap().to_string());
+        let filter = MessageFilter::default().addresses([sender.clone()]);
+        assert_eq!(client.sync(&filter).await.unwrap(), 1);
+        assert_eq!(client.sync(&filter).await.unwrap(), 1);
+
+        let cached = client
+            .cached_messages(
+                &MessageFilter::default()
+                    .addresses([sender])
+                    .message_type(MessageType::Post),
+            )
+            .await
+            .unwrap();
+        assert_eq!(cached.len(), 2);
+        assert_eq!(
+            cached[0].item_hash.to_string(),
+            second["item_hash"].as_str().unwrap()
+        );
+        assert!(
+            client
+                .cached_messages(&MessageFilter::default().channels([channel!("OTHER")]))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            client
+                .cached_messages(&MessageFilter::default().tags(["a"]))
+                .await,
+            Err(CacheError::UnsupportedFilter("tags"))
+        ));
+    }
+}
@@ -4,8 +4,9 @@ use aleph_types::channel::Channel;
 use aleph_types::item_hash::ItemHash;
 use aleph_types::message::execution::base::{Encoding, ExecutableContent, Interface, Payment};
 use aleph_types::message::execution::environment::{
-    FunctionEnvironment, FunctionTriggers, HostRequirements, Hypervisor, InstanceEnvironment,
-    MachineResources, PublishedPort, TrustedExecutionEnvironment,
+    ExecutionTimeout, FunctionEnvironment, FunctionTriggers, HostRequirements, Hypervisor,
+    InstanceEnvironment, InvalidExecutionTimeout, InvalidVcpus, MachineResources, PublishedPort,
+    TrustedExecutionEnvironment, Vcpus,
 };
 use aleph_types::message::execution::volume::{
     MachineVolume, ParentVolume, PersistentVolumeSize, RootfsVolume, VolumePersistence,
@@ -35,6 +36,12 @@ pub enum MessageBuildError {
     StorageEngineMismatch { engine: StorageEngine, hash: String },
     #[error("invalid authorization: {0}")]
     InvalidAuthorization(String),
+    #[error(transparent)]
+    InvalidTimeout(#[from] InvalidExecutionTimeout),
+    #[error(transparent)]
+    InvalidVcpus(#[from] InvalidVcpus),
+    #[error("aggregate content must be a JSON object")]
+    AggregateNotObject,
 }
 
 pub struct PostBuilder<'a, A: Account> {
@@ -445,6 +452,8 @@ impl<'a, A: Account> ProgramBuilder<'a, A> {
         self
     }
 
+    /// Number of vCPUs, checked against the network bounds by `build`. See
+    /// [`Vcpus`].
     pub fn vcpus(mut self, vcpus: u32) -> Self {
         self.vcpus = vcpus;
         self
@@ -455,11 +464,18 @@ impl<'a, A: Account> ProgramBuilder<'a, A> {
         self
     }
 
+    /// Execution timeout in seconds, checked against the network bounds by
+    /// `build`. See [`ExecutionTimeout`].
     pub fn timeout_seconds(mut self, seconds: u32) -> Self {
         self.seconds = seconds;
         self
     }
 
+    pub fn timeout(mut self, timeout: ExecutionTimeout) -> Self {
+        self.seconds = timeout.as_secs();
+        self
+    }
+
     pub fn data(mut self, data: DataContent) -> Self {
         self.data = Some(data);
         self
@@ -543,9 +559,9 @@ impl<'a, A: Account> ProgramBuilder<'a, A> {
                 metadata: self.metadata,
                 variables: self.variables,
                 resources: MachineResources {
-                    vcpus: self.vcpus,
+                    vcpus: Vcpus::new(self.vcpus)?,
                     memory: self.memory,
-                    seconds: self.seconds,
+                    seconds: ExecutionTimeout::new(self.seconds)?,
                     published_ports: self.published_ports,
                 },
                 payment: self.payment,
@@ -686,6 +702,8 @@ impl<'a, A: Account> InstanceBuilder<'a, A> {
         self
     }
 
+    /// Number of vCPUs, checked against the network bounds by `build`. See
+    /// [`Vcpus`].
     pub fn vcpus(mut self, vcpus: u32) -> Self {
         self.vcpus = vcpus;
         self
@@ -696,11 +714,27 @@ impl<'a, A: Account> InstanceBuilder<'a, A> {
         self
     }
 
+    /// Execution timeout in seconds, checked against the network bounds by
+    /// `build`. See [`ExecutionTimeout`].
     pub fn timeout_seconds(mut self, seconds: u32) -> Self {
         self.seconds = seconds;
         self
     }
 
+    pub fn timeout(mut self, timeout: ExecutionTimeout) -> Self {
+        self.seconds = timeout.as_secs();
+        self
+    }
+
+    /// Sets vCPUs, memory, timeout and published ports at once.
+    pub fn resources(mut self, resources: MachineResources) -> Self {
+        self.vcpus = resources.vcpus.get();
+        self.memory = resources.memory;
+        self.seconds = resources.seconds.as_secs();
+        self.published_ports = resources.published_ports;
+        self
+    }
+
     pub fn variables(mut self, variables: HashMap<String, String>) -> Self {
         self.variables = Some(variables);
         self
@@ -716,6 +750,12 @@ impl<'a, A: Account> InstanceBuilder<'a, A> {
         self
     }
 
+    /// Adds one volume to those already set.
+    pub fn volume(mut self, volume: impl Into<MachineVolume>) -> Self {
+        self.volumes.push(volume.into());
+        self
+    }
+
     pub fn payment(mut self, payment: Payment) -> Self {
         self.payment = Some(payment);
         self
@@ -758,9 +798,9 @@ impl<'a, A: Account> InstanceBuilder<'a, A> {
                 metadata: self.metadata,
                 variables: self.variables,
                 resources: MachineResources {
-                    vcpus: self.vcpus,
+                    vcpus: Vcpus::new(self.vcpus)?,
                     memory: self.memory,
-                    seconds: self.seconds,
+                    seconds: ExecutionTimeout::new(self.seconds)?,
                     published_ports: self.published_ports,
                 },
                 payment: self.payment,
@@ -872,35 +912,11 @@ impl AuthorizationBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aleph_types::account::{Account, SignError};
-    use aleph_types::chain::{Address, Chain, Signature};
+    use crate::test_utils::TestAccount;
+    use aleph_types::chain::{Address, Chain};
     use aleph_types::message::MessageType;
     use aleph_types::message::item_type::ItemType;
 
-    struct TestAccount {
-        address: Address,
-    }
-
-    impl TestAccount {
-        fn new() -> Self {
-            Self {
-                address: Address::from("0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string()),
-            }
-        }
-    }
-
-    impl Account for TestAccount {
-        fn chain(&self) -> Chain {
-            Chain::Ethereum
-        }
-        fn address(&self) -> &Address {
-            &self.address
-        }
-        fn sign_raw(&self, _buffer: &[u8]) -> Result<Signature, SignError> {
-            Ok(Signature::from("0xDUMMY".to_string()))
-        }
-    }
-
     #[test]
     fn test_post_builder_new() {
         let account = TestAccount::new();
@@ -1274,6 +1290,28 @@ mod tests {
         assert_eq!(parsed["allow_amend"], true);
     }
 
+    #[test]
+    fn test_program_builder_rejects_out_of_bounds_resources() {
+        let account = TestAccount::new();
+        let code_ref = aleph_types::item_hash!(
+            "9a4735bca0d3f7032ddd6659c35387b57b470550c931841e6862ece4e9e6523e"
+        );
+        let runtime_ref = aleph_types::item_hash!(
+            "63f07193e6ee9d207b7d1fcf8286f9aee34e6f12f101d2ec77c1229f92964696"
+        );
+
+        let result =
+            ProgramBuilder::new(&account, code_ref.clone(), "main:app", runtime_ref.clone())
+                .timeout_seconds(0)
+                .build();
+        assert!(matches!(result, Err(MessageBuildError::InvalidTimeout(_))));
+
+        let result = ProgramBuilder::new(&account, code_ref, "main:app", runtime_ref)
+            .vcpus(0)
+            .build();
+        assert!(matches!(result, Err(MessageBuildError::InvalidVcpus(_))));
+    }
+
     #[test]
     fn test_instance_builder_defaults() {
         let account = TestAccount::new();
@@ -0,0 +1,118 @@
+//! Official Aleph Cloud networks and their public endpoints.
+//!
+//! ```no_run
+//! use aleph_sdk::client::AlephClient;
+//! use aleph_sdk::network::Network;
+//!
+//! let client = AlephClient::for_network(&Network::Mainnet);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+/// Public CCN API of the main network.
+pub const MAINNET_CCN_URL: &str = "https://api.aleph.im";
+/// VM scheduler of the main network.
+pub const MAINNET_SCHEDULER_URL: &str = "https://scheduler.api.aleph.cloud";
+/// Public CCN API of the test network.
+pub const TESTNET_CCN_URL: &str = "https://api.twentysix.testnet.network";
+
+/// An Aleph network, identified by the CCN API it is reached through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    /// Any other CCN, e.g. a local node or a private network.
+    Custom(Url),
+}
+
+impl Network {
+    /// Base URL of the CCN API.
+    pub fn ccn_url(&self) -> Url {
+        match self {
+            Network::Mainnet => Url::parse(MAINNET_CCN_URL).expect("valid mainnet url"),
+            Network::Testnet => Url::parse(TESTNET_CCN_URL).expect("valid testnet url"),
+            Network::Custom(url) => url.clone(),
+        }
+    }
+
+    /// Base URL of the VM scheduler, for networks that run one.
+    pub fn scheduler_url(&self) -> Option<Url> {
+        match self {
+            Network::Mainnet => {
+                Some(Url::parse(MAINNET_SCHEDULER_URL).expect("valid scheduler url"))
+            }
+            Network::Testnet | Network::Custom(_) => None,
+        }
+    }
+
+    /// Base URL of the CRN list, for networks that publish one.
+    pub fn crns_list_url(&self) -> Option<Url> {
+        match self {
+            Network::Mainnet => Some(
+                Url::parse(crate::crns_list::DEFAULT_CRN_LIST_URL).expect("valid crns list url"),
+            ),
+            Network::Testnet | Network::Custom(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Mainnet => f.write_str("mainnet"),
+            Network::Testnet => f.write_str("testnet"),
+            Network::Custom(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown network '{0}': expected 'mainnet', 'testnet' or a CCN URL")]
+pub struct ParseNetworkError(String);
+
+/// Parses `mainnet`, `testnet` (case-insensitively) or an `http(s)` URL.
+impl FromStr for Network {
+    type Err = ParseNetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" => return Ok(Network::Mainnet),
+            "testnet" => return Ok(Network::Testnet),
+            _ => {}
+        }
+        match Url::parse(s) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(Network::Custom(url)),
+            _ => Err(ParseNetworkError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_presets_and_urls() {
+        assert_eq!("Mainnet".parse::<Network>().unwrap(), Network::Mainnet);
+        assert_eq!("testnet".parse::<Network>().unwrap(), Network::Testnet);
+        let local: Network = "http://localhost:4024".parse().unwrap();
+        assert_eq!(local.ccn_url().as_str(), "http://localhost:4024/");
+        assert!(local.scheduler_url().is_none());
+        assert!("devnet".parse::<Network>().is_err());
+        assert!("ftp://example.com".parse::<Network>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for network in [
+            Network::Mainnet,
+            Network::Testnet,
+            Network::Custom(Url::parse("http://localhost:4024/").unwrap()),
+        ] {
+            assert_eq!(network.to_string().parse::<Network>().unwrap(), network);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Models for the node metrics published by the aleph scoring service.
+//!
+//! The scoring service periodically measures every CCN and CRN (latency of a
+//! few API endpoints, software version, chain sync lag, diagnostic VM round
+//! trip) and publishes the results as POST messages of type
+//! [`METRICS_POST_TYPE`] on the [`SCORING_CHANNEL`] channel, signed by
+//! [`SCORING_ADDRESS`]. Monitoring tools can read them with
+//! [`AlephPostClient::get_latest_node_metrics`](crate::client::AlephPostClient::get_latest_node_metrics).
+//!
+//! Latency fields are in seconds and `None` when the probe failed: an
+//! unreachable endpoint is a measurement in itself, not a parse error.
+
+use crate::aggregate_models::corechannel::NodeHash;
+use aleph_types::address;
+use aleph_types::chain::Address;
+use aleph_types::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// Address of the scoring service that publishes metrics and scores.
+pub static SCORING_ADDRESS: LazyLock<Address> =
+    LazyLock::new(|| address!("0x4D52380D3191274a04846c89c069E6C3F2Ed94e4"));
+
+/// Channel the scoring service posts on.
+pub const SCORING_CHANNEL: &str = "aleph-scoring";
+
+/// Post type of the raw measurement posts.
+pub const METRICS_POST_TYPE: &str = "aleph-scoring-metrics";
+
+/// Content of one metrics post.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeMetricsContent {
+    /// Version of the scoring service that produced the measurements.
+    pub version: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub metrics: NodeMetrics,
+}
+
+/// One measurement round over the whole network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeMetrics {
+    #[serde(default)]
+    pub ccn: Vec<CcnMeasurement>,
+    #[serde(default)]
+    pub crn: Vec<CrnMeasurement>,
+    /// Host name of the server that ran the probes.
+    #[serde(default)]
+    pub server: Option<String>,
+    /// Autonomous system of the probing server.
+    #[serde(default)]
+    pub server_asn: Option<u32>,
+    #[serde(default)]
+    pub server_as_name: Option<String>,
+}
+
+impl NodeMetrics {
+    /// Returns the measurement of the given CCN, if it was probed this round.
+    pub fn ccn(&self, node: &NodeHash) -> Option<&CcnMeasurement> {
+        self.ccn.iter().find(|m| &m.node_id == node)
+    }
+
+    /// Returns the measurement of the given CRN, if it was probed this round.
+    pub fn crn(&self, node: &NodeHash) -> Option<&CrnMeasurement> {
+        self.crn.iter().find(|m| &m.node_id == node)
+    }
+}
+
+/// Measurement of a core channel node (CCN).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CcnMeasurement {
+    pub measured_at: Timestamp,
+    pub node_id: NodeHash,
+    /// Base URL the node was probed at.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Autonomous system the node is hosted in.
+    #[serde(default)]
+    pub asn: Option<u32>,
+    #[serde(default)]
+    pub as_name: Option<String>,
+    /// pyaleph version reported by the node.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Number of chain transactions the node has processed.
+    #[serde(default)]
+    pub txs_total: Option<u64>,
+    /// Size of the node's pending message queue.
+    #[serde(default)]
+    pub pending_messages: Option<u64>,
+    /// Blocks the node lags behind the Ethereum chain head.
+    #[serde(default)]
+    pub eth_height_remaining: Option<i64>,
+    #[serde(default)]
+    pub base_latency: Option<f64>,
+    #[serde(default)]
+    pub base_latency_ipv4: Option<f64>,
+    #[serde(default)]
+    pub metrics_latency: Option<f64>,
+    #[serde(default)]
+    pub aggregate_latency: Option<f64>,
+    #[serde(default)]
+    pub file_download_latency: Option<f64>,
+}
+
+/// Measurement of a compute resource node (CRN).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrnMeasurement {
+    pub measured_at: Timestamp,
+    pub node_id: NodeHash,
+    #[serde(default)]
+    pub asn: Option<u32>,
+    #[serde(default)]
+    pub as_name: Option<String>,
+    /// aleph-vm version reported by the node.
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub base_latency: Option<f64>,
+    #[serde(default)]
+    pub base_latency_ipv4: Option<f64>,
+    /// Round trip of the CRN's full self-check endpoint.
+    #[serde(default)]
+    pub full_check_latency: Option<f64>,
+    /// Round trip of a call to the diagnostic VM, including its cold start.
+    #[serde(default)]
+    pub diagnostic_vm_latency: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const METRICS: &str = r#"{
+        "version": "1.0.0",
+        "tags": ["mainnet"],
+        "metrics": {
+            "server": "scoring-01",
+            "server_asn": 24940,
+            "server_as_name": "HETZNER-AS, DE",
+            "ccn": [{
+                "measured_at": 1700000000.5,
+                "node_id": "b0e1ca0a1bbb3c49f0ce5dd1b1fd4bc1bdee4b14c2a36f1c2d3cb1b7a47b1e22",
+                "url": "https://ccn.example.org/",
+                "asn": 16276,
+                "as_name": "OVH, FR",
+                "version": "v0.5.1",
+                "txs_total": 12,
+                "pending_messages": 3,
+                "eth_height_remaining": 0,
+                "base_latency": 0.12,
+                "base_latency_ipv4": 0.11,
+                "metrics_latency": 0.4,
+                "aggregate_latency": 0.2,
+                "file_download_latency": null
+            }],
+            "crn": [{
+                "measured_at": 1700000001.0,
+                "node_id": "c1f2db1b2ccc4d5a01df6ee2c20e5cd2ceff5c25d3b4702d3e4dc2c8b58c2f33",
+                "version": "1.2.0",
+                "base_latency": 0.3,
+                "full_check_latency": null,
+                "diagnostic_vm_latency": 1.5
+            }]
+        }
+    }"#;
+
+    #[test]
+    fn deserialize_metrics_post() {
+        let content: NodeMetricsContent = serde_json::from_str(METRICS).unwrap();
+        assert_eq!(content.version, "1.0.0");
+        assert_eq!(content.metrics.server_asn, Some(24940));
+
+        let ccn_hash =
+            NodeHash::from_str("b0e1ca0a1bbb3c49f0ce5dd1b1fd4bc1bdee4b14c2a36f1c2d3cb1b7a47b1e22")
+                .unwrap();
+        let ccn = content.metrics.ccn(&ccn_hash).unwrap();
+        assert_eq!(ccn.version.as_deref(), Some("v0.5.1"));
+        assert_eq!(ccn.pending_messages, Some(3));
+        assert_eq!(ccn.file_download_latency, None);
+
+        let crn_hash =
+            NodeHash::from_str("c1f2db1b2ccc4d5a01df6ee2c20e5cd2ceff5c25d3b4702d3e4dc2c8b58c2f33")
+                .unwrap();
+        let crn = content.metrics.crn(&crn_hash).unwrap();
+        assert_eq!(crn.full_check_latency, None);
+        assert_eq!(crn.diagnostic_vm_latency, Some(1.5));
+        assert!(crn.asn.is_none());
+        assert!(content.metrics.crn(&ccn_hash).is_none());
+    }
+}
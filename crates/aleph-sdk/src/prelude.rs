@@ -0,0 +1,21 @@
+//! Glob-importable set of the client, its traits and the common message
+//! types.
+//!
+//! ```
+//! use aleph_sdk::prelude::*;
+//! ```
+//!
+//! Also re-exports [`aleph_types::prelude`].
+
+pub use aleph_types::prelude::*;
+
+pub use crate::authorization::AlephAuthorizationClient;
+pub use crate::client::{
+    AlephAggregateClient, AlephClient, AlephClientBuilder, AlephMessageClient, AlephPostClient,
+    AlephStorageClient, MessageError, MessageFilter, MessageWithStatus, PaginationParams,
+};
+pub use crate::messages::{
+    AggregateBuilder, ForgetBuilder, InstanceBuilder, PostBuilder, ProgramBuilder, StoreBuilder,
+};
+pub use crate::network::Network;
+pub use crate::stream::MessageStreamExt;
@@ -0,0 +1,258 @@
+//! Provenance of forgotten messages.
+//!
+//! When a message is forgotten the CCN drops its content and only keeps the
+//! envelope and the hashes of the FORGET messages that removed it. This module
+//! fetches those FORGET messages and checks, like the CCN did when it accepted
+//! them, that their sender was entitled to forget on the owner's behalf.
+
+use crate::authorization::AlephAuthorizationClient;
+use crate::client::{AlephMessageClient, ForgottenMessage, MessageError, MessageWithStatus};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{Authorization, ForgetContent, Message, MessageStatus, MessageType};
+
+/// Why the sender of a FORGET message was allowed to send it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForgetAuthority {
+    /// The FORGET was sent by the owner itself.
+    Owner,
+    /// The FORGET was sent by a delegate of the owner under this authorization.
+    Delegate(Authorization),
+    /// None of the owner's current authorizations covers the sender. Either
+    /// the authorization was revoked after the fact or the CCN accepted a
+    /// message it should not have.
+    Unauthorized,
+}
+
+/// One FORGET message that targeted the forgotten message.
+#[derive(Debug)]
+pub struct ForgetRecord {
+    pub message: Message,
+    pub authority: ForgetAuthority,
+    /// Whether the FORGET was sent on behalf of the sender of the forgotten
+    /// message. The CCN drops the content of forgotten messages, so their
+    /// sender is the only owner information left to compare against; this is
+    /// `false` for messages originally sent on someone else's behalf.
+    pub owner_matches: bool,
+}
+
+impl ForgetRecord {
+    /// Reason given in the FORGET message, if any.
+    pub fn reason(&self) -> Option<&str> {
+        self.message
+            .typed_content::<ForgetContent>()
+            .and_then(ForgetContent::reason)
+    }
+}
+
+/// Who forgot a message, when, and on what authority.
+#[derive(Debug)]
+pub struct ForgetProvenance {
+    /// Envelope of the forgotten message, as still served by the CCN.
+    pub forgotten: ForgottenMessage,
+    /// The FORGET messages listed in `forgotten_by`, in the same order.
+    pub forgotten_by: Vec<ForgetRecord>,
+}
+
+/// Builds the provenance report of a forgotten message.
+///
+/// Fails with [`MessageError::UnexpectedStatus`] if `item_hash` is not
+/// forgotten, and with [`MessageError::InvalidType`] if one of the messages in
+/// `forgotten_by` is not a FORGET.
+pub async fn resolve_forget_provenance<C>(
+    client: &C,
+    item_hash: &ItemHash,
+) -> Result<ForgetProvenance, MessageError>
+where
+    C: AlephMessageClient + AlephAuthorizationClient + Sync,
+{
+    let (forgotten, forgotten_by) = match client.get_message(item_hash).await? {
+        MessageWithStatus::Forgotten {
+            message,
+            forgotten_by,
+        } => (message, forgotten_by),
+        other => {
+            return Err(MessageError::UnexpectedStatus {
+                item_hash: item_hash.clone(),
+                expected: MessageStatus::Forgotten,
+                actual: other.status(),
+            });
+        }
+    };
+
+    let mut records = Vec::with_capacity(forgotten_by.len());
+    for forget_hash in &forgotten_by {
+        let message = client
+            .get_typed_message::<ForgetContent>(forget_hash)
+            .await?
+            .into_message();
+        let authority = if message.sender.same_as(message.owner()) {
+            ForgetAuthority::Owner
+        } else {
+            client
+                .get_authorizations(message.owner())
+                .await?
+                .into_iter()
+                .find(|auth| permits_forget(auth, &message))
+                .map_or(ForgetAuthority::Unauthorized, ForgetAuthority::Delegate)
+        };
+        records.push(ForgetRecord {
+            owner_matches: message.owner().same_as(&forgotten.sender),
+            authority,
+            message,
+        });
+    }
+
+    Ok(ForgetProvenance {
+        forgotten,
+        forgotten_by: records,
+    })
+}
+
+/// Mirrors the CCN's delegation check for a FORGET message. Empty lists in an
+/// authorization are wildcards.
+fn permits_forget(auth: &Authorization, forget: &Message) -> bool {
+    auth.address.same_as(&forget.sender)
+        && auth
+            .chain
+            .as_ref()
+            .is_none_or(|chain| *chain == forget.chain)
+        && (auth.types.is_empty() || auth.types.contains(&MessageType::Forget))
+        && (auth.channels.is_empty()
+            || forget
+                .channel
+                .as_ref()
+                .is_some_and(|c| auth.channels.iter().any(|a| a == c.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AlephClient;
+    use crate::test_utils::inline_message_json;
+    use aleph_types::chain::Address;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const OWNER: &str = "0x0000000000000000000000000000000000000001";
+    const DELEGATE: &str = "0x00000000000000000000000000000000000000aB";
+
+    fn forget(hash: &ItemHash, sender: &str, target: &ItemHash) -> serde_json::Value {
+        let content = json!({
+            "address": OWNER,
+            "time": 1700000000.0,
+            "hashes": [target.to_string()],
+            "reason": "cleanup",
+        });
+        let mut message = inline_message_json("FORGET", content);
+        message["sender"] = sender.into();
+        message["item_hash"] = hash.to_string().into();
+        json!({"status": "processed", "message": message})
+    }
+
+    async fn mount(server: &MockServer, hash: &ItemHash, body: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/messages/{hash}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn provenance_reports_owner_and_delegate_forgets() {
+        let server = MockServer::start().await;
+        let target = ItemHash::from([1u8; 32]);
+        let by_owner = ItemHash::from([2u8; 32]);
+        let by_delegate = ItemHash::from([3u8; 32]);
+
+        mount(
+            &server,
+            &target,
+            json!({
+                "status": "forgotten",
+                "message": {
+                    "sender": OWNER,
+                    "chain": "ETH",
+                    "signature": null,
+                    "type": "POST",
+                    "item_hash": target.to_string(),
+                    "time": "2024-01-01T00:00:00Z",
+                    "channel": "TEST",
+                },
+                "forgotten_by": [by_owner.to_string(), by_delegate.to_string()],
+            }),
+        )
+        .await;
+        mount(&server, &by_owner, forget(&by_owner, OWNER, &target)).await;
+        mount(
+            &server,
+            &by_delegate,
+            forget(&by_delegate, DELEGATE, &target),
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v0/aggregates/{OWNER}.json")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "address": OWNER,
+                "data": {
+                    "security": {
+                        "authorizations": [
+                            {"address": DELEGATE, "types": ["POST"]},
+                            {"address": DELEGATE, "types": ["FORGET"], "channels": ["TEST"]},
+                        ],
+                    },
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let provenance = resolve_forget_provenance(&client, &target).await.unwrap();
+
+        let [owner, delegate] = provenance.forgotten_by.as_slice() else {
+            panic!("expected two forget records");
+        };
+        assert_eq!(owner.authority, ForgetAuthority::Owner);
+        assert!(owner.owner_matches);
+        assert_eq!(owner.reason(), Some("cleanup"));
+        let ForgetAuthority::Delegate(auth) = &delegate.authority else {
+            panic!("expected a delegated forget, got {:?}", delegate.authority);
+        };
+        assert_eq!(auth.address, Address::from(DELEGATE.to_string()));
+        assert_eq!(auth.types, vec![MessageType::Forget]);
+    }
+
+    #[test]
+    fn permits_forget_respects_channels_and_chain() {
+        let message: Message = serde_json::from_value(
+            forget(
+                &ItemHash::from([2u8; 32]),
+                DELEGATE,
+                &ItemHash::from([1u8; 32]),
+            )["message"]
+                .clone(),
+        )
+        .unwrap();
+        let auth =
+            |value: serde_json::Value| -> Authorization { serde_json::from_value(value).unwrap() };
+
+        assert!(permits_forget(
+            &auth(json!({"address": DELEGATE})),
+            &message
+        ));
+        assert!(permits_forget(
+            &auth(json!({"address": DELEGATE.to_lowercase()})),
+            &message
+        ));
+        assert!(!permits_forget(
+            &auth(json!({"address": DELEGATE, "channels": ["OTHER"]})),
+            &message
+        ));
+        assert!(!permits_forget(
+            &auth(json!({"address": DELEGATE, "chain": "SOL"})),
+            &message
+        ));
+        assert!(!permits_forget(&auth(json!({"address": OWNER})), &message));
+    }
+}
@@ -0,0 +1,298 @@
+//! Handling of CCN rate limits.
+//!
+//! A rate-limited CCN answers `429 Too Many Requests`, or `503 Service
+//! Unavailable` while overloaded, with a `Retry-After` header.
+//! [`RetryMiddleware`] waits as instructed, up to a bound, then retries.
+//! Responses without the header, and other transient failures, are retried
+//! with exponential backoff. Both share the same retry budget.
+//!
+//! The quota headers of the last response are kept so that callers can pace
+//! themselves, see [`AlephClient::rate_limit`](crate::client::AlephClient::rate_limit).
+
+use chrono::{DateTime, Utc};
+use http::{Extensions, HeaderMap, StatusCode};
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::{
+    RetryDecision, RetryPolicy, Retryable, default_on_request_failure, default_on_request_success,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Request quota advertised by the CCN in its response headers.
+///
+/// Both the `X-RateLimit-*` and the `RateLimit-*` header families are
+/// recognized. Values are reported as sent by the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed per window.
+    pub limit: Option<u64>,
+    /// Requests left in the current window.
+    pub remaining: Option<u64>,
+    /// When the window resets. Servers disagree on whether this is a delay in
+    /// seconds or a Unix timestamp.
+    pub reset: Option<u64>,
+}
+
+impl RateLimit {
+    /// Reads the quota headers of a response, or `None` if it has none.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            [format!("x-ratelimit-{name}"), format!("ratelimit-{name}")]
+                .iter()
+                .find_map(|key| headers.get(key.as_str()))
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+        let rate_limit = Self {
+            limit: header("limit"),
+            remaining: header("remaining"),
+            reset: header("reset"),
+        };
+        (rate_limit != Self::default()).then_some(rate_limit)
+    }
+}
+
+/// Delay requested by the `Retry-After` header of a 429 or 503 response.
+///
+/// The header holds either a number of seconds or an HTTP date; dates in the
+/// past mean "retry now".
+pub fn retry_after(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+    let value = headers
+        .get(http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Retries transient failures (network errors, 429 and 5xx responses) as long
+/// as `policy` allows it.
+///
+/// Responses carrying a `Retry-After` header are retried after the requested
+/// delay, capped at `max_wait`, instead of the backoff of `policy`; they still
+/// count against its retry budget. Records the quota headers of every
+/// response.
+pub(crate) struct RetryMiddleware {
+    pub(crate) policy: ExponentialBackoff,
+    pub(crate) max_wait: Duration,
+    pub(crate) last_rate_limit: Arc<Mutex<Option<RateLimit>>>,
+}
+
+impl RetryMiddleware {
+    fn observe(&self, response: &Response) {
+        if let Some(rate_limit) = RateLimit::from_headers(response.headers()) {
+            *self
+                .last_rate_limit
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(rate_limit);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let start_time = SystemTime::now();
+        let mut n_past_retries = 0;
+        loop {
+            // Streaming bodies cannot be replayed: send them once.
+            let Some(request) = req.try_clone() else {
+                let response = next.run(req, extensions).await?;
+                self.observe(&response);
+                return Ok(response);
+            };
+
+            let result = next.clone().run(request, extensions).await;
+            let (requested_delay, retryable) = match &result {
+                Ok(response) => {
+                    self.observe(response);
+                    (
+                        retry_after(response.status(), response.headers()),
+                        default_on_request_success(response),
+                    )
+                }
+                Err(error) => (None, default_on_request_failure(error)),
+            };
+            if requested_delay.is_none() && retryable != Some(Retryable::Transient) {
+                return result;
+            }
+            let RetryDecision::Retry { execute_after } =
+                self.policy.should_retry(start_time, n_past_retries)
+            else {
+                return result;
+            };
+            let backoff = execute_after
+                .duration_since(SystemTime::now())
+                .unwrap_or_default();
+            let delay = requested_delay.map_or(backoff, |delay| delay.min(self.max_wait));
+            tracing::debug!(retry = n_past_retries + 1, ?delay, "retrying request");
+            tokio::time::sleep(delay).await;
+            n_past_retries += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{
+        AlephClient, AlephMessageClient, MessageFilter, PaginationParams, RetryConfig,
+    };
+    use http::HeaderValue;
+    use serde_json::json;
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    http::HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn retry_after_parses_seconds_and_dates() {
+        let too_many = StatusCode::TOO_MANY_REQUESTS;
+        assert_eq!(
+            retry_after(too_many, &headers(&[("retry-after", "3")])),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            retry_after(
+                StatusCode::SERVICE_UNAVAILABLE,
+                &headers(&[("retry-after", "Wed, 21 Oct 2015 07:28:00 GMT")])
+            ),
+            Some(Duration::ZERO)
+        );
+        let later = (Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        let delay = retry_after(too_many, &headers(&[("retry-after", &later)])).unwrap();
+        assert!(delay > Duration::from_secs(100));
+        assert_eq!(
+            retry_after(too_many, &headers(&[("retry-after", "soon")])),
+            None
+        );
+        assert_eq!(retry_after(too_many, &HeaderMap::new()), None);
+        assert_eq!(
+            retry_after(StatusCode::OK, &headers(&[("retry-after", "3")])),
+            None
+        );
+    }
+
+    #[test]
+    fn rate_limit_reads_both_header_families() {
+        assert_eq!(
+            RateLimit::from_headers(&headers(&[
+                ("x-ratelimit-limit", "100"),
+                ("x-ratelimit-remaining", "42"),
+            ])),
+            Some(RateLimit {
+                limit: Some(100),
+                remaining: Some(42),
+                reset: None,
+            })
+        );
+        assert_eq!(
+            RateLimit::from_headers(&headers(&[("ratelimit-reset", "30")])),
+            Some(RateLimit {
+                reset: Some(30),
+                ..Default::default()
+            })
+        );
+        assert_eq!(RateLimit::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn client_waits_for_retry_after_and_records_quota() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("retry-after", "1")
+                    .insert_header("x-ratelimit-remaining", "0"),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-remaining", "9")
+                    .set_body_json(json!({
+                        "messages": [],
+                        "pagination_per_page": 20,
+                        "pagination_page": 1,
+                        "pagination_total": 0,
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let start = std::time::Instant::now();
+        let messages = client
+            .get_messages(&MessageFilter::default(), PaginationParams::default())
+            .await
+            .unwrap();
+        assert!(messages.is_empty());
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert_eq!(client.rate_limit().unwrap().remaining, Some(9));
+    }
+
+    #[tokio::test]
+    async fn rate_limited_and_transient_retries_share_one_budget() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/messages.json"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::builder(Url::parse(&server.uri()).unwrap())
+            .retry_config(
+                RetryConfig::default()
+                    .max_retries(2)
+                    .min_backoff(Duration::from_millis(1))
+                    .max_backoff(Duration::from_millis(1)),
+            )
+            .build();
+        let result = client
+            .get_messages(&MessageFilter::default(), PaginationParams::default())
+            .await;
+        assert!(result.is_err());
+    }
+}
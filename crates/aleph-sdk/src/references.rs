@@ -0,0 +1,112 @@
+//! Reverse lookups: which messages point to a given message.
+//!
+//! POST and STORE messages can carry a `ref` field naming another message
+//! (amendments, comments, new revisions of a code or data volume). The CCN
+//! indexes it and exposes it as the `refs` filter of the message list; this
+//! module sorts the results by the kind of relation so that dependency
+//! analysis does not have to re-inspect every message's content.
+
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::{Message, MessageContentEnum};
+
+/// Messages referencing a target message, grouped by relation.
+#[derive(Debug, Clone, Default)]
+pub struct MessageReferences {
+    /// POST messages of type `amend` that amend the target.
+    pub amends: Vec<Message>,
+    /// Other POST messages referencing the target (comments, replies, ...).
+    pub posts: Vec<Message>,
+    /// STORE messages referencing the target, typically newer revisions of a
+    /// code or data volume published under the same ref.
+    pub stores: Vec<Message>,
+    /// FORGET messages that target the message.
+    pub forgets: Vec<Message>,
+    /// Any other message type the CCN matched on its `ref`.
+    pub other: Vec<Message>,
+}
+
+impl MessageReferences {
+    /// Sorts `messages`, as returned by a `refs` query for `target`, by
+    /// relation.
+    pub fn classify(target: &ItemHash, messages: impl IntoIterator<Item = Message>) -> Self {
+        let mut references = Self::default();
+        for message in messages {
+            let bucket = match message.content() {
+                MessageContentEnum::Post(post) if post.is_amend() => &mut references.amends,
+                MessageContentEnum::Post(_) => &mut references.posts,
+                MessageContentEnum::Store(_) => &mut references.stores,
+                MessageContentEnum::Forget(forget) if forget.hashes().contains(target) => {
+                    &mut references.forgets
+                }
+                _ => &mut references.other,
+            };
+            bucket.push(message);
+        }
+        references
+    }
+
+    /// Total number of referencing messages.
+    pub fn len(&self) -> usize {
+        self.amends.len()
+            + self.posts.len()
+            + self.stores.len()
+            + self.forgets.len()
+            + self.other.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::inline_message as message;
+    use serde_json::json;
+
+    #[test]
+    fn classify_groups_by_relation() {
+        let target = ItemHash::from([9u8; 32]);
+        let base = json!({
+            "address": "0x0000000000000000000000000000000000000001",
+            "time": 1700000000.0,
+        });
+        let with = |extra: serde_json::Value| {
+            let mut content = base.clone();
+            content
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            content
+        };
+
+        let messages = vec![
+            message(
+                "POST",
+                with(json!({"type": "amend", "ref": target.to_string(), "content": {}})),
+            ),
+            message(
+                "POST",
+                with(json!({"type": "comment", "ref": target.to_string(), "content": {}})),
+            ),
+            message(
+                "STORE",
+                with(json!({
+                    "item_type": "storage",
+                    "item_hash": ItemHash::from([1u8; 32]).to_string(),
+                    "ref": target.to_string(),
+                })),
+            ),
+            message("FORGET", with(json!({"hashes": [target.to_string()]}))),
+        ];
+
+        let references = MessageReferences::classify(&target, messages);
+        assert_eq!(references.amends.len(), 1);
+        assert_eq!(references.posts.len(), 1);
+        assert_eq!(references.stores.len(), 1);
+        assert_eq!(references.forgets.len(), 1);
+        assert!(references.other.is_empty());
+        assert_eq!(references.len(), 4);
+    }
+}
@@ -0,0 +1,311 @@
+//! Signing through a remote service.
+//!
+//! [`RemoteSigner`] is an [`Account`] that never holds key material: it sends
+//! the verification buffer of each message to an HTTP signing service and
+//! attaches the signature it returns. Keys can then live in an HSM or a cloud
+//! KMS behind that service while messages are still built and published with
+//! this SDK.
+//!
+//! The service receives `POST` requests with a JSON body
+//!
+//! ```json
+//! {"chain": "ETH", "address": "0x...", "payload": "ETH\n0x...\nPOST\n<item hash>"}
+//! ```
+//!
+//! and answers with the signature in the format of the chain, plus the public
+//! key for chains that need it (Solana, Tezos):
+//!
+//! ```json
+//! {"signature": "0x...", "publicKey": null}
+//! ```
+//!
+//! Returned signatures are verified before use, so a misconfigured service
+//! signing with the wrong key is caught before any message is published.
+
+use aleph_types::account::{Account, SignError, check_signature_scheme};
+use aleph_types::chain::{Address, Chain, Signature};
+use aleph_types::message::pending::PendingMessage;
+use aleph_types::message::unsigned::UnsignedMessage;
+use aleph_types::verify_signature::{SignatureVerificationError, verify_buffer};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+/// Time allowed to the signing service to answer by default. Signing may wait
+/// on an HSM or on a human approval, hence the generous bound.
+pub const DEFAULT_SIGNING_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("signing service returned {status}: {body}")]
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("verification buffer is not valid UTF-8")]
+    InvalidPayload,
+    #[error("signing service returned an invalid signature: {0}")]
+    InvalidSignature(#[from] SignatureVerificationError),
+    #[error(transparent)]
+    Sign(#[from] SignError),
+}
+
+#[derive(Serialize)]
+struct SignRequest<'a> {
+    chain: &'a Chain,
+    address: &'a Address,
+    payload: &'a str,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+    #[serde(default, rename = "publicKey")]
+    public_key: Option<String>,
+}
+
+/// An account whose signatures are produced by an HTTP signing service.
+pub struct RemoteSigner {
+    http: reqwest::Client,
+    url: Url,
+    chain: Chain,
+    address: Address,
+    bearer_token: Option<String>,
+    timeout: Duration,
+}
+
+impl RemoteSigner {
+    /// Signs as `address` on `chain` through the service at `url`.
+    pub fn new(url: Url, chain: Chain, address: Address) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+            chain,
+            address,
+            bearer_token: None,
+            timeout: DEFAULT_SIGNING_TIMEOUT,
+        }
+    }
+
+    /// Authenticates to the service with an `Authorization: Bearer` header.
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Time allowed to the service to answer each request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Asks the service to sign `buffer`, and checks the signature it returns.
+    pub async fn sign(&self, buffer: &[u8]) -> Result<Signature, RemoteSignerError> {
+        self.request_signature(&self.http, buffer).await
+    }
+
+    async fn request_signature(
+        &self,
+        http: &reqwest::Client,
+        buffer: &[u8],
+    ) -> Result<Signature, RemoteSignerError> {
+        let payload = std::str::from_utf8(buffer).map_err(|_| RemoteSignerError::InvalidPayload)?;
+        let mut request = http
+            .post(self.url.clone())
+            .timeout(self.timeout)
+            .json(&SignRequest {
+                chain: &self.chain,
+                address: &self.address,
+                payload,
+            });
+        if let Some(token) = &self.bearer_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(RemoteSignerError::Status { status, body });
+        }
+        let response: SignResponse = response.json().await?;
+        let signature = match response.public_key {
+            Some(public_key) => Signature::with_public_key(response.signature, public_key),
+            None => Signature::from(response.signature),
+        };
+        match verify_buffer(&self.chain, &self.address, &signature, buffer) {
+            // Chains without a local verifier are trusted to the service.
+            Ok(()) | Err(SignatureVerificationError::UnsupportedChain(_)) => Ok(signature),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Signs `unsigned` without blocking, unlike [`Account::sign_message`],
+    /// with the same checks.
+    pub async fn sign_message_async(
+        &self,
+        unsigned: UnsignedMessage,
+    ) -> Result<PendingMessage, RemoteSignerError> {
+        check_signature_scheme(self, &self.chain)?;
+        let buffer = unsigned.verification_buffer(&self.chain, &self.address);
+        let signature = self.sign(buffer.as_bytes()).await?;
+        Ok(unsigned.with_signature(self.chain.clone(), self.address.clone(), signature))
+    }
+}
+
+impl Account for RemoteSigner {
+    fn chain(&self) -> Chain {
+        self.chain.clone()
+    }
+
+    fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Blocks the calling thread while the signing request runs on a thread
+    /// and runtime of its own, which works from synchronous code and from any
+    /// tokio runtime. Prefer [`RemoteSigner::sign_message_async`] from async
+    /// code: it does not block.
+    fn sign_raw(&self, buffer: &[u8]) -> Result<Signature, SignError> {
+        let signing_failed = |e: &dyn std::fmt::Display| SignError::SigningFailed(e.to_string());
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|e| signing_failed(&e))?;
+                    // Pooled connections of `self.http` are bound to the
+                    // runtime that opened them: use a client of this one.
+                    runtime
+                        .block_on(self.request_signature(&reqwest::Client::new(), buffer))
+                        .map_err(|e| signing_failed(&e))
+                })
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aleph_types::message::MessageType;
+    use aleph_types::message::item_type::ItemType;
+    use aleph_types::timestamp::Timestamp;
+    use aleph_types::{address, item_hash};
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SENDER: &str = "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef";
+
+    fn unsigned() -> UnsignedMessage {
+        UnsignedMessage {
+            message_type: MessageType::Post,
+            item_type: ItemType::Inline,
+            item_content: "{}".to_string(),
+            item_hash: item_hash!(
+                "d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+            ),
+            time: Timestamp::from(1_700_000_000.0),
+            channel: None,
+        }
+    }
+
+    fn signer(server: &MockServer, address: Address) -> RemoteSigner {
+        RemoteSigner::new(
+            Url::parse(&format!("{}/sign", server.uri())).unwrap(),
+            Chain::Ethereum,
+            address,
+        )
+        .bearer_token("secret")
+    }
+
+    // A current-thread runtime, on which blocking signing used to panic.
+    #[cfg(feature = "account-evm")]
+    #[tokio::test]
+    async fn sign_message_posts_the_verification_buffer() {
+        use aleph_types::account::EvmAccount;
+
+        let key = EvmAccount::new(Chain::Ethereum, &[1u8; 32]).unwrap();
+        let expected = key.sign_message(unsigned()).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/sign"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_json(serde_json::json!({
+                "chain": "ETH",
+                "address": key.address(),
+                "payload": unsigned().verification_buffer(&Chain::Ethereum, key.address()),
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "signature": expected.signature.as_str(),
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let signer = signer(&server, key.address().clone());
+        let pending = signer.sign_message(unsigned()).unwrap();
+        assert_eq!(pending.signature, expected.signature);
+        assert_eq!(pending.sender, *key.address());
+
+        let pending = signer.sign_message_async(unsigned()).await.unwrap();
+        assert_eq!(pending.signature, expected.signature);
+    }
+
+    #[tokio::test]
+    async fn invalid_signatures_are_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "signature": "0x1234",
+            })))
+            .mount(&server)
+            .await;
+
+        let signer = signer(&server, address!(SENDER));
+        assert!(matches!(
+            signer.sign_message_async(unsigned()).await,
+            Err(RemoteSignerError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            signer.sign_message(unsigned()),
+            Err(SignError::SigningFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn service_errors_fail_signing() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("denied"))
+            .mount(&server)
+            .await;
+
+        let signer = signer(&server, address!(SENDER));
+        assert!(matches!(
+            signer.sign_message_async(unsigned()).await,
+            Err(RemoteSignerError::Status { body, .. }) if body == "denied"
+        ));
+        assert!(matches!(
+            signer.sign_message(unsigned()),
+            Err(SignError::SigningFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn sign_message_async_checks_the_signature_scheme() {
+        let signer = RemoteSigner::new(
+            Url::parse("http://signer.test/sign").unwrap(),
+            Chain::Ethereum,
+            address!("5SwCeGqZ3EVhMDuBaiKsNdhAGNFmMN2HgeZpVdQJwvg5"),
+        );
+        assert!(matches!(
+            signer.sign_message_async(unsigned()).await,
+            Err(RemoteSignerError::Sign(SignError::ChainMismatch { .. }))
+        ));
+    }
+}
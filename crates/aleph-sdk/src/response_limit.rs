@@ -0,0 +1,273 @@
+//! Bounds on the size of CCN responses.
+//!
+//! JSON responses are buffered in full before being decoded, so a node
+//! answering with an oversized body (by mistake or on purpose) can exhaust the
+//! memory of the client. [`ResponseLimits`] caps the body size per endpoint:
+//! responses announcing a larger `Content-Length` are rejected before their
+//! body is read, and bodies without one are cut once they cross the limit.
+//! Either way the client fails with
+//! [`MessageError::ResponseTooLarge`](crate::client::MessageError::ResponseTooLarge).
+//!
+//! Files are better streamed than capped, see
+//! [`FileDownload::into_stream`](crate::client::FileDownload::into_stream) and
+//! [`FileDownload::with_max_size`](crate::client::FileDownload::with_max_size).
+
+use futures_util::TryStreamExt;
+use http::Extensions;
+use reqwest::{Request, Response, ResponseBuilderExt};
+use reqwest_middleware::{Middleware, Next};
+use url::Url;
+
+/// A response body crossed its size limit.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("response from {url} exceeds the limit of {limit} bytes")]
+pub struct ResponseTooLarge {
+    pub url: Url,
+    pub limit: u64,
+    /// Size announced by the server, if it sent a `Content-Length`.
+    pub content_length: Option<u64>,
+}
+
+impl ResponseTooLarge {
+    /// The limit violation behind `error`, whether the middleware rejected the
+    /// response upfront or its body was cut while being read.
+    pub(crate) fn find(error: &reqwest_middleware::Error) -> Option<Self> {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = match error {
+            reqwest_middleware::Error::Middleware(e) => Some(e.as_ref()),
+            reqwest_middleware::Error::Reqwest(e) => Some(e),
+        };
+        while let Some(e) = source {
+            if let Some(too_large) = e.downcast_ref::<Self>() {
+                return Some(too_large.clone());
+            }
+            source = e.source();
+        }
+        None
+    }
+}
+
+/// Maximum response body sizes, by endpoint.
+///
+/// Endpoints are matched on the longest path prefix; paths matching no
+/// endpoint get the default limit. No limit is applied by default.
+///
+/// ```
+/// # use aleph_sdk::response_limit::ResponseLimits;
+/// let limits = ResponseLimits::default()
+///     .default_limit(16 << 20)
+///     .endpoint("/api/v0/messages.json", Some(256 << 20))
+///     .endpoint("/api/v0/storage/raw", None);
+/// assert_eq!(limits.limit_for("/api/v0/storage/raw/abcd"), None);
+/// assert_eq!(limits.limit_for("/api/v0/aggregates/0x1.json"), Some(16 << 20));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseLimits {
+    default: Option<u64>,
+    endpoints: Vec<(String, Option<u64>)>,
+}
+
+impl ResponseLimits {
+    /// Limit applied to endpoints without a limit of their own.
+    pub fn default_limit(mut self, max_bytes: u64) -> Self {
+        self.default = Some(max_bytes);
+        self
+    }
+
+    /// Sets the limit of the endpoints under `path_prefix`; `None` lifts it.
+    pub fn endpoint(mut self, path_prefix: impl Into<String>, max_bytes: Option<u64>) -> Self {
+        let path_prefix = path_prefix.into();
+        self.endpoints.retain(|(prefix, _)| *prefix != path_prefix);
+        self.endpoints.push((path_prefix, max_bytes));
+        self
+    }
+
+    /// Limit applying to a request on `path`.
+    pub fn limit_for(&self, path: &str) -> Option<u64> {
+        self.endpoints
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, limit)| *limit)
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.default.is_none() && self.endpoints.iter().all(|(_, limit)| limit.is_none())
+    }
+}
+
+/// Enforces [`ResponseLimits`] on every response.
+pub(crate) struct ResponseLimitMiddleware {
+    pub(crate) limits: ResponseLimits,
+}
+
+#[async_trait::async_trait]
+impl Middleware for ResponseLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if self.limits.is_unlimited() {
+            return next.run(req, extensions).await;
+        }
+        let limit = self.limits.limit_for(req.url().path());
+        let response = next.run(req, extensions).await?;
+        let Some(limit) = limit else {
+            return Ok(response);
+        };
+
+        match response.content_length() {
+            Some(content_length) if content_length > limit => {
+                Err(reqwest_middleware::Error::middleware(ResponseTooLarge {
+                    url: response.url().clone(),
+                    limit,
+                    content_length: Some(content_length),
+                }))
+            }
+            // The HTTP layer already rejects bodies longer than announced.
+            Some(_) => Ok(response),
+            None => Ok(limit_body(response, limit)),
+        }
+    }
+}
+
+/// Wraps the body of `response` so that reading past `limit` bytes fails with
+/// [`ResponseTooLarge`].
+pub(crate) fn limit_body(response: Response, limit: u64) -> Response {
+    let url = response.url().clone();
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version())
+        .url(url.clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+
+    let mut read = 0u64;
+    let body = response
+        .bytes_stream()
+        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { Box::new(e) })
+        .and_then(move |chunk| {
+            read += chunk.len() as u64;
+            let result = if read > limit {
+                Err(ResponseTooLarge {
+                    url: url.clone(),
+                    limit,
+                    content_length: None,
+                }
+                .into())
+            } else {
+                Ok(chunk)
+            };
+            std::future::ready(result)
+        });
+
+    builder
+        .body(reqwest::Body::wrap_stream(body))
+        .map(Response::from)
+        .expect("status, version and headers come from a valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{AlephClient, AlephMessageClient, AlephStorageClient, MessageError};
+    use aleph_types::item_hash::ItemHash;
+    use wiremock::matchers::{method, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn limit_for_picks_longest_prefix() {
+        let limits = ResponseLimits::default()
+            .default_limit(10)
+            .endpoint("/api/v0/messages", Some(100))
+            .endpoint("/api/v0/messages/page", Some(1000))
+            .endpoint("/api/v0/messages", Some(200));
+        assert_eq!(limits.limit_for("/api/v0/messages.json"), Some(200));
+        assert_eq!(limits.limit_for("/api/v0/messages/page/2"), Some(1000));
+        assert_eq!(limits.limit_for("/metrics.json"), Some(10));
+        assert!(ResponseLimits::default().is_unlimited());
+    }
+
+    #[tokio::test]
+    async fn client_rejects_announced_oversized_bodies() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/api/v0/messages/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(2048)))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::builder(Url::parse(&server.uri()).unwrap())
+            .response_limits(ResponseLimits::default().default_limit(1024))
+            .build();
+        let err = client
+            .get_message(&ItemHash::from([1u8; 32]))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MessageError::ResponseTooLarge(ResponseTooLarge {
+                limit: 1024,
+                content_length: Some(2048),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn file_download_honors_max_size() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/api/v0/storage/raw/"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 2048]))
+            .mount(&server)
+            .await;
+
+        let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+        let hash = ItemHash::from([1u8; 32]);
+        let download = client.download_file_by_hash(&hash).await.unwrap();
+        assert!(matches!(
+            download.with_max_size(1024).bytes().await,
+            Err(MessageError::ResponseTooLarge(ResponseTooLarge {
+                content_length: Some(2048),
+                ..
+            }))
+        ));
+        let download = client.download_file_by_hash(&hash).await.unwrap();
+        assert_eq!(
+            download.with_max_size(2048).bytes().await.unwrap().len(),
+            2048
+        );
+    }
+
+    #[tokio::test]
+    async fn limit_body_cuts_unannounced_bodies() {
+        let chunks = futures_util::stream::iter(
+            (0..4).map(|_| Ok::<_, std::io::Error>(bytes::Bytes::from(vec![0u8; 512]))),
+        );
+        let response = || {
+            Response::from(
+                http::Response::builder()
+                    .url(Url::parse("http://ccn.test/api/v0/messages.json").unwrap())
+                    .body(reqwest::Body::wrap_stream(chunks.clone()))
+                    .unwrap(),
+            )
+        };
+
+        assert_eq!(
+            limit_body(response(), 2048).bytes().await.unwrap().len(),
+            2048
+        );
+        let err = limit_body(response(), 1500).bytes().await.unwrap_err();
+        assert!(matches!(
+            MessageError::from(reqwest_middleware::Error::from(err)),
+            MessageError::ResponseTooLarge(ResponseTooLarge {
+                limit: 1500,
+                content_length: None,
+                ..
+            })
+        ));
+    }
+}
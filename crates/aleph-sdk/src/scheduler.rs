@@ -45,6 +45,29 @@ pub struct NodeEntry {
     pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
+impl NodeEntry {
+    /// The node's HTTP base URL, if the scheduler knows a valid one.
+    pub fn url(&self) -> Option<Url> {
+        self.address.as_deref().and_then(|a| Url::parse(a).ok())
+    }
+}
+
+/// Where the scheduler placed a VM, see [`SchedulerClient::get_allocation`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Allocation {
+    pub vm: VmEntry,
+    /// The node in `vm.allocated_node`, or `None` if the VM is not allocated
+    /// or the scheduler has no record of its node.
+    pub node: Option<NodeEntry>,
+}
+
+impl Allocation {
+    /// HTTP base URL of the CRN running the VM.
+    pub fn node_url(&self) -> Option<Url> {
+        self.node.as_ref().and_then(NodeEntry::url)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum SchedulerError {
     #[error("HTTP error: {0}")]
@@ -227,6 +250,23 @@ impl SchedulerClient {
             .map_err(|e| SchedulerError::InvalidResponse(format!("decode failed: {e}")))?;
         Ok(Some(entry))
     }
+
+    /// Fetch a VM together with the node it is allocated to, to find out
+    /// which CRN actually runs it. Returns `Ok(None)` if the scheduler does
+    /// not know the VM.
+    pub async fn get_allocation(
+        &self,
+        vm_hash: &ItemHash,
+    ) -> Result<Option<Allocation>, SchedulerError> {
+        let Some(vm) = self.get_vm(vm_hash).await? else {
+            return Ok(None);
+        };
+        let node = match vm.allocated_node.as_deref() {
+            Some(node_hash) => self.get_node(node_hash).await?,
+            None => None,
+        };
+        Ok(Some(Allocation { vm, node }))
+    }
 }
 
 #[cfg(test)]
@@ -610,6 +650,42 @@ mod tests {
         assert_eq!(entry.status.as_deref(), Some("Healthy"));
     }
 
+    #[tokio::test]
+    async fn get_allocation_resolves_the_allocated_node() {
+        let server = MockServer::start().await;
+        let vm = "5a586d6f59f6c2e6862f155204626dcf01a6ec1107e7aba67063cd48ffe41d99";
+        let node = "d704be0b15e2fb600c5998581cb9af01bd74a9cf61b586ccc849ad78e0709d77";
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v1/vms/{vm}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sample_vm_json()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v1/nodes/{node}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "node_hash": node,
+                "address": "https://crn.example.io/",
+                "status": "Healthy",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = SchedulerClient::new(Url::parse(&server.uri()).unwrap());
+        let allocation = client
+            .get_allocation(&vm.parse().unwrap())
+            .await
+            .unwrap()
+            .expect("Some(allocation)");
+        assert_eq!(allocation.vm.status, "dispatched");
+        assert_eq!(
+            allocation.node_url().unwrap().as_str(),
+            "https://crn.example.io/"
+        );
+
+        let unknown = ItemHash::from([7u8; 32]);
+        assert!(client.get_allocation(&unknown).await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn get_node_returns_none_on_404() {
         let server = MockServer::start().await;
@@ -0,0 +1,213 @@
+//! Client-side filtering of message streams.
+//!
+//! The CCN can only filter messages on indexed fields (type, sender, channel,
+//! refs, ...). [`MessageStreamExt`] post-filters any message stream, whether a
+//! paginated [`get_messages_iterator`] or a live [`subscribe_to_messages`]
+//! subscription, on anything else in the message:
+//!
+//! ```ignore
+//! use aleph_sdk::stream::MessageStreamExt;
+//! use aleph_types::message::StoreContent;
+//!
+//! let mut stores = client
+//!     .subscribe_to_messages(&filter, None)
+//!     .await?
+//!     .only_type::<StoreContent>()
+//!     .filter_content(|content| {
+//!         content
+//!             .as_typed::<StoreContent>()
+//!             .is_some_and(|store| store.metadata.is_some())
+//!     });
+//! ```
+//!
+//! Errors always pass through so that the consumer still sees them.
+//!
+//! [`get_messages_iterator`]: crate::client::AlephMessageClient::get_messages_iterator
+//! [`subscribe_to_messages`]: crate::client::AlephMessageClient::subscribe_to_messages
+
+use crate::client::MessageError;
+use aleph_types::message::{Message, MessageContentEnum, MessageType, TypedContent};
+use futures_util::Stream;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+/// A test applied to each message of a [`FilterMessages`] stream.
+pub trait MessagePredicate {
+    fn matches(&mut self, message: &Message) -> bool;
+}
+
+/// Keeps messages for which the closure returns `true`.
+pub struct ByMessage<F>(F);
+
+impl<F: FnMut(&Message) -> bool> MessagePredicate for ByMessage<F> {
+    fn matches(&mut self, message: &Message) -> bool {
+        (self.0)(message)
+    }
+}
+
+/// Keeps messages whose content makes the closure return `true`.
+pub struct ByContent<F>(F);
+
+impl<F: FnMut(&MessageContentEnum) -> bool> MessagePredicate for ByContent<F> {
+    fn matches(&mut self, message: &Message) -> bool {
+        (self.0)(message.content())
+    }
+}
+
+/// Keeps messages carrying content of type `T`.
+pub struct ByType<T>(PhantomData<fn() -> T>);
+
+impl<T: TypedContent> MessagePredicate for ByType<T> {
+    fn matches(&mut self, message: &Message) -> bool {
+        message.message_type == T::MESSAGE_TYPE
+    }
+}
+
+/// Keeps messages of one of the given types.
+pub struct ByMessageTypes(Vec<MessageType>);
+
+impl MessagePredicate for ByMessageTypes {
+    fn matches(&mut self, message: &Message) -> bool {
+        self.0.contains(&message.message_type)
+    }
+}
+
+/// Stream returned by the [`MessageStreamExt`] combinators.
+///
+/// The inner stream is boxed so that the adapter is `Unpin` regardless of the
+/// stream it wraps, like the subscription streams of the client.
+pub struct FilterMessages<S, P> {
+    stream: Pin<Box<S>>,
+    predicate: P,
+}
+
+impl<S, P> Stream for FilterMessages<S, P>
+where
+    S: Stream<Item = Result<Message, MessageError>>,
+    P: MessagePredicate + Unpin,
+{
+    type Item = Result<Message, MessageError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(message)) if !this.predicate.matches(&message) => continue,
+                item => return Poll::Ready(item),
+            }
+        }
+    }
+}
+
+/// Filtering combinators for streams of messages.
+pub trait MessageStreamExt: Stream<Item = Result<Message, MessageError>> + Sized {
+    /// Keeps messages for which `predicate` returns `true`.
+    fn filter_messages<F>(self, predicate: F) -> FilterMessages<Self, ByMessage<F>>
+    where
+        F: FnMut(&Message) -> bool,
+    {
+        filter(self, ByMessage(predicate))
+    }
+
+    /// Keeps messages whose content matches `predicate`.
+    fn filter_content<F>(self, predicate: F) -> FilterMessages<Self, ByContent<F>>
+    where
+        F: FnMut(&MessageContentEnum) -> bool,
+    {
+        filter(self, ByContent(predicate))
+    }
+
+    /// Keeps messages carrying content of type `T`, e.g.
+    /// `only_type::<PostContent>()`.
+    fn only_type<T: TypedContent>(self) -> FilterMessages<Self, ByType<T>> {
+        filter(self, ByType(PhantomData))
+    }
+
+    /// Keeps messages of any of the given types.
+    fn only_types(
+        self,
+        types: impl IntoIterator<Item = MessageType>,
+    ) -> FilterMessages<Self, ByMessageTypes> {
+        filter(self, ByMessageTypes(types.into_iter().collect()))
+    }
+}
+
+impl<S: Stream<Item = Result<Message, MessageError>>> MessageStreamExt for S {}
+
+fn filter<S, P>(stream: S, predicate: P) -> FilterMessages<S, P> {
+    FilterMessages {
+        stream: Box::pin(stream),
+        predicate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::inline_message as message;
+    use aleph_types::item_hash::ItemHash;
+    use aleph_types::message::{PostContent, StoreContent};
+    use futures_util::{StreamExt, TryStreamExt};
+    use serde_json::json;
+
+    fn post(post_type: &str) -> Message {
+        let content = json!({
+            "address": "0x0000000000000000000000000000000000000001",
+            "time": 1700000000.0,
+            "type": post_type,
+            "content": {},
+        });
+        message("POST", content)
+    }
+
+    fn store() -> Message {
+        let content = json!({
+            "address": "0x0000000000000000000000000000000000000001",
+            "time": 1700000000.0,
+            "item_type": "storage",
+            "item_hash": ItemHash::from([1u8; 32]).to_string(),
+        });
+        message("STORE", content)
+    }
+
+    fn messages() -> impl Stream<Item = Result<Message, MessageError>> {
+        futures_util::stream::iter(vec![Ok(post("chat")), Ok(store()), Ok(post("amend"))])
+    }
+
+    #[tokio::test]
+    async fn only_type_keeps_matching_messages() {
+        let stores: Vec<_> = messages()
+            .only_type::<StoreContent>()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(stores.len(), 1);
+        assert!(stores[0].typed_content::<StoreContent>().is_some());
+    }
+
+    #[tokio::test]
+    async fn filter_content_composes_with_only_type() {
+        let amends: Vec<_> = messages()
+            .only_type::<PostContent>()
+            .filter_content(|c| {
+                c.as_typed::<PostContent>()
+                    .is_some_and(PostContent::is_amend)
+            })
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(amends.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn errors_pass_through() {
+        let stream = futures_util::stream::iter(vec![
+            Ok(store()),
+            Err(MessageError::NotFound(ItemHash::from([2u8; 32]))),
+        ]);
+        let items: Vec<_> = stream.only_type::<PostContent>().collect().await;
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Helpers shared by the unit tests of the crate.
+
+use crate::client::{
+    AlephAggregateClient, AlephMessageClient, AlephStorageClient, FileDownload, FileMetadata,
+    MessageError, MessageFilter, MessageVerification, MessageWithStatus, PaginationParams,
+    PostMessageResponse, PublicationStatus, StorageError,
+};
+use aleph_types::account::{Account, SignError};
+use aleph_types::chain::{Address, Chain, Signature};
+use aleph_types::item_hash::ItemHash;
+use aleph_types::message::pending::PendingMessage;
+use aleph_types::message::{FileRef, Message};
+use futures_util::Stream;
+use memsizes::Bytes;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Ethereum account producing a dummy signature, for tests that only check
+/// what is sent to the CCN.
+pub(crate) struct TestAccount(pub(crate) Address);
+
+impl TestAccount {
+    pub(crate) fn new() -> Self {
+        Self(Address::from(
+            "0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string(),
+        ))
+    }
+}
+
+impl Account for TestAccount {
+    fn chain(&self) -> Chain {
+        Chain::Ethereum
+    }
+    fn address(&self) -> &Address {
+        &self.0
+    }
+    fn sign_raw(&self, _buffer: &[u8]) -> Result<Signature, SignError> {
+        Ok(Signature::from("0xDUMMY".to_string()))
+    }
+}
+
+/// JSON of an inline message carrying `content`, as served by the CCN API.
+/// Tests override the fields they care about, such as `sender` or
+/// `item_hash`.
+pub(crate) fn inline_message_json(
+    message_type: &str,
+    content: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "chain": "ETH",
+        "sender": "0x0000000000000000000000000000000000000001",
+        "signature": null,
+        "type": message_type,
+        "item_type": "inline",
+        "item_content": content.to_string(),
+        "item_hash": ItemHash::from([0u8; 32]).to_string(),
+        "confirmations": [],
+        "time": 1700000000.0,
+        "channel": "TEST",
+        "content": content,
+    })
+}
+
+/// [`inline_message_json`], deserialized.
+pub(crate) fn inline_message(message_type: &str, content: serde_json::Value) -> Message {
+    serde_json::from_value(inline_message_json(message_type, content)).unwrap()
+}
+
+/// Answer of the CCN to `GET /api/v0/messages/{hash}` for a processed inline
+/// POST carrying `{"body": "hello"}`.
+pub(crate) fn processed_post_json(hash: &ItemHash) -> serde_json::Value {
+    let content = serde_json::json!({
+        "address": "0x0000000000000000000000000000000000000001",
+        "time": 1700000000.0,
+        "type": "chat",
+        "content": {"body": "hello"},
+    });
+    let mut message = inline_message_json("POST", content);
+    message["item_hash"] = hash.to_string().into();
+    serde_json::json!({
+        "status": "processed",
+        "item_hash": hash.to_string(),
+        "reception_time": "2024-01-01T00:00:00Z",
+        "message": message,
+    })
+}
+
+/// In-memory client, for tests of the default methods of the client traits.
+///
+/// Posts are accepted as processed and uploads answer `upload_hash`; both
+/// record that they were called. The node has no messages, files or
+/// aggregates.
+pub(crate) struct MockClient {
+    pub(crate) upload_storage_called: AtomicBool,
+    pub(crate) upload_ipfs_called: AtomicBool,
+    pub(crate) post_called: AtomicBool,
+    /// Hash returned by upload_to_storage / upload_to_ipfs
+    pub(crate) upload_hash: ItemHash,
+}
+
+impl MockClient {
+    pub(crate) fn new(upload_hash: ItemHash) -> Self {
+        Self {
+            upload_storage_called: AtomicBool::new(false),
+            upload_ipfs_called: AtomicBool::new(false),
+            post_called: AtomicBool::new(false),
+            upload_hash,
+        }
+    }
+}
+
+impl Default for MockClient {
+    fn default() -> Self {
+        Self::new(ItemHash::from([0u8; 32]))
+    }
+}
+
+impl AlephMessageClient for MockClient {
+    async fn get_message(
+        &self,
+        item_hash: &ItemHash,
+    ) -> Result<MessageWithStatus<Message>, MessageError> {
+        Err(MessageError::NotFound(item_hash.clone()))
+    }
+
+    async fn get_messages(
+        &self,
+        _filter: &MessageFilter,
+        _pagination: PaginationParams,
+    ) -> Result<Vec<Message>, MessageError> {
+        Ok(Vec::new())
+    }
+
+    fn get_messages_iterator(
+        &self,
+        _filter: MessageFilter,
+        _pagination: Option<u32>,
+    ) -> impl Stream<Item = Result<Message, MessageError>> + Send + '_ {
+        futures_util::stream::empty()
+    }
+
+    async fn subscribe_to_messages(
+        &self,
+        _filter: &MessageFilter,
+        _history: Option<u32>,
+    ) -> Result<impl Stream<Item = Result<Message, MessageError>> + Send + Unpin, MessageError>
+    {
+        Ok(tokio_stream::empty())
+    }
+
+    async fn post_message(
+        &self,
+        _message: &PendingMessage,
+        _sync: bool,
+    ) -> Result<PostMessageResponse, MessageError> {
+        self.post_called.store(true, Ordering::SeqCst);
+
+        Ok(PostMessageResponse {
+            publication_status: PublicationStatus {
+                status: "success".to_string(),
+                failed: vec![],
+            },
+            message_status: "processed".to_string(),
+        })
+    }
+
+    async fn get_messages_and_verify(
+        &self,
+        _filter: &MessageFilter,
+    ) -> Result<Vec<MessageVerification>, MessageError>
+    where
+        Self: AlephStorageClient + Sync,
+    {
+        Ok(Vec::new())
+    }
+}
+
+impl AlephStorageClient for MockClient {
+    async fn get_file_size(&self, file_hash: &ItemHash) -> Result<Bytes, MessageError> {
+        Err(StorageError::NotFound(file_hash.clone()).into())
+    }
+
+    async fn get_file_metadata_by_message_hash(
+        &self,
+        message_hash: &ItemHash,
+    ) -> Result<FileMetadata, MessageError> {
+        Err(MessageError::NotFound(message_hash.clone()))
+    }
+
+    async fn get_file_metadata_by_ref(
+        &self,
+        file_ref: &FileRef,
+    ) -> Result<FileMetadata, MessageError> {
+        Err(StorageError::RefNotFound(file_ref.clone()).into())
+    }
+
+    async fn download_file_by_hash(
+        &self,
+        file_hash: &ItemHash,
+    ) -> Result<FileDownload, MessageError> {
+        Err(StorageError::NotFound(file_hash.clone()).into())
+    }
+
+    fn upload_to_storage(
+        &self,
+        _data: &[u8],
+        _message: Option<&PendingMessage>,
+        _sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, StorageError>> + Send {
+        self.upload_storage_called.store(true, Ordering::SeqCst);
+        let hash = self.upload_hash.clone();
+        async move { Ok(hash) }
+    }
+
+    fn upload_to_ipfs(
+        &self,
+        _data: &[u8],
+        _message: Option<&PendingMessage>,
+        _sync: bool,
+    ) -> impl Future<Output = Result<ItemHash, StorageError>> + Send {
+        self.upload_ipfs_called.store(true, Ordering::SeqCst);
+        let hash = self.upload_hash.clone();
+        async move { Ok(hash) }
+    }
+
+    async fn upload_file_to_storage(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> Result<ItemHash, StorageError> {
+        let data = tokio::fs::read(path).await?;
+        self.upload_to_storage(&data, message, sync).await
+    }
+
+    async fn upload_file_to_ipfs(
+        &self,
+        path: impl AsRef<std::path::Path> + Send,
+        message: Option<&PendingMessage>,
+        sync: bool,
+    ) -> Result<ItemHash, StorageError> {
+        let data = tokio::fs::read(path).await?;
+        self.upload_to_ipfs(&data, message, sync).await
+    }
+}
+
+impl AlephAggregateClient for MockClient {
+    #[allow(clippy::manual_async_fn)]
+    fn get_aggregate<T: DeserializeOwned>(
+        &self,
+        _address: &Address,
+        _key: &str,
+    ) -> impl Future<Output = Result<T, MessageError>> + Send {
+        // Written as `fn -> impl Future` to mirror the trait declaration —
+        // `async fn` would silently drop the `+ Send` bound.
+        async {
+            Err(MessageError::ApiError {
+                status: 404,
+                body: "aggregate not found".to_string(),
+            })
+        }
+    }
+
+    async fn get_aggregates(
+        &self,
+        _address: &Address,
+        _keys: &[&str],
+    ) -> Result<HashMap<String, serde_json::Value>, MessageError> {
+        Ok(HashMap::new())
+    }
+
+    async fn get_all_aggregates(
+        &self,
+        _address: &Address,
+    ) -> Result<HashMap<String, serde_json::Value>, MessageError> {
+        Ok(HashMap::new())
+    }
+}
@@ -5,7 +5,9 @@
 //! the content (the `storage` engine), `Ipfs` hashes are recomputed with the
 //! kubo-compatible hasher matching the CID's version and codec.
 
+use crate::client::{AlephStorageClient, IntegrityError, MessageError, StorageError};
 use aleph_types::item_hash::{AlephItemHash, ItemHash};
+use aleph_types::message::{Message, MessageVerificationError};
 use sha2::{Digest, Sha256};
 
 pub use aleph_cid::verify::compute_cid;
@@ -93,8 +95,10 @@ impl HashVerifier {
     pub fn finalize(self) -> Result<(), VerifyError> {
         let computed = self.hasher.finalize();
         if computed == self.expected {
+            tracing::debug!(item_hash = %self.expected, "content hash verified");
             Ok(())
         } else {
+            tracing::warn!(expected = %self.expected, actual = %computed, "content hash mismatch");
             Err(VerifyError::IntegrityMismatch {
                 expected: self.expected,
                 actual: computed,
@@ -103,6 +107,73 @@ impl HashVerifier {
     }
 }
 
+/// Item hash verification that also covers non-inline messages.
+pub trait ResolvedItemHashExt {
+    /// Checks the item hash against the bytes it was computed from.
+    ///
+    /// Inline messages are checked locally, like
+    /// [`Message::verify_item_hash`]. The content of storage and IPFS messages
+    /// is downloaded from `/api/v0/storage/raw/` with
+    /// [`with_verification`](crate::client::FileDownload::with_verification)
+    /// and hashed as it streams in: re-serializing the deserialized content
+    /// would not reproduce the original bytes. A mismatch fails with
+    /// [`IntegrityError::HashMismatch`].
+    fn verify_item_hash_resolved<C>(
+        &self,
+        client: &C,
+    ) -> impl Future<Output = Result<(), MessageError>> + Send
+    where
+        C: AlephStorageClient + Sync + ?Sized;
+
+    /// Checks the item hash against raw content already fetched from
+    /// `/storage/raw/`, hashed with a [`HashVerifier`]. Inline messages are
+    /// checked against their `item_content` and `content` is ignored.
+    fn verify_item_hash_with_content(&self, content: &[u8]) -> Result<(), VerifyError>;
+}
+
+impl ResolvedItemHashExt for Message {
+    #[tracing::instrument(level = "debug", skip_all, fields(item_hash = %self.item_hash))]
+    async fn verify_item_hash_resolved<C>(&self, client: &C) -> Result<(), MessageError>
+    where
+        C: AlephStorageClient + Sync + ?Sized,
+    {
+        match self.verify_item_hash() {
+            Ok(()) => return Ok(()),
+            Err(MessageVerificationError::ItemHashVerificationFailed { expected, actual }) => {
+                return Err(IntegrityError::HashMismatch { expected, actual }.into());
+            }
+            Err(MessageVerificationError::NonInlineMessage) => {}
+        }
+
+        let download = client.download_file_by_hash(&self.item_hash).await?;
+        match download
+            .with_verification()
+            .to_writer(&mut tokio::io::sink())
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(MessageError::Storage(StorageError::IntegrityError(
+                VerifyError::IntegrityMismatch { expected, actual },
+            ))) => Err(IntegrityError::HashMismatch { expected, actual }.into()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn verify_item_hash_with_content(&self, content: &[u8]) -> Result<(), VerifyError> {
+        match self.verify_item_hash() {
+            Ok(()) => Ok(()),
+            Err(MessageVerificationError::ItemHashVerificationFailed { expected, actual }) => {
+                Err(VerifyError::IntegrityMismatch { expected, actual })
+            }
+            Err(MessageVerificationError::NonInlineMessage) => {
+                let mut verifier = HashVerifier::new(&self.item_hash)?;
+                verifier.update(content);
+                verifier.finalize()
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,4 +261,78 @@ mod tests {
         let hash = hasher.finalize();
         assert_eq!(hash, ItemHash::Ipfs(compute_cid(data)));
     }
+
+    mod resolved {
+        use super::*;
+        use crate::client::AlephClient;
+        use aleph_types::message::ContentSource;
+        use url::Url;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        async fn stored_message(server: &MockServer, stored: &'static [u8]) -> Message {
+            let mut message: Message =
+                serde_json::from_str(include_str!("../../../fixtures/messages/post/post.json"))
+                    .unwrap();
+            message.content_source = ContentSource::Storage;
+            message.item_hash = ItemHash::Native(AlephItemHash::from_bytes(b"original content"));
+            Mock::given(method("GET"))
+                .and(path(format!("/api/v0/storage/raw/{}", message.item_hash)))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(stored))
+                .mount(server)
+                .await;
+            message
+        }
+
+        #[tokio::test]
+        async fn verifies_downloaded_content() {
+            let server = MockServer::start().await;
+            let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+            let message = stored_message(&server, b"original content").await;
+            message.verify_item_hash_resolved(&client).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn rejects_tampered_content() {
+            let server = MockServer::start().await;
+            let client = AlephClient::new(Url::parse(&server.uri()).unwrap());
+
+            let message = stored_message(&server, b"tampered content").await;
+            assert!(matches!(
+                message.verify_item_hash_resolved(&client).await,
+                Err(MessageError::Integrity(IntegrityError::HashMismatch { .. }))
+            ));
+        }
+
+        #[test]
+        fn verifies_content_fetched_beforehand() {
+            let mut message: Message =
+                serde_json::from_str(include_str!("../../../fixtures/messages/post/post.json"))
+                    .unwrap();
+            message.verify_item_hash_with_content(b"ignored").unwrap();
+
+            let content = vec![0xABu8; 262144 + 100];
+            message.content_source = ContentSource::Ipfs;
+            message.item_hash = ItemHash::Ipfs(compute_cid(&content));
+            message.verify_item_hash_with_content(&content).unwrap();
+            assert!(matches!(
+                message.verify_item_hash_with_content(b"tampered"),
+                Err(VerifyError::IntegrityMismatch { .. })
+            ));
+
+            message.content_source = ContentSource::Storage;
+            message.item_hash = ItemHash::Native(AlephItemHash::from_bytes(&content));
+            message.verify_item_hash_with_content(&content).unwrap();
+        }
+
+        #[tokio::test]
+        async fn checks_inline_messages_locally() {
+            let client = AlephClient::new(Url::parse("http://localhost:1").unwrap());
+            let message: Message =
+                serde_json::from_str(include_str!("../../../fixtures/messages/post/post.json"))
+                    .unwrap();
+            message.verify_item_hash_resolved(&client).await.unwrap();
+        }
+    }
 }
@@ -1,9 +1,11 @@
 use crate::client::{MessageError, MessageFilter};
 use aleph_types::message::Message;
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
 use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use tracing::Instrument;
 use url::Url;
 
 const INITIAL_BACKOFF_MS: u64 = 100;
@@ -45,24 +47,58 @@ fn build_ws_url(
     Ok(ws_url)
 }
 
-/// Spawns a background task that manages the websocket connection and returns a receiver stream.
+/// What a websocket subscription delivers.
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum SubscriptionEvent {
+    Message(Message),
+    /// The connection dropped and was re-established: messages sent in
+    /// between were not delivered.
+    Reconnected,
+}
+
+/// Spawns a background task that manages the websocket connection and returns
+/// the messages it receives as a [`Stream`].
+///
+/// The connection is re-established after errors, which are yielded without
+/// ending the stream. Dropping the stream stops the task.
 pub async fn subscribe(
     base_url: Url,
     filter: &MessageFilter,
     history: Option<u32>,
-) -> Result<mpsc::Receiver<Result<Message, MessageError>>, MessageError> {
+) -> Result<impl Stream<Item = Result<Message, MessageError>> + Send + Unpin + 'static, MessageError>
+{
+    Ok(subscribe_events(base_url, filter, history)
+        .await?
+        .filter_map(|event| {
+            std::future::ready(match event {
+                Ok(SubscriptionEvent::Message(message)) => Some(Ok(message)),
+                Ok(SubscriptionEvent::Reconnected) => None,
+                Err(e) => Some(Err(e)),
+            })
+        }))
+}
+
+/// Like [`subscribe`], but also reports reconnections.
+pub(crate) async fn subscribe_events(
+    base_url: Url,
+    filter: &MessageFilter,
+    history: Option<u32>,
+) -> Result<ReceiverStream<Result<SubscriptionEvent, MessageError>>, MessageError> {
     let ws_url = build_ws_url(&base_url, filter, history)?;
 
     // Try initial connection to fail fast if URL is invalid
     let (ws_stream, _) = connect_async(ws_url.as_str())
         .await
         .map_err(|e| MessageError::WebsocketConnect(Box::new(e)))?;
+    tracing::debug!(url = %ws_url, "websocket connected");
 
     let (tx, rx) = mpsc::channel(CHANNEL_BUFFER_SIZE);
 
-    tokio::spawn(run_ws_loop(ws_url, ws_stream, tx));
+    let span = tracing::debug_span!("ccn_subscription", url = %ws_url);
+    tokio::spawn(run_ws_loop(ws_url, ws_stream, tx).instrument(span));
 
-    Ok(rx)
+    Ok(ReceiverStream::new(rx))
 }
 
 async fn run_ws_loop(
@@ -70,7 +106,7 @@ async fn run_ws_loop(
     initial_stream: tokio_tungstenite::WebSocketStream<
         tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
     >,
-    tx: mpsc::Sender<Result<Message, MessageError>>,
+    tx: mpsc::Sender<Result<SubscriptionEvent, MessageError>>,
 ) {
     let mut ws_stream = initial_stream;
     let mut backoff_ms = INITIAL_BACKOFF_MS;
@@ -85,7 +121,11 @@ async fn run_ws_loop(
                     // Reset backoff on successful message
                     backoff_ms = INITIAL_BACKOFF_MS;
 
-                    let item = serde_json::from_str::<Message>(&text)
+                    let item = crate::json::from_bytes::<Message>(text.into())
+                        .map(|message| {
+                            tracing::trace!(item_hash = %message.item_hash, "message received");
+                            SubscriptionEvent::Message(message)
+                        })
                         .map_err(MessageError::WebsocketParse);
 
                     if tx.send(item).await.is_err() {
@@ -95,6 +135,7 @@ async fn run_ws_loop(
                 }
                 Ok(WsMessage::Close(_)) => {
                     // Server closed connection, break to reconnect
+                    tracing::debug!("websocket closed by the server");
                     break;
                 }
                 Ok(_) => {
@@ -102,6 +143,7 @@ async fn run_ws_loop(
                 }
                 Err(e) => {
                     // Connection error, break to reconnect
+                    tracing::warn!(error = %e, "websocket stream failed");
                     let _ = tx
                         .send(Err(MessageError::WebsocketStream(Box::new(e))))
                         .await;
@@ -112,15 +154,22 @@ async fn run_ws_loop(
 
         // Reconnection loop with exponential backoff
         loop {
+            tracing::debug!(backoff_ms, "reconnecting websocket");
             tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
             backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
 
             match connect_async(ws_url.as_str()).await {
                 Ok((new_stream, _)) => {
                     ws_stream = new_stream;
+                    tracing::info!("websocket reconnected");
+                    if tx.send(Ok(SubscriptionEvent::Reconnected)).await.is_err() {
+                        // Receiver dropped
+                        return;
+                    }
                     break;
                 }
                 Err(e) => {
+                    tracing::warn!(error = %e, "websocket reconnection failed");
                     if tx
                         .send(Err(MessageError::WebsocketConnect(Box::new(e))))
                         .await
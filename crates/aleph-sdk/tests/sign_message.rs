@@ -39,6 +39,7 @@ fn test_evm_end_to_end() {
         &pending.signature,
         pending.message_type,
         &pending.item_hash,
+        &pending.time,
     )
     .expect("EVM end-to-end: signature should verify");
 }
@@ -59,6 +60,7 @@ fn test_solana_end_to_end() {
         &pending.signature,
         pending.message_type,
         &pending.item_hash,
+        &pending.time,
     )
     .expect("Solana end-to-end: signature should verify");
 }
@@ -30,15 +30,46 @@ impl EvmAccount {
 
         let signing_key = SigningKey::from_bytes((&key_bytes).into())
             .map_err(|e| AccountError::InvalidKey(e.to_string()))?;
+        Ok(Self::from_signing_key(chain, signing_key))
+    }
+
+    /// Creates a new EVM account from an English BIP39 phrase, deriving the
+    /// key along `derivation_path` (e.g. [`DEFAULT_EVM_DERIVATION_PATH`]
+    /// for the first MetaMask account).
+    ///
+    /// [`DEFAULT_EVM_DERIVATION_PATH`]: crate::account::DEFAULT_EVM_DERIVATION_PATH
+    pub fn from_mnemonic(
+        chain: Chain,
+        phrase: &str,
+        derivation_path: &str,
+    ) -> Result<Self, AccountError> {
+        if !chain.is_evm() {
+            return Err(AccountError::UnsupportedChain(chain));
+        }
+        let signing_key = crate::account::mnemonic::derive_signing_key(phrase, derivation_path)?;
+        Ok(Self::from_signing_key(chain, signing_key))
+    }
 
+    fn from_signing_key(chain: Chain, signing_key: SigningKey) -> Self {
         let verifying_key = VerifyingKey::from(&signing_key);
         let address = Address::from(public_key_to_address(&verifying_key));
 
-        Ok(Self {
+        Self {
             chain,
             address,
             signing_key,
-        })
+        }
+    }
+
+    /// Creates a new EVM account from a hex-encoded private key, with or
+    /// without a `0x` prefix, as exported by most wallets.
+    pub fn from_hex(chain: Chain, private_key: &str) -> Result<Self, AccountError> {
+        let hex_key = private_key.trim();
+        let hex_key = hex_key.strip_prefix("0x").unwrap_or(hex_key);
+        let mut key_bytes = [0u8; 32];
+        hex::decode_to_slice(hex_key, &mut key_bytes)
+            .map_err(|e| AccountError::InvalidKey(e.to_string()))?;
+        Self::new(chain, &key_bytes)
     }
 
     /// Returns a reference to the underlying secp256k1 signing key.
@@ -102,6 +133,33 @@ mod tests {
         assert_eq!(account.address().as_str().len(), 42);
     }
 
+    #[test]
+    fn test_evm_account_from_hex() {
+        let hex_key = hex::encode(TEST_KEY);
+        let account = EvmAccount::new(Chain::Ethereum, &TEST_KEY).unwrap();
+        for key in [hex_key.clone(), format!("0x{hex_key}")] {
+            let parsed = EvmAccount::from_hex(Chain::Ethereum, &key).unwrap();
+            assert_eq!(parsed.address(), account.address());
+        }
+        assert!(EvmAccount::from_hex(Chain::Ethereum, "0x1234").is_err());
+    }
+
+    #[test]
+    fn test_evm_account_from_mnemonic() {
+        let phrase = "test test test test test test test test test test test junk";
+        let account = EvmAccount::from_mnemonic(
+            Chain::Ethereum,
+            phrase,
+            crate::account::DEFAULT_EVM_DERIVATION_PATH,
+        )
+        .unwrap();
+        assert_eq!(
+            account.address().as_str(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert!(EvmAccount::from_mnemonic(Chain::Sol, phrase, "m/44'/60'/0'/0/0").is_err());
+    }
+
     #[test]
     fn test_evm_account_wrong_chain() {
         let result = EvmAccount::new(Chain::Sol, &TEST_KEY);
@@ -136,7 +194,40 @@ mod tests {
             &signature,
             message_type,
             &item_hash,
+            &crate::timestamp::Timestamp::now(),
         )
         .expect("round-trip verification should pass");
     }
+
+    #[test]
+    fn test_offline_signing_matches_sign_message() {
+        use crate::message::item_type::ItemType;
+        use crate::message::unsigned::UnsignedMessage;
+
+        let account = EvmAccount::new(Chain::Ethereum, &TEST_KEY).unwrap();
+        let unsigned = UnsignedMessage {
+            message_type: MessageType::Post,
+            item_type: ItemType::Inline,
+            item_content: "{}".to_string(),
+            item_hash: crate::item_hash!(
+                "d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+            ),
+            time: crate::timestamp::Timestamp::from(1_700_000_000.0),
+            channel: None,
+        };
+        let expected = account.sign_message(unsigned.clone()).unwrap();
+
+        // Ship the message to the signer and back as JSON.
+        let json = serde_json::to_string(&unsigned).unwrap();
+        let transported: UnsignedMessage = serde_json::from_str(&json).unwrap();
+        let buffer = transported.verification_buffer(&Chain::Ethereum, account.address());
+        let signature = account.sign_raw(buffer.as_bytes()).unwrap();
+        let pending =
+            transported.with_signature(Chain::Ethereum, account.address().clone(), signature);
+
+        assert_eq!(
+            serde_json::to_value(&pending).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+    }
 }
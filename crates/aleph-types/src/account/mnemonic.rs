@@ -0,0 +1,93 @@
+//! BIP39 seed phrases and BIP32 key derivation for secp256k1 accounts, on
+//! top of the mnemonic support of `alloy-signer-local`.
+//!
+//! Keys derived along [`DEFAULT_EVM_DERIVATION_PATH`] match the first account
+//! MetaMask (and most EVM wallets) derive from the same phrase.
+
+use crate::account::AccountError;
+use alloy_signer_local::MnemonicBuilder;
+use alloy_signer_local::coins_bip39::{English, Mnemonic};
+use k256::ecdsa::SigningKey;
+
+/// BIP44 path of the first EVM account, as used by MetaMask.
+pub const DEFAULT_EVM_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Word counts accepted by [`generate_mnemonic`].
+pub const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Generates a random English BIP39 phrase of `word_count` words.
+pub fn generate_mnemonic(word_count: usize) -> Result<String, AccountError> {
+    if !MNEMONIC_WORD_COUNTS.contains(&word_count) {
+        return Err(AccountError::InvalidMnemonic(format!(
+            "unsupported word count {word_count}, expected one of {MNEMONIC_WORD_COUNTS:?}"
+        )));
+    }
+    let mnemonic = Mnemonic::<English>::new_with_count(&mut rand::thread_rng(), word_count)
+        .map_err(|e| AccountError::InvalidMnemonic(e.to_string()))?;
+    Ok(mnemonic.to_phrase())
+}
+
+/// Derives the secp256k1 key at `derivation_path` from an English BIP39
+/// phrase, without passphrase.
+pub(crate) fn derive_signing_key(
+    phrase: &str,
+    derivation_path: &str,
+) -> Result<SigningKey, AccountError> {
+    // Phrases are often pasted with extra spaces or capitalized words.
+    let phrase = phrase
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let signer = MnemonicBuilder::<English>::default()
+        .phrase(phrase)
+        .derivation_path(derivation_path)
+        .map_err(|_| AccountError::InvalidDerivationPath(derivation_path.to_string()))?
+        .build()
+        .map_err(|e| AccountError::InvalidMnemonic(e.to_string()))?;
+    Ok(signer.into_credential())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HARDHAT_PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn test_derive_matches_wallet_keys() {
+        let key = derive_signing_key(HARDHAT_PHRASE, DEFAULT_EVM_DERIVATION_PATH).unwrap();
+        assert_eq!(
+            hex::encode(key.to_bytes()),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+
+        let key = derive_signing_key(HARDHAT_PHRASE, "m/44'/60'/0'/0/1").unwrap();
+        assert_eq!(
+            hex::encode(key.to_bytes()),
+            "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        );
+    }
+
+    #[test]
+    fn test_derive_rejects_invalid_input() {
+        assert!(matches!(
+            derive_signing_key("test test test", DEFAULT_EVM_DERIVATION_PATH),
+            Err(AccountError::InvalidMnemonic(_))
+        ));
+        assert!(matches!(
+            derive_signing_key(HARDHAT_PHRASE, "m/44'/x/0"),
+            Err(AccountError::InvalidDerivationPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_generate_mnemonic_word_counts() {
+        for count in MNEMONIC_WORD_COUNTS {
+            let phrase = generate_mnemonic(count).unwrap();
+            assert_eq!(phrase.split(' ').count(), count);
+            derive_signing_key(&phrase, DEFAULT_EVM_DERIVATION_PATH).unwrap();
+        }
+        assert!(generate_mnemonic(13).is_err());
+    }
+}
@@ -1,18 +1,26 @@
 #[cfg(feature = "account-evm")]
 mod evm;
+#[cfg(feature = "account-evm")]
+mod mnemonic;
 #[cfg(feature = "account-sol")]
 mod solana;
+#[cfg(feature = "account-tezos")]
+mod tezos;
 
 #[cfg(feature = "account-evm")]
 pub use evm::EvmAccount;
+#[cfg(feature = "account-evm")]
+pub use mnemonic::{DEFAULT_EVM_DERIVATION_PATH, MNEMONIC_WORD_COUNTS, generate_mnemonic};
 #[cfg(feature = "account-sol")]
 pub use solana::SolanaAccount;
+#[cfg(feature = "account-tezos")]
+pub use tezos::TezosAccount;
 
-use crate::chain::{Address, Chain, Signature};
-use crate::item_hash::ItemHash;
-use crate::message::MessageType;
+use crate::chain::{Address, Chain, Signature, SignatureScheme};
 use crate::message::pending::PendingMessage;
 use crate::message::unsigned::UnsignedMessage;
+pub use crate::message::unsigned::verification_buffer;
+use crate::timestamp::Timestamp;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -20,6 +28,12 @@ use thiserror::Error;
 pub enum SignError {
     #[error("signing failed: {0}")]
     SigningFailed(String),
+    #[error("cannot sign as {chain}: signer {address} uses {scheme} signatures")]
+    ChainMismatch {
+        chain: Chain,
+        address: Address,
+        scheme: SignatureScheme,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -29,51 +43,88 @@ pub enum AccountError {
     UnsupportedChain(Chain),
     #[error("invalid private key: {0}")]
     InvalidKey(String),
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
 }
 
 pub trait Account: Send + Sync {
     fn chain(&self) -> Chain;
     fn address(&self) -> &Address;
     fn sign_raw(&self, buffer: &[u8]) -> Result<Signature, SignError>;
-}
 
-pub fn verification_buffer(
-    chain: &Chain,
-    sender: &Address,
-    message_type: MessageType,
-    item_hash: &ItemHash,
-) -> String {
-    format!("{chain}\n{sender}\n{message_type}\n{item_hash}")
+    /// Signs the verification buffer of a message sent at `time`.
+    ///
+    /// Defaults to [`sign_raw`](Self::sign_raw). Signers whose signature also
+    /// covers the time, like Tezos accounts in `micheline` mode, override it.
+    fn sign_message_buffer(&self, buffer: &[u8], time: &Timestamp) -> Result<Signature, SignError> {
+        let _ = time;
+        self.sign_raw(buffer)
+    }
+
+    /// Signature scheme of the signer, derived from its address format and
+    /// falling back to its chain.
+    fn signature_scheme(&self) -> Option<SignatureScheme> {
+        self.address()
+            .signature_scheme()
+            .or_else(|| self.chain().signature_scheme())
+    }
+
+    /// Signs `unsigned` as sent from the account's chain, see [`sign_message`].
+    fn sign_message(&self, unsigned: UnsignedMessage) -> Result<PendingMessage, SignError>
+    where
+        Self: Sized,
+    {
+        sign_message(self, unsigned)
+    }
 }
 
+/// Signs `unsigned` as sent from the account's own chain.
 pub fn sign_message<A: Account>(
     account: &A,
     unsigned: UnsignedMessage,
 ) -> Result<PendingMessage, SignError> {
-    let buffer = verification_buffer(
-        &account.chain(),
-        account.address(),
-        unsigned.message_type,
-        &unsigned.item_hash,
-    );
-    let signature = account.sign_raw(buffer.as_bytes())?;
-
-    Ok(PendingMessage {
-        chain: account.chain(),
-        sender: account.address().clone(),
-        signature,
-        message_type: unsigned.message_type,
-        item_type: unsigned.item_type,
-        item_content: unsigned.item_content,
-        item_hash: unsigned.item_hash,
-        time: unsigned.time,
-        channel: unsigned.channel,
-    })
+    sign_message_for_chain(account, unsigned, account.chain())
+}
+
+/// Fails with [`SignError::ChainMismatch`] if `chain` uses a different
+/// signature scheme than `account`. Signers that do not go through
+/// [`sign_message_for_chain`] must run this check themselves.
+pub fn check_signature_scheme<A: Account>(account: &A, chain: &Chain) -> Result<(), SignError> {
+    if let (Some(expected), Some(scheme)) = (chain.signature_scheme(), account.signature_scheme())
+        && expected != scheme
+    {
+        return Err(SignError::ChainMismatch {
+            chain: chain.clone(),
+            address: account.address().clone(),
+            scheme,
+        });
+    }
+    Ok(())
+}
+
+/// Signs `unsigned` as sent from `chain`, e.g. an EVM key sending from Base
+/// rather than Ethereum.
+///
+/// Fails with [`SignError::ChainMismatch`] if the chain uses a different
+/// signature scheme than the signer, since the network would reject the
+/// message.
+pub fn sign_message_for_chain<A: Account>(
+    account: &A,
+    unsigned: UnsignedMessage,
+    chain: Chain,
+) -> Result<PendingMessage, SignError> {
+    check_signature_scheme(account, &chain)?;
+    let buffer = unsigned.verification_buffer(&chain, account.address());
+    let signature = account.sign_message_buffer(buffer.as_bytes(), &unsigned.time)?;
+    Ok(unsigned.with_signature(chain, account.address().clone(), signature))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::MessageType;
     use crate::{address, item_hash};
 
     #[test]
@@ -93,4 +144,41 @@ mod tests {
              d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
         );
     }
+
+    #[cfg(feature = "account-evm")]
+    fn unsigned() -> UnsignedMessage {
+        UnsignedMessage {
+            message_type: MessageType::Post,
+            item_type: crate::message::item_type::ItemType::Inline,
+            item_content: "{}".to_string(),
+            item_hash: item_hash!(
+                "d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+            ),
+            time: crate::timestamp::Timestamp::from(1_700_000_000.0),
+            channel: None,
+        }
+    }
+
+    #[cfg(feature = "account-evm")]
+    #[test]
+    fn test_sign_message_for_chain_checks_signature_scheme() {
+        let account = EvmAccount::new(Chain::Ethereum, &[1u8; 32]).unwrap();
+        assert_eq!(
+            account.sign_message(unsigned()).unwrap().chain,
+            Chain::Ethereum
+        );
+        assert_eq!(
+            sign_message_for_chain(&account, unsigned(), Chain::Base)
+                .unwrap()
+                .chain,
+            Chain::Base
+        );
+        assert!(matches!(
+            sign_message_for_chain(&account, unsigned(), Chain::Sol),
+            Err(SignError::ChainMismatch {
+                scheme: SignatureScheme::Eip191,
+                ..
+            })
+        ));
+    }
 }
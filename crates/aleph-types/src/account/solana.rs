@@ -43,6 +43,17 @@ impl SolanaAccount {
             signing_key,
         })
     }
+
+    /// Creates a new Solana account from a base58-encoded private key, as
+    /// exported by Phantom and Solflare (32 or 64 bytes once decoded).
+    pub fn from_base58(chain: Chain, private_key: &str) -> Result<Self, AccountError> {
+        let mut key_bytes = bs58::decode(private_key.trim())
+            .into_vec()
+            .map_err(|e| AccountError::InvalidKey(e.to_string()))?;
+        let account = Self::new(chain, &key_bytes);
+        key_bytes.fill(0);
+        account
+    }
 }
 
 impl Account for SolanaAccount {
@@ -111,6 +122,16 @@ mod tests {
         assert_eq!(account.address(), account_32.address());
     }
 
+    #[test]
+    fn test_solana_account_from_base58() {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&TEST_KEY);
+        let account = SolanaAccount::new(Chain::Sol, &TEST_KEY).unwrap();
+        let phantom_key = bs58::encode(signing_key.to_keypair_bytes()).into_string();
+        let parsed = SolanaAccount::from_base58(Chain::Sol, &phantom_key).unwrap();
+        assert_eq!(parsed.address(), account.address());
+        assert!(SolanaAccount::from_base58(Chain::Sol, "0OIl").is_err());
+    }
+
     #[test]
     fn test_solana_account_invalid_key_length() {
         let result = SolanaAccount::new(Chain::Sol, &[0u8; 16]);
@@ -146,6 +167,7 @@ mod tests {
             &signature,
             message_type,
             &item_hash,
+            &crate::timestamp::Timestamp::now(),
         )
         .expect("round-trip verification should pass");
     }
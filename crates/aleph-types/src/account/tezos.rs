@@ -0,0 +1,282 @@
+use crate::account::{Account, AccountError, SignError};
+use crate::chain::{Address, Chain, Signature};
+use crate::timestamp::Timestamp;
+use crate::verify_signature::tezos::{
+    EDSIG_PREFIX, EDSK_PREFIX, EDSK64_PREFIX, PublicKey, SPSIG_PREFIX, SPSK_PREFIX,
+    base58check_decode, base58check_encode, digest, micheline_payload,
+};
+use ed25519_dalek::Signer;
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+enum TezosKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+/// An Aleph account backed by a Tezos key: Ed25519 for `tz1` addresses,
+/// secp256k1 for `tz2` addresses.
+///
+/// Messages are signed like any Tezos wallet signs raw bytes: over the
+/// BLAKE2b-256 digest of the verification buffer. The signature carries the
+/// signer's public key, which the network needs to check it.
+///
+/// [`micheline`](Self::micheline) switches to the signing type of browser
+/// wallets instead: a Micheline-packed "Tezos Signed Message" string naming
+/// a dApp URL and the message time, followed by the verification buffer.
+///
+/// Both signing key types implement `ZeroizeOnDrop`, so the key is securely
+/// wiped from memory when this account is dropped.
+pub struct TezosAccount {
+    address: Address,
+    public_key: String,
+    signing_key: TezosKey,
+    /// Set in `micheline` mode.
+    dapp_url: Option<String>,
+}
+
+impl TezosAccount {
+    /// Creates a `tz1` account from a 32-byte Ed25519 seed.
+    pub fn ed25519(private_key: &[u8]) -> Result<Self, AccountError> {
+        let seed: [u8; 32] = private_key.try_into().map_err(|_| {
+            AccountError::InvalidKey(format!("expected 32 bytes, got {}", private_key.len()))
+        })?;
+        Ok(Self::from_key(TezosKey::Ed25519(
+            ed25519_dalek::SigningKey::from_bytes(&seed),
+        )))
+    }
+
+    /// Creates a `tz2` account from a 32-byte secp256k1 private key.
+    pub fn secp256k1(private_key: &[u8]) -> Result<Self, AccountError> {
+        let signing_key = k256::ecdsa::SigningKey::from_slice(private_key)
+            .map_err(|e| AccountError::InvalidKey(e.to_string()))?;
+        Ok(Self::from_key(TezosKey::Secp256k1(signing_key)))
+    }
+
+    /// Creates an account from an unencrypted secret key as exported by
+    /// Tezos wallets and `octez-client` (`edsk...` or `spsk...`).
+    pub fn from_secret_key(secret_key: &str) -> Result<Self, AccountError> {
+        let secret_key = secret_key.trim();
+        let secret_key = secret_key
+            .strip_prefix("unencrypted:")
+            .unwrap_or(secret_key);
+        if let Some(mut bytes) = base58check_decode(secret_key, EDSK_PREFIX) {
+            let account = Self::ed25519(&bytes);
+            bytes.fill(0);
+            account
+        } else if let Some(mut bytes) = base58check_decode(secret_key, EDSK64_PREFIX) {
+            // The seed followed by the public key, which is derived again.
+            let account = match bytes.len() {
+                64 => Self::ed25519(&bytes[..32]),
+                n => Err(AccountError::InvalidKey(format!(
+                    "expected 64 bytes, got {n}"
+                ))),
+            };
+            bytes.fill(0);
+            account
+        } else if let Some(mut bytes) = base58check_decode(secret_key, SPSK_PREFIX) {
+            let account = Self::secp256k1(&bytes);
+            bytes.fill(0);
+            account
+        } else {
+            Err(AccountError::InvalidKey(
+                "expected an unencrypted edsk or spsk secret key".to_string(),
+            ))
+        }
+    }
+
+    fn from_key(signing_key: TezosKey) -> Self {
+        let public_key = match &signing_key {
+            TezosKey::Ed25519(key) => PublicKey::Ed25519(key.verifying_key()),
+            TezosKey::Secp256k1(key) => PublicKey::Secp256k1(*key.verifying_key()),
+        };
+        Self {
+            address: Address::from(public_key.address()),
+            public_key: public_key.encode(),
+            signing_key,
+            dapp_url: None,
+        }
+    }
+
+    /// Signs messages with the `micheline` signing type of browser wallets,
+    /// naming `dapp_url` in the signed payload.
+    pub fn micheline(mut self, dapp_url: impl Into<String>) -> Self {
+        self.dapp_url = Some(dapp_url.into());
+        self
+    }
+
+    /// The base58check-encoded public key (`edpk...` or `sppk...`).
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// Signs the BLAKE2b-256 digest of `payload`, returning the encoded
+    /// signature.
+    fn sign_digest(&self, payload: &[u8]) -> Result<String, SignError> {
+        let digest = digest(payload);
+        let signature = match &self.signing_key {
+            TezosKey::Ed25519(key) => {
+                base58check_encode(EDSIG_PREFIX, &key.sign(&digest).to_bytes())
+            }
+            TezosKey::Secp256k1(key) => {
+                let sig: k256::ecdsa::Signature = key
+                    .sign_prehash(&digest)
+                    .map_err(|e| SignError::SigningFailed(e.to_string()))?;
+                let sig = sig.normalize_s().unwrap_or(sig);
+                base58check_encode(SPSIG_PREFIX, &sig.to_bytes())
+            }
+        };
+        Ok(signature)
+    }
+}
+
+impl Account for TezosAccount {
+    fn chain(&self) -> Chain {
+        Chain::Tezos
+    }
+
+    fn address(&self) -> &Address {
+        &self.address
+    }
+
+    fn sign_raw(&self, buffer: &[u8]) -> Result<Signature, SignError> {
+        let signature = self.sign_digest(buffer)?;
+        Ok(Signature::with_public_key(
+            signature,
+            self.public_key.clone(),
+        ))
+    }
+
+    fn sign_message_buffer(&self, buffer: &[u8], time: &Timestamp) -> Result<Signature, SignError> {
+        let Some(dapp_url) = &self.dapp_url else {
+            return self.sign_raw(buffer);
+        };
+        let payload = micheline_payload(buffer, dapp_url, time)
+            .map_err(|e| SignError::SigningFailed(e.to_string()))?;
+        Ok(Signature::micheline(
+            self.sign_digest(&payload)?,
+            self.public_key.clone(),
+            dapp_url.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::verification_buffer;
+    use crate::message::MessageType;
+
+    // The `bootstrap1` account of the Tezos sandbox.
+    const BOOTSTRAP1_SK: &str = "edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsh";
+    // The same key in its 64-byte form, as listed by `octez-client`.
+    const BOOTSTRAP1_SK64: &str = "edskRuR1azSfboG86YPTyxrQgosh5zChf5bVDmptqLTb5EuXAm9rsnDYfTKhq7rDQujdn5WWzwUMeV3agaZ6J2vPQT58jJAJPi";
+
+    // Compile-time guarantee backing the type's doc comment.
+    static_assertions::assert_impl_all!(ed25519_dalek::SigningKey: zeroize::ZeroizeOnDrop);
+    static_assertions::assert_impl_all!(k256::ecdsa::SigningKey: zeroize::ZeroizeOnDrop);
+
+    #[test]
+    fn test_tezos_account_from_secret_key() {
+        let account = TezosAccount::from_secret_key(BOOTSTRAP1_SK).unwrap();
+        assert_eq!(
+            account.address().as_str(),
+            "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx"
+        );
+        assert_eq!(
+            account.public_key(),
+            "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav"
+        );
+        assert_eq!(account.chain(), Chain::Tezos);
+        let account64 = TezosAccount::from_secret_key(BOOTSTRAP1_SK64).unwrap();
+        assert_eq!(account64.address(), account.address());
+        assert_eq!(account64.public_key(), account.public_key());
+        assert!(
+            TezosAccount::from_secret_key("edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_tezos_sign_and_verify_roundtrip() {
+        let item_hash =
+            crate::item_hash!("d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c");
+        let accounts = [
+            TezosAccount::from_secret_key(BOOTSTRAP1_SK).unwrap(),
+            TezosAccount::secp256k1(&[7u8; 32]).unwrap(),
+        ];
+        for account in accounts {
+            let buffer = verification_buffer(
+                &Chain::Tezos,
+                account.address(),
+                MessageType::Post,
+                &item_hash,
+            );
+            let signature = account.sign_raw(buffer.as_bytes()).unwrap();
+            assert_eq!(signature.public_key(), Some(account.public_key()));
+
+            crate::verify_signature::verify(
+                &Chain::Tezos,
+                account.address(),
+                &signature,
+                MessageType::Post,
+                &item_hash,
+                &crate::timestamp::Timestamp::now(),
+            )
+            .expect("round-trip verification should pass");
+        }
+    }
+
+    #[test]
+    fn test_tezos_micheline_signing() {
+        let account = TezosAccount::from_secret_key(BOOTSTRAP1_SK)
+            .unwrap()
+            .micheline("https://app.aleph.cloud");
+        let unsigned = crate::message::unsigned::UnsignedMessage {
+            message_type: MessageType::Post,
+            item_type: crate::message::item_type::ItemType::Inline,
+            item_content: "{}".to_string(),
+            item_hash: crate::item_hash!(
+                "d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+            ),
+            time: Timestamp::from(1_700_000_000.0),
+            channel: None,
+        };
+        let pending = account.sign_message(unsigned).unwrap();
+
+        // Ed25519 is deterministic: same signature as the independently
+        // computed one in `verify_signature::tezos`.
+        assert_eq!(
+            pending.signature.as_str(),
+            "edsigtYqFNAQmqfz6XMSgvsM4oYeorBs9Qn95wQ8VnAC8PUS9X8ZNb8oxHDH6AweAXpxtCbMjjpyQGWQ4P8vbj1otnJGQrc8mLr"
+        );
+        assert_eq!(
+            pending.signature.dapp_url(),
+            Some("https://app.aleph.cloud")
+        );
+        let verify_at = |time: f64| {
+            crate::verify_signature::verify(
+                &pending.chain,
+                &pending.sender,
+                &pending.signature,
+                pending.message_type,
+                &pending.item_hash,
+                &Timestamp::from(time),
+            )
+        };
+        verify_at(1_700_000_000.0).unwrap();
+        assert!(verify_at(1_700_000_001.0).is_err());
+    }
+
+    #[test]
+    fn test_tezos_secp256k1_address() {
+        let account = TezosAccount::secp256k1(&[7u8; 32]).unwrap();
+        assert_eq!(
+            account.address().as_str(),
+            "tz2J8kpqrRxWLk4T9sxkThvJrLmV5rSzWvLH"
+        );
+        assert_eq!(
+            account.public_key(),
+            "sppk7aTr4XUNgoLL6nspoSPPPyBMhpB1QtmW5Cj2NdxSTLQTAEV1XEU"
+        );
+    }
+}
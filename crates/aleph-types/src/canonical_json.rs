@@ -0,0 +1,198 @@
+//! JSON serialization matching Python's `json.dumps`.
+//!
+//! Item hashes are computed over the exact bytes of the item content, so a
+//! message built here hashes like one built by the Python SDK only if both
+//! serialize the content the same way. `serde_json` differs from
+//! `json.dumps` in two places: it writes non-ASCII characters as UTF-8 where
+//! Python escapes them (`é`), and it formats some floats differently
+//! (`1e16` and `1e-5` where Python writes `1e+16` and `1e-05`).
+//! [`to_string`] follows Python on both, as configured by a [`JsonFormat`].
+//!
+//! Object keys are written in the order of the map, which for
+//! `serde_json::Value` is sorted: the output matches `json.dumps(...,
+//! sort_keys=True)`.
+
+/// How [`to_string`] writes JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonFormat {
+    /// Escape non-ASCII characters as `\uXXXX`, as Python does by default.
+    pub ensure_ascii: bool,
+    /// Separate items with `", "` and keys from values with `": "`, Python's
+    /// default, instead of `,` and `:`.
+    pub spaced: bool,
+}
+
+impl JsonFormat {
+    /// `json.dumps(value, separators=(",", ":"))`, the item content format of
+    /// the Python SDK.
+    pub const PYTHON_COMPACT: JsonFormat = JsonFormat {
+        ensure_ascii: true,
+        spaced: false,
+    };
+    /// `json.dumps(value)`.
+    pub const PYTHON_DEFAULT: JsonFormat = JsonFormat {
+        ensure_ascii: true,
+        spaced: true,
+    };
+    /// Compact JSON with UTF-8 strings, like `serde_json::to_string` except
+    /// for float formatting.
+    pub const COMPACT_UTF8: JsonFormat = JsonFormat {
+        ensure_ascii: false,
+        spaced: false,
+    };
+}
+
+impl Default for JsonFormat {
+    fn default() -> Self {
+        Self::PYTHON_COMPACT
+    }
+}
+
+/// Serializes `value` in `format`.
+pub fn to_string(value: &serde_json::Value, format: JsonFormat) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, format);
+    out
+}
+
+fn write_value(out: &mut String, value: &serde_json::Value, format: JsonFormat) {
+    let (item_separator, key_separator) = if format.spaced {
+        (", ", ": ")
+    } else {
+        (",", ":")
+    };
+    match value {
+        serde_json::Value::Null => out.push_str("null"),
+        serde_json::Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        serde_json::Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() => write_float(out, f),
+            _ => out.push_str(&n.to_string()),
+        },
+        serde_json::Value::String(s) => write_string(out, s, format.ensure_ascii),
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(item_separator);
+                }
+                write_value(out, item, format);
+            }
+            out.push(']');
+        }
+        serde_json::Value::Object(map) => {
+            out.push('{');
+            for (i, (key, item)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(item_separator);
+                }
+                write_string(out, key, format.ensure_ascii);
+                out.push_str(key_separator);
+                write_value(out, item, format);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str, ensure_ascii: bool) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            // Python escapes DEL too when ensuring ASCII.
+            c if c < ' ' || (ensure_ascii && !(' '..='~').contains(&c)) => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{unit:04x}"));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes `f` like Python's `repr(float)`: the shortest digits that round
+/// trip, in positional notation for exponents from -5 to 15, and in
+/// scientific notation with a signed, two-digit exponent otherwise.
+fn write_float(out: &mut String, f: f64) {
+    // `{:e}` gives the shortest round-trip digits as `d.ddde-N`.
+    let scientific = format!("{:e}", f.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("LowerExp output has an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().expect("LowerExp exponent is an integer");
+    // Position of the decimal point relative to the first digit.
+    let point = exponent + 1;
+
+    if f.is_sign_negative() {
+        out.push('-');
+    }
+    if !(-4 < point && point <= 16) {
+        out.push_str(&digits[..1]);
+        if digits.len() > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let sign = if exponent < 0 { '-' } else { '+' };
+        out.push_str(&format!("e{sign}{:02}", exponent.abs()));
+    } else if point <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat(point.unsigned_abs() as usize));
+        out.push_str(&digits);
+    } else {
+        let point = point as usize;
+        if digits.len() <= point {
+            out.push_str(&digits);
+            out.push_str(&"0".repeat(point - digits.len()));
+            out.push_str(".0");
+        } else {
+            out.push_str(&digits[..point]);
+            out.push('.');
+            out.push_str(&digits[point..]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outputs of Python's `json.dumps(value, sort_keys=True, ...)`.
+    const PYTHON_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../fixtures/json/python-dumps.json"
+    ));
+
+    #[test]
+    fn test_matches_python_json_dumps() {
+        let cases: Vec<serde_json::Value> = serde_json::from_str(PYTHON_FIXTURE).unwrap();
+        for case in cases {
+            let value = &case["value"];
+            for (key, format) in [
+                ("compact", JsonFormat::PYTHON_COMPACT),
+                ("default", JsonFormat::PYTHON_DEFAULT),
+                ("compact_utf8", JsonFormat::COMPACT_UTF8),
+            ] {
+                assert_eq!(to_string(value, format), case[key].as_str().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trips_through_serde_json() {
+        let cases: Vec<serde_json::Value> = serde_json::from_str(PYTHON_FIXTURE).unwrap();
+        for case in cases {
+            let json = to_string(&case["value"], JsonFormat::default());
+            let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, case["value"]);
+        }
+    }
+}
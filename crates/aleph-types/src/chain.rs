@@ -1,5 +1,6 @@
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Chain {
@@ -112,7 +113,136 @@ impl std::fmt::Display for Chain {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown chain '{0}'")]
+pub struct UnknownChain(String);
+
+/// Parses the code a chain is serialized as (`ETH`, `SOL`, ...),
+/// case-insensitively.
+impl FromStr for Chain {
+    type Err = UnknownChain;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Chain::all()
+            .iter()
+            .find(|chain| chain.to_string().eq_ignore_ascii_case(s))
+            .cloned()
+            .ok_or_else(|| UnknownChain(s.to_string()))
+    }
+}
+
 impl Chain {
+    const ALL: [Chain; 33] = [
+        Chain::Arbitrum,
+        Chain::Aurora,
+        Chain::Avax,
+        Chain::Base,
+        Chain::Blast,
+        Chain::Bob,
+        Chain::Bsc,
+        Chain::Csdk,
+        Chain::Cyber,
+        Chain::Polkadot,
+        Chain::Eclipse,
+        Chain::Ethereum,
+        Chain::Etherlink,
+        Chain::Fraxtal,
+        Chain::Hype,
+        Chain::Ink,
+        Chain::Lens,
+        Chain::Linea,
+        Chain::Lisk,
+        Chain::Metis,
+        Chain::Mode,
+        Chain::Neo,
+        Chain::Nuls,
+        Chain::Nuls2,
+        Chain::Optimism,
+        Chain::Pol,
+        Chain::Sol,
+        Chain::Somnia,
+        Chain::Sonic,
+        Chain::Tezos,
+        Chain::Unichain,
+        Chain::Worldchain,
+        Chain::Zora,
+    ];
+
+    /// Every chain, in the order of their codes.
+    pub fn all() -> &'static [Chain] {
+        &Self::ALL
+    }
+
+    /// The EIP-155 chain id of EVM chains.
+    pub fn evm_chain_id(&self) -> Option<u64> {
+        let chain_id = match self {
+            Chain::Arbitrum => 42161,
+            Chain::Aurora => 1313161554,
+            Chain::Avax => 43114,
+            Chain::Base => 8453,
+            Chain::Blast => 81457,
+            Chain::Bob => 60808,
+            Chain::Bsc => 56,
+            Chain::Cyber => 7560,
+            Chain::Ethereum => 1,
+            Chain::Etherlink => 42793,
+            Chain::Fraxtal => 252,
+            Chain::Hype => 999,
+            Chain::Ink => 57073,
+            Chain::Lens => 232,
+            Chain::Linea => 59144,
+            Chain::Lisk => 1135,
+            Chain::Metis => 1088,
+            Chain::Mode => 34443,
+            Chain::Optimism => 10,
+            Chain::Pol => 137,
+            Chain::Somnia => 5031,
+            Chain::Sonic => 146,
+            Chain::Unichain => 130,
+            Chain::Worldchain => 480,
+            Chain::Zora => 7777777,
+            _ => return None,
+        };
+        Some(chain_id)
+    }
+
+    /// Ticker of the token paying for gas on this chain.
+    pub fn native_token(&self) -> &'static str {
+        match self {
+            Chain::Avax => "AVAX",
+            Chain::Bsc => "BNB",
+            Chain::Csdk => "ATOM",
+            Chain::Polkadot => "DOT",
+            Chain::Etherlink | Chain::Tezos => "XTZ",
+            Chain::Fraxtal => "FRAX",
+            Chain::Hype => "HYPE",
+            Chain::Lens => "GHO",
+            Chain::Metis => "METIS",
+            Chain::Neo => "NEO",
+            Chain::Nuls | Chain::Nuls2 => "NULS",
+            Chain::Pol => "POL",
+            Chain::Sol => "SOL",
+            Chain::Somnia => "SOMI",
+            Chain::Sonic => "S",
+            Chain::Arbitrum
+            | Chain::Aurora
+            | Chain::Base
+            | Chain::Blast
+            | Chain::Bob
+            | Chain::Cyber
+            | Chain::Eclipse
+            | Chain::Ethereum
+            | Chain::Ink
+            | Chain::Linea
+            | Chain::Lisk
+            | Chain::Mode
+            | Chain::Optimism
+            | Chain::Unichain
+            | Chain::Worldchain
+            | Chain::Zora => "ETH",
+        }
+    }
+
     /// Returns true if this chain uses EVM-compatible signature verification
     /// (secp256k1 + EIP-191 personal sign).
     ///
@@ -157,6 +287,84 @@ impl Chain {
     pub fn is_svm(&self) -> bool {
         matches!(self, Chain::Eclipse | Chain::Sol)
     }
+
+    /// The family of this chain, which determines the format of its
+    /// addresses, or `None` for chains this crate knows nothing about.
+    pub fn family(&self) -> Option<ChainFamily> {
+        if self.is_evm() {
+            Some(ChainFamily::Evm)
+        } else if self.is_svm() {
+            Some(ChainFamily::Svm)
+        } else {
+            match self {
+                Chain::Polkadot => Some(ChainFamily::Substrate),
+                Chain::Tezos => Some(ChainFamily::Tezos),
+                _ => None,
+            }
+        }
+    }
+
+    /// The signature scheme messages sent from this chain must use, or `None`
+    /// for chains this crate knows nothing about.
+    pub fn signature_scheme(&self) -> Option<SignatureScheme> {
+        if self.is_evm() {
+            Some(SignatureScheme::Eip191)
+        } else if self.is_svm() {
+            Some(SignatureScheme::Ed25519)
+        } else if matches!(self, Chain::Polkadot) {
+            Some(SignatureScheme::Substrate)
+        } else {
+            None
+        }
+    }
+}
+
+/// Chains sharing an address format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChainFamily {
+    /// `0x` and 40 hex digits, with an optional EIP-55 checksum.
+    Evm,
+    /// Base58 of a 32-byte Ed25519 public key.
+    Svm,
+    /// SS58 (base58) encoding.
+    Substrate,
+    /// `tz1`/`tz2`/`tz3`/`KT1` base58check encoding.
+    Tezos,
+}
+
+/// How a message is signed, which also determines the format of the sender
+/// address and the shape of the signature field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureScheme {
+    /// secp256k1 over an EIP-191 personal-sign digest. Hex `0x` addresses,
+    /// plain hex signatures.
+    Eip191,
+    /// Ed25519 over the raw verification buffer. Base58 addresses, signatures
+    /// carrying the signer's public key.
+    Ed25519,
+    /// Substrate (sr25519) signatures. SS58 addresses.
+    Substrate,
+}
+
+impl SignatureScheme {
+    /// The chain used for messages when the signer does not name one.
+    pub fn default_chain(self) -> Chain {
+        match self {
+            SignatureScheme::Eip191 => Chain::Ethereum,
+            SignatureScheme::Ed25519 => Chain::Sol,
+            SignatureScheme::Substrate => Chain::Polkadot,
+        }
+    }
+}
+
+impl std::fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SignatureScheme::Eip191 => "EIP-191",
+            SignatureScheme::Ed25519 => "Ed25519",
+            SignatureScheme::Substrate => "Substrate",
+        })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -169,9 +377,177 @@ impl std::fmt::Display for Address {
 }
 
 impl Address {
+    /// Parses an address of `chain`, rejecting strings that are not valid in
+    /// the address format of its family:
+    ///
+    /// - EVM: `0x` and 40 hex digits. Mixed-case addresses must carry a valid
+    ///   EIP-55 checksum; all-lowercase and all-uppercase ones carry none.
+    /// - SVM: base58 of a 32-byte key.
+    /// - Substrate: base58 of SS58 length. The SS58 checksum is not verified.
+    /// - Tezos: `tz1`, `tz2`, `tz3` or `KT1` base58check.
+    ///
+    /// Addresses of chains without a known family are only checked for being
+    /// non-empty. Checksums and base58 payloads are verified when the
+    /// `signature-evm` and `signature-sol` features, which bring the hash and
+    /// base58 implementations, are enabled; otherwise only the format is
+    /// checked.
+    ///
+    /// Deserialization stays lenient: messages already on the network are
+    /// accepted as they are.
+    ///
+    /// ```
+    /// use aleph_types::chain::{Address, Chain};
+    ///
+    /// assert!(Address::parse(&Chain::Ethereum, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+    /// assert!(Address::parse(&Chain::Ethereum, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+    /// assert!(Address::parse(&Chain::Sol, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+    /// ```
+    pub fn parse(chain: &Chain, s: &str) -> Result<Self, AddressError> {
+        let invalid = || AddressError::InvalidFormat {
+            chain: chain.clone(),
+            address: s.to_string(),
+        };
+        let Some(family) = chain.family() else {
+            return if s.is_empty() {
+                Err(invalid())
+            } else {
+                Ok(Self(s.to_string()))
+            };
+        };
+        if format_family(s) != Some(family) {
+            if family == ChainFamily::Tezos && tezos_format(s) {
+                return Err(AddressError::Base58Check(s.to_string()));
+            }
+            return Err(invalid());
+        }
+
+        match family {
+            ChainFamily::Evm => {
+                #[cfg(feature = "signature-evm")]
+                {
+                    let hex = &s[2..];
+                    let has_lower = hex.bytes().any(|b| b.is_ascii_lowercase());
+                    let has_upper = hex.bytes().any(|b| b.is_ascii_uppercase());
+                    if has_lower && has_upper {
+                        let bytes = hex::decode(hex).map_err(|_| invalid())?;
+                        let expected = crate::verify_signature::ethereum::eip55_checksum(&bytes);
+                        if expected != s {
+                            return Err(AddressError::Checksum {
+                                address: s.to_string(),
+                                expected,
+                            });
+                        }
+                    }
+                }
+            }
+            ChainFamily::Svm =>
+            {
+                #[cfg(feature = "signature-sol")]
+                if bs58::decode(s).into_vec().map_err(|_| invalid())?.len() != 32 {
+                    return Err(invalid());
+                }
+            }
+            // The base58check encoding was verified by `format_family`.
+            ChainFamily::Substrate | ChainFamily::Tezos => {}
+        }
+        Ok(Self(s.to_string()))
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Guesses the chain family of the address from its format. See
+    /// [`Address::signature_scheme`] for the formats recognized.
+    pub fn chain_family(&self) -> Option<ChainFamily> {
+        format_family(&self.0)
+    }
+
+    /// Guesses the signature scheme of the address from its format: `0x` and
+    /// 40 hex digits for EVM, base58 of a 32-byte key (32 to 44 characters)
+    /// for Ed25519, and the longer base58 SS58 encoding for Substrate. Tezos
+    /// addresses are recognized by their prefix and base58check checksum, and
+    /// have no scheme.
+    ///
+    /// This is a format check only: EIP-55 checksums are not verified.
+    pub fn signature_scheme(&self) -> Option<SignatureScheme> {
+        match self.chain_family()? {
+            ChainFamily::Evm => Some(SignatureScheme::Eip191),
+            ChainFamily::Svm => Some(SignatureScheme::Ed25519),
+            ChainFamily::Substrate => Some(SignatureScheme::Substrate),
+            ChainFamily::Tezos => None,
+        }
+    }
+
+    /// The address in its canonical form for comparisons: EVM addresses are
+    /// lowercased, since their checksum is only carried by the case; others
+    /// are case-sensitive and returned as they are.
+    pub fn normalized(&self) -> std::borrow::Cow<'_, str> {
+        if self.chain_family() == Some(ChainFamily::Evm) {
+            std::borrow::Cow::Owned(self.0.to_ascii_lowercase())
+        } else {
+            std::borrow::Cow::Borrowed(&self.0)
+        }
+    }
+
+    /// Whether two addresses are the same account, comparing their
+    /// [normalized](Address::normalized) forms.
+    pub fn same_as(&self, other: &Address) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+/// The family whose address format `s` follows.
+fn format_family(s: &str) -> Option<ChainFamily> {
+    if let Some(hex) = s.strip_prefix("0x") {
+        return (hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .then_some(ChainFamily::Evm);
+    }
+    let is_base58 = s
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() && !matches!(b, b'0' | b'O' | b'I' | b'l'));
+    if !is_base58 {
+        return None;
+    }
+    match s.len() {
+        // Solana addresses may look like Tezos ones: only the checksum tells.
+        _ if tezos_format(s) && tezos_checksum(s) => Some(ChainFamily::Tezos),
+        32..=44 => Some(ChainFamily::Svm),
+        46..=48 => Some(ChainFamily::Substrate),
+        _ => None,
+    }
+}
+
+/// Whether `s` has the prefix and length of a Tezos address.
+fn tezos_format(s: &str) -> bool {
+    s.len() == 36
+        && ["tz1", "tz2", "tz3", "KT1"]
+            .iter()
+            .any(|prefix| s.starts_with(prefix))
+}
+
+/// Whether `s` is valid base58check of a 20-byte hash behind a 3-byte prefix.
+fn tezos_checksum(s: &str) -> bool {
+    use sha2::{Digest, Sha256};
+
+    let Ok(bytes) = bs58::decode(s).into_vec() else {
+        return false;
+    };
+    if bytes.len() != 27 {
+        return false;
+    }
+    let (payload, checksum) = bytes.split_at(23);
+    Sha256::digest(Sha256::digest(payload))[..4] == *checksum
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AddressError {
+    #[error("'{address}' is not a valid {chain} address")]
+    InvalidFormat { chain: Chain, address: String },
+    #[error("invalid EIP-55 checksum for '{address}', expected '{expected}'")]
+    Checksum { address: String, expected: String },
+    #[error("invalid base58check checksum for '{0}'")]
+    Base58Check(String),
 }
 
 impl From<String> for Address {
@@ -199,6 +575,9 @@ macro_rules! address {
 /// - **Plain string** (EVM chains): `"0x636728db..."` — a hex-encoded ECDSA signature.
 /// - **Structured object** (Solana): `{"signature": "5HH5Z...", "publicKey": "5SwCe..."}`
 ///   — a base58-encoded Ed25519 signature plus the signer's public key.
+///
+/// Tezos signatures made by browser wallets add `"signingType": "micheline"`
+/// and the `dAppUrl` that was part of the signed payload.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Signature {
     /// The signature value (hex for EVM, base58 for Solana).
@@ -206,6 +585,8 @@ pub struct Signature {
     /// The signer's public key, present for chains that include it
     /// alongside the signature (e.g., Solana).
     public_key: Option<String>,
+    /// The dApp URL of Tezos `micheline` signatures.
+    dapp_url: Option<String>,
 }
 
 impl Signature {
@@ -224,8 +605,25 @@ impl Signature {
         Self {
             value,
             public_key: Some(public_key),
+            dapp_url: None,
+        }
+    }
+
+    /// Creates a Tezos signature of the `micheline` signing type, made over
+    /// a payload naming `dapp_url`.
+    pub fn micheline(value: String, public_key: String, dapp_url: String) -> Self {
+        Self {
+            value,
+            public_key: Some(public_key),
+            dapp_url: Some(dapp_url),
         }
     }
+
+    /// Returns the dApp URL of Tezos `micheline` signatures, `None` for
+    /// signatures over the verification buffer itself.
+    pub fn dapp_url(&self) -> Option<&str> {
+        self.dapp_url.as_deref()
+    }
 }
 
 impl From<String> for Signature {
@@ -233,6 +631,7 @@ impl From<String> for Signature {
         Self {
             value,
             public_key: None,
+            dapp_url: None,
         }
     }
 }
@@ -246,9 +645,14 @@ impl Serialize for Signature {
             None => serializer.serialize_str(&self.value),
             Some(pk) => {
                 use serde::ser::SerializeStruct;
-                let mut state = serializer.serialize_struct("Signature", 2)?;
+                let len = if self.dapp_url.is_some() { 4 } else { 2 };
+                let mut state = serializer.serialize_struct("Signature", len)?;
                 state.serialize_field("signature", &self.value)?;
                 state.serialize_field("publicKey", pk)?;
+                if let Some(dapp_url) = &self.dapp_url {
+                    state.serialize_field("signingType", "micheline")?;
+                    state.serialize_field("dAppUrl", dapp_url)?;
+                }
                 state.end()
             }
         }
@@ -265,6 +669,10 @@ impl<'de> Deserialize<'de> for Signature {
             signature: String,
             #[serde(rename = "publicKey")]
             public_key: String,
+            #[serde(rename = "signingType", default)]
+            signing_type: Option<String>,
+            #[serde(rename = "dAppUrl", default)]
+            dapp_url: Option<String>,
         }
 
         #[derive(Deserialize)]
@@ -278,11 +686,26 @@ impl<'de> Deserialize<'de> for Signature {
             SigFormat::Plain(s) => Ok(Signature {
                 value: s,
                 public_key: None,
+                dapp_url: None,
             }),
-            SigFormat::Structured(s) => Ok(Signature {
-                value: s.signature,
-                public_key: Some(s.public_key),
-            }),
+            SigFormat::Structured(s) => {
+                let dapp_url = match s.signing_type.as_deref() {
+                    None | Some("raw") => None,
+                    Some("micheline") => Some(s.dapp_url.ok_or_else(|| {
+                        serde::de::Error::custom("micheline signatures require a dAppUrl")
+                    })?),
+                    Some(other) => {
+                        return Err(serde::de::Error::custom(format!(
+                            "unsupported signing type '{other}'"
+                        )));
+                    }
+                };
+                Ok(Signature {
+                    value: s.signature,
+                    public_key: Some(s.public_key),
+                    dapp_url,
+                })
+            }
         }
     }
 }
@@ -356,6 +779,103 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chain_from_str_round_trips_display() {
+        for chain in Chain::all() {
+            assert_eq!(chain.to_string().parse::<Chain>().unwrap(), *chain);
+            assert_eq!(chain.evm_chain_id().is_some(), chain.is_evm());
+        }
+        assert_eq!("eth".parse::<Chain>().unwrap(), Chain::Ethereum);
+        assert_eq!(
+            "ETHEREUM".parse::<Chain>(),
+            Err(UnknownChain("ETHEREUM".into()))
+        );
+        assert_eq!(Chain::Base.evm_chain_id(), Some(8453));
+        assert_eq!(Chain::Base.native_token(), "ETH");
+        assert_eq!(Chain::Sol.native_token(), "SOL");
+    }
+
+    #[test]
+    fn test_signature_scheme_of_chains_and_addresses() {
+        assert_eq!(
+            Chain::Base.signature_scheme(),
+            Some(SignatureScheme::Eip191)
+        );
+        assert_eq!(
+            Chain::Eclipse.signature_scheme(),
+            Some(SignatureScheme::Ed25519)
+        );
+        assert_eq!(Chain::Tezos.signature_scheme(), None);
+
+        let scheme = |address: &str| Address::from(address.to_string()).signature_scheme();
+        assert_eq!(
+            scheme("0xB68B9D4f3771c246233823ed1D3Add451055F9Ef"),
+            Some(SignatureScheme::Eip191)
+        );
+        assert_eq!(
+            scheme("5SwCeGqZ3EVhMDuBaiKsNdhAGNFmMN2HgeZpVdQJwvg5"),
+            Some(SignatureScheme::Ed25519)
+        );
+        assert_eq!(
+            scheme("15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"),
+            Some(SignatureScheme::Substrate)
+        );
+        assert_eq!(scheme("0xTEST"), None);
+        assert_eq!(
+            SignatureScheme::Ed25519.default_chain().signature_scheme(),
+            Some(SignatureScheme::Ed25519)
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "signature-evm", feature = "signature-sol"))]
+    fn test_parse_address_per_chain() {
+        let eth = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(Address::parse(&Chain::Ethereum, eth).is_ok());
+        assert!(Address::parse(&Chain::Base, &eth.to_ascii_lowercase()).is_ok());
+        assert!(matches!(
+            Address::parse(&Chain::Ethereum, "0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            Err(AddressError::Checksum { expected, .. }) if expected == eth
+        ));
+        assert!(Address::parse(&Chain::Ethereum, "0x5aAeb6053F3E94C9b9A09f3366943").is_err());
+
+        let sol = "5SwCeGqZ3EVhMDuBaiKsNdhAGNFmMN2HgeZpVdQJwvg5";
+        assert!(Address::parse(&Chain::Sol, sol).is_ok());
+        assert!(Address::parse(&Chain::Sol, eth).is_err());
+        assert!(Address::parse(&Chain::Ethereum, sol).is_err());
+
+        let tezos = "tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjb";
+        assert!(Address::parse(&Chain::Tezos, tezos).is_ok());
+        assert!(Address::parse(&Chain::Tezos, "KT1PWx2mnDueood7fEmfbBDKx1D9BAnnXitn").is_ok());
+        assert!(matches!(
+            Address::parse(&Chain::Tezos, "tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjc"),
+            Err(AddressError::Base58Check(_))
+        ));
+        assert!(Address::parse(&Chain::Tezos, sol).is_err());
+
+        assert!(Address::parse(&Chain::Nuls2, "NULSd6Hgb6Z").is_ok());
+        assert!(Address::parse(&Chain::Nuls2, "").is_err());
+    }
+
+    #[test]
+    fn test_address_family_and_comparison() {
+        let checksummed = address!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        let lower = address!("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+        assert_eq!(checksummed.chain_family(), Some(ChainFamily::Evm));
+        assert!(checksummed.same_as(&lower));
+        assert_eq!(checksummed.normalized(), lower.as_str());
+
+        let tezos = address!("tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjb");
+        assert_eq!(tezos.chain_family(), Some(ChainFamily::Tezos));
+        // Tezos prefix and length, but not Tezos base58check.
+        let not_tezos = address!("tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjc");
+        assert_eq!(not_tezos.chain_family(), Some(ChainFamily::Svm));
+        assert_eq!(tezos.signature_scheme(), None);
+        assert!(!tezos.same_as(&address!("TZ1VSUR8WWNHLAZEMPOCH5D6HLRITH8CJCJB")));
+        assert_eq!(Chain::Tezos.family(), Some(ChainFamily::Tezos));
+        assert_eq!(Chain::Neo.family(), None);
+    }
+
     #[test]
     fn test_signature_with_public_key() {
         let sig = Signature::with_public_key("5HH5Z".to_string(), "5SwCe".to_string());
@@ -366,4 +886,34 @@ mod tests {
         assert_eq!(json["signature"], "5HH5Z");
         assert_eq!(json["publicKey"], "5SwCe");
     }
+
+    #[test]
+    fn test_micheline_signature_round_trip() {
+        let json = serde_json::json!({
+            "signature": "edsig",
+            "publicKey": "edpk",
+            "signingType": "micheline",
+            "dAppUrl": "https://app.aleph.cloud",
+        });
+        let sig: Signature = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(sig.dapp_url(), Some("https://app.aleph.cloud"));
+        assert_eq!(serde_json::to_value(&sig).unwrap(), json);
+
+        let raw: Signature = serde_json::from_value(serde_json::json!({
+            "signature": "edsig",
+            "publicKey": "edpk",
+            "signingType": "raw",
+        }))
+        .unwrap();
+        assert_eq!(raw.dapp_url(), None);
+
+        assert!(
+            serde_json::from_value::<Signature>(serde_json::json!({
+                "signature": "edsig",
+                "publicKey": "edpk",
+                "signingType": "micheline",
+            }))
+            .is_err()
+        );
+    }
 }
@@ -9,6 +9,18 @@ impl From<String> for Channel {
     }
 }
 
+impl Channel {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 /// Macro for creating Channel instances from string literals.
 ///
 /// # Example
@@ -4,12 +4,18 @@
 pub use aleph_cid::cid;
 
 pub mod account;
+pub mod canonical_json;
 pub mod chain;
 pub mod channel;
 pub mod item_hash;
 pub mod memory_size;
 pub mod message;
+pub mod prelude;
 pub mod timestamp;
 pub(crate) mod toolkit;
-#[cfg(any(feature = "signature-evm", feature = "signature-sol"))]
+#[cfg(any(
+    feature = "signature-evm",
+    feature = "signature-sol",
+    feature = "signature-tezos"
+))]
 pub mod verify_signature;
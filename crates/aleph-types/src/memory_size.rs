@@ -1,3 +1,35 @@
+//! Memory and disk sizes.
+//!
+//! Messages carry sizes in the units of the [`memsizes`] crate (`MiB` for VM
+//! resources, `Bytes` for stored files). [`MemorySize`] is a byte count on top
+//! of them that reads and prints the human forms users type in CLI flags and
+//! configuration files:
+//!
+//! ```
+//! use aleph_types::memory_size::MemorySize;
+//!
+//! let size: MemorySize = "2GiB".parse().unwrap();
+//! assert_eq!(size.to_string(), "2 GiB");
+//! assert_eq!((size * 2 - MemorySize::mib(512)).to_string(), "3.5 GiB");
+//! assert_eq!(size.to_mib().count(), 2048);
+//! ```
+//!
+//! `memsizes` stops at gibibytes; [`TiB`] and [`PiB`] cover the sizes of
+//! storage accounting, and [`AnySize`] keeps a size in whichever unit reads
+//! best.
+
+use memsizes::{Bytes, GiB, KiB, MiB};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+const KIB: u64 = 1 << 10;
+const MIB: u64 = 1 << 20;
+const GIB: u64 = 1 << 30;
+const TIB: u64 = 1 << 40;
+const PIB: u64 = 1 << 50;
+
 /// Convert Gigabytes to Mebibytes (the unit used for VM volumes).
 /// Rounds up to ensure that data of a given size will fit in the space allocated.
 pub const fn gigabyte_to_mebibyte(gb: u64) -> u64 {
@@ -8,6 +40,374 @@ pub const fn gigabyte_to_mebibyte(gb: u64) -> u64 {
     result.ceil() as u64
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MemorySizeError {
+    #[error("missing unit in size '{0}' (use e.g. 20GB, 1024MB, 1TiB)")]
+    MissingUnit(String),
+    #[error("invalid number in size '{0}'")]
+    InvalidNumber(String),
+    #[error("unknown size unit '{0}' (use B, KB, MB, GB, TB, PB, KiB, MiB, GiB, TiB, PiB)")]
+    UnknownUnit(String),
+    #[error("size '{0}' does not fit in 64 bits")]
+    Overflow(String),
+}
+
+/// A size in bytes.
+///
+/// Displays in the largest binary unit it reaches (`512 MiB`, `1.5 GiB`) and
+/// parses both binary (`KiB` to `PiB`) and decimal (`KB` to `PB`) units,
+/// case-insensitively. A unit is required: a bare number is too ambiguous to
+/// accept.
+///
+/// Arithmetic saturates at zero and at `u64::MAX` bytes rather than
+/// overflowing; the `checked_*` methods report overflows instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemorySize(u64);
+
+impl MemorySize {
+    pub const fn from_bytes(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn kib(kib: u64) -> Self {
+        Self(kib.saturating_mul(KIB))
+    }
+
+    pub const fn mib(mib: u64) -> Self {
+        Self(mib.saturating_mul(MIB))
+    }
+
+    pub const fn gib(gib: u64) -> Self {
+        Self(gib.saturating_mul(GIB))
+    }
+
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(bytes) => Some(Self(bytes)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(bytes) => Some(Self(bytes)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_mul(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_mul(rhs) {
+            Some(bytes) => Some(Self(bytes)),
+            None => None,
+        }
+    }
+
+    pub const fn bytes(&self) -> u64 {
+        self.0
+    }
+
+    /// The size in mebibytes, rounded up so that the data still fits.
+    pub fn to_mib(&self) -> MiB {
+        MiB::from(self.0.div_ceil(MIB))
+    }
+
+    /// The size in gibibytes, rounded up so that the data still fits.
+    pub fn to_gib(&self) -> GiB {
+        GiB::from(self.0.div_ceil(GIB))
+    }
+}
+
+impl fmt::Display for MemorySize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (unit_size, unit) = [
+            (PIB, "PiB"),
+            (TIB, "TiB"),
+            (GIB, "GiB"),
+            (MIB, "MiB"),
+            (KIB, "KiB"),
+        ]
+        .into_iter()
+        .find(|(unit_size, _)| self.0 >= *unit_size)
+        .unwrap_or((1, "B"));
+        if self.0.is_multiple_of(unit_size) {
+            return write!(f, "{} {unit}", self.0 / unit_size);
+        }
+        let value = format!("{:.2}", self.0 as f64 / unit_size as f64);
+        let value = value.trim_end_matches('0').trim_end_matches('.');
+        write!(f, "{value} {unit}")
+    }
+}
+
+impl FromStr for MemorySize {
+    type Err = MemorySizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, _, unit_size) = split_unit(s)?;
+        let value: f64 = number
+            .parse()
+            .ok()
+            .filter(|value: &f64| value.is_finite() && *value >= 0.0)
+            .ok_or_else(|| MemorySizeError::InvalidNumber(s.to_string()))?;
+
+        let bytes = (value * unit_size as f64).round();
+        if bytes >= u64::MAX as f64 {
+            return Err(MemorySizeError::Overflow(s.to_string()));
+        }
+        Ok(Self(bytes as u64))
+    }
+}
+
+/// Splits a size into its number, its unit and the size of the unit in bytes.
+fn split_unit(s: &str) -> Result<(&str, &str, u64), MemorySizeError> {
+    let unit_start = s
+        .find(|c: char| c.is_alphabetic())
+        .ok_or_else(|| MemorySizeError::MissingUnit(s.to_string()))?;
+    let (number, unit) = s.split_at(unit_start);
+    let unit_size = match unit.to_ascii_lowercase().as_str() {
+        "b" => 1,
+        "kib" => KIB,
+        "mib" => MIB,
+        "gib" => GIB,
+        "tib" => TIB,
+        "pib" => PIB,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "pb" => 1_000_000_000_000_000,
+        _ => return Err(MemorySizeError::UnknownUnit(unit.to_string())),
+    };
+    Ok((number.trim(), unit, unit_size))
+}
+
+impl Add for MemorySize {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for MemorySize {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+/// Adds a number of bytes.
+impl Add<u64> for MemorySize {
+    type Output = Self;
+
+    fn add(self, rhs: u64) -> Self {
+        Self(self.0.saturating_add(rhs))
+    }
+}
+
+/// Subtracts a number of bytes.
+impl Sub<u64> for MemorySize {
+    type Output = Self;
+
+    fn sub(self, rhs: u64) -> Self {
+        Self(self.0.saturating_sub(rhs))
+    }
+}
+
+impl Mul<u64> for MemorySize {
+    type Output = Self;
+
+    fn mul(self, rhs: u64) -> Self {
+        Self(self.0.saturating_mul(rhs))
+    }
+}
+
+impl From<Bytes> for MemorySize {
+    fn from(size: Bytes) -> Self {
+        Self(size.count())
+    }
+}
+
+impl From<KiB> for MemorySize {
+    fn from(size: KiB) -> Self {
+        Self::kib(size.count())
+    }
+}
+
+impl From<MiB> for MemorySize {
+    fn from(size: MiB) -> Self {
+        Self::mib(size.count())
+    }
+}
+
+impl From<GiB> for MemorySize {
+    fn from(size: GiB) -> Self {
+        Self::gib(size.count())
+    }
+}
+
+impl From<MemorySize> for Bytes {
+    fn from(size: MemorySize) -> Self {
+        Bytes::from(size.0)
+    }
+}
+
+/// Tebibytes, for the sizes `memsizes` has no unit for.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct TiB(u64);
+
+/// Pebibytes, for the sizes `memsizes` has no unit for.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct PiB(u64);
+
+macro_rules! large_unit {
+    ($unit:ident, $unit_size:expr) => {
+        impl $unit {
+            pub const fn count(&self) -> u64 {
+                self.0
+            }
+        }
+
+        impl From<u64> for $unit {
+            fn from(count: u64) -> Self {
+                Self(count)
+            }
+        }
+
+        impl From<$unit> for MemorySize {
+            /// Saturates at `u64::MAX` bytes.
+            fn from(size: $unit) -> Self {
+                Self(size.0.saturating_mul($unit_size))
+            }
+        }
+    };
+}
+
+large_unit!(TiB, TIB);
+large_unit!(PiB, PIB);
+
+/// A size expressed in a unit picked at runtime.
+///
+/// [`AnySize::best_fit`] picks the biggest unit the size is a whole multiple
+/// of, so `1536 MiB` stays `1536 MiB` rather than becoming `1.5 GiB`. Displays
+/// and (de)serializes as `"<count> <unit>"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnySize {
+    Bytes(u64),
+    KiB(u64),
+    MiB(u64),
+    GiB(u64),
+    TiB(u64),
+    PiB(u64),
+}
+
+impl AnySize {
+    /// Expresses `size` in the biggest unit that divides it exactly.
+    pub fn best_fit(size: impl Into<MemorySize>) -> Self {
+        let bytes = size.into().bytes();
+        let exact = |unit_size: u64| bytes != 0 && bytes.is_multiple_of(unit_size);
+        if exact(PIB) {
+            AnySize::PiB(bytes / PIB)
+        } else if exact(TIB) {
+            AnySize::TiB(bytes / TIB)
+        } else if exact(GIB) {
+            AnySize::GiB(bytes / GIB)
+        } else if exact(MIB) {
+            AnySize::MiB(bytes / MIB)
+        } else if exact(KIB) {
+            AnySize::KiB(bytes / KIB)
+        } else {
+            AnySize::Bytes(bytes)
+        }
+    }
+
+    fn parts(&self) -> (u64, u64, &'static str) {
+        match *self {
+            AnySize::Bytes(count) => (count, 1, "B"),
+            AnySize::KiB(count) => (count, KIB, "KiB"),
+            AnySize::MiB(count) => (count, MIB, "MiB"),
+            AnySize::GiB(count) => (count, GIB, "GiB"),
+            AnySize::TiB(count) => (count, TIB, "TiB"),
+            AnySize::PiB(count) => (count, PIB, "PiB"),
+        }
+    }
+
+    /// Number of units, e.g. `4` for `4 GiB`.
+    pub fn count(&self) -> u64 {
+        self.parts().0
+    }
+
+    /// The size in bytes, or `None` if it does not fit in 64 bits.
+    pub fn to_memory_size(&self) -> Option<MemorySize> {
+        let (count, unit_size, _) = self.parts();
+        count.checked_mul(unit_size).map(MemorySize)
+    }
+}
+
+impl fmt::Display for AnySize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (count, _, unit) = self.parts();
+        write!(f, "{count} {unit}")
+    }
+}
+
+/// Parses a whole number of binary units, e.g. `4 GiB` or `512MiB`.
+impl FromStr for AnySize {
+    type Err = MemorySizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (count, unit, unit_size) = split_unit(s)?;
+        let count: u64 = count
+            .parse()
+            .map_err(|_| MemorySizeError::InvalidNumber(s.to_string()))?;
+        let size = match unit_size {
+            1 => AnySize::Bytes(count),
+            KIB => AnySize::KiB(count),
+            MIB => AnySize::MiB(count),
+            GIB => AnySize::GiB(count),
+            TIB => AnySize::TiB(count),
+            PIB => AnySize::PiB(count),
+            // Decimal units have no variant.
+            _ => return Err(MemorySizeError::UnknownUnit(unit.to_string())),
+        };
+        Ok(size)
+    }
+}
+
+impl From<AnySize> for MemorySize {
+    /// Saturates at `u64::MAX` bytes.
+    fn from(size: AnySize) -> Self {
+        size.to_memory_size().unwrap_or(MemorySize(u64::MAX))
+    }
+}
+
+impl Serialize for AnySize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for AnySize {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,4 +417,110 @@ mod tests {
         let mib = gigabyte_to_mebibyte(20);
         assert_eq!(mib, 19074);
     }
+
+    #[test]
+    fn display_picks_the_largest_binary_unit() {
+        assert_eq!(MemorySize::gib(4).to_string(), "4 GiB");
+        assert_eq!(MemorySize::mib(512).to_string(), "512 MiB");
+        assert_eq!(MemorySize::mib(1536).to_string(), "1.5 GiB");
+        assert_eq!(MemorySize::from_bytes(1000).to_string(), "1000 B");
+        assert_eq!(MemorySize::from_bytes(0).to_string(), "0 B");
+    }
+
+    #[test]
+    fn parse_binary_and_decimal_units() {
+        assert_eq!("2GiB".parse::<MemorySize>().unwrap(), MemorySize::gib(2));
+        assert_eq!(
+            "512 mib".parse::<MemorySize>().unwrap(),
+            MemorySize::mib(512)
+        );
+        assert_eq!(
+            "500MB".parse::<MemorySize>().unwrap(),
+            MemorySize::from_bytes(500_000_000)
+        );
+        assert_eq!(
+            "1.5KiB".parse::<MemorySize>().unwrap(),
+            MemorySize::from_bytes(1536)
+        );
+
+        assert!(matches!(
+            "1024".parse::<MemorySize>(),
+            Err(MemorySizeError::MissingUnit(_))
+        ));
+        assert!(matches!(
+            "1XB".parse::<MemorySize>(),
+            Err(MemorySizeError::UnknownUnit(_))
+        ));
+        assert!(matches!(
+            "-1GiB".parse::<MemorySize>(),
+            Err(MemorySizeError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn arithmetic_and_unit_conversions() {
+        let size = MemorySize::gib(1) + MemorySize::mib(512) - MemorySize::mib(256);
+        assert_eq!(size * 2, MemorySize::mib(2560));
+        assert_eq!(MemorySize::mib(1) + 1, MemorySize::from_bytes(MIB + 1));
+        assert_eq!((MemorySize::mib(1) + 1).to_mib().count(), 2);
+        assert_eq!(MemorySize::from(MiB::from(128)), MemorySize::mib(128));
+        assert_eq!(Bytes::from(MemorySize::kib(1)), Bytes::from(1024));
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        let max = MemorySize::from_bytes(u64::MAX);
+        assert_eq!(
+            MemorySize::mib(1) - MemorySize::gib(1),
+            MemorySize::default()
+        );
+        assert_eq!(MemorySize::from_bytes(1) - 2, MemorySize::default());
+        assert_eq!(max + MemorySize::kib(1), max);
+        assert_eq!(max * 2, max);
+        assert_eq!(MemorySize::gib(u64::MAX), max);
+        assert_eq!(MemorySize::from(PiB::from(u64::MAX)), max);
+
+        assert_eq!(MemorySize::mib(1).checked_sub(MemorySize::gib(1)), None);
+        assert_eq!(max.checked_add(MemorySize::from_bytes(1)), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(
+            MemorySize::mib(1).checked_mul(1024),
+            Some(MemorySize::gib(1))
+        );
+    }
+
+    #[test]
+    fn large_units() {
+        assert_eq!(MemorySize::from(TiB::from(2)), MemorySize::gib(2048));
+        assert_eq!(MemorySize::from(PiB::from(1)).to_string(), "1 PiB");
+        assert_eq!(
+            "1PB".parse::<MemorySize>().unwrap(),
+            MemorySize::from_bytes(1_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn any_size_best_fit() {
+        assert_eq!(AnySize::best_fit(MemorySize::gib(4)), AnySize::GiB(4));
+        assert_eq!(AnySize::best_fit(MemorySize::mib(1536)), AnySize::MiB(1536));
+        assert_eq!(AnySize::best_fit(TiB::from(1024)), AnySize::PiB(1));
+        assert_eq!(
+            AnySize::best_fit(MemorySize::from_bytes(1000)),
+            AnySize::Bytes(1000)
+        );
+        assert_eq!(AnySize::best_fit(MemorySize::default()), AnySize::Bytes(0));
+        assert_eq!(AnySize::PiB(u64::MAX).to_memory_size(), None);
+    }
+
+    #[test]
+    fn any_size_serde_round_trip() {
+        for size in [AnySize::Bytes(7), AnySize::MiB(512), AnySize::TiB(3)] {
+            let json = serde_json::to_string(&size).unwrap();
+            assert_eq!(json, format!("\"{size}\""));
+            assert_eq!(serde_json::from_str::<AnySize>(&json).unwrap(), size);
+        }
+        assert_eq!("512MiB".parse::<AnySize>().unwrap(), AnySize::MiB(512));
+        assert!("1.5 GiB".parse::<AnySize>().is_err());
+        assert!("1 GB".parse::<AnySize>().is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -33,6 +34,27 @@ impl AggregateContent {
     pub fn key(&self) -> &str {
         self.key.key()
     }
+
+    /// Deserializes the whole content into `T`.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(serde_json::Value::Object(self.content.clone()))
+    }
+
+    /// The value of one key of the content.
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.content.get(key)
+    }
+
+    /// Deserializes the value of one key of the content into `T`, or returns
+    /// `None` if the key is absent.
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<Result<T, serde_json::Error>> {
+        self.content.get(key).map(T::deserialize)
+    }
+
+    /// The keys of the content.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.content.keys().map(String::as_str)
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +117,26 @@ mod tests {
         assert_eq!(aggregate_content.key(), "corechannel");
         assert!(aggregate_content.content.contains_key("nodes"));
         assert!(aggregate_content.content.contains_key("resource_nodes"));
+        assert!(aggregate_content.keys().any(|key| key == "nodes"));
+        assert!(aggregate_content.get("nodes").unwrap().is_array());
+        assert!(aggregate_content.get("missing").is_none());
+
+        #[derive(serde::Deserialize)]
+        struct Node {
+            hash: String,
+        }
+        #[derive(serde::Deserialize)]
+        struct CoreChannel {
+            nodes: Vec<Node>,
+        }
+        let corechannel = aggregate_content.parse::<CoreChannel>().unwrap();
+        let nodes = aggregate_content
+            .get_as::<Vec<Node>>("nodes")
+            .unwrap()
+            .unwrap();
+        assert_eq!(corechannel.nodes.len(), nodes.len());
+        assert_eq!(corechannel.nodes[0].hash, nodes[0].hash);
+        assert!(aggregate_content.get_as::<u64>("nodes").unwrap().is_err());
 
         // No confirmation on this fixture
         assert!(!message.confirmed());
@@ -4,12 +4,14 @@ use crate::item_hash::{AlephItemHash, ItemHash};
 use crate::message::aggregate::AggregateContent;
 use crate::message::forget::ForgetContent;
 use crate::message::instance::InstanceContent;
+use crate::message::item_type::ItemType;
 use crate::message::post::PostContent;
 use crate::message::program::ProgramContent;
 use crate::message::store::StoreContent;
 use crate::timestamp::Timestamp;
 use serde::de::{self, Deserializer};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt::Formatter;
 use thiserror::Error;
 
@@ -88,6 +90,62 @@ pub enum MessageContentEnum {
     Store(StoreContent),
 }
 
+/// A content type of one message type, extractable from [`MessageContentEnum`].
+pub trait TypedContent {
+    /// The message type carrying this content.
+    const MESSAGE_TYPE: MessageType;
+
+    /// Returns the content if it is of this type.
+    fn from_content(content: &MessageContentEnum) -> Option<&Self>;
+
+    /// Takes the content if it is of this type, or gives it back.
+    fn try_from_content(content: MessageContentEnum) -> Result<Self, Box<MessageContentEnum>>
+    where
+        Self: Sized;
+}
+
+macro_rules! impl_typed_content {
+    ($($variant:ident => $content:ty),* $(,)?) => {
+        $(
+            impl TypedContent for $content {
+                const MESSAGE_TYPE: MessageType = MessageType::$variant;
+
+                fn from_content(content: &MessageContentEnum) -> Option<&Self> {
+                    match content {
+                        MessageContentEnum::$variant(c) => Some(c),
+                        _ => None,
+                    }
+                }
+
+                fn try_from_content(
+                    content: MessageContentEnum,
+                ) -> Result<Self, Box<MessageContentEnum>> {
+                    match content {
+                        MessageContentEnum::$variant(c) => Ok(c),
+                        other => Err(Box::new(other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_typed_content! {
+    Aggregate => AggregateContent,
+    Forget => ForgetContent,
+    Instance => InstanceContent,
+    Post => PostContent,
+    Program => ProgramContent,
+    Store => StoreContent,
+}
+
+impl MessageContentEnum {
+    /// Returns the content as `T` if it is of that type.
+    pub fn as_typed<T: TypedContent>(&self) -> Option<&T> {
+        T::from_content(self)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MessageContent {
     pub address: Address,
@@ -104,37 +162,223 @@ impl MessageContent {
         message_type: MessageType,
         raw: &[u8],
     ) -> Result<Self, serde_json::Error> {
-        let value: serde_json::Value = serde_json::from_slice(raw)?;
-        Self::from_json_value(message_type, &value)
+        let mut deserializer = serde_json::Deserializer::from_slice(raw);
+        let content = Self::deserialize_typed(message_type, &mut deserializer)?;
+        deserializer.end()?;
+        Ok(content)
     }
 
-    fn from_json_value(
+    /// Deserializes message content of a known type into the matching variant.
+    ///
+    /// The fields are read in one pass by [`TypedMessageContent`], without buffering.
+    pub fn deserialize_typed<'de, D>(
         message_type: MessageType,
-        value: &serde_json::Value,
-    ) -> Result<Self, serde_json::Error> {
-        let address = Address::deserialize(&value["address"])?;
-        let time = Timestamp::deserialize(&value["time"])?;
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn typed<'de, C, D>(
+            deserializer: D,
+            variant: fn(C) -> MessageContentEnum,
+        ) -> Result<MessageContent, D::Error>
+        where
+            C: Deserialize<'de>,
+            D: Deserializer<'de>,
+        {
+            let TypedMessageContent {
+                address,
+                time,
+                content,
+            } = TypedMessageContent::<C>::deserialize(deserializer)?;
+            Ok(MessageContent {
+                address,
+                time,
+                content: variant(content),
+            })
+        }
+
+        match message_type {
+            MessageType::Aggregate => typed(deserializer, MessageContentEnum::Aggregate),
+            MessageType::Forget => typed(deserializer, MessageContentEnum::Forget),
+            MessageType::Instance => typed(deserializer, MessageContentEnum::Instance),
+            MessageType::Post => typed(deserializer, MessageContentEnum::Post),
+            MessageType::Program => typed(deserializer, MessageContentEnum::Program),
+            MessageType::Store => typed(deserializer, MessageContentEnum::Store),
+        }
+    }
+}
+
+/// Message content whose fields, other than `address` and `time`, are deserialized as `C`.
+///
+/// Unlike `#[serde(flatten)]`, the fields are handed to `C` as they are read: nothing is
+/// buffered, so `&'de str` and `#[serde(borrow)] Cow<'de, str>` fields of `C` borrow from
+/// the input. Parse `item_content` with it to read messages without copying their strings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedMessageContent<C> {
+    pub address: Address,
+    pub time: Timestamp,
+    pub content: C,
+}
 
-        let variant = match message_type {
-            MessageType::Aggregate => {
-                MessageContentEnum::Aggregate(AggregateContent::deserialize(value)?)
+impl<'de, C> Deserialize<'de> for TypedMessageContent<C>
+where
+    C: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(TypedContentVisitor(std::marker::PhantomData))
+    }
+}
+
+struct TypedContentVisitor<C>(std::marker::PhantomData<C>);
+
+impl<'de, C> de::Visitor<'de> for TypedContentVisitor<C>
+where
+    C: Deserialize<'de>,
+{
+    type Value = TypedMessageContent<C>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("message content")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut fields = ContentFields {
+            map,
+            address: None,
+            time: None,
+        };
+        let content = C::deserialize(de::value::MapAccessDeserializer::new(&mut fields))?;
+        // `C` may stop reading before the end of the map: `address` and `time` can follow.
+        while de::MapAccess::next_key::<de::IgnoredAny>(&mut fields)?.is_some() {
+            de::MapAccess::next_value::<de::IgnoredAny>(&mut fields)?;
+        }
+        Ok(TypedMessageContent {
+            address: fields
+                .address
+                .ok_or_else(|| de::Error::missing_field("address"))?,
+            time: fields
+                .time
+                .ok_or_else(|| de::Error::missing_field("time"))?,
+            content,
+        })
+    }
+}
+
+/// The fields of message content as seen by the content type: `address` and `time` are
+/// taken out as they go by, every other entry is forwarded untouched.
+struct ContentFields<A> {
+    map: A,
+    address: Option<Address>,
+    time: Option<Timestamp>,
+}
+
+impl<'de, A> de::MapAccess<'de> for ContentFields<A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        while let Some(key) = self.map.next_key::<ContentKey<'de>>()? {
+            match key {
+                ContentKey::Address => {
+                    if self.address.is_some() {
+                        return Err(de::Error::duplicate_field("address"));
+                    }
+                    self.address = Some(self.map.next_value()?);
+                }
+                ContentKey::Time => {
+                    if self.time.is_some() {
+                        return Err(de::Error::duplicate_field("time"));
+                    }
+                    self.time = Some(self.map.next_value()?);
+                }
+                ContentKey::Other(Cow::Borrowed(name)) => {
+                    return seed
+                        .deserialize(de::value::BorrowedStrDeserializer::new(name))
+                        .map(Some);
+                }
+                ContentKey::Other(Cow::Owned(name)) => {
+                    return seed
+                        .deserialize(de::value::StringDeserializer::new(name))
+                        .map(Some);
+                }
             }
-            MessageType::Forget => MessageContentEnum::Forget(ForgetContent::deserialize(value)?),
-            MessageType::Instance => {
-                MessageContentEnum::Instance(InstanceContent::deserialize(value)?)
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// A key of message content, borrowed from the input when the format allows it.
+enum ContentKey<'de> {
+    Address,
+    Time,
+    Other(Cow<'de, str>),
+}
+
+impl<'de> Deserialize<'de> for ContentKey<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct KeyVisitor;
+
+        impl<'de> de::Visitor<'de> for KeyVisitor {
+            type Value = ContentKey<'de>;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("a field name")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(match v {
+                    "address" => ContentKey::Address,
+                    "time" => ContentKey::Time,
+                    other => ContentKey::Other(Cow::Borrowed(other)),
+                })
             }
-            MessageType::Post => MessageContentEnum::Post(PostContent::deserialize(value)?),
-            MessageType::Program => {
-                MessageContentEnum::Program(ProgramContent::deserialize(value)?)
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(match v {
+                    "address" => ContentKey::Address,
+                    "time" => ContentKey::Time,
+                    other => ContentKey::Other(Cow::Owned(other.to_string())),
+                })
             }
-            MessageType::Store => MessageContentEnum::Store(StoreContent::deserialize(value)?),
-        };
+        }
 
-        Ok(MessageContent {
-            address,
-            time,
-            content: variant,
-        })
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+/// Deserializes the content of a message whose type is already known.
+struct ContentSeed(MessageType);
+
+impl<'de> de::DeserializeSeed<'de> for ContentSeed {
+    type Value = MessageContent;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        MessageContent::deserialize_typed(self.0, deserializer)
     }
 }
 
@@ -165,14 +409,15 @@ impl<'de> Deserialize<'de> for ContentSource {
         D: Deserializer<'de>,
     {
         #[derive(Deserialize)]
-        struct ContentSourceRaw {
-            item_type: String,
+        struct ContentSourceRaw<'a> {
+            #[serde(borrow)]
+            item_type: Cow<'a, str>,
             item_content: Option<String>,
         }
 
         let raw = ContentSourceRaw::deserialize(deserializer)?;
 
-        match raw.item_type.as_str() {
+        match raw.item_type.as_ref() {
             "inline" => {
                 let item_content = raw
                     .item_content
@@ -261,12 +506,26 @@ impl MessageHeader {
         }
     }
 
+    /// The exact bytes the sender signed, see [`Message::verification_buffer`].
+    pub fn verification_buffer(&self) -> String {
+        crate::message::unsigned::verification_buffer(
+            &self.chain,
+            &self.sender,
+            self.message_type,
+            &self.item_hash,
+        )
+    }
+
     /// Verifies that the message signature was produced by the sender.
     ///
     /// Signature verification only depends on header fields (chain, sender,
     /// signature, message_type, item_hash), so it can run before content is
     /// downloaded or deserialized.
-    #[cfg(any(feature = "signature-evm", feature = "signature-sol"))]
+    #[cfg(any(
+        feature = "signature-evm",
+        feature = "signature-sol",
+        feature = "signature-tezos"
+    ))]
     pub fn verify_signature(
         &self,
     ) -> Result<(), crate::verify_signature::SignatureVerificationError> {
@@ -280,6 +539,7 @@ impl MessageHeader {
             signature,
             self.message_type,
             &self.item_hash,
+            &self.time,
         )
     }
 }
@@ -334,6 +594,11 @@ impl Message {
         &self.content.content
     }
 
+    /// Returns the content as `T` if the message is of the matching type.
+    pub fn typed_content<T: TypedContent>(&self) -> Option<&T> {
+        self.content().as_typed()
+    }
+
     pub fn confirmed(&self) -> bool {
         !self.confirmations.is_empty()
     }
@@ -366,8 +631,8 @@ impl Message {
     /// Verifies that the item hash of an inline message matches its content.
     ///
     /// For inline messages, the item hash is the SHA-256 hash of the `item_content` string.
-    /// For non-inline messages (storage/ipfs), use the client's `verify_message()` method
-    /// instead, which downloads the raw content from `/storage/raw/` for verification.
+    /// For non-inline messages (storage/ipfs), use the SDK's `verify_item_hash_resolved()` or
+    /// `verify_message()` instead, which download the raw content from `/storage/raw/`.
     pub fn verify_item_hash(&self) -> Result<(), MessageVerificationError> {
         match self.content_source.verify_inline_hash(&self.item_hash) {
             Some(Ok(())) => Ok(()),
@@ -378,13 +643,29 @@ impl Message {
         }
     }
 
+    /// The exact bytes the sender signed: chain, sender, message type and
+    /// item hash, one per line. The chain-specific signing scheme (EIP-191
+    /// for EVM chains, raw Ed25519 for SVM chains) applies on top of it.
+    pub fn verification_buffer(&self) -> String {
+        crate::message::unsigned::verification_buffer(
+            &self.chain,
+            &self.sender,
+            self.message_type,
+            &self.item_hash,
+        )
+    }
+
     /// Verifies that the message signature was produced by the sender.
     ///
     /// Constructs the verification buffer from the message fields, then
     /// dispatches to the chain-specific verification algorithm. Currently
-    /// supports EVM-compatible chains (Ethereum, Arbitrum, etc.) and SVM
-    /// chains (Solana, Eclipse).
-    #[cfg(any(feature = "signature-evm", feature = "signature-sol"))]
+    /// supports EVM-compatible chains (Ethereum, Arbitrum, etc.), SVM
+    /// chains (Solana, Eclipse) and Tezos.
+    #[cfg(any(
+        feature = "signature-evm",
+        feature = "signature-sol",
+        feature = "signature-tezos"
+    ))]
     pub fn verify_signature(
         &self,
     ) -> Result<(), crate::verify_signature::SignatureVerificationError> {
@@ -398,42 +679,116 @@ impl Message {
             signature,
             self.message_type,
             &self.item_hash,
+            &self.time,
         )
     }
 }
 
-/// Shared helper struct for deserializing message header fields.
-/// Used by both `Message` and `MessageHeader` Deserialize impls.
 #[derive(Deserialize)]
-struct MessageHeaderRaw {
-    chain: Chain,
-    sender: Address,
-    signature: Option<Signature>,
-    #[serde(flatten)]
-    content_source: ContentSource,
-    item_hash: ItemHash,
-    #[serde(default)]
-    confirmations: Option<Vec<MessageConfirmation>>,
-    time: Timestamp,
-    #[serde(default)]
-    channel: Option<Channel>,
+#[serde(field_identifier, rename_all = "snake_case")]
+enum MessageField {
+    Chain,
+    Sender,
+    Signature,
+    ItemType,
+    ItemContent,
+    ItemHash,
+    Confirmations,
+    Time,
+    Channel,
     #[serde(rename = "type")]
-    message_type: MessageType,
+    Type,
+    Content,
+    #[serde(other)]
+    Other,
 }
 
-impl MessageHeaderRaw {
-    fn into_header(self) -> MessageHeader {
-        MessageHeader {
-            chain: self.chain,
-            sender: self.sender,
-            signature: self.signature,
-            content_source: self.content_source,
-            item_hash: self.item_hash,
-            confirmations: self.confirmations.unwrap_or_default(),
-            time: self.time,
-            channel: self.channel,
-            message_type: self.message_type,
+/// Single-pass visitor shared by the `Message` and `MessageHeader` Deserialize impls.
+///
+/// CCN responses list `type` before `content`, so the content can usually be handed to
+/// [`MessageContent::deserialize_typed`] as it is read. When it comes first it is kept
+/// as a `serde_json::Value` until the type is known. Headers skip the content altogether.
+struct MessageVisitor {
+    with_content: bool,
+}
+
+impl<'de> de::Visitor<'de> for MessageVisitor {
+    type Value = (MessageHeader, Option<MessageContent>);
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("an aleph message")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut chain = None;
+        let mut sender = None;
+        let mut signature = None;
+        let mut item_type = None;
+        let mut item_content = None;
+        let mut item_hash = None;
+        let mut confirmations = None;
+        let mut time = None;
+        let mut channel = None;
+        let mut message_type = None;
+        let mut content = None;
+        let mut buffered_content = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                MessageField::Chain => chain = Some(map.next_value()?),
+                MessageField::Sender => sender = Some(map.next_value()?),
+                MessageField::Signature => signature = map.next_value()?,
+                MessageField::ItemType => item_type = Some(map.next_value::<ItemType>()?),
+                MessageField::ItemContent => item_content = map.next_value()?,
+                MessageField::ItemHash => item_hash = Some(map.next_value()?),
+                MessageField::Confirmations => confirmations = map.next_value()?,
+                MessageField::Time => time = Some(map.next_value()?),
+                MessageField::Channel => channel = map.next_value()?,
+                MessageField::Type => message_type = Some(map.next_value()?),
+                MessageField::Content if self.with_content => match message_type {
+                    Some(message_type) => {
+                        content = Some(map.next_value_seed(ContentSeed(message_type))?)
+                    }
+                    None => buffered_content = Some(map.next_value::<serde_json::Value>()?),
+                },
+                MessageField::Content | MessageField::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+
+        let content_source = match item_type.ok_or_else(|| de::Error::missing_field("item_type"))? {
+            ItemType::Inline => ContentSource::Inline {
+                item_content: item_content
+                    .ok_or_else(|| de::Error::missing_field("item_content"))?,
+            },
+            ItemType::Storage => ContentSource::Storage,
+            ItemType::Ipfs => ContentSource::Ipfs,
+        };
+        let message_type: MessageType =
+            message_type.ok_or_else(|| de::Error::missing_field("type"))?;
+        if let Some(value) = buffered_content {
+            content = Some(
+                MessageContent::deserialize_typed(message_type, value)
+                    .map_err(de::Error::custom)?,
+            );
         }
+
+        let header = MessageHeader {
+            chain: chain.ok_or_else(|| de::Error::missing_field("chain"))?,
+            sender: sender.ok_or_else(|| de::Error::missing_field("sender"))?,
+            signature,
+            content_source,
+            item_hash: item_hash.ok_or_else(|| de::Error::missing_field("item_hash"))?,
+            confirmations: confirmations.unwrap_or_default(),
+            time: time.ok_or_else(|| de::Error::missing_field("time"))?,
+            channel,
+            message_type,
+        };
+        Ok((header, content))
     }
 }
 
@@ -442,29 +797,23 @@ impl<'de> Deserialize<'de> for MessageHeader {
     where
         D: Deserializer<'de>,
     {
-        MessageHeaderRaw::deserialize(deserializer).map(MessageHeaderRaw::into_header)
+        let (header, _) = deserializer.deserialize_map(MessageVisitor {
+            with_content: false,
+        })?;
+        Ok(header)
     }
 }
 
-// Custom deserializer that uses message_type to efficiently deserialize content
+// Custom deserializer that uses message_type to pick the content variant
 impl<'de> Deserialize<'de> for Message {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        struct MessageRaw {
-            #[serde(flatten)]
-            header: MessageHeaderRaw,
-            content: serde_json::Value,
-        }
-
-        let raw = MessageRaw::deserialize(deserializer)?;
-
-        let content = MessageContent::from_json_value(raw.header.message_type, &raw.content)
-            .map_err(de::Error::custom)?;
-
-        Ok(raw.header.into_header().with_content(content))
+        let (header, content) =
+            deserializer.deserialize_map(MessageVisitor { with_content: true })?;
+        let content = content.ok_or_else(|| de::Error::missing_field("content"))?;
+        Ok(header.with_content(content))
     }
 }
 
@@ -544,6 +893,19 @@ mod tests {
         assert_matches!(content_source, ContentSource::Ipfs);
     }
 
+    #[test]
+    fn test_verification_buffer_of_fixture() {
+        let json = include_str!("../../../../fixtures/messages/post/post.json");
+        let message: Message = serde_json::from_str(json).unwrap();
+        let header: MessageHeader = serde_json::from_str(json).unwrap();
+        let expected = "ETH\n\
+             0xB68B9D4f3771c246233823ed1D3Add451055F9Ef\n\
+             POST\n\
+             d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c";
+        assert_eq!(message.verification_buffer(), expected);
+        assert_eq!(header.verification_buffer(), expected);
+    }
+
     #[test]
     fn test_verify_inline_message_item_hash() {
         let json = include_str!("../../../../fixtures/messages/post/post.json");
@@ -670,6 +1032,66 @@ mod tests {
         }
     }
 
+    /// Content is deserialized as it is read when `type` comes first, and through a
+    /// `serde_json::Value` otherwise.
+    /// Both orders must give the same message.
+    #[test]
+    fn test_deserialize_message_content_before_type() {
+        let json = include_str!("../../../../fixtures/messages/post/post.json");
+        let message: Message = serde_json::from_str(json).unwrap();
+
+        let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        let content = fields.remove("content").unwrap();
+        let mut reordered = serde_json::Map::new();
+        reordered.insert("content".to_string(), content);
+        reordered.extend(std::mem::take(fields));
+        let reordered = serde_json::to_string(&reordered).unwrap();
+        assert!(reordered.find("\"content\"") < reordered.find("\"type\""));
+
+        let parsed: Message = serde_json::from_str(&reordered).unwrap();
+        assert_eq!(parsed, message);
+    }
+
+    #[test]
+    fn test_deserialize_message_missing_content() {
+        let json = include_str!("../../../../fixtures/messages/post/post.json");
+        let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+        value.as_object_mut().unwrap().remove("content");
+
+        assert!(serde_json::from_value::<Message>(value.clone()).is_err());
+        // Headers never need the content.
+        serde_json::from_value::<MessageHeader>(value).unwrap();
+    }
+
+    #[test]
+    fn test_typed_message_content_borrows_from_the_input() {
+        #[derive(Deserialize)]
+        struct BorrowedPost<'a> {
+            #[serde(rename = "type")]
+            post_type: &'a str,
+            #[serde(rename = "ref", borrow)]
+            reference: Cow<'a, str>,
+        }
+
+        // `address` and `time` come between the content fields.
+        let json = r#"{"type":"chat","address":"0xB68B9D4f3771c246233823ed1D3Add451055F9Ef","ref":"thread","time":1700000000.0}"#;
+        let parsed: TypedMessageContent<BorrowedPost> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            parsed.address,
+            Address::from("0xB68B9D4f3771c246233823ed1D3Add451055F9Ef".to_string())
+        );
+        assert_eq!(parsed.time, Timestamp::from(1700000000.0));
+        assert_eq!(parsed.content.post_type, "chat");
+        assert!(matches!(parsed.content.reference, Cow::Borrowed("thread")));
+
+        let missing_time = r#"{"type":"chat","ref":"thread","address":"0xB68B9D4f3771c246233823ed1D3Add451055F9Ef"}"#;
+        let err = serde_json::from_str::<TypedMessageContent<BorrowedPost>>(missing_time)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("missing field `time`"), "{err}");
+    }
+
     #[test]
     fn test_deserialize_item_type_invalid_type() {
         let content_source_str = r#"{"item_type":"invalid"}"#;
@@ -684,7 +1106,11 @@ mod tests {
         assert!(result.is_err());
     }
 
-    #[cfg(any(feature = "signature-evm", feature = "signature-sol"))]
+    #[cfg(any(
+        feature = "signature-evm",
+        feature = "signature-sol",
+        feature = "signature-tezos"
+    ))]
     mod signature_tests {
         use super::*;
         use crate::verify_signature::SignatureVerificationError;
@@ -693,7 +1119,7 @@ mod tests {
         fn test_verify_signature_unsupported_chain() {
             let json = include_str!("../../../../fixtures/messages/post/post.json");
             let mut message: Message = serde_json::from_str(json).unwrap();
-            message.chain = Chain::Tezos;
+            message.chain = Chain::Nuls2;
             assert_matches!(
                 message.verify_signature(),
                 Err(SignatureVerificationError::UnsupportedChain(_))
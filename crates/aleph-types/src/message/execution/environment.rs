@@ -2,6 +2,7 @@ use crate::chain::Address;
 use crate::item_hash::ItemHash;
 use memsizes::MiB;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionTriggers {
@@ -35,31 +36,183 @@ pub struct PublishedPort {
     port: Port,
 }
 
-fn default_vcpus() -> u32 {
-    1
-}
-
 fn default_memory() -> MiB {
     MiB::from(128)
 }
 
-fn default_seconds() -> u32 {
-    1
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "{vcpus} vCPUs is out of bounds ({} to {})",
+    Vcpus::MIN.get(),
+    Vcpus::MAX.get()
+)]
+pub struct InvalidVcpus {
+    pub vcpus: u32,
+}
+
+/// Number of virtual CPUs of a VM.
+///
+/// [`new`](Self::new) enforces the bounds accepted by the network; the sizes
+/// actually offered are listed by the tiers of the pricing aggregate.
+/// Deserialization does not check the bounds so that any published message
+/// parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Vcpus(u32);
+
+impl Vcpus {
+    pub const MIN: Self = Self(1);
+    pub const MAX: Self = Self(64);
+
+    pub fn new(vcpus: u32) -> Result<Self, InvalidVcpus> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&vcpus) {
+            Ok(Self(vcpus))
+        } else {
+            Err(InvalidVcpus { vcpus })
+        }
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// Compute units needed to provide these vCPUs, for compute units of
+    /// `vcpus_per_unit` vCPUs each. Rounds up: a partial unit is billed whole.
+    pub fn compute_units(&self, vcpus_per_unit: u32) -> u32 {
+        self.0.div_ceil(vcpus_per_unit.max(1))
+    }
+}
+
+impl Default for Vcpus {
+    fn default() -> Self {
+        Self::MIN
+    }
+}
+
+impl std::fmt::Display for Vcpus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl TryFrom<u32> for Vcpus {
+    type Error = InvalidVcpus;
+
+    fn try_from(vcpus: u32) -> Result<Self, Self::Error> {
+        Self::new(vcpus)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "execution timeout of {seconds}s is out of bounds ({}s to {}s)",
+    ExecutionTimeout::MIN.as_secs(),
+    ExecutionTimeout::MAX.as_secs()
+)]
+pub struct InvalidExecutionTimeout {
+    pub seconds: u64,
+}
+
+/// Time a program may run to answer a request before it is stopped, in
+/// seconds.
+///
+/// [`new`](Self::new) enforces the bounds accepted by the network.
+/// Deserialization does not: messages published before those bounds were
+/// enforced must still parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExecutionTimeout(u32);
+
+impl ExecutionTimeout {
+    pub const MIN: Self = Self(1);
+    pub const MAX: Self = Self(3600);
+
+    pub fn new(seconds: u32) -> Result<Self, InvalidExecutionTimeout> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&seconds) {
+            Ok(Self(seconds))
+        } else {
+            Err(InvalidExecutionTimeout {
+                seconds: seconds.into(),
+            })
+        }
+    }
+
+    pub fn as_secs(&self) -> u32 {
+        self.0
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_secs(self.0.into())
+    }
+}
+
+impl Default for ExecutionTimeout {
+    fn default() -> Self {
+        Self::MIN
+    }
+}
+
+impl std::fmt::Display for ExecutionTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+impl TryFrom<u32> for ExecutionTimeout {
+    type Error = InvalidExecutionTimeout;
+
+    fn try_from(seconds: u32) -> Result<Self, Self::Error> {
+        Self::new(seconds)
+    }
+}
+
+/// Sub-second durations are rejected rather than rounded.
+impl TryFrom<Duration> for ExecutionTimeout {
+    type Error = InvalidExecutionTimeout;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let seconds = duration.as_secs();
+        if duration.subsec_nanos() != 0 {
+            return Err(InvalidExecutionTimeout { seconds });
+        }
+        u32::try_from(seconds)
+            .map_err(|_| InvalidExecutionTimeout { seconds })
+            .and_then(Self::new)
+    }
+}
+
+impl From<ExecutionTimeout> for Duration {
+    fn from(timeout: ExecutionTimeout) -> Self {
+        timeout.as_duration()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MachineResources {
-    #[serde(default = "default_vcpus")]
-    pub vcpus: u32,
+    #[serde(default)]
+    pub vcpus: Vcpus,
     #[serde(default = "default_memory")]
     pub memory: MiB,
-    #[serde(default = "default_seconds")]
-    pub seconds: u32,
+    #[serde(default)]
+    pub seconds: ExecutionTimeout,
     /// Guest IPv4 ports to map to open ports on the host.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub published_ports: Option<Vec<PublishedPort>>,
 }
 
+/// The smallest VM the network accepts: 1 vCPU, 128 MiB and a 1 second
+/// timeout, the same values a message omitting them deserializes to.
+impl Default for MachineResources {
+    fn default() -> Self {
+        Self {
+            vcpus: Vcpus::default(),
+            memory: default_memory(),
+            seconds: ExecutionTimeout::default(),
+            published_ports: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Architecture {
     #[serde(rename = "x86_64")]
@@ -209,3 +362,43 @@ pub struct HostRequirements {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gpu: Option<Vec<GpuProperties>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_timeout_bounds() {
+        assert_eq!(ExecutionTimeout::new(30).unwrap().as_secs(), 30);
+        assert!(ExecutionTimeout::new(0).is_err());
+        assert!(ExecutionTimeout::new(ExecutionTimeout::MAX.as_secs() + 1).is_err());
+        assert_eq!(
+            ExecutionTimeout::try_from(Duration::from_secs(30)).unwrap(),
+            ExecutionTimeout::new(30).unwrap()
+        );
+        assert!(ExecutionTimeout::try_from(Duration::from_millis(1500)).is_err());
+        assert_eq!(
+            Duration::from(ExecutionTimeout::default()),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn vcpus_bounds_and_compute_units() {
+        assert_eq!(Vcpus::new(4).unwrap().get(), 4);
+        assert!(Vcpus::new(0).is_err());
+        assert!(Vcpus::new(Vcpus::MAX.get() + 1).is_err());
+        assert_eq!(Vcpus::new(4).unwrap().compute_units(1), 4);
+        assert_eq!(Vcpus::new(3).unwrap().compute_units(2), 2);
+        assert_eq!(Vcpus::default().compute_units(0), 1);
+    }
+
+    #[test]
+    fn machine_resources_parse_out_of_bounds_timeouts() {
+        let resources: MachineResources =
+            serde_json::from_str(r#"{"vcpus": 1, "memory": 128, "seconds": 0}"#).unwrap();
+        assert_eq!(resources.seconds.as_secs(), 0);
+        let resources: MachineResources = serde_json::from_str("{}").unwrap();
+        assert_eq!(resources.seconds, ExecutionTimeout::default());
+    }
+}
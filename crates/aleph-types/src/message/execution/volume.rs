@@ -33,6 +33,21 @@ pub struct ImmutableVolume {
     pub use_latest: bool,
 }
 
+impl ImmutableVolume {
+    /// Mounts the file stored by the STORE message `reference`, following
+    /// its amends.
+    pub fn new(reference: ItemHash, mount: impl Into<PathBuf>) -> Self {
+        Self {
+            base: BaseVolume {
+                comment: None,
+                mount: Some(mount.into()),
+            },
+            reference,
+            use_latest: true,
+        }
+    }
+}
+
 impl IsReadOnly for ImmutableVolume {
     fn is_read_only() -> bool {
         true
@@ -184,6 +199,35 @@ pub struct PersistentVolume {
     pub size_mib: PersistentVolumeSize,
 }
 
+impl PersistentVolume {
+    /// Creates an empty volume persisted on the host.
+    pub fn new(
+        name: impl Into<String>,
+        size_mib: u64,
+        mount: impl Into<PathBuf>,
+    ) -> Result<Self, VolumeError> {
+        Ok(Self {
+            base: BaseVolume {
+                comment: None,
+                mount: Some(mount.into()),
+            },
+            parent: None,
+            persistence: Some(VolumePersistence::Host),
+            name: Some(name.into()),
+            size_mib: PersistentVolumeSize::try_from(size_mib)?,
+        })
+    }
+
+    /// Initializes the volume as a copy of `parent` instead of empty.
+    pub fn with_parent(mut self, parent: ItemHash) -> Self {
+        self.parent = Some(ParentVolume {
+            reference: parent,
+            use_latest: true,
+        });
+        self
+    }
+}
+
 impl IsReadOnly for PersistentVolume {
     fn is_read_only() -> bool {
         false
@@ -198,6 +242,24 @@ pub enum MachineVolume {
     Persistent(PersistentVolume),
 }
 
+impl From<ImmutableVolume> for MachineVolume {
+    fn from(volume: ImmutableVolume) -> Self {
+        Self::Immutable(volume)
+    }
+}
+
+impl From<EphemeralVolume> for MachineVolume {
+    fn from(volume: EphemeralVolume) -> Self {
+        Self::Ephemeral(volume)
+    }
+}
+
+impl From<PersistentVolume> for MachineVolume {
+    fn from(volume: PersistentVolume) -> Self {
+        Self::Persistent(volume)
+    }
+}
+
 /// Root file system of a VM instance.
 ///
 /// The root file system of an instance is built as a copy of a reference image, named parent
@@ -211,6 +273,22 @@ pub struct RootfsVolume {
     pub forgotten_by: Option<Vec<ItemHash>>,
 }
 
+impl RootfsVolume {
+    /// Copies the image `parent` (following its amends) into a root file
+    /// system of `size_mib` persisted on the host.
+    pub fn new(parent: ItemHash, size_mib: PersistentVolumeSize) -> Self {
+        Self {
+            parent: ParentVolume {
+                reference: parent,
+                use_latest: true,
+            },
+            persistence: VolumePersistence::Host,
+            size_mib,
+            forgotten_by: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,4 +300,32 @@ mod tests {
         assert!(!EphemeralVolume::is_read_only());
         assert!(!PersistentVolume::is_read_only());
     }
+
+    #[test]
+    fn test_volume_helpers_serialize_like_messages() {
+        let code = ItemHash::from([1u8; 32]);
+        let volume = MachineVolume::from(
+            PersistentVolume::new("data", 1024, "/var/lib/data")
+                .unwrap()
+                .with_parent(code.clone()),
+        );
+        assert_eq!(
+            serde_json::to_value(&volume).unwrap(),
+            serde_json::json!({
+                "mount": "/var/lib/data",
+                "parent": {"ref": code.to_string(), "use_latest": true},
+                "persistence": "host",
+                "name": "data",
+                "size_mib": 1024,
+            })
+        );
+        assert!(PersistentVolume::new("data", 0, "/data").is_err());
+
+        let volume = MachineVolume::from(ImmutableVolume::new(code, "/opt/packages"));
+        let json = serde_json::to_string(&volume).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MachineVolume>(&json).unwrap(),
+            volume
+        );
+    }
 }
@@ -20,8 +20,8 @@ mod test {
     use crate::message::base_message::{MessageConfirmation, MessageContentEnum};
     use crate::message::execution::base::{Payment, PaymentType};
     use crate::message::execution::environment::{
-        GpuDeviceClass, GpuProperties, HostRequirements, Hypervisor, MachineResources,
-        NodeRequirements,
+        ExecutionTimeout, GpuDeviceClass, GpuProperties, HostRequirements, Hypervisor,
+        MachineResources, NodeRequirements, Vcpus,
     };
     use crate::message::execution::volume::{ParentVolume, VolumePersistence};
     use crate::message::{ContentSource, Message, MessageType};
@@ -91,9 +91,9 @@ mod test {
         assert_eq!(
             instance_content.base.resources,
             MachineResources {
-                vcpus: 12,
+                vcpus: Vcpus::new(12).unwrap(),
                 memory: MiB::from(73728),
-                seconds: 30,
+                seconds: ExecutionTimeout::new(30).unwrap(),
                 published_ports: None,
             }
         );
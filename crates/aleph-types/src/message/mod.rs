@@ -11,13 +11,17 @@ mod program;
 mod store;
 pub mod unsigned;
 
-#[cfg(any(feature = "signature-evm", feature = "signature-sol"))]
+#[cfg(any(
+    feature = "signature-evm",
+    feature = "signature-sol",
+    feature = "signature-tezos"
+))]
 pub use crate::verify_signature::SignatureVerificationError;
 pub use aggregate::{AggregateContent, AggregateKey};
 pub use authorization::{Authorization, SecurityAggregateContent};
 pub use base_message::{
     ContentSource, Message, MessageConfirmation, MessageContent, MessageContentEnum, MessageHeader,
-    MessageStatus, MessageType, MessageVerificationError,
+    MessageStatus, MessageType, MessageVerificationError, TypedContent, TypedMessageContent,
 };
 pub use forget::ForgetContent;
 pub use instance::InstanceContent;
@@ -1,3 +1,4 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -18,6 +19,15 @@ impl PostContent {
     pub fn post_type_str(&self) -> &str {
         &self.post_type
     }
+
+    /// Deserializes the user-defined content into `T`. A post without content
+    /// deserializes from `null`, so `T = Option<_>` accepts it.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        match &self.content {
+            Some(content) => T::deserialize(content),
+            None => T::deserialize(serde_json::Value::Null),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -87,9 +97,17 @@ mod tests {
         struct ContentStruct {
             body: String,
         }
-        let deserialized_content =
-            serde_json::from_value::<ContentStruct>(post_content.content.clone().unwrap()).unwrap();
+        let deserialized_content = post_content.parse::<ContentStruct>().unwrap();
         assert_eq!(deserialized_content.body, "Hello World");
+        assert!(
+            PostContent {
+                content: None,
+                ..post_content.clone()
+            }
+            .parse::<Option<ContentStruct>>()
+            .unwrap()
+            .is_none()
+        );
 
         assert!(!post_content.is_amend());
         assert_eq!(
@@ -93,7 +93,7 @@ mod tests {
     use super::*;
     use crate::chain::{Address, Chain, Signature};
     use crate::message::base_message::MessageContentEnum;
-    use crate::message::execution::environment::MachineResources;
+    use crate::message::execution::environment::{ExecutionTimeout, MachineResources, Vcpus};
     use crate::message::execution::volume::{BaseVolume, ImmutableVolume, MachineVolume};
     use crate::message::{ContentSource, Message, MessageType};
     use crate::timestamp::Timestamp;
@@ -167,9 +167,9 @@ mod tests {
         assert_eq!(
             program_content.base.resources,
             MachineResources {
-                vcpus: 2,
+                vcpus: Vcpus::new(2).unwrap(),
                 memory: MiB::from(4096),
-                seconds: 30,
+                seconds: ExecutionTimeout::new(30).unwrap(),
                 published_ports: None,
             }
         );
@@ -1,15 +1,122 @@
+use crate::chain::{Address, Chain, Signature};
 use crate::channel::Channel;
 use crate::item_hash::ItemHash;
 use crate::message::MessageType;
 use crate::message::item_type::ItemType;
+use crate::message::pending::PendingMessage;
 use crate::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+/// Constructs the buffer a sender signs to authenticate a message.
+/// Format: "{chain}\n{sender}\n{message_type}\n{item_hash}"
+pub fn verification_buffer(
+    chain: &Chain,
+    sender: &Address,
+    message_type: MessageType,
+    item_hash: &ItemHash,
+) -> String {
+    format!("{chain}\n{sender}\n{message_type}\n{item_hash}")
+}
+
+/// A message waiting for its signature.
+///
+/// Serializes to JSON so that it can be handed to an offline signer: the
+/// signer signs [`verification_buffer`](Self::verification_buffer) and the
+/// resulting signature is attached back with
+/// [`with_signature`](Self::with_signature).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UnsignedMessage {
+    #[serde(rename = "type")]
     pub message_type: MessageType,
     pub item_type: ItemType,
     pub item_content: String,
     pub item_hash: ItemHash,
     pub time: Timestamp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub channel: Option<Channel>,
 }
+
+impl UnsignedMessage {
+    /// Bytes `sender` must sign to send this message from `chain`.
+    pub fn verification_buffer(&self, chain: &Chain, sender: &Address) -> String {
+        verification_buffer(chain, sender, self.message_type, &self.item_hash)
+    }
+
+    /// Attaches a signature produced over
+    /// [`verification_buffer`](Self::verification_buffer) for the same
+    /// `chain` and `sender`.
+    ///
+    /// The signature is not checked here; the network rejects the message if
+    /// it does not match.
+    pub fn with_signature(
+        self,
+        chain: Chain,
+        sender: Address,
+        signature: Signature,
+    ) -> PendingMessage {
+        PendingMessage {
+            chain,
+            sender,
+            signature,
+            message_type: self.message_type,
+            item_type: self.item_type,
+            item_content: self.item_content,
+            item_hash: self.item_hash,
+            time: self.time,
+            channel: self.channel,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{address, item_hash};
+
+    fn unsigned() -> UnsignedMessage {
+        UnsignedMessage {
+            message_type: MessageType::Post,
+            item_type: ItemType::Inline,
+            item_content: r#"{"type":"test"}"#.to_string(),
+            item_hash: item_hash!(
+                "d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+            ),
+            time: Timestamp::from(1_700_000_000.0),
+            channel: Some(Channel::from("TEST".to_string())),
+        }
+    }
+
+    #[test]
+    fn test_unsigned_message_serde_roundtrip() {
+        let message = unsigned();
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], "POST");
+        assert_eq!(json["channel"], "TEST");
+
+        let parsed: UnsignedMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, message);
+    }
+
+    #[test]
+    fn test_with_signature_keeps_signed_fields() {
+        let sender = address!("0xB68B9D4f3771c246233823ed1D3Add451055F9Ef");
+        let message = unsigned();
+        assert_eq!(
+            message.verification_buffer(&Chain::Ethereum, &sender),
+            "ETH\n\
+             0xB68B9D4f3771c246233823ed1D3Add451055F9Ef\n\
+             POST\n\
+             d281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c"
+        );
+
+        let pending = message.clone().with_signature(
+            Chain::Ethereum,
+            sender.clone(),
+            Signature::from("0x12".to_string()),
+        );
+        assert_eq!(pending.sender, sender);
+        assert_eq!(pending.item_hash, message.item_hash);
+        assert_eq!(pending.item_content, message.item_content);
+        assert_eq!(pending.channel, message.channel);
+    }
+}
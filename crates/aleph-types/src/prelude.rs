@@ -0,0 +1,21 @@
+//! The types most programs handling Aleph messages need.
+//!
+//! ```
+//! use aleph_types::prelude::*;
+//! ```
+
+pub use crate::account::Account;
+pub use crate::chain::{Address, Chain, ChainFamily, Signature, SignatureScheme};
+pub use crate::channel::Channel;
+pub use crate::item_hash::ItemHash;
+pub use crate::message::pending::PendingMessage;
+pub use crate::message::{
+    AggregateContent, ForgetContent, InstanceContent, Message, MessageContentEnum, MessageHeader,
+    MessageStatus, MessageType, PostContent, ProgramContent, StoreContent, TypedContent,
+};
+pub use crate::timestamp::Timestamp;
+
+#[cfg(feature = "account-evm")]
+pub use crate::account::EvmAccount;
+#[cfg(feature = "account-sol")]
+pub use crate::account::SolanaAccount;
@@ -1,13 +1,15 @@
-use chrono::{DateTime, TimeZone, Timelike, Utc};
+use chrono::{DateTime, TimeDelta, TimeZone, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
 
 #[derive(Debug, thiserror::Error)]
 pub enum TimestampError {
     #[error("Timestamp out of bounds")]
     OutOfBounds,
-    #[error("Failed to parse timestamp")]
-    ParseError,
+    #[error("Failed to parse timestamp: '{0}' is neither epoch seconds nor an RFC 3339 date")]
+    ParseError(String),
 }
 
 /// Timestamp type on the Aleph Cloud network.
@@ -15,7 +17,12 @@ pub enum TimestampError {
 /// Time in Aleph messages is usually represented as a floating-point epoch timestamp. This type
 /// keeps the floating point representation for fast serialization/deserialization and to avoid
 /// loss of precision, but provides helpers to convert to datetime for human readability.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// JSON timestamps round-trip to the same digits: they are serialized in their shortest
+/// representation, which is what Python's `json` module emits, and parsed exactly (serde_json's
+/// `float_roundtrip` feature). Hashes over re-serialized content only hold under these
+/// conditions; verification should still prefer the original bytes when they are available.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Timestamp(f64);
 
 impl From<f64> for Timestamp {
@@ -56,6 +63,53 @@ impl Timestamp {
     }
 }
 
+/// Parses epoch seconds (`1700000000.5`) or an RFC 3339 date
+/// (`2023-11-14T22:13:20.5Z`).
+impl FromStr for Timestamp {
+    type Err = TimestampError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(secs) = s.parse::<f64>()
+            && secs.is_finite()
+        {
+            return Ok(Self(secs));
+        }
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| Self::from(dt.with_timezone(&Utc)))
+            .map_err(|_| TimestampError::ParseError(s.to_string()))
+    }
+}
+
+fn delta_as_secs(delta: TimeDelta) -> f64 {
+    delta.num_seconds() as f64 + delta.subsec_nanos() as f64 / 1_000_000_000.0
+}
+
+impl Add<TimeDelta> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, delta: TimeDelta) -> Timestamp {
+        Timestamp(self.0 + delta_as_secs(delta))
+    }
+}
+
+impl Sub<TimeDelta> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, delta: TimeDelta) -> Timestamp {
+        Timestamp(self.0 - delta_as_secs(delta))
+    }
+}
+
+/// Time elapsed between two timestamps, to the microsecond.
+impl Sub for Timestamp {
+    type Output = TimeDelta;
+
+    fn sub(self, other: Timestamp) -> TimeDelta {
+        TimeDelta::microseconds(((self.0 - other.0) * 1_000_000.0).round() as i64)
+    }
+}
+
 impl Display for Timestamp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let datetime_str = match self.to_datetime() {
@@ -87,6 +141,20 @@ mod tests {
         assert_eq!(timestamp.0, 1635789600.5);
     }
 
+    #[test]
+    fn test_timestamp_json_roundtrip_preserves_digits() {
+        for literal in [
+            "1762518461.4893668",
+            "1700000000.123456",
+            "1635789600.5",
+            "1713874022.5670002",
+            "0.30000000000000004",
+        ] {
+            let timestamp: Timestamp = serde_json::from_str(literal).unwrap();
+            assert_eq!(serde_json::to_string(&timestamp).unwrap(), literal);
+        }
+    }
+
     #[test]
     fn test_timestamp_now() {
         let before = chrono::Utc::now().timestamp() as f64;
@@ -98,6 +166,44 @@ mod tests {
         assert!(ts_f64 <= after + 1.0);
     }
 
+    #[test]
+    fn test_timestamp_from_str() {
+        assert_eq!(
+            "1635789600.5".parse::<Timestamp>().unwrap(),
+            Timestamp(1635789600.5)
+        );
+        assert_eq!(
+            "2021-11-01T18:00:00.5Z".parse::<Timestamp>().unwrap(),
+            Timestamp(1635789600.5)
+        );
+        assert_eq!(
+            "2021-11-01T20:00:00+02:00".parse::<Timestamp>().unwrap(),
+            Timestamp(1635789600.0)
+        );
+        assert!(matches!(
+            "yesterday".parse::<Timestamp>(),
+            Err(TimestampError::ParseError(_))
+        ));
+        assert!("NaN".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn test_timestamp_ordering_and_arithmetic() {
+        let t = Timestamp(1635789600.5);
+        let later = t.clone() + TimeDelta::milliseconds(1500);
+        assert_eq!(later, Timestamp(1635789602.0));
+        assert!(later > t);
+        assert_eq!(
+            later.clone() - TimeDelta::seconds(2),
+            Timestamp(1635789600.0)
+        );
+        assert_eq!(later - t, TimeDelta::milliseconds(1500));
+        assert_eq!(
+            Timestamp(10.0) - TimeDelta::milliseconds(-500),
+            Timestamp(10.5)
+        );
+    }
+
     #[test]
     fn test_timestamp_display() {
         let dt = Utc.timestamp_opt(1635789600, 500_000_000).unwrap();
@@ -74,7 +74,7 @@ pub(crate) fn public_key_to_address(key: &VerifyingKey) -> String {
 }
 
 /// Encodes raw address bytes as an EIP-55 checksummed hex string.
-fn eip55_checksum(address_bytes: &[u8]) -> String {
+pub(crate) fn eip55_checksum(address_bytes: &[u8]) -> String {
     let hex_addr = hex::encode(address_bytes);
     let hash = Keccak256::digest(hex_addr.as_bytes());
 
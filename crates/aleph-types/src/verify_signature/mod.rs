@@ -2,10 +2,14 @@
 pub(crate) mod ethereum;
 #[cfg(feature = "signature-sol")]
 mod solana;
+#[cfg(feature = "signature-tezos")]
+pub(crate) mod tezos;
 
 use crate::chain::{Address, Chain, Signature};
 use crate::item_hash::ItemHash;
 use crate::message::MessageType;
+use crate::message::unsigned::verification_buffer;
+use crate::timestamp::Timestamp;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -32,30 +36,41 @@ pub enum SignatureVerificationError {
     MissingSignature,
 }
 
-/// Constructs the verification buffer that was signed by the sender.
-/// Format: "{chain}\n{sender}\n{message_type}\n{item_hash}"
-fn verification_buffer(
+/// Verifies the cryptographic signature of a message sent at `time`.
+///
+/// Only Tezos `micheline` signatures cover the time; other signatures are
+/// made over the verification buffer alone.
+pub fn verify(
     chain: &Chain,
     sender: &Address,
+    signature: &Signature,
     message_type: MessageType,
     item_hash: &ItemHash,
-) -> String {
-    format!("{chain}\n{sender}\n{message_type}\n{item_hash}")
+    time: &Timestamp,
+) -> Result<(), SignatureVerificationError> {
+    let buffer = verification_buffer(chain, sender, message_type, item_hash);
+    #[cfg(feature = "signature-tezos")]
+    if let (Chain::Tezos, Some(dapp_url)) = (chain, signature.dapp_url()) {
+        let payload = tezos::micheline_payload(buffer.as_bytes(), dapp_url, time)?;
+        return verify_buffer(chain, sender, signature, &payload);
+    }
+    #[cfg(not(feature = "signature-tezos"))]
+    let _ = time;
+    verify_buffer(chain, sender, signature, buffer.as_bytes())
 }
 
-/// Verifies the cryptographic signature of a message.
-pub fn verify(
+/// Verifies that `signature` is the signature of `sender` over `buffer`, as
+/// produced on `chain`. [`verify`] applies it to the verification buffer of a
+/// message, or to its Micheline payload for Tezos `micheline` signatures.
+pub fn verify_buffer(
     chain: &Chain,
     sender: &Address,
     signature: &Signature,
-    message_type: MessageType,
-    item_hash: &ItemHash,
+    buffer: &[u8],
 ) -> Result<(), SignatureVerificationError> {
-    let buffer = verification_buffer(chain, sender, message_type, item_hash);
-
     #[cfg(feature = "signature-evm")]
     if chain.is_evm() {
-        let recovered = ethereum::recover_address(buffer.as_bytes(), signature.as_str())?;
+        let recovered = ethereum::recover_address(buffer, signature.as_str())?;
         let recovered_addr = Address::from(recovered);
 
         if !sender
@@ -74,7 +89,18 @@ pub fn verify(
     #[cfg(feature = "signature-sol")]
     if chain.is_svm() {
         // For SVM chains, the sender address is the base58-encoded Ed25519 public key.
-        return solana::verify(buffer.as_bytes(), signature.as_str(), sender.as_str());
+        return solana::verify(buffer, signature.as_str(), sender.as_str());
+    }
+
+    #[cfg(feature = "signature-tezos")]
+    if matches!(chain, Chain::Tezos) {
+        // Tezos addresses are key hashes: the key travels with the signature.
+        let public_key = signature.public_key().ok_or_else(|| {
+            SignatureVerificationError::InvalidSignature(
+                "Tezos signatures must carry the signer's public key".to_string(),
+            )
+        })?;
+        return tezos::verify(buffer, signature.as_str(), public_key, sender.as_str());
     }
 
     Err(SignatureVerificationError::UnsupportedChain(chain.clone()))
@@ -0,0 +1,247 @@
+//! Tezos keys, addresses and signatures.
+//!
+//! Tezos signs the BLAKE2b-256 digest of the payload, with Ed25519 for `tz1`
+//! accounts and secp256k1 for `tz2` accounts. Keys, signatures and addresses
+//! are base58check-encoded behind a type-specific prefix (`edpk`, `spsig1`,
+//! `tz1`...). Aleph messages carry the signature together with the public key,
+//! as `{"signature": "edsig...", "publicKey": "edpk..."}`, and sign either
+//! the verification buffer as is (the `raw` signing type of pyaleph) or, for
+//! browser wallets, a Micheline-packed string embedding it (the `micheline`
+//! signing type, see [`micheline_payload`]).
+
+use super::SignatureVerificationError;
+use crate::timestamp::Timestamp;
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use sha2::{Digest, Sha256};
+
+pub(crate) const TZ1_PREFIX: &[u8] = &[6, 161, 159];
+pub(crate) const TZ2_PREFIX: &[u8] = &[6, 161, 161];
+pub(crate) const EDPK_PREFIX: &[u8] = &[13, 15, 37, 217];
+pub(crate) const SPPK_PREFIX: &[u8] = &[3, 254, 226, 86];
+/// Prefix of 32-byte Ed25519 seeds.
+pub(crate) const EDSK_PREFIX: &[u8] = &[13, 15, 58, 7];
+/// Prefix of 64-byte Ed25519 secret keys: the seed followed by the public key.
+pub(crate) const EDSK64_PREFIX: &[u8] = &[43, 246, 78, 7];
+pub(crate) const SPSK_PREFIX: &[u8] = &[17, 162, 224, 201];
+pub(crate) const EDSIG_PREFIX: &[u8] = &[9, 245, 205, 134, 18];
+pub(crate) const SPSIG_PREFIX: &[u8] = &[13, 115, 101, 19, 63];
+/// Prefix of signatures that do not name their curve.
+const GENERIC_SIG_PREFIX: &[u8] = &[4, 130, 43];
+
+/// A Tezos public key.
+pub(crate) enum PublicKey {
+    Ed25519(ed25519_dalek::VerifyingKey),
+    Secp256k1(k256::ecdsa::VerifyingKey),
+}
+
+impl PublicKey {
+    pub(crate) fn parse(encoded: &str) -> Result<Self, SignatureVerificationError> {
+        let invalid = |e: String| SignatureVerificationError::InvalidSignature(e);
+        if let Some(bytes) = base58check_decode(encoded, EDPK_PREFIX) {
+            let bytes: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| invalid("invalid edpk length".into()))?;
+            ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                .map(PublicKey::Ed25519)
+                .map_err(|e| invalid(e.to_string()))
+        } else if let Some(bytes) = base58check_decode(encoded, SPPK_PREFIX) {
+            k256::ecdsa::VerifyingKey::from_sec1_bytes(&bytes)
+                .map(PublicKey::Secp256k1)
+                .map_err(|e| invalid(e.to_string()))
+        } else {
+            Err(invalid(format!("unsupported Tezos public key '{encoded}'")))
+        }
+    }
+
+    /// The base58check encoding of the key (`edpk...` or `sppk...`).
+    pub(crate) fn encode(&self) -> String {
+        match self {
+            PublicKey::Ed25519(key) => base58check_encode(EDPK_PREFIX, key.as_bytes()),
+            PublicKey::Secp256k1(key) => {
+                base58check_encode(SPPK_PREFIX, key.to_encoded_point(true).as_bytes())
+            }
+        }
+    }
+
+    /// The address of the key (`tz1...` or `tz2...`).
+    pub(crate) fn address(&self) -> String {
+        let (prefix, bytes) = match self {
+            PublicKey::Ed25519(key) => (TZ1_PREFIX, key.as_bytes().to_vec()),
+            PublicKey::Secp256k1(key) => {
+                (TZ2_PREFIX, key.to_encoded_point(true).as_bytes().to_vec())
+            }
+        };
+        let hash = blake2b_simd::Params::new().hash_length(20).hash(&bytes);
+        base58check_encode(prefix, hash.as_bytes())
+    }
+}
+
+/// The digest Tezos keys sign for `message`.
+pub(crate) fn digest(message: &[u8]) -> [u8; 32] {
+    let hash = blake2b_simd::Params::new().hash_length(32).hash(message);
+    hash.as_bytes().try_into().expect("hash length is 32")
+}
+
+/// The payload signed by the `micheline` signing type for `buffer`: the
+/// Micheline packing (`0x05`, then `0x01` and the big-endian 4-byte length of
+/// a string) of `Tezos Signed Message: {dapp_url} {time} {buffer}`, with
+/// `time` in ISO 8601 with milliseconds, as browsers format it.
+pub(crate) fn micheline_payload(
+    buffer: &[u8],
+    dapp_url: &str,
+    time: &Timestamp,
+) -> Result<Vec<u8>, SignatureVerificationError> {
+    let time = time
+        .to_datetime()
+        .map_err(|e| SignatureVerificationError::InvalidSignature(e.to_string()))?
+        .format("%Y-%m-%dT%H:%M:%S%.3fZ");
+    let mut text = format!("Tezos Signed Message: {dapp_url} {time} ").into_bytes();
+    text.extend_from_slice(buffer);
+    let length = u32::try_from(text.len()).map_err(|_| {
+        SignatureVerificationError::InvalidSignature("micheline payload too long".to_string())
+    })?;
+
+    let mut payload = Vec::with_capacity(text.len() + 6);
+    payload.extend_from_slice(&[0x05, 0x01]);
+    payload.extend_from_slice(&length.to_be_bytes());
+    payload.extend_from_slice(&text);
+    Ok(payload)
+}
+
+/// Verifies that `signature` is a signature over `message` by `public_key`,
+/// and that `public_key` is the key of the `sender` address.
+pub(super) fn verify(
+    message: &[u8],
+    signature: &str,
+    public_key: &str,
+    sender: &str,
+) -> Result<(), SignatureVerificationError> {
+    let public_key = PublicKey::parse(public_key)?;
+    let address = public_key.address();
+    if address != sender {
+        return Err(SignatureVerificationError::SignatureMismatch {
+            expected: sender.to_string().into(),
+            recovered: address.into(),
+        });
+    }
+
+    let sig_bytes: [u8; 64] = [EDSIG_PREFIX, SPSIG_PREFIX, GENERIC_SIG_PREFIX]
+        .into_iter()
+        .find_map(|prefix| base58check_decode(signature, prefix))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            SignatureVerificationError::InvalidSignature(format!(
+                "unsupported Tezos signature '{signature}'"
+            ))
+        })?;
+
+    let digest = digest(message);
+    let result = match public_key {
+        PublicKey::Ed25519(key) => key
+            .verify_strict(&digest, &ed25519_dalek::Signature::from_bytes(&sig_bytes))
+            .map_err(|e| e.to_string()),
+        PublicKey::Secp256k1(key) => k256::ecdsa::Signature::from_slice(&sig_bytes)
+            .and_then(|sig| key.verify_prehash(&digest, &sig))
+            .map_err(|e| e.to_string()),
+    };
+    result.map_err(SignatureVerificationError::InvalidSignature)
+}
+
+pub(crate) fn base58check_encode(prefix: &[u8], payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(prefix.len() + payload.len() + 4);
+    data.extend_from_slice(prefix);
+    data.extend_from_slice(payload);
+    let checksum = Sha256::digest(Sha256::digest(&data));
+    data.extend_from_slice(&checksum[..4]);
+    bs58::encode(data).into_string()
+}
+
+/// Decodes a base58check string starting with `prefix`, returning the bytes
+/// after the prefix, or `None` if the string is invalid or has another prefix.
+pub(crate) fn base58check_decode(encoded: &str, prefix: &[u8]) -> Option<Vec<u8>> {
+    let data = bs58::decode(encoded).into_vec().ok()?;
+    if data.len() < prefix.len() + 4 {
+        return None;
+    }
+    let (data, checksum) = data.split_at(data.len() - 4);
+    if Sha256::digest(Sha256::digest(data))[..4] != *checksum {
+        return None;
+    }
+    data.strip_prefix(prefix).map(<[u8]>::to_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The `bootstrap1` account of the Tezos sandbox.
+    const BOOTSTRAP1_PK: &str = "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav";
+    const BOOTSTRAP1_ADDRESS: &str = "tz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx";
+    const MESSAGE: &[u8] = b"ETH\n0xabc\nPOST\nd281";
+    const SIGNATURE: &str = "edsigtqXbWZ5nWuqw5u4n5G4GJqpqapZVqBUXSoNuEiizDwiWTTaBWtux5VjY4AAiXCSCabMx7YxVwpRyqQh5WKHkMTp9sB4Jtw";
+    const BUFFER: &[u8] = b"TEZOS\ntz1KqTpEZ7Yob7QbPE4Hy4Wo8fHG8LhKxZSx\nPOST\nd281eb8a69ba1f4dda2d71aaf3ded06caa92edd690ef3d0632f41aa91167762c";
+    // Signed by `bootstrap1` with Python's `cryptography`, independently of
+    // this crate.
+    const MICHELINE_SIGNATURE: &str = "edsigtYqFNAQmqfz6XMSgvsM4oYeorBs9Qn95wQ8VnAC8PUS9X8ZNb8oxHDH6AweAXpxtCbMjjpyQGWQ4P8vbj1otnJGQrc8mLr";
+
+    #[test]
+    fn test_public_key_address() {
+        let key = PublicKey::parse(BOOTSTRAP1_PK).unwrap();
+        assert_eq!(key.address(), BOOTSTRAP1_ADDRESS);
+        assert_eq!(key.encode(), BOOTSTRAP1_PK);
+    }
+
+    #[test]
+    fn test_verify_ed25519() {
+        verify(MESSAGE, SIGNATURE, BOOTSTRAP1_PK, BOOTSTRAP1_ADDRESS).unwrap();
+        assert!(matches!(
+            verify(b"tampered", SIGNATURE, BOOTSTRAP1_PK, BOOTSTRAP1_ADDRESS),
+            Err(SignatureVerificationError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            verify(
+                MESSAGE,
+                SIGNATURE,
+                BOOTSTRAP1_PK,
+                "tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjb"
+            ),
+            Err(SignatureVerificationError::SignatureMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_micheline_payload() {
+        let time = Timestamp::from(1_700_000_000.0);
+        let payload = micheline_payload(BUFFER, "https://app.aleph.cloud", &time).unwrap();
+        let text = "Tezos Signed Message: https://app.aleph.cloud 2023-11-14T22:13:20.000Z ";
+        assert_eq!(payload[..6], [0x05, 0x01, 0x00, 0x00, 0x00, 0xb7]);
+        assert_eq!(payload.len(), 6 + text.len() + BUFFER.len());
+        assert_eq!(&payload[6..6 + text.len()], text.as_bytes());
+        assert!(payload.ends_with(BUFFER));
+
+        verify(
+            &payload,
+            MICHELINE_SIGNATURE,
+            BOOTSTRAP1_PK,
+            BOOTSTRAP1_ADDRESS,
+        )
+        .unwrap();
+        assert!(
+            verify(
+                BUFFER,
+                MICHELINE_SIGNATURE,
+                BOOTSTRAP1_PK,
+                BOOTSTRAP1_ADDRESS
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_base58check_rejects_bad_checksum() {
+        let mut corrupted = BOOTSTRAP1_PK.to_string();
+        corrupted.replace_range(10..11, "X");
+        assert!(base58check_decode(&corrupted, EDPK_PREFIX).is_none());
+        assert!(base58check_decode(BOOTSTRAP1_PK, SPPK_PREFIX).is_none());
+    }
+}
@@ -144,6 +144,7 @@ pub fn verify_signature(msg: &IncomingMessage) -> ProcessingResult<()> {
         &msg.signature,
         msg.message_type,
         &msg.item_hash,
+        &msg.time,
     )
     .map_err(|e| ProcessingError::InvalidSignature(e.to_string()))
 }
@@ -183,7 +184,7 @@ fn check_balance(db: &Db, msg: &IncomingMessage, content: &MessageContent) -> Pr
                     ));
                 }
             };
-            let vcpus = program.base.resources.vcpus;
+            let vcpus = program.base.resources.vcpus.get();
             let memory_mib: u64 = program.base.resources.memory.count();
             let memory_mib_u32 = memory_mib.min(u32::MAX as u64) as u32;
             let total_volume_mib = collect_vm_volume_mib_program(program);
@@ -200,7 +201,7 @@ fn check_balance(db: &Db, msg: &IncomingMessage, content: &MessageContent) -> Pr
                     ));
                 }
             };
-            let vcpus = instance.base.resources.vcpus;
+            let vcpus = instance.base.resources.vcpus.get();
             let memory_mib: u64 = instance.base.resources.memory.count();
             let memory_mib_u32 = memory_mib.min(u32::MAX as u64) as u32;
             let total_volume_mib = collect_vm_volume_mib_instance(instance);
@@ -474,7 +475,7 @@ pub(crate) fn compute_cost_records(
                 MessageContentEnum::Program(p) => p,
                 _ => return Vec::new(),
             };
-            let vcpus = program.base.resources.vcpus;
+            let vcpus = program.base.resources.vcpus.get();
             let memory_mib: u64 = program.base.resources.memory.count();
             let memory_mib_u32 = memory_mib.min(u32::MAX as u64) as u32;
             let total_volume_mib = collect_vm_volume_mib_program(program);
@@ -497,7 +498,7 @@ pub(crate) fn compute_cost_records(
                 MessageContentEnum::Instance(i) => i,
                 _ => return Vec::new(),
             };
-            let vcpus = instance.base.resources.vcpus;
+            let vcpus = instance.base.resources.vcpus.get();
             let memory_mib: u64 = instance.base.resources.memory.count();
             let memory_mib_u32 = memory_mib.min(u32::MAX as u64) as u32;
             let total_volume_mib = collect_vm_volume_mib_instance(instance);